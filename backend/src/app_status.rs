@@ -0,0 +1,93 @@
+// One periodic `app-status` event with the headline numbers the taskbar shows:
+// server states, a download summary, the active llama.cpp build and system stats.
+// The frontend listens to it instead of polling each of those on its own timer.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::downloader::DownloadState;
+use crate::models::ProcessStatus;
+use crate::system_monitor::{get_system_stats, SystemStats};
+use crate::AppState;
+
+const STATUS_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProcessSummary {
+    pub total: usize,
+    pub starting: usize,
+    pub running: usize,
+    pub stopped: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DownloadSummary {
+    pub active: usize, // Starting, downloading or extracting
+    pub paused: usize,
+    pub failed: usize,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
+    pub speed: f64, // Bytes per second across active downloads
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppStatus {
+    pub processes: ProcessSummary,
+    pub downloads: DownloadSummary,
+    pub active_version: Option<String>,
+    pub system: Option<SystemStats>,
+}
+
+pub async fn run_status_job(state: AppState, app_handle: tauri::AppHandle) {
+    loop {
+        let status = collect(&state).await;
+        let _ = app_handle.emit("app-status", &status);
+        tokio::time::sleep(STATUS_INTERVAL).await;
+    }
+}
+
+pub async fn collect(state: &AppState) -> AppStatus {
+    let mut processes = ProcessSummary::default();
+    for process in state.processes.list().await {
+        processes.total += 1;
+        match process.status {
+            ProcessStatus::Starting | ProcessStatus::Restarting => processes.starting += 1,
+            ProcessStatus::Running => processes.running += 1,
+            ProcessStatus::Stopped => processes.stopped += 1,
+            ProcessStatus::Failed => processes.failed += 1,
+        }
+    }
+
+    let mut downloads = DownloadSummary::default();
+    for download in state.downloads.active().await {
+        match download.status {
+            DownloadState::Starting | DownloadState::Downloading | DownloadState::Extracting => {
+                downloads.active += 1;
+                downloads.downloaded_bytes += download.downloaded_bytes;
+                downloads.total_bytes += download.total_bytes;
+                downloads.speed += download.speed;
+            }
+            DownloadState::Retrying => downloads.active += 1,
+            DownloadState::Paused => downloads.paused += 1,
+            DownloadState::Failed => downloads.failed += 1,
+            DownloadState::Completed | DownloadState::Cancelled => {}
+        }
+    }
+
+    let active = state.versions.active().await;
+    let active_version = active.version.or(active.folder);
+
+    // Blocking sysinfo/NVML calls, same as the get_system_stats command
+    let system = match get_system_stats().await {
+        Ok(stats) => Some(stats),
+        Err(e) => {
+            eprintln!("Failed to collect system stats: {}", e);
+            None
+        }
+    };
+
+    AppStatus { processes, downloads, active_version, system }
+}
@@ -0,0 +1,79 @@
+// Apple Silicon stats. M-series GPUs have no memory of their own: Metal allocates from
+// unified memory up to a wired limit, so the numbers that matter are how much of that
+// the GPU holds, the GPU's utilization and the system's memory pressure. The IOAccelerator
+// registry entry publishes the GPU figures without special privileges and sysctl covers
+// the rest, both read through their command line tools to avoid IOKit bindings.
+
+use std::process::Command;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::gpu_probe::{GpuProbe, GpuSample};
+
+// Integer entries of the PerformanceStatistics dictionary that the probe reads
+static UTILIZATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#""Device Utilization %"=(\d+)"#).unwrap());
+static IN_USE_MEMORY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#""In use system memory"=(\d+)"#).unwrap());
+
+pub struct AppleGpuProbe;
+
+fn sysctl(name: &str) -> Option<String> {
+    let output = Command::new("sysctl").args(["-n", name]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// "Apple M2 Pro" on Apple Silicon, None on Intel Macs
+fn chip_name() -> Option<String> {
+    sysctl("machdep.cpu.brand_string").filter(|brand| brand.starts_with("Apple"))
+}
+
+/// How much unified memory Metal lets the GPU wire. `iogpu.wired_limit_mb` is set when the
+/// user raised it; otherwise macOS allows roughly two thirds of RAM, three quarters above 36 GB
+fn gpu_memory_limit() -> Option<u64> {
+    let configured = sysctl("iogpu.wired_limit_mb")
+        .and_then(|mb| mb.parse::<u64>().ok())
+        .filter(|mb| *mb > 0);
+    if let Some(mb) = configured {
+        return Some(mb * 1024 * 1024);
+    }
+    let total: u64 = sysctl("hw.memsize")?.parse().ok()?;
+    Some(if total > 36 * 1024 * 1024 * 1024 { total / 4 * 3 } else { total / 3 * 2 })
+}
+
+/// `"Device Utilization %"` and `"In use system memory"` from the accelerator's PerformanceStatistics
+fn performance_statistics() -> Option<(f32, u64)> {
+    let output = Command::new("ioreg").args(["-r", "-d", "1", "-w", "0", "-c", "IOAccelerator"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let read = |pattern: &Regex| pattern.captures(&text)?[1].parse::<u64>().ok();
+    let utilization = read(&UTILIZATION)?;
+    let in_use = read(&IN_USE_MEMORY).unwrap_or(0);
+    Some((utilization.min(100) as f32, in_use))
+}
+
+impl GpuProbe for AppleGpuProbe {
+    fn vendor(&self) -> &'static str {
+        "apple"
+    }
+
+    fn sample(&self) -> Option<GpuSample> {
+        let chip = chip_name()?;
+        let (usage_percent, memory_used_bytes) = performance_statistics()?;
+        Some(GpuSample {
+            name: format!("{} GPU", chip),
+            usage_percent,
+            memory_total_bytes: gpu_memory_limit().unwrap_or(0),
+            memory_used_bytes,
+            shared_memory: true,
+        })
+    }
+}
+
+/// Memory pressure in percent, the inverse of the kernel's available memory level that
+/// Activity Monitor's pressure graph follows
+pub fn memory_pressure() -> Option<f32> {
+    let available: f32 = sysctl("kern.memorystatus_level")?.parse().ok()?;
+    Some((100.0 - available).clamp(0.0, 100.0))
+}
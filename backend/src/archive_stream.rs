@@ -0,0 +1,198 @@
+// Extract zip archives while they download. Zip entries are preceded by their local
+// headers, so entries can be unpacked in order as bytes land in the partial file
+// instead of waiting for the central directory at the end. A journal next to the
+// archive records the entries already written and where the next one starts, so a
+// crash or restart resumes extraction instead of starting over.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+const LOCAL_HEADER_LEN: usize = 30;
+// General purpose flag: sizes follow the data instead of being in the header
+const DATA_DESCRIPTOR_FLAG: u16 = 1 << 3;
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Shared between the downloader writing the archive and the extractor reading it
+#[derive(Default)]
+pub struct StreamProgress {
+    pub written: AtomicU64,    // Bytes of the archive on disk so far
+    pub finished: AtomicBool,  // No more bytes will arrive
+    pub cancelled: AtomicBool, // Stop extracting as soon as possible
+}
+
+/// Stops the extractor when the download bails out early, so it doesn't wait forever
+pub struct CancelOnDrop(pub Arc<StreamProgress>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if !self.0.finished.load(Ordering::Acquire) {
+            self.0.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Why streaming stopped without unpacking the whole archive
+pub enum StreamError {
+    /// The archive can't be read front to back (e.g. entries with data descriptors),
+    /// extract it from the central directory once the download is done
+    Unsupported(String),
+    Failed(String),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExtractJournal {
+    source: String,           // Download URL, a different archive invalidates the journal
+    size: u64,                // Expected archive size, 0 when unknown
+    next_offset: u64,         // Where the next local header starts
+    completed: Vec<String>,   // Entries already written
+}
+
+pub fn journal_path(archive_path: &Path) -> PathBuf {
+    let mut name = archive_path.as_os_str().to_os_string();
+    name.push(".extract.json");
+    PathBuf::from(name)
+}
+
+fn load_journal(path: &Path, source: &str, size: u64) -> ExtractJournal {
+    let journal = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<ExtractJournal>(&contents).ok());
+    match journal {
+        Some(journal) if journal.source == source && journal.size == size => journal,
+        _ => ExtractJournal { source: source.to_string(), size, ..Default::default() },
+    }
+}
+
+fn save_journal(path: &Path, journal: &ExtractJournal) {
+    // Write then rename, a torn journal would be worse than a missing one
+    let temp = path.with_extension("tmp");
+    let saved = serde_json::to_vec(journal)
+        .map_err(std::io::Error::other)
+        .and_then(|contents| std::fs::write(&temp, contents))
+        .and_then(|_| std::fs::rename(&temp, path));
+    if let Err(e) = saved {
+        eprintln!("Failed to save extraction journal {:?}: {}", path, e);
+    }
+}
+
+/// Reads a file that is still being written, waiting for more bytes until the
+/// writer reports it is finished
+struct FollowingReader {
+    file: std::fs::File,
+    position: u64,
+    progress: Arc<StreamProgress>,
+}
+
+impl Read for FollowingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.progress.cancelled.load(Ordering::Relaxed) {
+                // Not ErrorKind::Interrupted, read_exact would just retry
+                return Err(std::io::Error::other("Extraction cancelled"));
+            }
+            let finished = self.progress.finished.load(Ordering::Acquire);
+            let available = self.progress.written.load(Ordering::Acquire);
+            if self.position < available {
+                let wanted = buf.len().min((available - self.position) as usize);
+                let read = self.file.read(&mut buf[..wanted])?;
+                if read > 0 {
+                    self.position += read as u64;
+                    return Ok(read);
+                }
+                // Counted but not flushed to the file yet
+            } else if finished {
+                return Ok(0);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Extract entries from `archive_path` as they become available. `on_entry` gets each
+/// entry name and the archive offset reached. Blocking, run it on a blocking thread.
+pub fn extract_following(
+    archive_path: &Path,
+    destination: &Path,
+    source: &str,
+    expected_size: u64,
+    progress: Arc<StreamProgress>,
+    mut on_entry: impl FnMut(&str, u64),
+) -> Result<usize, StreamError> {
+    let journal_file = journal_path(archive_path);
+    let mut journal = load_journal(&journal_file, source, expected_size);
+    if !journal.completed.is_empty() {
+        println!("Resuming extraction of {:?} after {} entries", archive_path, journal.completed.len());
+    }
+
+    // The downloader may not have created the file yet
+    let file = loop {
+        match std::fs::File::open(archive_path) {
+            Ok(file) => break file,
+            Err(_) if !progress.finished.load(Ordering::Acquire) && !progress.cancelled.load(Ordering::Relaxed) => {
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) => return Err(StreamError::Failed(format!("Failed to open archive: {}", e))),
+        }
+    };
+    let mut reader = FollowingReader { file, position: 0, progress };
+    reader.file.seek(SeekFrom::Start(journal.next_offset)).map_err(|e| StreamError::Failed(e.to_string()))?;
+    reader.position = journal.next_offset;
+
+    let mut extracted = 0;
+    loop {
+        let mut header = [0u8; LOCAL_HEADER_LEN];
+        reader.read_exact(&mut header).map_err(|e| StreamError::Failed(format!("Failed to read archive: {}", e)))?;
+        let signature = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        if signature != LOCAL_HEADER_SIGNATURE {
+            // The central directory follows the last entry
+            break;
+        }
+        let flags = u16::from_le_bytes([header[6], header[7]]);
+        if flags & DATA_DESCRIPTOR_FLAG != 0 {
+            return Err(StreamError::Unsupported("archive entries have no sizes in their local headers".to_string()));
+        }
+
+        let mut chained = std::io::Cursor::new(header).chain(&mut reader);
+        let mut entry = match zip::read::read_zipfile_from_stream(&mut chained) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => break,
+            Err(e) => return Err(StreamError::Unsupported(format!("Failed to read archive entry: {}", e))),
+        };
+        let name = entry.name().to_string();
+        let relative = entry.enclosed_name()
+            .ok_or_else(|| StreamError::Failed(format!("Archive entry {} points outside the destination", name)))?;
+        let out_path = destination.join(relative);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&out_path).map_err(|e| StreamError::Failed(format!("Failed to create directory: {}", e)))?;
+        } else {
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| StreamError::Failed(format!("Failed to create parent directory: {}", e)))?;
+            }
+            // Unpack next to the target so a crash never leaves a truncated file behind
+            let temp = crate::downloader::partial_path(&out_path);
+            let mut out = std::fs::File::create(&temp).map_err(|e| StreamError::Failed(format!("Failed to create output file: {}", e)))?;
+            std::io::copy(&mut entry, &mut out).map_err(|e| StreamError::Failed(format!("Failed to extract {}: {}", name, e)))?;
+            drop(out);
+            crate::downloader::restore_unix_mode(&temp, entry.unix_mode());
+            std::fs::rename(&temp, &out_path).map_err(|e| StreamError::Failed(format!("Failed to finalize {}: {}", name, e)))?;
+        }
+        // Dropping the entry skips any unread data, leaving the reader at the next header
+        drop(entry);
+
+        extracted += 1;
+        journal.next_offset = reader.position;
+        journal.completed.push(name.clone());
+        save_journal(&journal_file, &journal);
+        on_entry(&name, reader.position);
+    }
+
+    let _ = std::fs::remove_file(&journal_file);
+    Ok(extracted)
+}
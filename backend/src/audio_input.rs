@@ -0,0 +1,52 @@
+// Audio attachments for chats with audio-capable models. llama-server takes audio as an
+// OpenAI `input_audio` content part with base64 WAV or MP3 data, so a picked file is
+// checked and encoded here and handed back ready to go into the next message.
+
+use std::path::Path;
+
+use base64::Engine;
+use serde::Serialize;
+
+// Well past a few minutes of speech, larger clips only bloat the request
+const MAX_AUDIO_BYTES: u64 = 25 * 1024 * 1024;
+
+/// An audio file staged for a chat message
+#[derive(Debug, Clone, Serialize)]
+pub struct StagedAudio {
+    pub file_name: String,
+    pub format: String, // "wav" or "mp3", as `input_audio.format` expects
+    pub size_bytes: u64,
+    pub data: String, // Base64 of the file
+}
+
+/// Formats llama-server decodes, told apart by their leading bytes rather than the extension
+fn detect_format(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WAVE" {
+        Some("wav")
+    } else if bytes.starts_with(b"ID3") || (bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] & 0xE0 == 0xE0) {
+        Some("mp3")
+    } else {
+        None
+    }
+}
+
+pub async fn stage(path: &str) -> Result<StagedAudio, String> {
+    let path = Path::new(path);
+    let size_bytes = tokio::fs::metadata(path).await.map_err(|e| e.to_string())?.len();
+    if size_bytes > MAX_AUDIO_BYTES {
+        return Err(format!(
+            "Audio file is {} MB, the limit is {} MB",
+            size_bytes / (1024 * 1024),
+            MAX_AUDIO_BYTES / (1024 * 1024)
+        ));
+    }
+    let bytes = tokio::fs::read(path).await.map_err(|e| e.to_string())?;
+    let format = detect_format(&bytes).ok_or("Unsupported audio format, use WAV or MP3")?;
+
+    Ok(StagedAudio {
+        file_name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        format: format.to_string(),
+        size_bytes,
+        data: base64::engine::general_purpose::STANDARD.encode(&bytes),
+    })
+}
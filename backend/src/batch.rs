@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+use tokio::fs;
+
+use crate::config::save_settings;
+use crate::models::*;
+use crate::AppState;
+
+// Deleted files are parked here until the whole batch has succeeded
+pub const TRASH_DIR_PREFIX: &str = ".llama-os-trash-";
+
+fn emit_progress(app_handle: &tauri::AppHandle, operation: &str, completed: usize, total: usize, current: &str) {
+    let _ = app_handle.emit("batch-operation-progress", BatchProgress {
+        operation: operation.to_string(),
+        completed,
+        total,
+        current: current.to_string(),
+    });
+}
+
+/// Check every path before touching anything, so a batch either starts fully valid or not at all
+async fn validate_paths(paths: &[String], state: &AppState) -> Result<PathBuf, String> {
+    let (models_dir, extra_directories) = {
+        let config = state.config.lock().await;
+        (PathBuf::from(&config.models_directory), config.extra_model_directories.clone())
+    };
+    
+    if paths.is_empty() {
+        return Err("No models selected".to_string());
+    }
+    
+    let mut seen = HashSet::new();
+    for path in paths {
+        let file = Path::new(path);
+        if !seen.insert(path) {
+            return Err(format!("{} is listed more than once", path));
+        }
+        if crate::scanner::in_extra_directory(file, &extra_directories) {
+            return Err(format!("{} is in a read-only extra model directory", path));
+        }
+        if !file.starts_with(&models_dir) {
+            return Err(format!("{} is outside of the models directory", path));
+        }
+        if !path.to_lowercase().ends_with(".gguf") {
+            return Err(format!("{} is not a .gguf file", path));
+        }
+        if !file.is_file() {
+            return Err(format!("{} does not exist", path));
+        }
+        if let Some((_, message)) = crate::model_in_use(path, state).await {
+            return Err(message);
+        }
+    }
+    
+    Ok(models_dir)
+}
+
+// Rename with a copy fallback for moves across filesystems
+async fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to).await?;
+    fs::remove_file(from).await
+}
+
+// Put files back where they came from after a failed batch
+async fn undo_moves(moved: &[(PathBuf, PathBuf)]) {
+    for (original, current) in moved.iter().rev() {
+        if let Err(e) = move_file(current, original).await {
+            eprintln!("Failed to restore {:?} to {:?}: {}", current, original, e);
+        }
+    }
+}
+
+pub async fn delete_models(paths: Vec<String>, state: &AppState, app_handle: &tauri::AppHandle) -> Result<BatchResult, String> {
+    let models_dir = validate_paths(&paths, state).await?;
+    let total = paths.len();
+    
+    let trash_dir = models_dir.join(format!("{}{}", TRASH_DIR_PREFIX, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&trash_dir).await
+        .map_err(|e| format!("Failed to prepare deletion: {}", e))?;
+    
+    let mut moved = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        emit_progress(app_handle, "delete", index, total, path);
+        let original = PathBuf::from(path);
+        let parked = trash_dir.join(format!("{}-{}", index, original.file_name().unwrap_or_default().to_string_lossy()));
+        if let Err(e) = move_file(&original, &parked).await {
+            undo_moves(&moved).await;
+            let _ = fs::remove_dir_all(&trash_dir).await;
+            return Err(format!("Failed to delete {}: {}", path, e));
+        }
+        moved.push((original, parked));
+    }
+    
+    {
+        let mut model_configs = state.library.configs.lock().await;
+        let mut history = state.library.history.lock().await;
+        for path in &paths {
+            model_configs.remove(path);
+            history.remove(path);
+        }
+    }
+    
+    let saved = save_settings(state).await.map_err(|e| e.to_string());
+    if let Err(e) = saved {
+        undo_moves(&moved).await;
+        let _ = fs::remove_dir_all(&trash_dir).await;
+        return Err(format!("Failed to save settings: {}", e));
+    }
+    
+    if let Err(e) = fs::remove_dir_all(&trash_dir).await {
+        eprintln!("Failed to clean up {:?}: {}", trash_dir, e);
+    }
+    emit_progress(app_handle, "delete", total, total, "");
+    
+    Ok(BatchResult { processed: total, paths })
+}
+
+pub async fn move_models(paths: Vec<String>, destination: String, state: &AppState, app_handle: &tauri::AppHandle) -> Result<BatchResult, String> {
+    let models_dir = validate_paths(&paths, state).await?;
+    let total = paths.len();
+    
+    let destination = PathBuf::from(&destination);
+    if !destination.starts_with(&models_dir) || crate::scanner::in_extra_directory(&destination, &state.config.lock().await.extra_model_directories) {
+        return Err("Destination must be inside the models directory".to_string());
+    }
+    fs::create_dir_all(&destination).await
+        .map_err(|e| format!("Failed to create destination folder: {}", e))?;
+    
+    // Work out every target first so name clashes fail the batch before anything moves
+    let mut targets = Vec::new();
+    let mut target_set = HashSet::new();
+    for path in &paths {
+        let source = PathBuf::from(path);
+        let target = destination.join(source.file_name().unwrap_or_default());
+        if target.exists() || !target_set.insert(target.clone()) {
+            return Err(format!("{} already exists", target.display()));
+        }
+        targets.push((source, target));
+    }
+    
+    let mut moved = Vec::new();
+    for (index, (source, target)) in targets.iter().enumerate() {
+        emit_progress(app_handle, "move", index, total, &source.to_string_lossy());
+        if let Err(e) = move_file(source, target).await {
+            undo_moves(&moved).await;
+            return Err(format!("Failed to move {}: {}", source.display(), e));
+        }
+        moved.push((source.clone(), target.clone()));
+    }
+    
+    // Settings follow the files to their new paths
+    let new_paths: Vec<String> = targets.iter().map(|(_, t)| t.to_string_lossy().to_string()).collect();
+    {
+        let mut model_configs = state.library.configs.lock().await;
+        let mut history = state.library.history.lock().await;
+        for (old_path, new_path) in paths.iter().zip(&new_paths) {
+            if let Some(mut config) = model_configs.remove(old_path) {
+                config.model_path = new_path.clone();
+                model_configs.insert(new_path.clone(), config);
+            }
+            if let Some(mut entries) = history.remove(old_path) {
+                for snapshot in entries.iter_mut() {
+                    snapshot.config.model_path = new_path.clone();
+                }
+                history.insert(new_path.clone(), entries);
+            }
+        }
+    }
+    
+    if let Err(e) = save_settings(state).await {
+        return Err(format!("Models were moved but settings could not be saved: {}", e));
+    }
+    emit_progress(app_handle, "move", total, total, "");
+    
+    Ok(BatchResult { processed: total, paths: new_paths })
+}
+
+pub async fn apply_preset_bulk(paths: Vec<String>, preset: LaunchOverrides, state: &AppState, app_handle: &tauri::AppHandle) -> Result<BatchResult, String> {
+    validate_paths(&paths, state).await?;
+    let total = paths.len();
+    
+    let changes = {
+        let mut model_configs = state.library.configs.lock().await;
+        let mut changes = Vec::new();
+        for (index, path) in paths.iter().enumerate() {
+            emit_progress(app_handle, "apply_preset", index, total, path);
+            let previous = model_configs.get(path).cloned();
+            let mut config = previous.clone().unwrap_or_else(|| ModelConfig::new(path.clone()));
+            preset.clone().apply_to(&mut config);
+            model_configs.insert(path.clone(), config.clone());
+            changes.push((previous, config));
+        }
+        changes
+    };
+    
+    for (previous, config) in changes {
+        state.library.record_history(previous, &config).await;
+    }
+    
+    save_settings(state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    emit_progress(app_handle, "apply_preset", total, total, "");
+    
+    Ok(BatchResult { processed: total, paths })
+}
@@ -0,0 +1,389 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::process::Command as TokioCommand;
+use crate::config::get_app_data_path;
+use crate::gpu_telemetry::{GpuRunTelemetry, TelemetrySampler};
+
+const BENCHMARKS_FILE: &str = "benchmarks.json";
+
+// A tokens/sec drop larger than this (in percent) is reported as a regression
+const REGRESSION_THRESHOLD_PERCENT: f64 = 10.0;
+
+// Short run used when checking a llama.cpp update, enough to spot large regressions
+const QUICK_BENCH_ARGS: [&str; 6] = ["-p", "256", "-n", "64", "-r", "2"];
+
+/// One llama-bench test (either prompt processing or generation)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub n_prompt: u64,
+    pub n_gen: u64,
+    pub n_gpu_layers: i64,
+    pub avg_ts: f64,
+    pub stddev_ts: f64,
+    pub backends: Option<String>,
+    pub build_commit: Option<String>,
+    #[serde(default)]
+    pub n_batch: Option<u64>,
+    #[serde(default)]
+    pub n_ubatch: Option<u64>,
+    #[serde(default)]
+    pub n_threads: Option<u64>,
+    #[serde(default)]
+    pub flash_attn: Option<bool>,
+    #[serde(default)]
+    pub model_type: Option<String>, // e.g. "llama 8B Q4_K - Medium", tells quants apart
+    #[serde(default)]
+    pub model_size: Option<u64>,
+}
+
+/// What a `run_benchmark` call measures. Each list is one llama-bench parameter, every
+/// combination is tested; an empty list keeps llama-bench's default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BenchOptions {
+    #[serde(default)]
+    pub prompt_tokens: Vec<u32>,
+    #[serde(default)]
+    pub gen_tokens: Vec<u32>,
+    #[serde(default)]
+    pub gpu_layers: Vec<u32>,
+    #[serde(default)]
+    pub batch_sizes: Vec<u32>,
+    #[serde(default)]
+    pub threads: Vec<u32>,
+    #[serde(default)]
+    pub flash_attention: Vec<bool>,
+    #[serde(default)]
+    pub repetitions: Option<u32>,
+}
+
+impl BenchOptions {
+    fn to_args(&self) -> Vec<String> {
+        fn list<T: ToString>(values: impl Iterator<Item = T>) -> String {
+            values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+        }
+        let mut args = Vec::new();
+        for (flag, values) in [
+            ("-p", list(self.prompt_tokens.iter())),
+            ("-n", list(self.gen_tokens.iter())),
+            ("-ngl", list(self.gpu_layers.iter())),
+            ("-b", list(self.batch_sizes.iter())),
+            ("-t", list(self.threads.iter())),
+            ("-fa", list(self.flash_attention.iter().map(|&on| on as u8))),
+        ] {
+            if !values.is_empty() {
+                args.extend([flag.to_string(), values]);
+            }
+        }
+        if let Some(repetitions) = self.repetitions {
+            args.extend(["-r".to_string(), repetitions.to_string()]);
+        }
+        args
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    pub model_path: String,
+    pub version: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub results: Vec<BenchResult>,
+    #[serde(default)]
+    pub gpu: Vec<GpuRunTelemetry>, // Clocks, temperatures and throttling sampled during the run
+    #[serde(default)]
+    pub options: Option<BenchOptions>, // None for the quick runs of a version regression check
+}
+
+impl BenchmarkRecord {
+    /// Average prompt processing speed across all pp tests
+    pub fn prompt_tps(&self) -> Option<f64> {
+        average(self.results.iter().filter(|r| r.n_prompt > 0 && r.n_gen == 0).map(|r| r.avg_ts))
+    }
+    
+    /// Average generation speed across all tg tests
+    pub fn generation_tps(&self) -> Option<f64> {
+        average(self.results.iter().filter(|r| r.n_gen > 0 && r.n_prompt == 0).map(|r| r.avg_ts))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct BenchmarkStore {
+    records: Vec<BenchmarkRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionReport {
+    pub model_path: String,
+    pub version: String,
+    pub baseline_version: Option<String>,
+    pub prompt_tps: Option<f64>,
+    pub generation_tps: Option<f64>,
+    pub baseline_prompt_tps: Option<f64>,
+    pub baseline_generation_tps: Option<f64>,
+    pub prompt_change_percent: Option<f64>,
+    pub generation_change_percent: Option<f64>,
+    pub regression: bool,
+    pub message: String,
+    #[serde(default)]
+    pub gpu: Vec<GpuRunTelemetry>,
+    #[serde(default)]
+    pub throttling: Option<String>, // Set when the GPU throttled during the candidate run
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(s, c), v| (s + v, c + 1));
+    if count > 0 { Some(sum / count as f64) } else { None }
+}
+
+fn change_percent(current: Option<f64>, baseline: Option<f64>) -> Option<f64> {
+    match (current, baseline) {
+        (Some(current), Some(baseline)) if baseline > 0.0 => Some((current - baseline) / baseline * 100.0),
+        _ => None,
+    }
+}
+
+/// Path of a llama.cpp tool binary inside a version folder
+pub fn tool_path(version_dir: &Path, tool: &str) -> PathBuf {
+    if cfg!(windows) {
+        version_dir.join(format!("{}.exe", tool))
+    } else {
+        version_dir.join(tool)
+    }
+}
+
+/// Run llama-bench from a version folder and parse its JSON output, sampling the GPUs
+/// while it runs
+pub async fn run_llama_bench(
+    version_dir: &Path,
+    model_path: &str,
+    extra_args: &[String],
+) -> Result<(Vec<BenchResult>, Vec<GpuRunTelemetry>), Box<dyn std::error::Error + Send + Sync>> {
+    let bench_path = tool_path(version_dir, "llama-bench");
+    if !bench_path.exists() {
+        return Err(format!("llama-bench not found at: {:?}", bench_path).into());
+    }
+    
+    let mut cmd = TokioCommand::new(&bench_path);
+    cmd.args(["-m", model_path])
+       .args(extra_args)
+       .args(["-o", "json"])
+       .kill_on_drop(true);
+    
+    // Hide console window on Windows release builds
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    
+    println!("Running benchmark: {:?} on {}", bench_path, model_path);
+    let sampler = TelemetrySampler::start();
+    let output = cmd.output().await;
+    let gpu = sampler.finish().await;
+    let output = output?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let last_line = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("");
+        return Err(format!("llama-bench exited with {}: {}", output.status, last_line).into());
+    }
+    
+    let results = parse_bench_json(&String::from_utf8_lossy(&output.stdout))?;
+    if let Some(note) = crate::gpu_telemetry::throttling_note(&gpu) {
+        println!("Benchmark of {}: {}", model_path, note);
+    }
+    Ok((results, gpu))
+}
+
+fn parse_bench_json(stdout: &str) -> Result<Vec<BenchResult>, Box<dyn std::error::Error + Send + Sync>> {
+    // llama-bench may print log lines before the JSON array
+    let start = stdout.find('[').ok_or("No JSON output from llama-bench")?;
+    let entries: Vec<serde_json::Value> = serde_json::from_str(stdout[start..].trim())?;
+    
+    Ok(entries
+        .iter()
+        .map(|entry| BenchResult {
+            n_prompt: entry["n_prompt"].as_u64().unwrap_or(0),
+            n_gen: entry["n_gen"].as_u64().unwrap_or(0),
+            n_gpu_layers: entry["n_gpu_layers"].as_i64().unwrap_or(0),
+            avg_ts: entry["avg_ts"].as_f64().unwrap_or(0.0),
+            stddev_ts: entry["stddev_ts"].as_f64().unwrap_or(0.0),
+            backends: entry["backends"].as_str().map(|s| s.to_string()),
+            build_commit: entry["build_commit"].as_str().map(|s| s.to_string()),
+            n_batch: entry["n_batch"].as_u64(),
+            n_ubatch: entry["n_ubatch"].as_u64(),
+            n_threads: entry["n_threads"].as_u64(),
+            // Older builds write a bool, newer ones the 0/1 flag value
+            flash_attn: entry["flash_attn"].as_bool().or_else(|| entry["flash_attn"].as_i64().map(|v| v != 0)),
+            model_type: entry["model_type"].as_str().map(|s| s.to_string()),
+            model_size: entry["model_size"].as_u64(),
+        })
+        .collect())
+}
+
+async fn load_store() -> BenchmarkStore {
+    let Ok(path) = get_app_data_path(BENCHMARKS_FILE).await else {
+        return BenchmarkStore::default();
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        Err(_) => BenchmarkStore::default(),
+    }
+}
+
+async fn save_store(store: &BenchmarkStore) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = get_app_data_path(BENCHMARKS_FILE).await.map_err(|e| e.to_string())?;
+    let contents = serde_json::to_string_pretty(store)?;
+    tokio::fs::write(&path, contents).await?;
+    Ok(())
+}
+
+pub async fn store_record(record: BenchmarkRecord) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut store = load_store().await;
+    store.records.push(record);
+    save_store(&store).await
+}
+
+/// Most recent quick-benchmark record for a model on a given llama.cpp version. Runs with
+/// custom options measure something else and can't serve as a baseline
+pub async fn latest_record(model_path: &str, version: &str) -> Option<BenchmarkRecord> {
+    let store = load_store().await;
+    store.records
+        .into_iter()
+        .filter(|r| r.model_path == model_path && r.version == version && r.options.is_none())
+        .max_by_key(|r| r.timestamp)
+}
+
+/// Benchmark a model with the given options and keep the result in its history
+pub async fn run_benchmark(
+    model_path: &str,
+    version_dir: &Path,
+    options: BenchOptions,
+) -> Result<BenchmarkRecord, Box<dyn std::error::Error + Send + Sync>> {
+    if options.repetitions == Some(0) {
+        return Err("Repetitions must be at least 1".into());
+    }
+    let (results, gpu) = run_llama_bench(version_dir, model_path, &options.to_args()).await?;
+    let record = BenchmarkRecord {
+        model_path: model_path.to_string(),
+        version: version_name(version_dir),
+        timestamp: chrono::Utc::now(),
+        results,
+        gpu,
+        options: Some(options),
+    };
+    store_record(record.clone()).await?;
+    Ok(record)
+}
+
+/// Stored runs, newest first, for one model or all of them
+pub async fn history(model_path: Option<&str>) -> Vec<BenchmarkRecord> {
+    let mut records: Vec<BenchmarkRecord> = load_store().await.records
+        .into_iter()
+        .filter(|r| model_path.is_none_or(|path| r.model_path == path))
+        .collect();
+    records.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
+    records
+}
+
+/// Run a quick benchmark with a candidate version and compare it with the baseline version.
+/// The baseline is benchmarked first if no stored result exists for it.
+pub async fn check_regression(
+    model_path: &str,
+    candidate_dir: &Path,
+    baseline_dir: Option<&Path>,
+) -> Result<RegressionReport, Box<dyn std::error::Error + Send + Sync>> {
+    let quick_args: Vec<String> = QUICK_BENCH_ARGS.iter().map(|s| s.to_string()).collect();
+    
+    let baseline = match baseline_dir {
+        Some(dir) => {
+            let version = version_name(dir);
+            match latest_record(model_path, &version).await {
+                Some(record) => Some(record),
+                None => {
+                    let (results, gpu) = run_llama_bench(dir, model_path, &quick_args).await?;
+                    let record = BenchmarkRecord {
+                        model_path: model_path.to_string(),
+                        version,
+                        timestamp: chrono::Utc::now(),
+                        results,
+                        gpu,
+                        options: None,
+                    };
+                    store_record(record.clone()).await?;
+                    Some(record)
+                }
+            }
+        }
+        None => None,
+    };
+    
+    let (results, gpu) = run_llama_bench(candidate_dir, model_path, &quick_args).await?;
+    let record = BenchmarkRecord {
+        model_path: model_path.to_string(),
+        version: version_name(candidate_dir),
+        timestamp: chrono::Utc::now(),
+        results,
+        gpu,
+        options: None,
+    };
+    store_record(record.clone()).await?;
+    
+    Ok(build_report(&record, baseline.as_ref()))
+}
+
+fn build_report(record: &BenchmarkRecord, baseline: Option<&BenchmarkRecord>) -> RegressionReport {
+    let prompt_tps = record.prompt_tps();
+    let generation_tps = record.generation_tps();
+    let baseline_prompt_tps = baseline.and_then(|b| b.prompt_tps());
+    let baseline_generation_tps = baseline.and_then(|b| b.generation_tps());
+    let prompt_change_percent = change_percent(prompt_tps, baseline_prompt_tps);
+    let generation_change_percent = change_percent(generation_tps, baseline_generation_tps);
+    
+    let regression = [prompt_change_percent, generation_change_percent]
+        .iter()
+        .flatten()
+        .any(|change| *change < -REGRESSION_THRESHOLD_PERCENT);
+    
+    let throttling = crate::gpu_telemetry::throttling_note(&record.gpu);
+    let message = match baseline {
+        None => "No baseline available, stored this run as the new baseline".to_string(),
+        Some(b) if regression => format!(
+            "Performance regression compared to {} (prompt {:+.1}%, generation {:+.1}%)",
+            b.version,
+            prompt_change_percent.unwrap_or(0.0),
+            generation_change_percent.unwrap_or(0.0)
+        ),
+        Some(b) => format!(
+            "No significant regression compared to {} (prompt {:+.1}%, generation {:+.1}%)",
+            b.version,
+            prompt_change_percent.unwrap_or(0.0),
+            generation_change_percent.unwrap_or(0.0)
+        ),
+    };
+    // A throttled run explains a drop better than the build does
+    let message = match (&throttling, regression) {
+        (Some(note), true) => format!("{}. The GPU was throttled during the run and may account for it: {}", message, note),
+        _ => message,
+    };
+    
+    RegressionReport {
+        model_path: record.model_path.clone(),
+        version: record.version.clone(),
+        baseline_version: baseline.map(|b| b.version.clone()),
+        prompt_tps,
+        generation_tps,
+        baseline_prompt_tps,
+        baseline_generation_tps,
+        prompt_change_percent,
+        generation_change_percent,
+        regression,
+        message,
+        gpu: record.gpu.clone(),
+        throttling,
+    }
+}
+
+fn version_name(version_dir: &Path) -> String {
+    version_dir
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How long to wait for a freshly launched server to finish loading before giving up
+const STARTUP_PROBE_ATTEMPTS: u32 = 120;
+const STARTUP_PROBE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Features a running llama-server supports, so the UI can adapt to the llama.cpp build
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    pub version: Option<String>,
+    pub build_info: Option<String>,
+    pub total_slots: Option<u64>,
+    pub context_size: Option<u64>,
+    pub has_chat_template: bool,
+    pub jinja: bool,
+    pub reranking: bool,
+    pub embeddings: bool,
+    pub slots_endpoint: bool,
+    pub vision: bool,
+    pub audio: bool,
+    pub probed_at: chrono::DateTime<chrono::Utc>,
+}
+
+fn has_flag(args: &[String], flags: &[&str]) -> bool {
+    args.iter().any(|a| flags.contains(&a.as_str()))
+}
+
+/// Query `/props`, `/version` and `/slots` of a server. Flags that the endpoints don't
+/// report (jinja, reranking, embeddings) are taken from the launch arguments.
+pub async fn probe(base: &str, launch_args: &[String]) -> Result<ServerCapabilities, String> {
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    // /props answers 503 while the model is still loading
+    let response = client.get(format!("{}/props", base)).send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("Server not ready: {}", response.status()));
+    }
+    let props: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+
+    let version = match client.get(format!("{}/version", base)).send().await {
+        Ok(r) if r.status().is_success() => r
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|v| v.get("version").and_then(|v| v.as_str()).map(|s| s.to_string())),
+        _ => None,
+    };
+
+    // /slots is disabled with --no-slots and then answers 501
+    let slots_endpoint = matches!(client.get(format!("{}/slots", base)).send().await, Ok(r) if r.status().is_success());
+
+    let modality = |name: &str| props.get("modalities").and_then(|m| m.get(name)).and_then(|v| v.as_bool()).unwrap_or(false);
+
+    Ok(ServerCapabilities {
+        version,
+        build_info: props.get("build_info").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        total_slots: props.get("total_slots").and_then(|v| v.as_u64()),
+        context_size: props.get("default_generation_settings").and_then(|s| s.get("n_ctx")).and_then(|v| v.as_u64()),
+        has_chat_template: props.get("chat_template").and_then(|v| v.as_str()).map(|s| !s.is_empty()).unwrap_or(false),
+        jinja: has_flag(launch_args, &["--jinja"]) || props.get("chat_template_caps").is_some(),
+        reranking: has_flag(launch_args, &["--reranking", "--rerank"]),
+        embeddings: has_flag(launch_args, &["--embedding", "--embeddings"]),
+        slots_endpoint,
+        vision: modality("vision"),
+        audio: modality("audio"),
+        probed_at: chrono::Utc::now(),
+    })
+}
+
+/// Number of generations a server is working on, from `/slots` or the Prometheus `/metrics`.
+/// None when neither endpoint is available (disabled, or the server is still loading).
+pub async fn requests_in_flight(base: &str) -> Option<u64> {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(2)).build().ok()?;
+
+    if let Ok(response) = client.get(format!("{}/slots", base)).send().await {
+        if response.status().is_success() {
+            if let Ok(slots) = response.json::<Vec<serde_json::Value>>().await {
+                return Some(slots.iter().filter(|s| s.get("is_processing").and_then(|v| v.as_bool()).unwrap_or(false)).count() as u64);
+            }
+        }
+    }
+
+    let response = client.get(format!("{}/metrics", base)).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let text = response.text().await.ok()?;
+    text.lines()
+        .find(|l| l.starts_with("llamacpp:requests_processing"))
+        .and_then(|l| l.split_whitespace().last())
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|v| v as u64)
+}
+
+/// Probe a process and store the result in the capability cache
+pub async fn refresh(process_id: &str, state: &AppState) -> Result<ServerCapabilities, String> {
+    let process = state.processes.get(process_id).await.ok_or("Process not found")?;
+    // The first entry is the executable
+    let args = process.command.iter().skip(1).cloned().collect::<Vec<_>>();
+
+    let capabilities = probe(&process.client_url(), &args).await?;
+    state.processes.set_capabilities(process_id, capabilities.clone()).await;
+    Ok(capabilities)
+}
+
+/// Wait for a new server to come up and cache its capabilities in the background
+pub fn spawn_startup_probe(process_id: String, state: AppState) {
+    tokio::spawn(async move {
+        for _ in 0..STARTUP_PROBE_ATTEMPTS {
+            tokio::time::sleep(STARTUP_PROBE_INTERVAL).await;
+
+            let alive = state.processes.get(&process_id).await
+                .is_some_and(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running));
+            if !alive {
+                return;
+            }
+
+            if refresh(&process_id, &state).await.is_ok() {
+                println!("Cached server capabilities for {}", process_id);
+                return;
+            }
+        }
+    });
+}
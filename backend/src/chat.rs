@@ -0,0 +1,172 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::models::{client_host, ChatMessage, ChatState, ProcessStatus};
+use crate::AppState;
+
+const TITLE_PROMPT: &str = "Summarize the conversation above as a short title of at most 6 words. \
+Reply with the title only, without quotes or punctuation at the end.";
+const TITLE_MAX_CHARS: usize = 60;
+const TITLE_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Chats with a title request in flight, so repeated saves don't queue duplicate requests
+static PENDING_TITLES: LazyLock<std::sync::Mutex<HashSet<String>>> = LazyLock::new(|| std::sync::Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatTitleUpdate {
+    pub chat_id: String,
+    pub title: String,
+}
+
+/// Sent with `open-chat-request` so the frontend opens a window for a chat started elsewhere
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenChatRequest {
+    pub chat_id: String,
+    pub process_id: String,
+    pub model_name: String,
+    pub model_path: String,
+    pub host: String,
+    pub port: u16,
+    pub initial_prompt: Option<String>,
+    pub launched: bool, // The server was started for this chat and has no terminal yet
+}
+
+/// Make sure a server is running for the model, register a chat session for it and ask
+/// the frontend to open it. Used by the CLI, deep links and tray actions.
+pub async fn open_chat_for_model(
+    model_path: String,
+    initial_prompt: Option<String>,
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+) -> Result<OpenChatRequest, String> {
+    let running = state.processes.list().await
+        .into_iter()
+        .find(|p| p.model_path == model_path && !p.interactive && matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running));
+    let (process_id, model_name, host, port, launched) = match running {
+        Some(process) => (process.id, process.model_name, process.host, process.port, false),
+        None => {
+            let result = crate::process::launch_model_server(model_path.clone(), state)
+                .await
+                .map_err(|e| e.to_string())?;
+            (result.process_id, result.model_name, result.server_host, result.server_port, true)
+        }
+    };
+    let host = client_host(&host).to_string();
+
+    let chat_id = format!("chat_{}", uuid::Uuid::new_v4());
+    let chat = ChatState {
+        model_name: model_name.clone(),
+        host: host.clone(),
+        port,
+        messages: Vec::new(),
+        title: None,
+    };
+    state.session_state.lock().await.chats.insert(chat_id.clone(), chat);
+
+    let request = OpenChatRequest {
+        chat_id,
+        process_id,
+        model_name,
+        model_path,
+        host,
+        port,
+        initial_prompt: initial_prompt.filter(|p| !p.trim().is_empty()),
+        launched,
+    };
+    let _ = app_handle.emit("open-chat-request", &request);
+    Ok(request)
+}
+
+/// A chat is ready for a title once it has its first user message and a reply
+pub fn needs_title(chat: &ChatState) -> bool {
+    chat.title.is_none()
+        && chat.messages.iter().any(|m| m.role == "user")
+        && chat.messages.iter().any(|m| m.role == "assistant" && !m.content.trim().is_empty())
+}
+
+/// Ask the serving model for a short title summarizing the first exchange
+pub async fn generate_title(host: &str, port: u16, messages: &[ChatMessage]) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let mut prompt_messages: Vec<serde_json::Value> = messages
+        .iter()
+        .filter(|m| m.role == "user" || m.role == "assistant")
+        .take(2)
+        .map(|m| serde_json::json!({ "role": m.role, "content": m.content }))
+        .collect();
+    prompt_messages.push(serde_json::json!({ "role": "user", "content": TITLE_PROMPT }));
+
+    let client = reqwest::Client::builder().timeout(TITLE_REQUEST_TIMEOUT).build()?;
+    let response = client
+        .post(format!("http://{}:{}/v1/chat/completions", host, port))
+        .json(&serde_json::json!({
+            "messages": prompt_messages,
+            "stream": false,
+            "temperature": 0.2,
+            "max_tokens": 32,
+        }))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(format!("Server returned status {}", response.status()).into());
+    }
+
+    let body: serde_json::Value = response.json().await?;
+    let content = body["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or("Response did not contain a message")?;
+
+    let title = clean_title(content);
+    if title.is_empty() {
+        return Err("Model returned an empty title".into());
+    }
+    Ok(title)
+}
+
+// Strip reasoning blocks, quotes and trailing punctuation models tend to add
+fn clean_title(raw: &str) -> String {
+    let text = match raw.rfind("</think>") {
+        Some(pos) => &raw[pos + "</think>".len()..],
+        None => raw,
+    };
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    let line = line.trim_start_matches("Title:").trim();
+    let line = line.trim_matches(|c: char| c == '"' || c == '\'' || c == '*' || c == '`');
+    let line = line.trim_end_matches(['.', '!', '?', ':']).trim();
+    line.chars().take(TITLE_MAX_CHARS).collect()
+}
+
+/// Generate a title in the background, store it on the chat and notify the frontend
+pub fn spawn_title_generation(chat_id: String, chat: ChatState, state: AppState, app_handle: tauri::AppHandle) {
+    if !PENDING_TITLES.lock().unwrap().insert(chat_id.clone()) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let result = generate_title(&chat.host, chat.port, &chat.messages)
+            .await
+            .map_err(|e| e.to_string());
+        PENDING_TITLES.lock().unwrap().remove(&chat_id);
+        match result {
+            Ok(title) => {
+                let updated = {
+                    let mut session = state.session_state.lock().await;
+                    match session.chats.get_mut(&chat_id) {
+                        Some(stored) if stored.title.is_none() => {
+                            stored.title = Some(title.clone());
+                            stored.clone()
+                        }
+                        _ => return,
+                    }
+                };
+                if let Err(e) = crate::chat_store::store_chat(chat_id.clone(), updated).await {
+                    eprintln!("{}", e);
+                }
+                let _ = app_handle.emit("chat-title-updated", ChatTitleUpdate { chat_id, title });
+            }
+            Err(e) => eprintln!("Failed to generate title for chat {}: {}", chat_id, e),
+        }
+    });
+}
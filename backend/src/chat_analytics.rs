@@ -0,0 +1,241 @@
+// Per-request chat statistics for comparing local models. Chats talk to llama-server
+// directly, so the frontend reports each finished request; the backend attributes it
+// to the model serving that port and estimates its energy from GPU power samples.
+// The estimate is whole-GPU draw over the request window, so requests that overlap
+// with other GPU work (a second model, a game) are charged for it too.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+use crate::config::get_app_data_path;
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+const CHAT_ANALYTICS_FILE: &str = "chat_analytics.jsonl";
+const POWER_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+const POWER_HISTORY_SECS: i64 = 30 * 60;
+// Short requests may fall between two samples, use the closest one this near
+const POWER_SAMPLE_SLACK_SECS: i64 = 5;
+
+// (time, total draw of all NVIDIA GPUs in watts)
+type PowerSample = (DateTime<Utc>, f64);
+
+// Only sampled while a local model runs
+static POWER_SAMPLES: LazyLock<std::sync::Mutex<VecDeque<PowerSample>>> = LazyLock::new(|| std::sync::Mutex::new(VecDeque::new()));
+
+/// A finished chat request as reported by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatRequestSample {
+    pub host: String,
+    pub port: u16,
+    pub chat_name: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub time_to_first_token_ms: Option<u64>,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: u64,
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// One line of the analytics log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatRequestRecord {
+    pub model_name: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub time_to_first_token_ms: Option<u64>,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: u64,
+    pub energy_wh: Option<f64>,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelChatAnalytics {
+    pub model_name: String,
+    pub requests: usize,
+    pub cancelled: usize,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub avg_time_to_first_token_ms: Option<f64>,
+    pub p95_time_to_first_token_ms: Option<u64>,
+    pub avg_generation_tps: Option<f64>, // Completion tokens over time after the first token
+    pub energy_wh: Option<f64>,          // Sum over requests with a power estimate
+    pub wh_per_1k_tokens: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatAnalytics {
+    pub period: String,
+    pub from: Option<DateTime<Utc>>,
+    pub models: Vec<ModelChatAnalytics>,
+}
+
+/// Sample GPU power draw while local models are running
+pub async fn run_power_sampler(state: AppState) {
+    loop {
+        tokio::time::sleep(POWER_SAMPLE_INTERVAL).await;
+        let serving = state.processes.list().await
+            .iter()
+            .any(|p| p.remote_host.is_none() && matches!(p.status, ProcessStatus::Running));
+        if !serving {
+            continue;
+        }
+
+        let Ok(Some(watts)) = tokio::task::spawn_blocking(total_power_watts).await else {
+            continue;
+        };
+        let now = Utc::now();
+        let mut samples = POWER_SAMPLES.lock().unwrap();
+        samples.push_back((now, watts));
+        while samples.front().is_some_and(|(at, _)| (now - *at).num_seconds() > POWER_HISTORY_SECS) {
+            samples.pop_front();
+        }
+    }
+}
+
+fn total_power_watts() -> Option<f64> {
+    let nvml = nvml_wrapper::Nvml::init().ok()?;
+    let count = nvml.device_count().ok()?;
+    let milliwatts: u32 = (0..count)
+        .filter_map(|index| nvml.device_by_index(index).ok()?.power_usage().ok())
+        .sum();
+    (milliwatts > 0).then(|| milliwatts as f64 / 1000.0)
+}
+
+/// Average draw over the request window times its duration
+fn estimate_energy_wh(started_at: DateTime<Utc>, duration_ms: u64) -> Option<f64> {
+    let ended_at = started_at + chrono::Duration::milliseconds(duration_ms as i64);
+    let samples = POWER_SAMPLES.lock().unwrap();
+    let in_window: Vec<f64> = samples
+        .iter()
+        .filter(|(at, _)| *at >= started_at && *at <= ended_at)
+        .map(|(_, watts)| *watts)
+        .collect();
+    let watts = if in_window.is_empty() {
+        let slack = chrono::Duration::seconds(POWER_SAMPLE_SLACK_SECS);
+        samples
+            .iter()
+            .filter(|(at, _)| *at >= started_at - slack && *at <= ended_at + slack)
+            .min_by_key(|(at, _)| (*at - ended_at).num_milliseconds().abs())
+            .map(|(_, watts)| *watts)?
+    } else {
+        in_window.iter().sum::<f64>() / in_window.len() as f64
+    };
+    Some(watts * duration_ms as f64 / 3_600_000.0)
+}
+
+/// Attribute a finished request to the model serving its port and append it to the log
+pub async fn record_request(sample: ChatRequestSample, state: &AppState) -> Result<(), String> {
+    let model_name = state.processes.list().await
+        .into_iter()
+        .find(|p| p.port == sample.port && p.remote_host.is_none())
+        .map(|p| p.model_name)
+        .unwrap_or_else(|| sample.chat_name.clone());
+
+    let record = ChatRequestRecord {
+        model_name,
+        energy_wh: estimate_energy_wh(sample.started_at, sample.duration_ms),
+        started_at: sample.started_at,
+        duration_ms: sample.duration_ms,
+        time_to_first_token_ms: sample.time_to_first_token_ms,
+        prompt_tokens: sample.prompt_tokens,
+        completion_tokens: sample.completion_tokens,
+        cancelled: sample.cancelled,
+    };
+
+    let path = get_app_data_path(CHAT_ANALYTICS_FILE).await.map_err(|e| e.to_string())?;
+    let mut line = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .map_err(|e| e.to_string())?;
+    file.write_all(line.as_bytes()).await.map_err(|e| e.to_string())
+}
+
+async fn read_records() -> Result<Vec<ChatRequestRecord>, String> {
+    let path = get_app_data_path(CHAT_ANALYTICS_FILE).await.map_err(|e| e.to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| e.to_string())?;
+    // Skip lines that can't be parsed (e.g. a partial write after a crash)
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Aggregate the log per model for "day", "week", "month" or "all" (default "week")
+pub async fn get_analytics(model: Option<String>, period: Option<String>) -> Result<ChatAnalytics, String> {
+    let period = period.unwrap_or_else(|| "week".to_string());
+    let from = match period.as_str() {
+        "day" => Some(Utc::now() - chrono::Duration::days(1)),
+        "week" => Some(Utc::now() - chrono::Duration::weeks(1)),
+        "month" => Some(Utc::now() - chrono::Duration::days(30)),
+        "all" => None,
+        other => return Err(format!("Invalid period '{}', expected day, week, month or all", other)),
+    };
+
+    let mut by_model: HashMap<String, Vec<ChatRequestRecord>> = HashMap::new();
+    for record in read_records().await? {
+        if from.is_some_and(|from| record.started_at < from) {
+            continue;
+        }
+        if model.as_ref().is_some_and(|m| m != &record.model_name) {
+            continue;
+        }
+        by_model.entry(record.model_name.clone()).or_default().push(record);
+    }
+
+    let mut models: Vec<ModelChatAnalytics> = by_model.into_iter().map(|(name, records)| summarize(name, &records)).collect();
+    models.sort_by(|a, b| b.requests.cmp(&a.requests).then_with(|| a.model_name.cmp(&b.model_name)));
+    Ok(ChatAnalytics { period, from, models })
+}
+
+fn summarize(model_name: String, records: &[ChatRequestRecord]) -> ModelChatAnalytics {
+    let mut first_token: Vec<u64> = records.iter().filter_map(|r| r.time_to_first_token_ms).collect();
+    first_token.sort_unstable();
+    let avg_time_to_first_token_ms = (!first_token.is_empty())
+        .then(|| first_token.iter().sum::<u64>() as f64 / first_token.len() as f64);
+    let p95_time_to_first_token_ms = (!first_token.is_empty())
+        .then(|| first_token[((first_token.len() - 1) as f64 * 0.95).round() as usize]);
+
+    // Token-weighted, a long answer says more about throughput than a one-liner
+    let (generated, generating_ms) = records
+        .iter()
+        .filter(|r| r.completion_tokens > 0)
+        .map(|r| (r.completion_tokens, r.duration_ms.saturating_sub(r.time_to_first_token_ms.unwrap_or(0))))
+        .filter(|(_, ms)| *ms > 0)
+        .fold((0u64, 0u64), |(tokens, ms), (t, m)| (tokens + t, ms + m));
+    let avg_generation_tps = (generating_ms > 0).then(|| generated as f64 * 1000.0 / generating_ms as f64);
+
+    let completion_tokens: u64 = records.iter().map(|r| r.completion_tokens).sum();
+    let measured: Vec<&ChatRequestRecord> = records.iter().filter(|r| r.energy_wh.is_some()).collect();
+    let energy_wh = (!measured.is_empty()).then(|| measured.iter().filter_map(|r| r.energy_wh).sum::<f64>());
+    let measured_tokens: u64 = measured.iter().map(|r| r.completion_tokens).sum();
+    let wh_per_1k_tokens = energy_wh.filter(|_| measured_tokens > 0).map(|wh| wh * 1000.0 / measured_tokens as f64);
+
+    ModelChatAnalytics {
+        model_name,
+        requests: records.len(),
+        cancelled: records.iter().filter(|r| r.cancelled).count(),
+        prompt_tokens: records.iter().filter_map(|r| r.prompt_tokens).sum(),
+        completion_tokens,
+        avg_time_to_first_token_ms,
+        p95_time_to_first_token_ms,
+        avg_generation_tps,
+        energy_wh,
+        wh_per_1k_tokens,
+    }
+}
@@ -0,0 +1,302 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::config::get_app_data_path;
+use crate::encryption;
+use crate::models::ChatState;
+
+const CHAT_DB_FILE: &str = "chats.db";
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatSearchFilters {
+    pub model_name: Option<String>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatSearchHit {
+    pub chat_id: String,
+    pub title: Option<String>,
+    pub model_name: String,
+    pub role: String,
+    pub snippet: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+fn open(path: &std::path::Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS chats (
+            id TEXT PRIMARY KEY,
+            title TEXT,
+            model_name TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS messages (
+            chat_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            PRIMARY KEY (chat_id, position)
+        );
+        CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+            content,
+            chat_id UNINDEXED,
+            position UNINDEXED
+        );",
+    )?;
+    Ok(conn)
+}
+
+async fn db_path() -> Result<std::path::PathBuf, String> {
+    get_app_data_path(CHAT_DB_FILE).await.map_err(|e| e.to_string())
+}
+
+/// Replace the stored copy of a chat with its current messages.
+/// With encryption at rest the title and message text are sealed and kept out of the
+/// full-text index; model name and timestamps stay readable for the search filters.
+pub async fn store_chat(chat_id: String, chat: ChatState) -> Result<(), String> {
+    let path = db_path().await?;
+    tokio::task::spawn_blocking(move || -> Result<(), String> {
+        let encrypt = encryption::is_enabled();
+        let title = chat.title.as_deref().map(encryption::seal).transpose()?;
+        let contents = chat.messages.iter().map(|m| encryption::seal(&m.content)).collect::<Result<Vec<_>, _>>()?;
+
+        let mut conn = open(&path).map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO chats (id, title, model_name, updated_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET title = excluded.title, model_name = excluded.model_name, updated_at = excluded.updated_at",
+            params![chat_id, title, chat.model_name, chrono::Utc::now()],
+        ).map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM messages WHERE chat_id = ?1", params![chat_id]).map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM messages_fts WHERE chat_id = ?1", params![chat_id]).map_err(|e| e.to_string())?;
+        for (position, (message, content)) in chat.messages.iter().zip(&contents).enumerate() {
+            tx.execute(
+                "INSERT INTO messages (chat_id, position, role, content, timestamp) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![chat_id, position as i64, message.role, content, message.timestamp],
+            ).map_err(|e| e.to_string())?;
+            if !encrypt {
+                tx.execute(
+                    "INSERT INTO messages_fts (content, chat_id, position) VALUES (?1, ?2, ?3)",
+                    params![message.content, chat_id, position as i64],
+                ).map_err(|e| e.to_string())?;
+            }
+        }
+        tx.commit().map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to store chat: {}", e))
+}
+
+/// Encrypt or decrypt every stored chat to match the encryption setting, rebuilding
+/// the full-text index for plaintext storage. Returns the number of messages converted.
+pub async fn convert_all(encrypt: bool) -> Result<usize, String> {
+    let path = db_path().await?;
+    tokio::task::spawn_blocking(move || -> Result<usize, String> {
+        let convert = |value: &str| -> Result<String, String> {
+            if encrypt {
+                if encryption::is_sealed(value) { Ok(value.to_string()) } else { encryption::encrypt(value) }
+            } else {
+                encryption::decrypt(value)
+            }
+        };
+
+        let mut conn = open(&path).map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        let titles: Vec<(String, Option<String>)> = {
+            let mut stmt = tx.prepare("SELECT id, title FROM chats").map_err(|e| e.to_string())?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+            rows.collect::<rusqlite::Result<_>>().map_err(|e| e.to_string())?
+        };
+        for (id, title) in titles {
+            let title = title.as_deref().map(convert).transpose()?;
+            tx.execute("UPDATE chats SET title = ?1 WHERE id = ?2", params![title, id]).map_err(|e| e.to_string())?;
+        }
+
+        let messages: Vec<(String, i64, String)> = {
+            let mut stmt = tx.prepare("SELECT chat_id, position, content FROM messages").map_err(|e| e.to_string())?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))).map_err(|e| e.to_string())?;
+            rows.collect::<rusqlite::Result<_>>().map_err(|e| e.to_string())?
+        };
+        tx.execute("DELETE FROM messages_fts", []).map_err(|e| e.to_string())?;
+        for (chat_id, position, content) in &messages {
+            let converted = convert(content)?;
+            tx.execute(
+                "UPDATE messages SET content = ?1 WHERE chat_id = ?2 AND position = ?3",
+                params![converted, chat_id, position],
+            ).map_err(|e| e.to_string())?;
+            if !encrypt {
+                tx.execute(
+                    "INSERT INTO messages_fts (content, chat_id, position) VALUES (?1, ?2, ?3)",
+                    params![converted, chat_id, position],
+                ).map_err(|e| e.to_string())?;
+            }
+        }
+
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(messages.len())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to convert stored chats: {}", e))
+}
+
+pub async fn delete_chat(chat_id: String) -> Result<(), String> {
+    let path = db_path().await?;
+    tokio::task::spawn_blocking(move || -> rusqlite::Result<()> {
+        let conn = open(&path)?;
+        conn.execute("DELETE FROM chats WHERE id = ?1", params![chat_id])?;
+        conn.execute("DELETE FROM messages WHERE chat_id = ?1", params![chat_id])?;
+        conn.execute("DELETE FROM messages_fts WHERE chat_id = ?1", params![chat_id])?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to delete chat: {}", e))
+}
+
+/// Turn free text into an FTS5 query: every word has to match, as a prefix of a token.
+/// Quoting each word keeps FTS syntax characters in user input from causing errors.
+fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub async fn search_chats(query: String, filters: ChatSearchFilters) -> Result<Vec<ChatSearchHit>, String> {
+    let match_query = fts_query(&query);
+    if match_query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let path = db_path().await?;
+    if encryption::is_enabled() {
+        return tokio::task::spawn_blocking(move || scan_encrypted(&path, &query, &filters))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Search failed: {}", e));
+    }
+
+    tokio::task::spawn_blocking(move || -> rusqlite::Result<Vec<ChatSearchHit>> {
+        let conn = open(&path)?;
+        let mut stmt = conn.prepare(
+            "SELECT m.chat_id, c.title, c.model_name, m.role,
+                    snippet(messages_fts, 0, '[', ']', '…', 12), m.timestamp
+             FROM messages_fts
+             JOIN messages m ON m.chat_id = messages_fts.chat_id AND m.position = messages_fts.position
+             JOIN chats c ON c.id = m.chat_id
+             WHERE messages_fts MATCH ?1
+               AND (?2 IS NULL OR c.model_name = ?2)
+               AND (?3 IS NULL OR m.timestamp >= ?3)
+               AND (?4 IS NULL OR m.timestamp <= ?4)
+             ORDER BY rank
+             LIMIT ?5",
+        )?;
+        let limit = filters.limit.unwrap_or(DEFAULT_SEARCH_LIMIT) as i64;
+        let hits = stmt
+            .query_map(params![match_query, filters.model_name, filters.from, filters.to, limit], |row| {
+                Ok(ChatSearchHit {
+                    chat_id: row.get(0)?,
+                    title: row.get(1)?,
+                    model_name: row.get(2)?,
+                    role: row.get(3)?,
+                    snippet: row.get(4)?,
+                    timestamp: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(hits)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Search failed: {}", e))
+}
+
+/// Encrypted chats have no full-text index, so decrypt and match them in memory.
+/// Same semantics as the FTS query: every word has to appear, case-insensitively.
+fn scan_encrypted(path: &std::path::Path, query: &str, filters: &ChatSearchFilters) -> Result<Vec<ChatSearchHit>, String> {
+    let words: Vec<String> = query.split_whitespace().map(|w| w.to_lowercase()).collect();
+    let limit = filters.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    let conn = open(path).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT m.chat_id, c.title, c.model_name, m.role, m.content, m.timestamp
+         FROM messages m
+         JOIN chats c ON c.id = m.chat_id
+         WHERE (?1 IS NULL OR c.model_name = ?1)
+           AND (?2 IS NULL OR m.timestamp >= ?2)
+           AND (?3 IS NULL OR m.timestamp <= ?3)
+         ORDER BY m.timestamp DESC",
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![filters.model_name, filters.from, filters.to], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, chrono::DateTime<chrono::Utc>>(5)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut hits = Vec::new();
+    for row in rows {
+        let (chat_id, title, model_name, role, content, timestamp) = row.map_err(|e| e.to_string())?;
+        let content = encryption::decrypt(&content)?;
+        let lowered = content.to_lowercase();
+        if !words.iter().all(|w| lowered.contains(w.as_str())) {
+            continue;
+        }
+        hits.push(ChatSearchHit {
+            chat_id,
+            title: title.as_deref().map(encryption::decrypt).transpose()?,
+            model_name,
+            role,
+            snippet: snippet_around(&content, &lowered, &words[0]),
+            timestamp,
+        });
+        if hits.len() >= limit {
+            break;
+        }
+    }
+    Ok(hits)
+}
+
+/// A few words of context around the first match, marked like the FTS snippets
+fn snippet_around(content: &str, lowered: &str, word: &str) -> String {
+    const CONTEXT_CHARS: usize = 60;
+    // Lowercasing can change byte lengths, fall back to the start when offsets disagree
+    let (start, end) = lowered.find(word)
+        .filter(|&i| lowered.len() == content.len() && content.is_char_boundary(i) && content.is_char_boundary(i + word.len()))
+        .map(|i| (i, i + word.len()))
+        .unwrap_or((0, 0));
+    let from = content[..start].char_indices().rev().nth(CONTEXT_CHARS).map(|(i, _)| i).unwrap_or(0);
+    let to = content[end..].char_indices().nth(CONTEXT_CHARS).map(|(i, _)| end + i).unwrap_or(content.len());
+
+    let mut snippet = String::new();
+    if from > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(&content[from..start]);
+    if end > start {
+        snippet.push('[');
+        snippet.push_str(&content[start..end]);
+        snippet.push(']');
+    }
+    snippet.push_str(&content[end..to]);
+    if to < content.len() {
+        snippet.push('…');
+    }
+    snippet
+}
@@ -0,0 +1,137 @@
+// Interactive llama-cli sessions. The cli runs with its stdin piped, so the terminal window
+// becomes a REPL: each line typed there is written to the process, and its replies come
+// back through the same output capture and `process-output` events as a server's logs.
+
+use std::process::Stdio;
+use std::sync::Arc;
+use chrono::Utc;
+use tokio::process::Command as TokioCommand;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::process::{add_output_line, handle_process_output, resolve_llama_server_path_with_fallback, ProcessHandle};
+use crate::AppState;
+
+/// llama-cli from the same build as the resolved llama-server. llama-run isn't used, it reads
+/// a piped stdin to the end as one prompt instead of turn by turn
+async fn resolve_cli_path(state: &AppState) -> Result<std::path::PathBuf, String> {
+    let global_config = state.config.lock().await.clone();
+    let server_path = resolve_llama_server_path_with_fallback(state, &global_config).await;
+    let exe_name = if cfg!(windows) { "llama-cli.exe" } else { "llama-cli" };
+    let cli_path = server_path.with_file_name(exe_name);
+    if cli_path.exists() {
+        Ok(cli_path)
+    } else {
+        Err(format!("llama-cli not found at: {:?}", cli_path))
+    }
+}
+
+/// Only the structured launch settings carry over, custom arguments are llama-server's
+fn build_cli_args(model_config: &ModelConfig) -> Vec<String> {
+    let mut args = vec![
+        "-m".to_string(),
+        crate::scanner::first_shard(&model_config.model_path),
+    ];
+    args.extend(crate::launch_settings::to_args(&model_config.launch_settings));
+    // Conversation mode with plain line based IO, there's no console on the other end
+    args.extend(["-cnv".to_string(), "--simple-io".to_string()]);
+    args
+}
+
+pub async fn launch_cli_session(
+    model_path: String,
+    state: &AppState,
+) -> Result<LaunchResult, Box<dyn std::error::Error>> {
+    let model_path = crate::scanner::first_shard(&model_path);
+    let model_config = state.library.config_for(&model_path).await;
+    let executable_path = resolve_cli_path(state).await?;
+    let args = build_cli_args(&model_config);
+
+    let mut cmd = TokioCommand::new(&executable_path);
+    cmd.args(&args)
+       .envs(&model_config.env_vars)
+       .stdin(Stdio::piped())
+       .stdout(Stdio::piped())
+       .stderr(Stdio::piped())
+       .kill_on_drop(true);
+
+    // Hide console window on Windows release builds
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let mut child = cmd.spawn()?;
+    let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to get stderr")?;
+
+    let process_id = Uuid::new_v4().to_string();
+    let model_name = std::path::Path::new(&model_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    // Nothing to probe, the session takes input as soon as it's spawned
+    let process_info = ProcessInfo {
+        id: process_id.clone(),
+        model_path: model_path.clone(),
+        model_name: model_name.clone(),
+        host: String::new(),
+        port: 0,
+        command: std::iter::once(executable_path.to_string_lossy().to_string()).chain(args).collect(),
+        status: ProcessStatus::Running,
+        output: Vec::new(),
+        created_at: Utc::now(),
+        remote_host: None,
+        restarts: 0,
+        metrics: ProcessMetrics::default(),
+        interactive: true,
+        resources: None,
+    };
+
+    let process_handle = Arc::new(Mutex::new(ProcessHandle::new(child, process_id.clone())));
+    state.processes.register(process_info, process_handle.clone()).await;
+
+    let state_clone = state.clone();
+    let process_id_clone = process_id.clone();
+    tokio::spawn(async move {
+        handle_process_output(state_clone, process_id_clone, process_handle, stdout, stderr).await;
+    });
+
+    crate::gpu_power::on_model_started(state).await;
+    crate::config::record_model_usage(state, &model_path, None).await;
+
+    Ok(LaunchResult {
+        success: true,
+        process_id,
+        server_host: String::new(),
+        server_port: 0,
+        model_name,
+        message: "Interactive session started".to_string(),
+        computed_context: None,
+    })
+}
+
+/// Send a line of input to an interactive session. Several lines are joined with llama-cli's
+/// trailing backslash, so they arrive as one message instead of one turn each
+pub async fn send_input(process_id: &str, input: &str, state: &AppState) -> Result<(), String> {
+    let interactive = state.processes.get(process_id).await
+        .ok_or("Process not found")?
+        .interactive;
+    if !interactive {
+        return Err("Only interactive sessions accept input".to_string());
+    }
+    let handle = state.processes.children.lock().await
+        .get(process_id)
+        .cloned()
+        .ok_or("Process has exited")?;
+
+    let message = input.lines().collect::<Vec<_>>().join("\\\n");
+    handle.lock().await.write_line(&message).await?;
+
+    // Echoed into the output so the transcript shows both sides
+    for line in input.lines() {
+        add_output_line(state, process_id, format!("[IN] {}", line)).await;
+    }
+    Ok(())
+}
@@ -1,28 +1,177 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
-use serde_json;
+use serde_json::{self, Value};
 use tokio::fs;
 use crate::models::*;
 use crate::AppState;
 
 const SETTINGS_FILE: &str = "launcher_settings.json";
 
+// Bump together with a new entry in MIGRATIONS whenever the settings layout changes
+const CURRENT_SCHEMA_VERSION: u32 = 3;
+
+// MIGRATIONS[n] upgrades a settings file from schema version n to n + 1
+const MIGRATIONS: &[fn(&mut Value)] = &[
+    migrate_legacy_theme_fields,
+    migrate_fill_required_fields,
+    migrate_lift_launch_settings,
+];
+
 pub async fn get_settings_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    get_app_data_path(SETTINGS_FILE).await
+}
+
+/// Path of a file inside the ~/.llama-os data directory
+pub async fn get_app_data_path(file_name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
     let mut path = dirs::home_dir()
         .ok_or("Could not find home directory")?;
     path.push(".llama-os");
     
     // Create directory if it doesn't exist
     fs::create_dir_all(&path).await?;
-    path.push(SETTINGS_FILE);
+    path.push(file_name);
     
     Ok(path)
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
 struct SettingsFile {
+    #[serde(default)]
+    schema_version: u32,
     global_config: GlobalConfig,
     model_configs: HashMap<String, ModelConfig>,
+    #[serde(default)]
+    stacks: HashMap<String, StackDefinition>,
+    #[serde(default)]
+    model_config_history: HashMap<String, Vec<ModelConfigSnapshot>>,
+    #[serde(default)]
+    model_usage: HashMap<String, ModelUsage>,
+    #[serde(default)]
+    desktop_state: DesktopState,
+    #[serde(default)]
+    presets: HashMap<String, ArgumentPreset>,
+}
+
+/// v0 -> v1: early builds stored the theme under the desktop state names
+/// (theme/background/theme_synced) instead of the global config ones.
+fn migrate_legacy_theme_fields(settings: &mut Value) {
+    let Some(global) = settings.get_mut("global_config").and_then(|v| v.as_object_mut()) else {
+        return;
+    };
+    for (legacy, current) in [("theme", "theme_color"), ("background", "background_color"), ("theme_synced", "theme_is_synced")] {
+        if let Some(value) = global.remove(legacy) {
+            global.entry(current).or_insert(value);
+        }
+    }
+}
+
+/// v1 -> v2: fill fields that have no serde default, so partial files still load
+fn migrate_fill_required_fields(settings: &mut Value) {
+    let Some(root) = settings.as_object_mut() else {
+        return;
+    };
+    let defaults = serde_json::to_value(GlobalConfig::default()).unwrap_or(Value::Null);
+    let global = root.entry("global_config").or_insert_with(|| Value::Object(Default::default()));
+    if let (Some(global), Some(defaults)) = (global.as_object_mut(), defaults.as_object()) {
+        for key in ["models_directory", "executable_folder", "theme_color"] {
+            if global.get(key).is_none_or(|v| v.is_null()) {
+                if let Some(value) = defaults.get(key) {
+                    global.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+    }
+    root.entry("model_configs").or_insert_with(|| Value::Object(Default::default()));
+}
+
+/// v2 -> v3: common flags moved from custom_args into the structured launch settings
+fn migrate_lift_launch_settings(settings: &mut Value) {
+    let Some(configs) = settings.get_mut("model_configs").and_then(|v| v.as_object_mut()) else {
+        return;
+    };
+    for config in configs.values_mut() {
+        let Some(config) = config.as_object_mut() else {
+            continue;
+        };
+        let custom_args = config.get("custom_args").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let mut launch_settings: LaunchSettings = config.get("launch_settings")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let remaining = crate::launch_settings::lift(&mut launch_settings, &custom_args);
+        config.insert("custom_args".to_string(), Value::from(remaining));
+        if let Ok(value) = serde_json::to_value(&launch_settings) {
+            config.insert("launch_settings".to_string(), value);
+        }
+    }
+}
+
+/// Run every migration between the file's schema version and the current one.
+/// Returns the version the file started at.
+fn migrate_settings(settings: &mut Value) -> u32 {
+    let from_version = settings.get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+        println!("Migrating settings from schema version {} to {}", version, version + 1);
+        migration(settings);
+    }
+    
+    if let Some(root) = settings.as_object_mut() {
+        root.insert("schema_version".to_string(), Value::from(CURRENT_SCHEMA_VERSION.max(from_version)));
+    }
+    from_version
+}
+
+/// Keep a copy of a settings file before it gets rewritten or replaced
+async fn backup_settings_file(settings_path: &std::path::Path, suffix: &str) {
+    let backup_path = settings_path.with_extension(format!("{}.json", suffix));
+    match fs::copy(settings_path, &backup_path).await {
+        Ok(_) => println!("Backed up settings to {:?}", backup_path),
+        Err(e) => eprintln!("Failed to back up settings to {:?}: {}", backup_path, e),
+    }
+}
+
+/// Note a launch for the "last used" ordering and persist it.
+/// Local launches also pass their effective invocation so it can be repeated.
+pub async fn record_model_usage(state: &AppState, model_path: &str, launch: Option<LaunchRecord>) {
+    state.library.record_launch(model_path, launch).await;
+    if let Err(e) = save_settings(state).await {
+        eprintln!("Failed to save model usage: {}", e);
+    }
+}
+
+/// Pin or unpin a model in the launcher and persist it
+pub async fn set_model_pinned(state: &AppState, model_path: &str, pinned: bool) -> Result<(), String> {
+    state.library.set_pinned(model_path, pinned).await;
+    save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Pinned models in pin order, then up to `limit` of the most recently launched others
+pub async fn recent_models(state: &AppState, limit: usize) -> Vec<RecentModel> {
+    let usage = state.library.usage().await;
+    let entry = |path: &String, usage: &ModelUsage| RecentModel {
+        path: path.clone(),
+        name: Path::new(path).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| path.clone()),
+        last_used: usage.last_used,
+        launch_count: usage.launch_count,
+        pinned: usage.pinned_at.is_some(),
+        exists: Path::new(path).exists(),
+    };
+
+    let mut pinned: Vec<(&String, &ModelUsage)> = usage.iter().filter(|(_, u)| u.pinned_at.is_some()).collect();
+    pinned.sort_by_key(|(_, u)| u.pinned_at);
+    let mut recent: Vec<(&String, &ModelUsage)> = usage
+        .iter()
+        .filter(|(_, u)| u.pinned_at.is_none() && u.last_used.is_some())
+        .collect();
+    recent.sort_by_key(|(_, u)| std::cmp::Reverse(u.last_used));
+
+    pinned
+        .into_iter()
+        .map(|(path, u)| entry(path, u))
+        .chain(recent.into_iter().map(|(path, u)| entry(path, u)).filter(|m| m.exists).take(limit))
+        .collect()
 }
 
 pub async fn load_settings(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
@@ -34,20 +183,88 @@ pub async fn load_settings(state: &AppState) -> Result<(), Box<dyn std::error::E
     }
     
     let contents = fs::read_to_string(&settings_path).await?;
-    let settings: SettingsFile = serde_json::from_str(&contents)?;
+    let mut raw: Value = match serde_json::from_str(&contents) {
+        Ok(raw) => raw,
+        Err(e) => {
+            // Keep the unreadable file around instead of overwriting it with defaults
+            eprintln!("Settings file is not valid JSON, using defaults: {}", e);
+            backup_settings_file(&settings_path, "corrupt").await;
+            return Ok(());
+        }
+    };
+    
+    let from_version = migrate_settings(&mut raw);
+    if from_version > CURRENT_SCHEMA_VERSION {
+        // The next save writes this build's layout and drops what it doesn't know, so the
+        // newer file is kept as settings.v<N>.json for when the newer build runs again
+        eprintln!("Settings file has schema version {} which is newer than this build ({}), keeping a copy", from_version, CURRENT_SCHEMA_VERSION);
+        backup_settings_file(&settings_path, &format!("v{}", from_version)).await;
+    }
+    
+    let settings: SettingsFile = match serde_json::from_value(raw) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Failed to read settings after migration, using defaults: {}", e);
+            backup_settings_file(&settings_path, "corrupt").await;
+            return Ok(());
+        }
+    };
+    let migrated = from_version < CURRENT_SCHEMA_VERSION;
     
     // Update global config
     {
         let mut config = state.config.lock().await;
         *config = settings.global_config;
+        // The token is sealed on disk when encryption at rest is on
+        match crate::encryption::decrypt(&config.hf_token) {
+            Ok(token) => config.hf_token = token,
+            Err(e) => {
+                eprintln!("Failed to read the stored Hugging Face token, it needs to be entered again: {}", e);
+                config.hf_token.clear();
+            }
+        }
+        crate::huggingface::set_access_token(&config.hf_token);
     }
     
     // Update model configs
     {
-        let mut model_configs = state.model_configs.lock().await;
+        let mut model_configs = state.library.configs.lock().await;
         *model_configs = settings.model_configs;
     }
     
+    // Update stack definitions
+    {
+        let mut stacks = state.stacks.lock().await;
+        *stacks = settings.stacks;
+    }
+    
+    // Update argument presets
+    {
+        let mut presets = state.presets.lock().await;
+        *presets = settings.presets;
+    }
+    
+    // Update per-model settings history
+    {
+        let mut history = state.library.history.lock().await;
+        *history = settings.model_config_history;
+    }
+    
+    // Update usage stats
+    {
+        let mut usage = state.library.usage.lock().await;
+        *usage = settings.model_usage;
+    }
+    
+    // Desktop folders live in the desktop state, the only part of the session kept on disk here
+    state.session_state.lock().await.desktop_state = settings.desktop_state;
+    
+    // Persist the upgraded layout, keeping the original next to it
+    if migrated {
+        backup_settings_file(&settings_path, &format!("v{}", from_version)).await;
+        save_settings(state).await?;
+    }
+    
     tracing::info!("Settings loaded successfully from {:?}", settings_path);
     Ok(())
 }
@@ -55,19 +272,41 @@ pub async fn load_settings(state: &AppState) -> Result<(), Box<dyn std::error::E
 pub async fn save_settings(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
     let settings_path = get_settings_path().await?;
     
-    let global_config = {
+    let mut global_config = {
         let config = state.config.lock().await;
         config.clone()
     };
+    if !global_config.hf_token.is_empty() {
+        global_config.hf_token = crate::encryption::seal(&global_config.hf_token)?;
+    }
+    
+    let model_configs = state.library.all_configs().await;
+    
+    let stacks = {
+        let stacks = state.stacks.lock().await;
+        stacks.clone()
+    };
     
-    let model_configs = {
-        let configs = state.model_configs.lock().await;
-        configs.clone()
+    let model_config_history = {
+        let history = state.library.history.lock().await;
+        history.clone()
     };
     
+    let model_usage = state.library.usage().await;
+    
+    let desktop_state = state.session_state.lock().await.desktop_state.clone();
+    
+    let presets = state.presets.lock().await.clone();
+    
     let settings = SettingsFile {
+        schema_version: CURRENT_SCHEMA_VERSION,
         global_config,
         model_configs,
+        stacks,
+        model_config_history,
+        model_usage,
+        desktop_state,
+        presets,
     };
     
     let contents = serde_json::to_string_pretty(&settings)?;
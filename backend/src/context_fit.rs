@@ -0,0 +1,232 @@
+// Automatic context sizing. Instead of a fixed `-c`, pick the largest context whose
+// KV cache still fits in free VRAM next to the offloaded weights. The estimate uses
+// the GGUF header (layer count, KV heads, head size) and the launch arguments
+// (`-ngl`, cache types); llama.cpp's compute buffers are covered by a fixed reserve.
+// The same numbers give the pre-launch memory estimate shown for a model.
+
+use std::path::Path;
+
+use sysinfo::System;
+
+use crate::gguf::{self, GgufHeader};
+use crate::models::ModelMemoryEstimate;
+
+// Compute buffers, CUDA context and driver overhead
+const VRAM_RESERVE_BYTES: u64 = 768 * 1024 * 1024;
+// Keep some headroom so other applications can still allocate a little VRAM
+const VRAM_HEADROOM: f64 = 0.05;
+const MIN_CONTEXT: u64 = 512;
+const CONTEXT_ALIGNMENT: u64 = 256;
+// llama-server's context when the arguments don't set one
+const DEFAULT_CONTEXT: u64 = 4096;
+
+const GPU_LAYER_FLAGS: &[&str] = &["-ngl", "--gpu-layers", "--n-gpu-layers"];
+const CONTEXT_FLAGS: &[&str] = &["-c", "--ctx-size"];
+
+/// Context size for a launch, or None when free VRAM can't be measured (no supported GPU)
+pub async fn fit_for_launch(model_path: &str, args: &[String]) -> Result<Option<u64>, String> {
+    let model_path = model_path.to_string();
+    let args = args.to_vec();
+    tokio::task::spawn_blocking(move || {
+        let Some(free_vram) = crate::system_monitor::free_vram_bytes() else {
+            return Ok(None);
+        };
+        fit_context(Path::new(&model_path), &args, free_vram).map(Some)
+    })
+    .await
+    .map_err(|e| format!("Context sizing task failed: {}", e))?
+}
+
+/// Largest context that fits in `free_vram` for the given server arguments
+pub fn fit_context(model_path: &Path, args: &[String], free_vram: u64) -> Result<u64, String> {
+    let header = read_header(model_path)?;
+    let model_bytes = model_size(model_path)?;
+
+    let block_count = header.arch_u64("block_count").ok_or("Model has no layer count in its metadata")?;
+    let max_context = header.context_length().unwrap_or(u64::MAX);
+
+    // Without an explicit -ngl assume everything is offloaded, which gives the safer (smaller) context
+    let gpu_layers = gpu_layers(args, block_count);
+    if gpu_layers == 0 {
+        // The KV cache stays in system memory, VRAM is not the limit
+        return Ok(max_context);
+    }
+
+    let offload_fraction = gpu_layers as f64 / block_count as f64;
+    let weights_on_gpu = (model_bytes as f64 * offload_fraction) as u64;
+
+    let kv_per_token = (kv_layer_bytes_per_token(&header, args) * gpu_layers as f64).ceil() as u64;
+    if kv_per_token == 0 {
+        return Err("Model metadata has no attention dimensions".to_string());
+    }
+
+    let usable = (free_vram as f64 * (1.0 - VRAM_HEADROOM)) as u64;
+    let available = usable
+        .checked_sub(weights_on_gpu + VRAM_RESERVE_BYTES)
+        .ok_or_else(|| format!(
+            "Not enough free VRAM for {} offloaded layers ({} MiB free)",
+            gpu_layers, free_vram / (1024 * 1024)
+        ))?;
+
+    let context = (available / kv_per_token).min(max_context);
+    let context = context - context % CONTEXT_ALIGNMENT;
+    if context < MIN_CONTEXT {
+        return Err(format!(
+            "Only {} tokens of context fit in free VRAM, reduce -ngl or free up GPU memory",
+            context
+        ));
+    }
+    Ok(context)
+}
+
+/// Expected VRAM and RAM use for the given server arguments, and how many layers fit in
+/// `free_vram`. Weights are spread evenly over the layers, which slightly overstates the
+/// per-layer size since the embedding and output tensors are counted in as well.
+pub fn estimate_memory(
+    model_path: &Path,
+    args: &[String],
+    context_size: Option<u64>,
+    free_vram: Option<u64>,
+    total_vram: Option<u64>,
+) -> Result<ModelMemoryEstimate, String> {
+    let header = read_header(model_path)?;
+    let weights_bytes = model_size(model_path)?;
+    let block_count = header.arch_u64("block_count").ok_or("Model has no layer count in its metadata")?.max(1);
+    let trained_context = header.context_length();
+
+    // `-c 0` asks llama-server for the trained context
+    let context_size = match context_size.or_else(|| arg_value(args, CONTEXT_FLAGS).and_then(|v| v.parse().ok())) {
+        Some(0) => trained_context.unwrap_or(DEFAULT_CONTEXT),
+        Some(context) => context,
+        None => trained_context.map_or(DEFAULT_CONTEXT, |trained| trained.min(DEFAULT_CONTEXT)),
+    };
+
+    let gpu_layers = gpu_layers(args, block_count);
+    let layer_kv = (kv_layer_bytes_per_token(&header, args) * context_size as f64).ceil() as u64;
+    let layer_bytes = weights_bytes / block_count + layer_kv;
+    let vram_bytes = if gpu_layers > 0 { layer_bytes * gpu_layers + VRAM_RESERVE_BYTES } else { 0 };
+
+    let layers_that_fit = free_vram.map(|free| {
+        let usable = (free as f64 * (1.0 - VRAM_HEADROOM)) as u64;
+        (usable.saturating_sub(VRAM_RESERVE_BYTES) / layer_bytes.max(1)).min(block_count)
+    });
+
+    let mut sys = System::new();
+    sys.refresh_memory();
+
+    Ok(ModelMemoryEstimate {
+        model_path: model_path.to_string_lossy().to_string(),
+        context_size,
+        trained_context,
+        quantization: header
+            .get("general.file_type")
+            .and_then(|v| v.as_u64())
+            .and_then(gguf::file_type_name)
+            .map(str::to_string),
+        block_count,
+        gpu_layers,
+        weights_bytes,
+        kv_cache_bytes: layer_kv * block_count,
+        vram_bytes,
+        ram_bytes: layer_bytes * (block_count - gpu_layers),
+        gpu_total_bytes: total_vram,
+        gpu_free_bytes: free_vram,
+        layers_that_fit,
+        fits_in_vram: layers_that_fit.map(|fit| fit >= gpu_layers),
+        ram_available_bytes: sys.available_memory(),
+    })
+}
+
+/// Replace any context flag in the arguments with the computed size
+pub fn apply_context(args: &mut Vec<String>, context: u64) {
+    let mut i = 0;
+    while i < args.len() {
+        if CONTEXT_FLAGS.contains(&args[i].as_str()) {
+            let end = (i + 2).min(args.len());
+            args.drain(i..end);
+        } else if CONTEXT_FLAGS.iter().any(|f| args[i].starts_with(&format!("{}=", f))) {
+            args.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    args.push("-c".to_string());
+    args.push(context.to_string());
+}
+
+fn read_header(model_path: &Path) -> Result<GgufHeader, String> {
+    let file = std::fs::File::open(model_path).map_err(|e| format!("Failed to open model: {}", e))?;
+    gguf::read_header(&mut std::io::BufReader::new(file), false)
+        .map_err(|e| format!("Failed to read GGUF header: {}", e))
+}
+
+/// Weight size in bytes, including the other shards of a split model
+fn model_size(model_path: &Path) -> Result<u64, String> {
+    let size = std::fs::metadata(model_path).map_err(|e| format!("Failed to read model size: {}", e))?.len();
+    let file_name = model_path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let Some(captures) = crate::scanner::SPLIT_FILE.captures(file_name).filter(|c| &c[2] == "00001") else {
+        return Ok(size);
+    };
+
+    let shard_count: u32 = captures[3].parse().unwrap_or(1);
+    let mut total = size;
+    for index in 2..=shard_count {
+        let shard = model_path.with_file_name(format!("{}-{:05}-of-{}.gguf", &captures[1], index, &captures[3]));
+        total += std::fs::metadata(&shard).map(|m| m.len()).unwrap_or(0);
+    }
+    Ok(total)
+}
+
+/// Offloaded layer count, all of them when `-ngl` is missing or negative (llama-server's default)
+fn gpu_layers(args: &[String], block_count: u64) -> u64 {
+    arg_value(args, GPU_LAYER_FLAGS)
+        .and_then(|v| v.parse::<i64>().ok())
+        .map(|v| if v < 0 { block_count } else { (v as u64).min(block_count) })
+        .unwrap_or(block_count)
+}
+
+/// KV cache bytes one layer needs per token of context, at the configured cache types
+fn kv_layer_bytes_per_token(header: &GgufHeader, args: &[String]) -> f64 {
+    let key_bytes = kv_width(header, "key_length") as f64 * cache_type_bytes(arg_value(args, &["-ctk", "--cache-type-k"]));
+    let value_bytes = kv_width(header, "value_length") as f64 * cache_type_bytes(arg_value(args, &["-ctv", "--cache-type-v"]));
+    key_bytes + value_bytes
+}
+
+/// Per-layer K or V width: KV heads times head size
+fn kv_width(header: &GgufHeader, length_key: &str) -> u64 {
+    let heads = header.arch_u64("attention.head_count").unwrap_or(0);
+    let kv_heads = header.arch_u64("attention.head_count_kv").unwrap_or(heads);
+    let head_size = header.arch_u64(&format!("attention.{}", length_key)).or_else(|| {
+        let embedding = header.arch_u64("embedding_length")?;
+        (heads > 0).then(|| embedding / heads)
+    });
+    kv_heads * head_size.unwrap_or(0)
+}
+
+/// Bytes per element for a KV cache type, f16 when unset
+fn cache_type_bytes(cache_type: Option<&str>) -> f64 {
+    match cache_type.unwrap_or("f16") {
+        "f32" => 4.0,
+        "q8_0" => 34.0 / 32.0,
+        "q5_0" => 22.0 / 32.0,
+        "q5_1" => 24.0 / 32.0,
+        "q4_0" => 18.0 / 32.0,
+        "q4_1" | "iq4_nl" => 20.0 / 32.0,
+        _ => 2.0,
+    }
+}
+
+/// Last value given for any of the flags, in either `-x value` or `-x=value` form
+fn arg_value<'a>(args: &'a [String], flags: &[&str]) -> Option<&'a str> {
+    let mut value = None;
+    for (i, arg) in args.iter().enumerate() {
+        for flag in flags {
+            if arg == flag {
+                value = args.get(i + 1).map(|v| v.as_str());
+            } else if let Some(v) = arg.strip_prefix(flag).and_then(|rest| rest.strip_prefix('=')) {
+                value = Some(v);
+            }
+        }
+    }
+    value
+}
@@ -0,0 +1,170 @@
+// Helpers for model files llama-server won't load as they are. Split GGUFs can be
+// merged into one file with the bundled llama-gguf-split. Pre-GGUF files (GGML, GGMF,
+// GGJT) are recognized by their magic and reported with guidance, since llama.cpp
+// releases no longer ship a converter for them.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+use tokio::process::Command as TokioCommand;
+
+use crate::benchmark::tool_path;
+use crate::AppState;
+
+// First shard of a split model, `<name>-00001-of-00003.gguf`
+static FIRST_SHARD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^(.+)-00001-of-(\d{5})\.gguf$").unwrap());
+
+// Legacy magics, stored as little-endian u32 so the bytes read reversed
+const MAGIC_GGML: u32 = 0x6767_6d6c;
+const MAGIC_GGMF: u32 = 0x6767_6d66;
+const MAGIC_GGJT: u32 = 0x6767_6a74;
+const MAGIC_GGLA: u32 = 0x6767_6c61;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelFormatReport {
+    pub path: String,
+    pub format: String, // "gguf", "gguf-split", "ggml", "ggmf", "ggjt", "ggla", "safetensors" or "unknown"
+    pub version: Option<u32>,
+    pub loadable: bool,
+    pub shards: Option<u32>,
+    pub can_merge: bool,
+    pub guidance: Option<String>,
+}
+
+/// Identify a model file's format from its header.
+/// Blocking: reads the first bytes of the file.
+pub fn inspect_format(path: &Path) -> Result<ModelFormatReport, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut head = [0u8; 9];
+    let read = file.read(&mut head).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let magic = (read >= 4).then(|| u32::from_le_bytes([head[0], head[1], head[2], head[3]]));
+    let version = (read >= 8).then(|| u32::from_le_bytes([head[4], head[5], head[6], head[7]]));
+
+    let mut report = ModelFormatReport {
+        path: path.to_string_lossy().to_string(),
+        format: "unknown".to_string(),
+        version: None,
+        loadable: false,
+        shards: None,
+        can_merge: false,
+        guidance: None,
+    };
+
+    if read >= 4 && &head[..4] == b"GGUF" {
+        report.version = version;
+        report.loadable = true;
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if let Some(captures) = FIRST_SHARD.captures(file_name) {
+            report.format = "gguf-split".to_string();
+            report.shards = captures[2].parse().ok();
+            report.can_merge = true;
+            report.guidance = Some("Split model, it loads as is. Merging makes a single file that is easier to move or share.".to_string());
+        } else {
+            report.format = "gguf".to_string();
+        }
+        return Ok(report);
+    }
+
+    match magic {
+        Some(MAGIC_GGML) | Some(MAGIC_GGMF) | Some(MAGIC_GGJT) => {
+            report.format = match magic {
+                Some(MAGIC_GGML) => "ggml",
+                Some(MAGIC_GGMF) => "ggmf",
+                _ => "ggjt",
+            }.to_string();
+            // Unversioned GGML has tensor data right after the magic
+            report.version = if magic == Some(MAGIC_GGML) { None } else { version };
+            report.guidance = Some(
+                "Pre-GGUF llama.cpp format, no longer supported. Download a GGUF build of this model, or convert it \
+                 with convert_llama_ggml_to_gguf.py from a llama.cpp checkout older than mid-2024.".to_string(),
+            );
+        }
+        Some(MAGIC_GGLA) => {
+            report.format = "ggla".to_string();
+            report.version = version;
+            report.guidance = Some("Legacy GGML LoRA adapter. Convert the original adapter with convert_lora_to_gguf.py.".to_string());
+        }
+        _ if read == 9 && head[8] == b'{' => {
+            // safetensors: u64 header length followed by the JSON header
+            report.format = "safetensors".to_string();
+            report.guidance = Some("Safetensors weights, convert them with convert_hf_to_gguf.py or download a GGUF build.".to_string());
+        }
+        _ => {
+            report.guidance = Some("Not a recognized model format.".to_string());
+        }
+    }
+    Ok(report)
+}
+
+/// Merge a split model into one GGUF next to its shards, `<name>.gguf` unless an output is given.
+/// The shards are kept unless `delete_shards` is set.
+pub async fn merge_split_gguf(
+    first_shard: &Path,
+    output: Option<PathBuf>,
+    delete_shards: bool,
+    state: &AppState,
+) -> Result<String, String> {
+    let file_name = first_shard.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let captures = FIRST_SHARD.captures(file_name).ok_or("Not the first shard of a split GGUF (<name>-00001-of-0000N.gguf)")?;
+    let base = captures[1].to_string();
+    let shard_count: u32 = captures[2].parse().unwrap_or(1);
+
+    let shards: Vec<PathBuf> = (1..=shard_count)
+        .map(|index| first_shard.with_file_name(format!("{}-{:05}-of-{}.gguf", base, index, &captures[2])))
+        .collect();
+    if let Some(missing) = shards.iter().find(|shard| !shard.exists()) {
+        return Err(format!("Shard missing: {}", missing.display()));
+    }
+
+    // Shards a server has open can't be removed, refuse before spending time on the merge
+    if delete_shards {
+        if let Some((_, message)) = crate::model_in_use(&first_shard.to_string_lossy(), state).await {
+            return Err(message);
+        }
+    }
+
+    let output = output.unwrap_or_else(|| first_shard.with_file_name(format!("{}.gguf", base)));
+    if output.exists() {
+        return Err(format!("{} already exists", output.display()));
+    }
+
+    let version_dir = state.versions.active().await.folder.ok_or("No active llama.cpp version")?;
+    let split_tool = tool_path(Path::new(&version_dir), "llama-gguf-split");
+    if !split_tool.exists() {
+        return Err(format!("llama-gguf-split not found at: {:?}", split_tool));
+    }
+
+    let mut cmd = TokioCommand::new(&split_tool);
+    cmd.arg("--merge").arg(first_shard).arg(&output).kill_on_drop(true);
+
+    // Hide console window on Windows release builds
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    println!("Merging {} shards of {} into {}", shard_count, base, output.display());
+    let result = cmd.output().await.map_err(|e| format!("Failed to run llama-gguf-split: {}", e))?;
+    if !result.status.success() {
+        // Don't leave a truncated model behind for the scanner to pick up
+        let _ = tokio::fs::remove_file(&output).await;
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let last_line = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("");
+        return Err(format!("llama-gguf-split exited with {}: {}", result.status, last_line));
+    }
+
+    if delete_shards {
+        // A server may have been started on the shards while the merge ran
+        if let Some((_, message)) = crate::model_in_use(&first_shard.to_string_lossy(), state).await {
+            eprintln!("Keeping the shards of {}: {}", base, message);
+            return Ok(output.to_string_lossy().to_string());
+        }
+        for shard in &shards {
+            if let Err(e) = tokio::fs::remove_file(shard).await {
+                eprintln!("Failed to delete shard {}: {}", shard.display(), e);
+            }
+        }
+    }
+    Ok(output.to_string_lossy().to_string())
+}
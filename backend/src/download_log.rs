@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use tokio::io::AsyncWriteExt;
+use crate::config::get_app_data_path;
+
+const DOWNLOAD_LOG_FILE: &str = "download_audit.jsonl";
+
+/// One finished download, appended as a JSON line to the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadLogEntry {
+    pub download_id: String,
+    pub source_url: String,
+    pub destination: String,
+    pub files: Vec<String>,
+    pub bytes_transferred: u64,
+    pub duration_secs: i64,
+    pub outcome: String,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    #[serde(default)]
+    pub file_sources: std::collections::HashMap<String, String>, // Provenance: which endpoint served each file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandwidthUsage {
+    pub month: String,
+    pub total_bytes: u64,
+    pub download_count: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub entries: Vec<DownloadLogEntry>,
+}
+
+/// Append an entry to the audit log; the log is never rewritten
+pub async fn append_entry(entry: &DownloadLogEntry) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = get_app_data_path(DOWNLOAD_LOG_FILE).await.map_err(|e| e.to_string())?;
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await?;
+    file.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+pub async fn read_entries() -> Result<Vec<DownloadLogEntry>, Box<dyn std::error::Error + Send + Sync>> {
+    let path = get_app_data_path(DOWNLOAD_LOG_FILE).await.map_err(|e| e.to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = tokio::fs::read_to_string(&path).await?;
+    // Skip lines that can't be parsed (e.g. a partial write after a crash)
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Sum the traffic of all downloads that finished in the given month ("YYYY-MM", defaults to current)
+pub async fn bandwidth_usage(month: Option<String>) -> Result<BandwidthUsage, Box<dyn std::error::Error + Send + Sync>> {
+    let month = month.unwrap_or_else(|| Utc::now().format("%Y-%m").to_string());
+    if chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").is_err() {
+        return Err(format!("Invalid month '{}', expected YYYY-MM", month).into());
+    }
+    
+    let entries: Vec<DownloadLogEntry> = read_entries()
+        .await?
+        .into_iter()
+        .filter(|e| e.finished_at.format("%Y-%m").to_string() == month)
+        .collect();
+    
+    let count_outcome = |outcome: &str| entries.iter().filter(|e| e.outcome == outcome).count();
+    
+    Ok(BandwidthUsage {
+        total_bytes: entries.iter().map(|e| e.bytes_transferred).sum(),
+        download_count: entries.len(),
+        completed: count_outcome("Completed"),
+        failed: count_outcome("Failed"),
+        cancelled: count_outcome("Cancelled"),
+        month,
+        entries,
+    })
+}
+
+#[tauri::command]
+pub async fn get_bandwidth_usage(month: Option<String>) -> Result<BandwidthUsage, String> {
+    bandwidth_usage(month).await.map_err(|e| format!("Failed to read download log: {}", e))
+}
@@ -1,17 +1,52 @@
 use crate::AppState;
-use crate::models::DownloadStartResult;
+use crate::models::{DownloadStartResult, QuantAlternative};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tokio::sync::Mutex;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use chrono::{DateTime, Utc};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::{Emitter};
+use crate::archive_stream::StreamError;
+use crate::config::get_app_data_path;
+
+// Unfinished downloads, rewritten whenever one starts, pauses or ends
+const PENDING_DOWNLOADS_FILE: &str = "pending_downloads.json";
+
+// Serializes writes of the pending downloads file
+static PENDING_SAVE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+// Failed downloads and hash mismatches per Hub file ("repo/file") this session
+static FILE_FAILURES: LazyLock<std::sync::Mutex<HashMap<String, u32>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+// Failures before other quantizations are suggested, a missing file gets them right away
+const REPEATED_FAILURE_THRESHOLD: u32 = 2;
+
+// Attempts after a transient failure before the download is marked failed, waiting
+// RETRY_BASE_DELAY_SECS doubled per attempt and capped at RETRY_MAX_DELAY_SECS
+const DOWNLOAD_RETRIES: u32 = 5;
+const RETRY_BASE_DELAY_SECS: u64 = 2;
+const RETRY_MAX_DELAY_SECS: u64 = 60;
+
+// Accelerated transfers split files into ranges of this size, each one a request
+const ACCELERATED_CHUNK_SIZE: u64 = 32 * 1024 * 1024;
+const DEFAULT_TRANSFER_CONNECTIONS: usize = 8;
+const MAX_TRANSFER_CONNECTIONS: usize = 32;
+const RANGE_RETRIES: u32 = 3;
+// Next to the .part, lists the ranges that made it to disk
+pub const CHUNK_MAP_SUFFIX: &str = ".chunks";
+
+// `download-progress` events per download at most this often (~4 Hz)
+const PROGRESS_EMIT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+// Range requests in flight across all downloads, so parallel downloads share one budget
+static TRANSFER_SLOTS: LazyLock<tokio::sync::Semaphore> = LazyLock::new(|| tokio::sync::Semaphore::new(MAX_TRANSFER_CONNECTIONS));
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum DownloadState {
     Starting,
     Downloading,
+    Retrying, // Waiting out the backoff after a transient failure
     Paused,
     Extracting,
     Completed,
@@ -49,6 +84,57 @@ pub struct DownloadStatus {
     pub pause_start_time: Option<DateTime<Utc>>,
     pub error: Option<String>,
     pub message: Option<String>,
+    #[serde(default)]
+    pub transferred_bytes: u64, // Bytes received over the network across all files
+    #[serde(default)]
+    pub file_sources: HashMap<String, String>, // File -> URL that actually served it
+    #[serde(default)]
+    pub speed_limit: Option<u64>, // Bytes per second for this download alone, on top of the global cap
+    #[serde(default)]
+    pub alternatives: Vec<QuantAlternative>, // Other quantizations to try when a Hub file is missing or keeps failing
+    #[serde(default)]
+    pub eta_seconds: Option<u64>, // Time left on the current file at the current speed
+}
+
+impl DownloadStatus {
+    fn update_eta(&mut self) {
+        self.eta_seconds = (self.speed > 0.0 && self.total_bytes > 0)
+            .then(|| (self.total_bytes.saturating_sub(self.downloaded_bytes) as f64 / self.speed).ceil() as u64);
+    }
+}
+
+/// Token bucket refilled at `rate` bytes per second, holding at most one second of burst
+#[derive(Debug)]
+struct TokenBucket {
+    rate: u64,
+    tokens: f64,
+    last_refill: std::time::Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        Self { rate, tokens: rate as f64, last_refill: std::time::Instant::now() }
+    }
+
+    /// Take `bytes` out of the bucket and return how long to wait until it is back in credit
+    fn consume(&mut self, bytes: u64) -> std::time::Duration {
+        let now = std::time::Instant::now();
+        let refill = now.duration_since(self.last_refill).as_secs_f64() * self.rate as f64;
+        self.tokens = (self.tokens + refill).min(self.rate as f64) - bytes as f64;
+        self.last_refill = now;
+        if self.tokens >= 0.0 {
+            std::time::Duration::ZERO
+        } else {
+            std::time::Duration::from_secs_f64(-self.tokens / self.rate as f64)
+        }
+    }
+}
+
+/// A download that was running or paused, kept on disk so it survives a restart
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingDownload {
+    status: DownloadStatus,
+    config: DownloadConfig,
 }
 
 #[derive(Debug)]
@@ -56,6 +142,10 @@ pub struct DownloadManager {
     pub downloads: HashMap<String, DownloadStatus>,
     pub download_history: Vec<DownloadStatus>,
     cancellation_tokens: HashMap<String, Arc<Mutex<bool>>>,
+    configs: HashMap<String, DownloadConfig>, // Resumable downloads, archives always start over
+    interrupted: HashSet<String>, // Restored from a previous session, no task running yet
+    global_limit: Option<TokenBucket>,
+    download_limits: HashMap<String, TokenBucket>,
 }
 
 impl DownloadManager {
@@ -64,6 +154,10 @@ impl DownloadManager {
             downloads: HashMap::new(),
             download_history: Vec::new(),
             cancellation_tokens: HashMap::new(),
+            configs: HashMap::new(),
+            interrupted: HashSet::new(),
+            global_limit: None,
+            download_limits: HashMap::new(),
         }
     }
 
@@ -78,7 +172,7 @@ impl DownloadManager {
 
     pub fn pause_download(&mut self, id: &str) -> Result<(), String> {
         if let Some(status) = self.downloads.get_mut(id) {
-            if matches!(status.status, DownloadState::Downloading) {
+            if matches!(status.status, DownloadState::Downloading | DownloadState::Retrying) {
                 status.status = DownloadState::Paused;
                 status.pause_start_time = Some(chrono::Utc::now());
                 Ok(())
@@ -111,6 +205,12 @@ impl DownloadManager {
     pub fn cancel_download(&mut self, id: &str) -> Result<(), String> {
         if let Some(status) = self.downloads.get_mut(id) {
             status.status = DownloadState::Cancelled;
+            // No task will clean up after a download that was never restarted
+            if self.interrupted.remove(id) {
+                for part in partial_files(status) {
+                    let _ = std::fs::remove_file(part);
+                }
+            }
             if let Some(token) = self.cancellation_tokens.get(id) {
                 let token = token.clone();
                 tokio::spawn(async move {
@@ -124,12 +224,180 @@ impl DownloadManager {
         }
     }
 
+    /// Cap the speed of all downloads together, or of one download when an id is given.
+    /// A limit of 0 removes the cap.
+    pub fn set_speed_limit(&mut self, download_id: Option<&str>, bytes_per_second: u64) -> Result<(), String> {
+        let bucket = (bytes_per_second > 0).then(|| TokenBucket::new(bytes_per_second));
+        match download_id {
+            None => self.global_limit = bucket,
+            Some(id) => {
+                let status = self.downloads.get_mut(id).ok_or("Download not found")?;
+                status.speed_limit = (bytes_per_second > 0).then_some(bytes_per_second);
+                match bucket {
+                    Some(bucket) => { self.download_limits.insert(id.to_string(), bucket); }
+                    None => { self.download_limits.remove(id); }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Account for bytes just received, returns how long the download should pause
+    fn throttle(&mut self, id: &str, bytes: u64) -> std::time::Duration {
+        let global = self.global_limit.as_mut().map(|b| b.consume(bytes)).unwrap_or_default();
+        let own = self.download_limits.get_mut(id).map(|b| b.consume(bytes)).unwrap_or_default();
+        global.max(own)
+    }
+
     pub fn clear_download_history(&mut self) {
         self.downloads.retain(|_, d|
             !matches!(d.status, DownloadState::Completed | DownloadState::Failed | DownloadState::Cancelled)
         );
+        let downloads = &self.downloads;
+        self.configs.retain(|id, _| downloads.contains_key(id));
+        self.download_limits.retain(|id, _| downloads.contains_key(id));
         self.download_history.clear();
     }
+
+    fn pending(&self) -> Vec<PendingDownload> {
+        self.downloads
+            .values()
+            .filter(|s| matches!(s.status, DownloadState::Starting | DownloadState::Downloading | DownloadState::Retrying | DownloadState::Paused))
+            .filter_map(|s| self.configs.get(&s.id).map(|config| PendingDownload { status: s.clone(), config: config.clone() }))
+            .collect()
+    }
+}
+
+/// Write unfinished downloads to disk so they can be resumed after a restart
+pub async fn save_pending_downloads(manager: &Mutex<DownloadManager>) {
+    let _guard = PENDING_SAVE_LOCK.lock().await;
+    let pending = manager.lock().await.pending();
+    let result = async {
+        let path = get_app_data_path(PENDING_DOWNLOADS_FILE).await.map_err(|e| e.to_string())?;
+        let json = serde_json::to_string_pretty(&pending).map_err(|e| e.to_string())?;
+        let temp = partial_path(&path);
+        tokio::fs::write(&temp, json).await.map_err(|e| e.to_string())?;
+        tokio::fs::rename(&temp, &path).await.map_err(|e| e.to_string())
+    }.await;
+    if let Err(e) = result {
+        eprintln!("Failed to save pending downloads: {}", e);
+    }
+}
+
+async fn load_pending_downloads() -> Vec<PendingDownload> {
+    let path = match get_app_data_path(PENDING_DOWNLOADS_FILE).await.map_err(|e| e.to_string()) {
+        Ok(path) => path,
+        Err(_) => return Vec::new(),
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Ignoring unreadable pending downloads file: {}", e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Partial files that belong to unfinished downloads and must survive startup cleanup
+pub async fn pending_partial_files() -> HashSet<PathBuf> {
+    load_pending_downloads().await
+        .iter()
+        .flat_map(|pending| partial_files(&pending.status))
+        .collect()
+}
+
+fn partial_files(status: &DownloadStatus) -> Vec<PathBuf> {
+    status.files
+        .iter()
+        .filter_map(|file| Path::new(file).file_name())
+        .map(|name| partial_path(&Path::new(&status.destination).join(name)))
+        .flat_map(|part| [chunk_map_path(&part), part])
+        .collect()
+}
+
+/// Put downloads interrupted by the app closing back into the manager as paused,
+/// with their progress taken from what made it to disk. Returns how many came back.
+pub async fn restore_pending_downloads(manager: &Mutex<DownloadManager>) -> usize {
+    let mut restored = 0;
+    {
+        let mut download_manager = manager.lock().await;
+        for PendingDownload { mut status, config } in load_pending_downloads().await {
+            if !Path::new(&status.destination).is_dir() {
+                continue;
+            }
+
+            // Files are fetched in order, everything before the current one is done
+            let file_index = status.files.iter().position(|f| f == &status.current_file).unwrap_or(0);
+            let current_part = status.files
+                .get(file_index)
+                .and_then(|file| Path::new(file).file_name())
+                .map(|name| partial_path(&Path::new(&status.destination).join(name)));
+            // Accelerated transfers preallocate the .part, their chunk map knows what arrived
+            let downloaded = current_part
+                .and_then(|part| match load_chunk_map(&part) {
+                    Some(map) => Some(map.done_bytes()),
+                    None => std::fs::metadata(part).ok().map(|m| m.len()),
+                })
+                .unwrap_or(0);
+            let file_progress = if status.total_bytes > 0 {
+                (downloaded as f32 / status.total_bytes as f32).min(1.0)
+            } else {
+                0.0
+            };
+
+            status.status = DownloadState::Paused;
+            status.files_completed = file_index;
+            status.downloaded_bytes = downloaded;
+            status.progress = ((file_index as f32 + file_progress) / status.total_files.max(1) as f32 * 100.0) as u8;
+            status.speed = 0.0;
+            status.eta_seconds = None;
+            status.pause_start_time = None;
+            status.error = None;
+            status.message = Some("Interrupted when Llama-OS closed, resume to continue".to_string());
+
+            let id = status.id.clone();
+            download_manager.add_download(id.clone(), status);
+            download_manager.configs.insert(id.clone(), config);
+            download_manager.interrupted.insert(id);
+            restored += 1;
+        }
+    }
+
+    // Drops entries whose destination is gone
+    save_pending_downloads(manager).await;
+    if restored > 0 {
+        println!("Restored {} interrupted downloads", restored);
+    }
+    restored
+}
+
+/// Start the task again for a download restored from a previous session.
+/// Returns false when the download is not one of those, so it resumes normally.
+pub async fn restart_interrupted_download(
+    download_id: &str,
+    state: &AppState,
+    app_handle: tauri::AppHandle,
+) -> Result<bool, String> {
+    let (config, destination, files) = {
+        let mut download_manager = state.downloads.manager.lock().await;
+        if !download_manager.interrupted.remove(download_id) {
+            return Ok(false);
+        }
+        let config = download_manager.configs.get(download_id).cloned()
+            .ok_or("Download not found")?;
+        let status = download_manager.downloads.get_mut(download_id)
+            .ok_or("Download not found")?;
+        // Time spent closed doesn't count towards the download
+        status.status = DownloadState::Starting;
+        status.start_time = chrono::Utc::now();
+        status.elapsed_time = 0;
+        status.total_paused_time = 0;
+        status.message = Some("Resuming interrupted download".to_string());
+        (config, status.destination.clone(), status.files.clone())
+    };
+
+    spawn_download_task(download_id.to_string(), config, destination, files, state.clone(), app_handle);
+    Ok(true)
 }
 
 
@@ -164,7 +432,7 @@ pub async fn start_download(
 
     // Add to download manager
     {
-        let mut download_manager = state.download_manager.lock().await;
+        let mut download_manager = state.downloads.manager.lock().await;
         let download_status = DownloadStatus {
             id: download_id.clone(),
             status: DownloadState::Starting,
@@ -184,37 +452,24 @@ pub async fn start_download(
             pause_start_time: None,
             error: None,
             message: Some(format!("Starting download from {}", config.base_url)),
+            transferred_bytes: 0,
+            file_sources: HashMap::new(),
+            speed_limit: None,
+            alternatives: Vec::new(),
+            eta_seconds: None,
         };
 
         download_manager.add_download(download_id.clone(), download_status);
+        if !config.auto_extract {
+            download_manager.configs.insert(download_id.clone(), config.clone());
+        }
     }
 
     // Start the download task in the background
-    let state_clone = state.clone();
-    let download_id_for_task = download_id.clone();
-    let config_clone = config.clone();
-    let app_handle_clone = app_handle.clone();
-
-    tokio::spawn(async move {
-        if let Err(e) = execute_download(
-            download_id_for_task.clone(),
-            config_clone,
-            final_destination,
-            files_to_download,
-            &state_clone,
-            app_handle,
-        ).await {
-            // Update download status to failed
-            let mut download_manager = state_clone.download_manager.lock().await;
-            if let Some(status) = download_manager.downloads.get_mut(&download_id_for_task) {
-                status.status = DownloadState::Failed;
-                status.error = Some(e.to_string());
-            }
-        }
-    });
+    spawn_download_task(download_id.clone(), config.clone(), final_destination, files_to_download, state.clone(), app_handle.clone());
 
     // Emit an event to open the download manager window
-    let _ = app_handle_clone.emit("open-download-manager", ());
+    let _ = app_handle.emit("open-download-manager", ());
 
     Ok(DownloadStartResult {
         download_id,
@@ -222,6 +477,70 @@ pub async fn start_download(
     })
 }
 
+fn spawn_download_task(
+    download_id: String,
+    config: DownloadConfig,
+    destination: String,
+    files: Vec<String>,
+    state: AppState,
+    app_handle: tauri::AppHandle,
+) {
+    tokio::spawn(async move {
+        save_pending_downloads(&state.downloads.manager).await;
+        let base_url = config.base_url.clone();
+        let requested_files = files.clone();
+        let mut attempt = 0;
+        let result = loop {
+            let result = execute_download(
+                download_id.clone(),
+                config.clone(),
+                destination.clone(),
+                files.clone(),
+                &state,
+                app_handle.clone(),
+            ).await;
+            // Finished files and .part files stay on disk, the next attempt resumes from them
+            match result {
+                Err(e) if attempt < DOWNLOAD_RETRIES && e.is_transient() => {
+                    attempt += 1;
+                    if !wait_before_retry(&download_id, attempt, &e.to_string(), &state, &app_handle).await {
+                        break Err(e);
+                    }
+                }
+                result => break result,
+            }
+        };
+        if let Err(e) = result {
+            let cancelled = {
+                let download_manager = state.downloads.manager.lock().await;
+                download_manager.downloads.get(&download_id).is_some_and(|s| matches!(s.status, DownloadState::Cancelled))
+            };
+            let alternatives = if cancelled {
+                Vec::new()
+            } else {
+                alternatives_after_failure(&base_url, &requested_files, e.status()).await
+            };
+
+            // Update download status to failed
+            let mut download_manager = state.downloads.manager.lock().await;
+            if let Some(status) = download_manager.downloads.get_mut(&download_id) {
+                if !matches!(status.status, DownloadState::Cancelled) {
+                    status.status = DownloadState::Failed;
+                }
+                status.error = Some(e.to_string());
+                status.alternatives = alternatives;
+                status.speed = 0.0;
+                status.eta_seconds = None;
+            }
+        }
+        
+        // Listeners only hear about progress, the last event carries the final state
+        emit_progress(&download_id, &state, &app_handle).await;
+        save_pending_downloads(&state.downloads.manager).await;
+        record_download_outcome(&download_id, &state).await;
+    });
+}
+
 async fn execute_download(
     download_id: String,
     config: DownloadConfig,
@@ -229,22 +548,30 @@ async fn execute_download(
     files: Vec<String>,
     state: &AppState,
     app_handle: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<(), DownloadFailure> {
     use tokio::fs::File;
     use tokio::io::AsyncWriteExt;
     use std::path::Path;
     use futures_util::StreamExt;
     use tauri::Emitter;
-    use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, USER_AGENT};
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, RANGE, USER_AGENT};
+    use reqwest::StatusCode;
 
     let client = reqwest::Client::new();
     let mut last_emit_time = std::time::Instant::now();
-    let mut last_progress = 0u8;
+    let accelerated = state.config.lock().await.accelerated_transfer.clone();
+    
+    // Pick the fastest Hub endpoint once per download, the rest serve as failover
+    let endpoints = crate::mirrors::ranked_endpoints(state).await;
+    
+    // Finished files stay as .part until every file is in, so a split model
+    // never shows up on the desktop with shards missing
+    let mut completed_parts: Vec<(std::path::PathBuf, std::path::PathBuf)> = Vec::new();
 
     for (file_index, file_path) in files.iter().enumerate() {
         // Check if download was cancelled before starting each file
         if check_cancellation_status(&download_id, state).await? {
-            return Err("Download cancelled by user".to_string());
+            return Err("Download cancelled by user".to_string().into());
         }
 
         // Wait if paused
@@ -252,7 +579,7 @@ async fn execute_download(
 
         // Update current file
         {
-            let mut download_manager = state.download_manager.lock().await;
+            let mut download_manager = state.downloads.manager.lock().await;
             if let Some(status) = download_manager.downloads.get_mut(&download_id) {
                 status.current_file = file_path.clone();
                 status.status = DownloadState::Downloading;
@@ -269,15 +596,15 @@ async fn execute_download(
         };
 
         let file_name = Path::new(file_path).file_name()
-            .ok_or("Invalid file path")?
+            .ok_or_else(|| "Invalid file path".to_string())?
             .to_string_lossy()
             .to_string();
         let final_path = Path::new(&destination_folder).join(&file_name);
-        let temp_path = Path::new(&destination_folder).join(format!("{}.download", file_name));
+        let temp_path = partial_path(&final_path);
 
         // Check if final file already exists
         if final_path.exists() {
-            let mut download_manager = state.download_manager.lock().await;
+            let mut download_manager = state.downloads.manager.lock().await;
             if let Some(status) = download_manager.downloads.get_mut(&download_id) {
                 status.files_completed = file_index + 1;
                 status.progress = ((file_index + 1) as f32 / files.len() as f32 * 100.0) as u8;
@@ -307,32 +634,123 @@ async fn execute_download(
             );
         }
 
-        let request = client.get(&download_url).headers(headers_map);
+        // A .part left by an earlier session continues where it stopped, archives start over
+        let is_archive = config.auto_extract && file_name.to_lowercase().ends_with(".zip");
+        if accelerated.enabled && !is_archive {
+            let ranged = RangedFile {
+                download_id: &download_id,
+                file_path,
+                temp_path: &temp_path,
+                urls: crate::mirrors::candidate_urls(&download_url, &endpoints),
+                headers: headers_map.clone(),
+                file_index,
+                file_count: files.len(),
+            };
+            let connections = if accelerated.connections == 0 { DEFAULT_TRANSFER_CONNECTIONS } else { accelerated.connections };
+            if accelerated_transfer(&client, ranged, connections, state, &app_handle).await? {
+                completed_parts.push((temp_path.clone(), final_path.clone()));
+                let mut download_manager = state.downloads.manager.lock().await;
+                if let Some(status) = download_manager.downloads.get_mut(&download_id) {
+                    status.files_completed = file_index + 1;
+                    status.progress = ((file_index + 1) as f32 / files.len() as f32 * 100.0) as u8;
+                }
+                continue;
+            }
+        }
 
-        // Start downloading to temp file
-        let response = request
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        // A preallocated .part from an accelerated transfer can't be appended to, start over
+        if chunk_map_path(&temp_path).exists() {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            let _ = tokio::fs::remove_file(chunk_map_path(&temp_path)).await;
+        }
 
-        if !response.status().is_success() {
-            return Err(format!("Failed to download {}: {}", file_path, response.status()));
+        let resume_from = if is_archive {
+            0
+        } else {
+            tokio::fs::metadata(&temp_path).await.map(|m| m.len()).unwrap_or(0)
+        };
+        if resume_from > 0 {
+            if let Ok(range) = HeaderValue::from_str(&format!("bytes={}-", resume_from)) {
+                headers_map.insert(RANGE, range);
+            }
         }
 
-        let total_size = response.content_length().unwrap_or(0);
+        // Start downloading to temp file, falling through to the next endpoint on failure
+        let mut served = None;
+        let mut already_complete = false;
+        let mut last_error = None;
+        for url in crate::mirrors::candidate_urls(&download_url, &endpoints) {
+            let request = crate::huggingface::authorize(client.get(&url), &url).headers(headers_map.clone());
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    served = Some((url, response));
+                    break;
+                }
+                // Nothing past the end, the file finished before the app closed
+                Ok(response) if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && resume_from > 0 => {
+                    already_complete = true;
+                    break;
+                }
+                Ok(response) => {
+                    let message = format!("Failed to download {}: {}", file_path, response.status());
+                    last_error = Some(DownloadFailure::Status(response.status(), message));
+                }
+                Err(e) => last_error = Some(DownloadFailure::Network(e.to_string())),
+            }
+            if let Some(e) = &last_error {
+                println!("Download of {} from {} failed: {}", file_path, url, e);
+            }
+        }
+        if already_complete {
+            println!("{} was already fully downloaded", file_path);
+            completed_parts.push((temp_path.clone(), final_path.clone()));
+            let mut download_manager = state.downloads.manager.lock().await;
+            if let Some(status) = download_manager.downloads.get_mut(&download_id) {
+                status.files_completed = file_index + 1;
+                status.progress = ((file_index + 1) as f32 / files.len() as f32 * 100.0) as u8;
+            }
+            continue;
+        }
+        let (served_url, response) = served
+            .ok_or_else(|| last_error.unwrap_or_else(|| DownloadFailure::Fatal(format!("No source for {}", file_path))))?;
+
+        // A server that ignores the range sends the whole file again
+        let offset = if resume_from > 0 && response.status() == StatusCode::PARTIAL_CONTENT {
+            println!("Resuming {} at byte {}", file_path, resume_from);
+            resume_from
+        } else {
+            0
+        };
+        let total_size = response.content_length().map(|len| len + offset).unwrap_or(0);
 
         // Update total bytes
         {
-            let mut download_manager = state.download_manager.lock().await;
+            let mut download_manager = state.downloads.manager.lock().await;
             if let Some(status) = download_manager.downloads.get_mut(&download_id) {
                 status.total_bytes = total_size;
+                status.file_sources.insert(file_path.clone(), served_url);
             }
         }
+        save_pending_downloads(&state.downloads.manager).await;
 
-        // Create the temp file
-        let mut file = File::create(&temp_path).await
-            .map_err(|e| e.to_string())?;
-        let mut downloaded = 0u64;
+        // Create the temp file, or append to the one being resumed
+        let mut file = if offset > 0 {
+            tokio::fs::OpenOptions::new().append(true).open(&temp_path).await
+        } else {
+            File::create(&temp_path).await
+        }
+        .map_err(|e| format!("Failed to create file: {}", e))?;
+        let mut downloaded = offset;
+        
+        // Archives are unpacked while they download
+        let stream_guard = is_archive.then(|| {
+            let progress = Arc::new(crate::archive_stream::StreamProgress::default());
+            let task = spawn_streaming_extraction(
+                temp_path.clone(), destination_folder.clone(), download_url.clone(), total_size,
+                progress.clone(), download_id.clone(), app_handle.clone(),
+            );
+            (crate::archive_stream::CancelOnDrop(progress), task)
+        });
         let mut stream = response.bytes_stream();
         let start_time = std::time::Instant::now();
 
@@ -340,27 +758,32 @@ async fn execute_download(
             // Check for cancellation during download
             if check_cancellation_status(&download_id, state).await? {
                 let _ = tokio::fs::remove_file(&temp_path).await;
-                return Err("Download cancelled by user".to_string());
+                return Err("Download cancelled by user".to_string().into());
             }
 
             // Handle pause
             wait_if_paused(&download_id, state).await?;
 
-            let chunk = chunk.map_err(|e| e.to_string())?;
+            let chunk = chunk.map_err(|e| DownloadFailure::Network(e.to_string()))?;
             file.write_all(&chunk).await
-                .map_err(|e| e.to_string())?;
+                .map_err(|e| format!("Failed to write file: {}", e))?;
             downloaded += chunk.len() as u64;
+            if let Some((guard, _)) = &stream_guard {
+                guard.0.written.store(downloaded, std::sync::atomic::Ordering::Release);
+            }
 
             // Calculate speed and elapsed time
             let elapsed = start_time.elapsed().as_secs_f64();
-            let speed = if elapsed > 0.0 { downloaded as f64 / elapsed } else { 0.0 };
+            let speed = if elapsed > 0.0 { (downloaded - offset) as f64 / elapsed } else { 0.0 };
 
             // Update progress
-            {
-                let mut download_manager = state.download_manager.lock().await;
+            let throttle_delay = {
+                let mut download_manager = state.downloads.manager.lock().await;
                 if let Some(status) = download_manager.downloads.get_mut(&download_id) {
                     status.downloaded_bytes = downloaded;
+                    status.transferred_bytes += chunk.len() as u64;
                     status.speed = speed;
+                    status.update_eta();
                     
                     // Calculate elapsed time considering pauses
                     let current_elapsed = chrono::Utc::now().signed_duration_since(status.start_time).num_seconds();
@@ -372,43 +795,74 @@ async fn execute_download(
                         status.progress = overall_progress as u8;
                     }
                 }
-            }
-            
-            // Emit real-time progress update (throttled to every 500ms or 1% progress)
-            let current_time = std::time::Instant::now();
-            let time_since_last_emit = current_time.duration_since(last_emit_time).as_millis();
-            let current_progress = if total_size > 0 {
-                let file_progress = (downloaded as f32 / total_size as f32) * 100.0;
-                let overall_progress = ((file_index as f32 + file_progress / 100.0) / files.len() as f32) * 100.0;
-                overall_progress as u8
-            } else {
-                0
+                download_manager.throttle(&download_id, chunk.len() as u64)
             };
+            if !throttle_delay.is_zero() {
+                tokio::time::sleep(throttle_delay).await;
+            }
             
-            // Emit only if 500ms have passed or progress changed by at least 1%
-            if time_since_last_emit >= 500 || current_progress.abs_diff(last_progress) >= 1 {
-                last_emit_time = current_time;
-                last_progress = current_progress;
-                
-                //println!("Emitting download progress event for {}: {}%", download_id, current_progress);
-                
-                // Emit directly without spawning a new task
-                let download_manager = state.download_manager.lock().await;
-                if let Some(status) = download_manager.downloads.get(&download_id) {
-                    let _ = app_handle.emit("download-progress", status.clone());
-                }
+            // Push progress to the frontend at a steady rate, however small the chunks are
+            if last_emit_time.elapsed() >= PROGRESS_EMIT_INTERVAL {
+                last_emit_time = std::time::Instant::now();
+                emit_progress(&download_id, state, &app_handle).await;
             }
         }
 
-        // Move temp file to final location
-        tokio::fs::rename(&temp_path, &final_path).await
-            .map_err(|e| format!("Failed to finalize file: {}", e))?;
+        file.flush().await
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        drop(file);
 
-        // Extract if requested and file is a zip
-        if config.auto_extract && file_name.to_lowercase().ends_with(".zip") {
+        // Let the extractor catch up with the rest of the archive
+        let mut needs_extraction = false;
+        if let Some((guard, task)) = stream_guard {
+            guard.0.finished.store(true, std::sync::atomic::Ordering::Release);
+            {
+                let mut download_manager = state.downloads.manager.lock().await;
+                if let Some(status) = download_manager.downloads.get_mut(&download_id) {
+                    status.status = DownloadState::Extracting;
+                    status.message = Some("Finishing extraction...".to_string());
+                }
+            }
+            let streamed = task.await.unwrap_or_else(|e| Err(StreamError::Failed(e.to_string())));
+            match streamed {
+                Ok(entries) => {
+                    println!("Extracted {} entries from {} while downloading", entries, file_name);
+                    let _ = tokio::fs::remove_file(&temp_path).await;
+                    if let Err(e) = crate::llamacpp_manager::normalize_build_layout(Path::new(&destination_folder)) {
+                        eprintln!("Failed to prepare extracted build in {}: {}", destination_folder, e);
+                    }
+                }
+                Err(StreamError::Unsupported(reason)) => {
+                    println!("Streaming extraction not possible for {} ({}), extracting after download", file_name, reason);
+                    let _ = tokio::fs::remove_file(crate::archive_stream::journal_path(&temp_path)).await;
+                    needs_extraction = true;
+                }
+                Err(StreamError::Failed(e)) => {
+                    // Don't fail the download, keep the archive like a failed regular extraction
+                    {
+                        let mut download_manager = state.downloads.manager.lock().await;
+                        if let Some(status) = download_manager.downloads.get_mut(&download_id) {
+                            status.message = Some(format!("Downloaded but extraction failed: {}", e));
+                        }
+                    }
+                    let _ = tokio::fs::remove_file(crate::archive_stream::journal_path(&temp_path)).await;
+                    tokio::fs::rename(&temp_path, &final_path).await
+                        .map_err(|e| format!("Failed to finalize file: {}", e))?;
+                }
+            }
+        } else {
+            completed_parts.push((temp_path.clone(), final_path.clone()));
+        }
+
+        // Extract if streaming couldn't handle the archive
+        if needs_extraction {
+            // Archives are extracted right away, only their contents end up visible
+            tokio::fs::rename(&temp_path, &final_path).await
+                .map_err(|e| format!("Failed to finalize file: {}", e))?;
+            
             // Update status to extracting
             {
-                let mut download_manager = state.download_manager.lock().await;
+                let mut download_manager = state.downloads.manager.lock().await;
                 if let Some(status) = download_manager.downloads.get_mut(&download_id) {
                     status.status = DownloadState::Extracting;
                     status.message = Some("Extracting downloaded file...".to_string());
@@ -417,26 +871,29 @@ async fn execute_download(
             
             // Emit extraction start event
             //println!("Emitting extraction start event for {}", download_id);
-            let download_manager = state.download_manager.lock().await;
+            let download_manager = state.downloads.manager.lock().await;
             if let Some(status) = download_manager.downloads.get(&download_id) {
                 let _ = app_handle.emit("download-progress", status.clone());
             }
             
             if let Err(e) = extract_zip(&final_path, &destination_folder, &download_id, &app_handle).await {
                 // Don't fail the download, just log the extraction error
-                let mut download_manager = state.download_manager.lock().await;
+                let mut download_manager = state.downloads.manager.lock().await;
                 if let Some(status) = download_manager.downloads.get_mut(&download_id) {
                     status.message = Some(format!("Downloaded but extraction failed: {}", e));
                 }
             } else {
                 // Remove the zip file after successful extraction
                 let _ = tokio::fs::remove_file(&final_path).await;
+                if let Err(e) = crate::llamacpp_manager::normalize_build_layout(Path::new(&destination_folder)) {
+                    eprintln!("Failed to prepare extracted build in {}: {}", destination_folder, e);
+                }
             }
         }
 
         // Mark file as completed
         {
-            let mut download_manager = state.download_manager.lock().await;
+            let mut download_manager = state.downloads.manager.lock().await;
             if let Some(status) = download_manager.downloads.get_mut(&download_id) {
                 status.files_completed = file_index + 1;
                 status.progress = ((file_index + 1) as f32 / files.len() as f32 * 100.0) as u8;
@@ -444,15 +901,24 @@ async fn execute_download(
         }
     }
 
+    // Move every finished file into place
+    for (temp_path, final_path) in &completed_parts {
+        tokio::fs::rename(temp_path, final_path).await
+            .map_err(|e| format!("Failed to finalize file: {}", e))?;
+    }
+
     // Mark download as completed
     {
-        let mut download_manager = state.download_manager.lock().await;
+        let mut download_manager = state.downloads.manager.lock().await;
         if let Some(status) = download_manager.downloads.get_mut(&download_id) {
             status.status = DownloadState::Completed;
             status.progress = 100;
+            status.speed = 0.0;
+            status.eta_seconds = Some(0);
             status.message = Some(format!("Download completed from {}", config.base_url));
         }
     }
+    emit_progress(&download_id, state, &app_handle).await;
 
     // Emit event to frontend
     app_handle.emit("download-complete", ()).unwrap();
@@ -462,7 +928,442 @@ async fn execute_download(
 
 
 
+/// Completed ranges of a file fetched by `accelerated_transfer`, stored next to the .part
+/// so an interrupted transfer only fetches the ranges it is still missing
+#[derive(Debug, Serialize, Deserialize)]
+struct ChunkMap {
+    total: u64,
+    chunk_size: u64,
+    done: Vec<bool>,
+}
+
+impl ChunkMap {
+    fn new(total: u64) -> Self {
+        let count = total.div_ceil(ACCELERATED_CHUNK_SIZE) as usize;
+        Self { total, chunk_size: ACCELERATED_CHUNK_SIZE, done: vec![false; count] }
+    }
+
+    fn range(&self, index: usize) -> (u64, u64) {
+        let start = index as u64 * self.chunk_size;
+        (start, (start + self.chunk_size).min(self.total) - 1)
+    }
+
+    fn done_bytes(&self) -> u64 {
+        (0..self.done.len())
+            .filter(|&index| self.done[index])
+            .map(|index| {
+                let (start, end) = self.range(index);
+                end - start + 1
+            })
+            .sum()
+    }
+}
+
+fn chunk_map_path(temp_path: &Path) -> PathBuf {
+    let mut name = temp_path.as_os_str().to_os_string();
+    name.push(CHUNK_MAP_SUFFIX);
+    PathBuf::from(name)
+}
+
+fn load_chunk_map(temp_path: &Path) -> Option<ChunkMap> {
+    let contents = std::fs::read_to_string(chunk_map_path(temp_path)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Where one file of an accelerated transfer comes from and goes to
+struct RangedFile<'a> {
+    download_id: &'a str,
+    file_path: &'a str,
+    temp_path: &'a Path,
+    urls: Vec<String>,
+    headers: reqwest::header::HeaderMap,
+    file_index: usize,
+    file_count: usize,
+}
+
+/// Shared by the workers of one accelerated transfer
+struct RangedProgress {
+    downloaded: std::sync::atomic::AtomicU64,
+    started: std::time::Instant,
+    resumed_bytes: u64,
+    last_emit: std::sync::Mutex<std::time::Instant>,
+}
+
+/// Fetch a file as many parallel range requests written straight to their offset in a
+/// preallocated .part, the way hf_transfer does. Returns false without touching anything
+/// when the server doesn't serve ranges or the file is too small to gain from it, so the
+/// caller falls back to a single stream.
+async fn accelerated_transfer(
+    client: &reqwest::Client,
+    file: RangedFile<'_>,
+    connections: usize,
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+) -> Result<bool, DownloadFailure> {
+    use reqwest::header::{HeaderValue, RANGE};
+    use reqwest::StatusCode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // A .part without a chunk map was written sequentially, let that path resume it
+    let existing = load_chunk_map(file.temp_path);
+    if existing.is_none() && file.temp_path.exists() {
+        return Ok(false);
+    }
+
+    // Probe with a one-byte range, the Content-Range tells the full size
+    let mut probed = None;
+    for url in &file.urls {
+        let mut headers = file.headers.clone();
+        headers.insert(RANGE, HeaderValue::from_static("bytes=0-0"));
+        let request = crate::huggingface::authorize(client.get(url), url).headers(headers);
+        match request.send().await {
+            Ok(response) if response.status() == StatusCode::PARTIAL_CONTENT => {
+                let total = response.headers()
+                    .get(reqwest::header::CONTENT_RANGE)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.rsplit('/').next())
+                    .and_then(|total| total.parse::<u64>().ok());
+                if let Some(total) = total {
+                    // Workers go straight to where the Hub redirected, saving a round trip per range
+                    probed = Some((url.clone(), response.url().to_string(), total));
+                }
+                break;
+            }
+            Ok(response) if response.status().is_success() => break, // Ranges ignored
+            Ok(_) | Err(_) => continue,
+        }
+    }
+    let Some((served_url, resolved_url, total)) = probed else {
+        return Ok(false);
+    };
+    if total < ACCELERATED_CHUNK_SIZE * 2 {
+        return Ok(false);
+    }
+
+    let map = existing.filter(|map| map.total == total && map.chunk_size == ACCELERATED_CHUNK_SIZE).unwrap_or_else(|| ChunkMap::new(total));
+    let pending: Vec<usize> = (0..map.done.len()).filter(|&index| !map.done[index]).collect();
+    let resumed_bytes = map.done_bytes();
+    if resumed_bytes > 0 {
+        println!("Resuming accelerated transfer of {}, {} of {} ranges left", file.file_path, pending.len(), map.done.len());
+    }
+
+    {
+        let part = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(file.temp_path)
+            .map_err(|e| format!("Failed to create {}: {}", file.temp_path.display(), e))?;
+        part.set_len(total).map_err(|e| format!("Failed to allocate {}: {}", file.temp_path.display(), e))?;
+    }
+    {
+        let mut download_manager = state.downloads.manager.lock().await;
+        if let Some(status) = download_manager.downloads.get_mut(file.download_id) {
+            status.total_bytes = total;
+            status.downloaded_bytes = resumed_bytes;
+            status.file_sources.insert(file.file_path.to_string(), served_url.clone());
+        }
+    }
+    save_pending_downloads(&state.downloads.manager).await;
+
+    let map = Mutex::new(map);
+    let next = AtomicUsize::new(0);
+    let progress = RangedProgress {
+        downloaded: std::sync::atomic::AtomicU64::new(resumed_bytes),
+        started: std::time::Instant::now(),
+        resumed_bytes,
+        last_emit: std::sync::Mutex::new(std::time::Instant::now()),
+    };
+
+    let worker = || async {
+        let mut part = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(file.temp_path)
+            .await
+            .map_err(|e| DownloadFailure::Fatal(format!("Failed to create {}: {}", file.temp_path.display(), e)))?;
+        loop {
+            let Some(&index) = pending.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                return Ok::<(), DownloadFailure>(());
+            };
+            let range = map.lock().await.range(index);
+            let mut attempt = 0;
+            loop {
+                // The first try uses the redirect target, retries go through the Hub again
+                // in case the signed URL expired
+                let url = if attempt == 0 { &resolved_url } else { &served_url };
+                // fetch_range holds a transfer slot only while bytes are moving, the backoff below waits without one
+                match fetch_range(client, url, range, &mut part, &file, &progress, state, app_handle).await {
+                    Ok(()) => break,
+                    Err(RangeError::Stopped(e)) => return Err(DownloadFailure::Fatal(e)),
+                    Err(RangeError::Failed(e)) if attempt < RANGE_RETRIES => {
+                        attempt += 1;
+                        println!("Range {}-{} of {} failed ({}), retrying", range.0, range.1, file.file_path, e);
+                        tokio::time::sleep(std::time::Duration::from_secs(attempt as u64)).await;
+                    }
+                    Err(RangeError::Failed(e)) => return Err(e.context(&format!("Failed to download {}", file.file_path))),
+                }
+            }
+
+            let mut map = map.lock().await;
+            map.done[index] = true;
+            if let Ok(contents) = serde_json::to_string(&*map) {
+                let _ = tokio::fs::write(chunk_map_path(file.temp_path), contents).await;
+            }
+        }
+    };
+    let workers: Vec<_> = (0..connections.clamp(1, MAX_TRANSFER_CONNECTIONS)).map(|_| worker()).collect();
+    let result = futures_util::future::try_join_all(workers).await;
+
+    if let Err(e) = result {
+        if check_cancellation_status(file.download_id, state).await.unwrap_or(false) {
+            let _ = tokio::fs::remove_file(file.temp_path).await;
+            let _ = tokio::fs::remove_file(chunk_map_path(file.temp_path)).await;
+        }
+        return Err(e);
+    }
+    let _ = tokio::fs::remove_file(chunk_map_path(file.temp_path)).await;
+    Ok(true)
+}
+
+enum RangeError {
+    Stopped(String), // Cancelled or the download is gone, no point retrying
+    Failed(DownloadFailure),
+}
+
+/// Stream one byte range into its place in the .part
+#[allow(clippy::too_many_arguments)]
+async fn fetch_range(
+    client: &reqwest::Client,
+    url: &str,
+    (start, end): (u64, u64),
+    part: &mut tokio::fs::File,
+    file: &RangedFile<'_>,
+    progress: &RangedProgress,
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+) -> Result<(), RangeError> {
+    use futures_util::StreamExt;
+    use reqwest::header::{HeaderValue, RANGE};
+    use std::sync::atomic::Ordering;
+    use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+    let acquire_slot = || async {
+        TRANSFER_SLOTS.acquire().await.map_err(|e| RangeError::Stopped(e.to_string()))
+    };
+    let mut slot = Some(acquire_slot().await?);
+
+    let mut headers = file.headers.clone();
+    if let Ok(range) = HeaderValue::from_str(&format!("bytes={}-{}", start, end)) {
+        headers.insert(RANGE, range);
+    }
+    let response = crate::huggingface::authorize(client.get(url), url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(|e| RangeError::Failed(DownloadFailure::Network(e.to_string())))?;
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(RangeError::Failed(DownloadFailure::Status(response.status(), response.status().to_string())));
+    }
+
+    let write_error = |e: std::io::Error| RangeError::Stopped(format!("Failed to write {}: {}", file.temp_path.display(), e));
+    part.seek(std::io::SeekFrom::Start(start)).await.map_err(write_error)?;
+    let mut written = 0u64;
+    let mut stream = response.bytes_stream();
+    let outcome = async {
+        while let Some(chunk) = stream.next().await {
+            if check_cancellation_status(file.download_id, state).await.map_err(RangeError::Stopped)? {
+                return Err(RangeError::Stopped("Download cancelled by user".to_string()));
+            }
+            // A paused download gives its slot back so other downloads can use it meanwhile
+            if is_paused(file.download_id, state).await {
+                slot = None;
+                wait_if_paused(file.download_id, state).await.map_err(RangeError::Stopped)?;
+                slot = Some(acquire_slot().await?);
+            }
+
+            let chunk = chunk.map_err(|e| RangeError::Failed(DownloadFailure::Network(e.to_string())))?;
+            // A server sending more than asked would overwrite the next range
+            if written + chunk.len() as u64 > end - start + 1 {
+                return Err(RangeError::Failed(DownloadFailure::Network("Server sent more than the requested range".to_string())));
+            }
+            part.write_all(&chunk).await.map_err(write_error)?;
+            written += chunk.len() as u64;
+            let downloaded = progress.downloaded.fetch_add(chunk.len() as u64, Ordering::Relaxed) + chunk.len() as u64;
+            report_range_progress(file, progress, downloaded, chunk.len() as u64, state, app_handle).await;
+        }
+        if written != end - start + 1 {
+            return Err(RangeError::Failed(DownloadFailure::Network(format!("Range ended after {} of {} bytes", written, end - start + 1))));
+        }
+        part.flush().await.map_err(write_error)
+    }
+    .await;
+    drop(slot);
+
+    // A retried range starts over, take back what it counted
+    if outcome.is_err() {
+        progress.downloaded.fetch_sub(written, Ordering::Relaxed);
+    }
+    outcome
+}
+
+async fn report_range_progress(
+    file: &RangedFile<'_>,
+    progress: &RangedProgress,
+    downloaded: u64,
+    received: u64,
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+) {
+    let throttle_delay = {
+        let mut download_manager = state.downloads.manager.lock().await;
+        if let Some(status) = download_manager.downloads.get_mut(file.download_id) {
+            let elapsed = progress.started.elapsed().as_secs_f64();
+            status.downloaded_bytes = downloaded;
+            status.transferred_bytes += received;
+            status.speed = if elapsed > 0.0 { downloaded.saturating_sub(progress.resumed_bytes) as f64 / elapsed } else { 0.0 };
+            status.update_eta();
+            status.elapsed_time = chrono::Utc::now().signed_duration_since(status.start_time).num_seconds() - status.total_paused_time;
+            if status.total_bytes > 0 {
+                let file_progress = downloaded as f32 / status.total_bytes as f32;
+                status.progress = ((file.file_index as f32 + file_progress) / file.file_count as f32 * 100.0) as u8;
+            }
+        }
+        download_manager.throttle(file.download_id, received)
+    };
+    if !throttle_delay.is_zero() {
+        tokio::time::sleep(throttle_delay).await;
+    }
+
+    let due = {
+        let mut last_emit = progress.last_emit.lock().unwrap();
+        let due = last_emit.elapsed() >= PROGRESS_EMIT_INTERVAL;
+        if due {
+            *last_emit = std::time::Instant::now();
+        }
+        due
+    };
+    if due {
+        emit_progress(file.download_id, state, app_handle).await;
+    }
+}
+
+/// Send the current status of a download as a `download-progress` event
+async fn emit_progress(download_id: &str, state: &AppState, app_handle: &tauri::AppHandle) {
+    let download_manager = state.downloads.manager.lock().await;
+    if let Some(status) = download_manager.downloads.get(download_id) {
+        let _ = app_handle.emit("download-progress", status.clone());
+    }
+}
+
+/// Count a failed download or hash mismatch of a Hub file, true once it has failed repeatedly
+pub fn record_file_failure(repo_id: &str, file: &str) -> bool {
+    let mut failures = FILE_FAILURES.lock().unwrap();
+    let count = failures.entry(format!("{}/{}", repo_id, file)).or_insert(0);
+    *count += 1;
+    *count >= REPEATED_FAILURE_THRESHOLD
+}
+
+/// Other quantizations of the repo when a Hub file 404s or has failed repeatedly
+async fn alternatives_after_failure(base_url: &str, files: &[String], status: Option<reqwest::StatusCode>) -> Vec<QuantAlternative> {
+    let Some(repo_id) = base_url
+        .strip_prefix("https://huggingface.co/")
+        .and_then(|rest| rest.strip_suffix("/resolve/main"))
+    else {
+        return Vec::new();
+    };
+    let missing = status == Some(reqwest::StatusCode::NOT_FOUND);
+    let mut repeated = false;
+    for file in files {
+        repeated |= record_file_failure(repo_id, file);
+    }
+    if !missing && !repeated {
+        return Vec::new();
+    }
+    crate::huggingface::quantization_alternatives(repo_id, files).await
+}
+
+/// Append the final state of a download to the audit log
+async fn record_download_outcome(download_id: &str, state: &AppState) {
+    let status = {
+        let download_manager = state.downloads.manager.lock().await;
+        download_manager.downloads.get(download_id).cloned()
+    };
+    let Some(status) = status else { return };
+    
+    let finished_at = chrono::Utc::now();
+    let entry = crate::download_log::DownloadLogEntry {
+        download_id: status.id,
+        source_url: status.source_url,
+        destination: status.destination,
+        files: status.files,
+        bytes_transferred: status.transferred_bytes,
+        duration_secs: finished_at.signed_duration_since(status.start_time).num_seconds() - status.total_paused_time,
+        outcome: format!("{:?}", status.status),
+        error: status.error,
+        started_at: status.start_time,
+        finished_at,
+        file_sources: status.file_sources,
+    };
+    
+    if let Err(e) = crate::download_log::append_entry(&entry).await {
+        eprintln!("Failed to write download audit log: {}", e);
+    }
+}
+
+/// Free bytes on the disk holding `path`, which doesn't have to exist yet.
+/// Blocking: lists the mounted disks.
+pub fn free_space_for(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let existing = existing.canonicalize().unwrap_or_else(|_| existing.to_path_buf());
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .iter()
+        .filter(|disk| existing.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// Bytes still to fetch for each (file, size) pair, counting finished files and partial
+/// downloads already in the destination. Blocking: stats the files.
+pub fn remaining_bytes(files: &[(String, u64)], destination: &Path) -> u64 {
+    files
+        .iter()
+        .map(|(file, size)| {
+            let Some(name) = Path::new(file).file_name() else { return *size };
+            let final_path = destination.join(name);
+            if final_path.exists() {
+                return 0;
+            }
+            let partial = std::fs::metadata(partial_path(&final_path)).map(|m| m.len()).unwrap_or(0);
+            size.saturating_sub(partial)
+        })
+        .sum()
+}
+
 // Helper functions
+/// Suffix for files that are still being written, renamed away once the download completes
+pub const PARTIAL_SUFFIX: &str = ".part";
+
+/// Give an extracted file the mode it had in the archive, so binaries stay executable on
+/// macOS and Linux. Entries without a mode and non-Unix hosts keep the defaults
+pub fn restore_unix_mode(path: &Path, mode: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o7777)) {
+            eprintln!("Failed to set permissions on {:?}: {}", path, e);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+}
+
+pub fn partial_path(final_path: &std::path::Path) -> std::path::PathBuf {
+    let mut name = final_path.as_os_str().to_os_string();
+    name.push(PARTIAL_SUFFIX);
+    std::path::PathBuf::from(name)
+}
+
 fn generate_download_id(config: &DownloadConfig) -> String {
     let filename = if config.files.is_empty() {
         extract_filename_from_url(&config.base_url)
@@ -494,8 +1395,97 @@ fn extract_filename_from_url(url: &str) -> Result<String, String> {
     Ok(filename)
 }
 
+/// Why a download attempt failed, which decides whether it is worth another one
+#[derive(Debug)]
+enum DownloadFailure {
+    Status(reqwest::StatusCode, String), // The server answered with an error status
+    Network(String), // Connection reset, timeout or a body cut short
+    Fatal(String), // Cancellation, local disk errors and anything else that would fail the same way again
+}
+
+impl DownloadFailure {
+    /// Connection trouble, timeouts, rate limits and server errors are retried
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Status(status, _) => matches!(status.as_u16(), 408 | 429) || status.is_server_error(),
+            Self::Network(_) => true,
+            Self::Fatal(_) => false,
+        }
+    }
+
+    /// HTTP status the server answered with, when that is what failed
+    fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Self::Status(status, _) => Some(*status),
+            _ => None,
+        }
+    }
+
+    fn context(self, prefix: &str) -> Self {
+        match self {
+            Self::Status(status, message) => Self::Status(status, format!("{}: {}", prefix, message)),
+            Self::Network(message) => Self::Network(format!("{}: {}", prefix, message)),
+            Self::Fatal(message) => Self::Fatal(format!("{}: {}", prefix, message)),
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Status(_, message) | Self::Network(message) | Self::Fatal(message) => f.write_str(message),
+        }
+    }
+}
+
+// Errors without a known cause are not retried
+impl From<String> for DownloadFailure {
+    fn from(message: String) -> Self {
+        Self::Fatal(message)
+    }
+}
+
+/// Show the download as retrying and wait out the backoff. Returns false when the
+/// download was cancelled in the meantime.
+async fn wait_before_retry(download_id: &str, attempt: u32, error: &str, state: &AppState, app_handle: &tauri::AppHandle) -> bool {
+    let delay = (RETRY_BASE_DELAY_SECS << (attempt - 1)).min(RETRY_MAX_DELAY_SECS);
+    println!("Download {} failed ({}), retrying in {}s (attempt {} of {})", download_id, error, delay, attempt, DOWNLOAD_RETRIES);
+    {
+        let mut download_manager = state.downloads.manager.lock().await;
+        if let Some(status) = download_manager.downloads.get_mut(download_id) {
+            if matches!(status.status, DownloadState::Cancelled) {
+                return false;
+            }
+            status.status = DownloadState::Retrying;
+            status.speed = 0.0;
+            status.eta_seconds = None;
+            status.message = Some(format!("{}, retrying in {}s (attempt {} of {})", error, delay, attempt, DOWNLOAD_RETRIES));
+            let _ = app_handle.emit("download-progress", status.clone());
+        }
+    }
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(delay);
+    while std::time::Instant::now() < deadline {
+        if check_cancellation_status(download_id, state).await.unwrap_or(true) {
+            return false;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    // Paused while waiting, the retry starts once resumed
+    if wait_if_paused(download_id, state).await.is_err() {
+        return false;
+    }
+
+    let mut download_manager = state.downloads.manager.lock().await;
+    if let Some(status) = download_manager.downloads.get_mut(download_id) {
+        status.status = DownloadState::Downloading;
+        status.message = Some(format!("Retrying (attempt {} of {})", attempt, DOWNLOAD_RETRIES));
+    }
+    true
+}
+
 async fn check_cancellation_status(download_id: &str, state: &AppState) -> Result<bool, String> {
-    let download_manager = state.download_manager.lock().await;
+    let download_manager = state.downloads.manager.lock().await;
     if let Some(status) = download_manager.downloads.get(download_id) {
         Ok(matches!(status.status, DownloadState::Cancelled))
     } else {
@@ -503,9 +1493,15 @@ async fn check_cancellation_status(download_id: &str, state: &AppState) -> Resul
     }
 }
 
+async fn is_paused(download_id: &str, state: &AppState) -> bool {
+    let download_manager = state.downloads.manager.lock().await;
+    download_manager.downloads.get(download_id)
+        .is_some_and(|status| matches!(status.status, DownloadState::Paused))
+}
+
 async fn wait_if_paused(download_id: &str, state: &AppState) -> Result<(), String> {
     loop {
-        let download_manager = state.download_manager.lock().await;
+        let download_manager = state.downloads.manager.lock().await;
         if let Some(status) = download_manager.downloads.get(download_id) {
             if matches!(status.status, DownloadState::Cancelled) {
                 return Err("Download cancelled by user".to_string());
@@ -523,6 +1519,39 @@ async fn wait_if_paused(download_id: &str, state: &AppState) -> Result<(), Strin
     Ok(())
 }
 
+/// Start unpacking an archive that is still downloading, emitting extraction progress per entry
+fn spawn_streaming_extraction(
+    archive_path: std::path::PathBuf,
+    destination: String,
+    source: String,
+    total_size: u64,
+    progress: Arc<crate::archive_stream::StreamProgress>,
+    download_id: String,
+    app_handle: tauri::AppHandle,
+) -> tokio::task::JoinHandle<Result<usize, StreamError>> {
+    tokio::task::spawn_blocking(move || {
+        let mut completed_files = 0;
+        crate::archive_stream::extract_following(
+            &archive_path,
+            Path::new(&destination),
+            &source,
+            total_size,
+            progress,
+            |name, offset| {
+                completed_files += 1;
+                let percent = if total_size > 0 { (offset as f64 / total_size as f64 * 100.0).min(100.0) as u8 } else { 0 };
+                let _ = app_handle.emit("extraction-progress", serde_json::json!({
+                    "download_id": download_id,
+                    "extraction_progress": percent,
+                    "extraction_total_files": 0, // Unknown until the central directory
+                    "extraction_completed_files": completed_files,
+                    "current_extracting_file": name
+                }));
+            },
+        )
+    })
+}
+
 async fn extract_zip(zip_path: &Path, destination: &str, download_id: &str, app_handle: &tauri::AppHandle) -> Result<(), String> {
     use std::fs::File;
     use std::io::BufReader;
@@ -559,6 +1588,7 @@ async fn extract_zip(zip_path: &Path, destination: &str, download_id: &str, app_
             }
             let mut outfile = File::create(&outpath).map_err(|e| format!("Failed to create output file: {}", e))?;
             std::io::copy(&mut file, &mut outfile).map_err(|e| format!("Failed to extract file: {}", e))?;
+            restore_unix_mode(&outpath, file.unix_mode());
         }
 
         // Calculate and emit progress
@@ -0,0 +1,109 @@
+// Optional encryption at rest for chats and stored secrets. Values are sealed with
+// ChaCha20-Poly1305 under a random key kept in the OS keyring, so copying the data
+// files off a shared machine doesn't expose them. Sealed values carry a prefix,
+// which lets plaintext and encrypted entries coexist while a store is converted.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+const KEYRING_SERVICE: &str = "llama-os";
+const KEYRING_USER: &str = "data-encryption-key";
+const SEALED_PREFIX: &str = "enc1:";
+const NONCE_LEN: usize = 12;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// The key is read from the keyring once per session
+static KEY: LazyLock<Mutex<Option<Key>>> = LazyLock::new(|| Mutex::new(None));
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Load the key from the keyring, creating it on first use. Blocking: the keyring
+/// may talk to a system service.
+pub fn ensure_key() -> Result<Key, String> {
+    let mut cached = KEY.lock().unwrap();
+    if let Some(key) = cached.as_ref() {
+        return Ok(*key);
+    }
+
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| format!("OS keyring unavailable: {}", e))?;
+    let key = match entry.get_password() {
+        Ok(encoded) => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| format!("Stored encryption key is corrupt: {}", e))?;
+            if bytes.len() != 32 {
+                return Err("Stored encryption key has the wrong length".to_string());
+            }
+            *Key::from_slice(&bytes)
+        }
+        Err(keyring::Error::NoEntry) => {
+            let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+            entry
+                .set_password(&base64::engine::general_purpose::STANDARD.encode(key))
+                .map_err(|e| format!("Failed to store encryption key in the OS keyring: {}", e))?;
+            key
+        }
+        Err(e) => return Err(format!("Failed to read encryption key from the OS keyring: {}", e)),
+    };
+
+    *cached = Some(key);
+    Ok(key)
+}
+
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(SEALED_PREFIX)
+}
+
+/// Encrypt a value regardless of the setting
+pub fn encrypt(plain: &str) -> Result<String, String> {
+    let cipher = ChaCha20Poly1305::new(&ensure_key()?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain.as_bytes())
+        .map_err(|_| "Encryption failed".to_string())?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", SEALED_PREFIX, base64::engine::general_purpose::STANDARD.encode(sealed)))
+}
+
+/// Decrypt a sealed value, plaintext values are returned unchanged
+pub fn decrypt(value: &str) -> Result<String, String> {
+    let Some(encoded) = value.strip_prefix(SEALED_PREFIX) else {
+        return Ok(value.to_string());
+    };
+    let sealed = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Encrypted value is corrupt: {}", e))?;
+    if sealed.len() < NONCE_LEN {
+        return Err("Encrypted value is truncated".to_string());
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(&ensure_key()?);
+    let plain = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt: the keyring key does not match this data".to_string())?;
+    String::from_utf8(plain).map_err(|e| format!("Decrypted value is not text: {}", e))
+}
+
+/// Encrypt when encryption at rest is on, otherwise keep the value as is
+pub fn seal(plain: &str) -> Result<String, String> {
+    if is_enabled() {
+        encrypt(plain)
+    } else {
+        Ok(plain.to_string())
+    }
+}
@@ -0,0 +1,167 @@
+// Windows Firewall inbound rules for model servers bound to a LAN address. Rules are
+// only created when `manage_firewall_rules` is on, are limited to the local subnet,
+// and are removed when their server stops. Each one is recorded in firewall_rules.json
+// so rules left behind by a crash can still be listed and cleaned up. Adding rules
+// needs an elevated app; a failure is logged and the server keeps running.
+
+use std::sync::LazyLock;
+
+use chrono::Utc;
+use tokio::sync::Mutex;
+
+use crate::config::get_app_data_path;
+use crate::models::{ManagedFirewallRule, ProcessStatus};
+use crate::AppState;
+
+const RULES_FILE: &str = "firewall_rules.json";
+const RULE_PREFIX: &str = "Llama-OS llama-server";
+
+// Serializes read-modify-write of the rules file
+static RULES_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// Wildcard or non-loopback addresses, i.e. hosts other machines can reach
+pub fn is_lan_host(host: &str) -> bool {
+    match host {
+        "0.0.0.0" | "::" => true,
+        host => host.parse::<std::net::IpAddr>().is_ok_and(|ip| !ip.is_loopback()),
+    }
+}
+
+/// Open the server's port when it listens on the LAN and the helper is enabled
+pub async fn on_model_started(state: &AppState, process_id: &str, host: &str, port: u16) {
+    if !state.config.lock().await.manage_firewall_rules || !is_lan_host(host) {
+        return;
+    }
+
+    let name = format!("{} {} ({})", RULE_PREFIX, port, &process_id[..process_id.len().min(8)]);
+    let args = vec![
+        "advfirewall".to_string(),
+        "firewall".to_string(),
+        "add".to_string(),
+        "rule".to_string(),
+        format!("name={}", name),
+        "dir=in".to_string(),
+        "action=allow".to_string(),
+        "protocol=TCP".to_string(),
+        format!("localport={}", port),
+        "remoteip=localsubnet".to_string(),
+    ];
+    if let Err(e) = run_netsh(args).await {
+        eprintln!("Failed to add firewall rule for port {}: {}", port, e);
+        return;
+    }
+    println!("Added firewall rule '{}'", name);
+
+    let rule = ManagedFirewallRule {
+        name,
+        process_id: process_id.to_string(),
+        port,
+        created_at: Utc::now(),
+        active: true,
+    };
+    let _guard = RULES_LOCK.lock().await;
+    let mut rules = load_rules().await;
+    rules.push(rule);
+    if let Err(e) = save_rules(&rules).await {
+        eprintln!("Failed to record firewall rule: {}", e);
+    }
+}
+
+/// Remove the rules created for a server that is going away
+pub async fn on_model_stopped(process_id: &str) {
+    let _guard = RULES_LOCK.lock().await;
+    let mut rules = load_rules().await;
+    let (stopped, remaining): (Vec<_>, Vec<_>) = rules.drain(..).partition(|r| r.process_id == process_id);
+    if stopped.is_empty() {
+        return;
+    }
+
+    let mut kept = remaining;
+    for rule in stopped {
+        match delete_rule(&rule.name).await {
+            Ok(()) => println!("Removed firewall rule '{}'", rule.name),
+            Err(e) => {
+                eprintln!("Failed to remove firewall rule '{}': {}", rule.name, e);
+                kept.push(rule);
+            }
+        }
+    }
+    if let Err(e) = save_rules(&kept).await {
+        eprintln!("Failed to record firewall rules: {}", e);
+    }
+}
+
+/// Rules created by the app, with the ones whose server is gone marked inactive
+pub async fn list_rules(state: &AppState) -> Vec<ManagedFirewallRule> {
+    let running: Vec<String> = state.processes.list().await
+        .into_iter()
+        .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running))
+        .map(|p| p.id)
+        .collect();
+    let _guard = RULES_LOCK.lock().await;
+    let mut rules = load_rules().await;
+    for rule in &mut rules {
+        rule.active = running.contains(&rule.process_id);
+    }
+    rules
+}
+
+/// Delete a managed rule by name, e.g. one left behind by a crash
+pub async fn remove_rule(name: &str) -> Result<(), String> {
+    let _guard = RULES_LOCK.lock().await;
+    let mut rules = load_rules().await;
+    let index = rules.iter().position(|r| r.name == name).ok_or("Firewall rule not found")?;
+    delete_rule(name).await?;
+    rules.remove(index);
+    save_rules(&rules).await
+}
+
+async fn delete_rule(name: &str) -> Result<(), String> {
+    run_netsh(vec![
+        "advfirewall".to_string(),
+        "firewall".to_string(),
+        "delete".to_string(),
+        "rule".to_string(),
+        format!("name={}", name),
+    ]).await
+}
+
+async fn load_rules() -> Vec<ManagedFirewallRule> {
+    let Ok(path) = get_app_data_path(RULES_FILE).await else {
+        return Vec::new();
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+async fn save_rules(rules: &[ManagedFirewallRule]) -> Result<(), String> {
+    let path = get_app_data_path(RULES_FILE).await.map_err(|e| e.to_string())?;
+    let contents = serde_json::to_string_pretty(rules).map_err(|e| e.to_string())?;
+    tokio::fs::write(&path, contents).await.map_err(|e| e.to_string())
+}
+
+#[cfg(windows)]
+async fn run_netsh(args: Vec<String>) -> Result<(), String> {
+    let mut cmd = tokio::process::Command::new("netsh");
+    cmd.args(&args);
+
+    // Hide console window on Windows release builds
+    #[cfg(not(debug_assertions))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let output = cmd.output().await.map_err(|e| format!("Failed to run netsh: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        // netsh reports errors such as "requires elevation" on stdout
+        let message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Err(if message.is_empty() { format!("netsh exited with {}", output.status) } else { message })
+    }
+}
+
+#[cfg(not(windows))]
+async fn run_netsh(_args: Vec<String>) -> Result<(), String> {
+    Err("Firewall rules are only managed on Windows".to_string())
+}
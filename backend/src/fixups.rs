@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::gguf::{read_header, GgufHeader};
+use crate::models::ModelConfig;
+
+/// A known-broken GGUF export and the token id it should have used as EOS
+struct FixupRule {
+    id: &'static str,
+    description: &'static str,
+    architectures: &'static [&'static str],
+    name_contains: &'static [&'static str], // Any of these in general.name or the file name (lowercase)
+    instruct_only: bool, // Base models of the family share the EOS and must keep it
+    broken_eos_token_id: u64,
+    fixed_eos_token_id: u64,
+}
+
+const RULES: &[FixupRule] = &[
+    FixupRule {
+        id: "llama3-instruct-eot",
+        description: "Early Llama 3 Instruct exports use <|end_of_text|> as EOS instead of <|eot_id|>, so generation never stops",
+        architectures: &["llama"],
+        name_contains: &["llama-3", "llama3", "llama 3"],
+        instruct_only: true,
+        broken_eos_token_id: 128001,
+        fixed_eos_token_id: 128009,
+    },
+    FixupRule {
+        id: "qwen2-instruct-im-end",
+        description: "Some Qwen2 Instruct exports use <|endoftext|> as EOS instead of <|im_end|>",
+        architectures: &["qwen2"],
+        name_contains: &["instruct", "chat"],
+        instruct_only: false,
+        broken_eos_token_id: 151643,
+        fixed_eos_token_id: 151645,
+    },
+    FixupRule {
+        id: "gemma-it-end-of-turn",
+        description: "Older Gemma instruction-tuned exports use <eos> instead of <end_of_turn>",
+        architectures: &["gemma", "gemma2"],
+        name_contains: &["-it", " it", "instruct"],
+        instruct_only: false,
+        broken_eos_token_id: 1,
+        fixed_eos_token_id: 107,
+    },
+    FixupRule {
+        id: "phi3-end",
+        description: "Early Phi-3 exports use <|endoftext|> as EOS instead of <|end|>",
+        architectures: &["phi3"],
+        name_contains: &["phi-3", "phi3"],
+        instruct_only: false,
+        broken_eos_token_id: 32000,
+        fixed_eos_token_id: 32007,
+    },
+];
+
+const EOS_KEY: &str = "tokenizer.ggml.eos_token_id";
+
+// Names of instruction-tuned exports, for rules whose base models share the broken EOS
+const INSTRUCT_MARKERS: &[&str] = &["instruct", "chat", "-it"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedFixup {
+    pub rule_id: String,
+    pub description: String,
+    pub args: Vec<String>,
+}
+
+fn rule_matches(rule: &FixupRule, header: &GgufHeader, file_name: &str) -> bool {
+    let architecture = header.architecture().unwrap_or("");
+    if !rule.architectures.contains(&architecture) {
+        return false;
+    }
+    if header.get(EOS_KEY).and_then(|v| v.as_u64()) != Some(rule.broken_eos_token_id) {
+        return false;
+    }
+    let name = header.get("general.name").and_then(|v| v.as_str()).unwrap_or("").to_lowercase();
+    if rule.instruct_only && !is_instruct(header, &name, file_name) {
+        return false;
+    }
+    rule.name_contains.iter().any(|s| name.contains(s) || file_name.contains(s))
+}
+
+/// Instruction-tuned by its name, or by a chat template that ends turns with the token
+/// the broken EOS should have been
+fn is_instruct(header: &GgufHeader, name: &str, file_name: &str) -> bool {
+    INSTRUCT_MARKERS.iter().any(|marker| name.contains(marker) || file_name.contains(marker))
+        || header.get("tokenizer.chat_template")
+            .and_then(|v| v.as_str())
+            .is_some_and(|template| template.contains("<|eot_id|>"))
+}
+
+/// Corrective flags for a model, empty when no rule applies, the model opted out,
+/// or the user already overrides the EOS token themselves. Reads the model's header
+pub fn find_fixups(model_config: &ModelConfig) -> Vec<AppliedFixup> {
+    let path = crate::scanner::first_shard(&model_config.model_path);
+    match std::fs::File::open(path).map(BufReader::new).and_then(|mut r| read_header(&mut r, false)) {
+        Ok(header) => fixups_for(model_config, &header),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// `find_fixups` for a header that was already read
+pub fn fixups_for(model_config: &ModelConfig, header: &GgufHeader) -> Vec<AppliedFixup> {
+    if model_config.disable_fixups
+        || model_config.custom_args.contains(EOS_KEY)
+        || model_config.kv_overrides.iter().any(|o| o.key == EOS_KEY)
+    {
+        return Vec::new();
+    }
+
+    let path = Path::new(&model_config.model_path);
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+    RULES
+        .iter()
+        .filter(|rule| rule_matches(rule, header, &file_name))
+        .take(1) // Rules all touch the EOS token, only one can apply
+        .map(|rule| AppliedFixup {
+            rule_id: rule.id.to_string(),
+            description: rule.description.to_string(),
+            args: vec![
+                "--override-kv".to_string(),
+                format!("{}=int:{}", EOS_KEY, rule.fixed_eos_token_id),
+            ],
+        })
+        .collect()
+}
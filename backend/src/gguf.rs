@@ -0,0 +1,436 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use crate::models::{ModelFilePreview, PreviewSection, TensorPreview};
+
+// Sanity limits so corrupt or hostile headers can't trigger huge allocations
+const MAX_KEY_LEN: u64 = 64 * 1024;
+const MAX_STRING_LEN: u64 = 64 * 1024 * 1024;
+const MAX_TENSOR_DIMS: u32 = 8;
+const MAX_ARRAY_DEPTH: u32 = 4; // Arrays of arrays are legal, deeper than this is not a real model
+
+// Arrays like tokenizer vocabularies are skipped past, only the first items are kept
+const MAX_ARRAY_ITEMS: usize = 16;
+
+// Raw header dump size for the inspector, kept small regardless of the file size
+const DEFAULT_HEXDUMP_BYTES: usize = 512;
+const MAX_HEXDUMP_BYTES: usize = 64 * 1024;
+
+// Encoder-only architectures, they produce embeddings and can't generate text
+const EMBEDDING_ARCHITECTURES: &[&str] = &[
+    "bert", "nomic-bert", "nomic-bert-moe", "jina-bert-v2", "jina-bert-v3",
+    "neo-bert", "modern-bert", "t5encoder", "gemma-embedding",
+];
+// `<arch>.pooling_type` values: 0 none, 1 mean, 2 cls, 3 last, 4 rank (rerankers)
+const POOLING_TYPE_NONE: u64 = 0;
+const POOLING_TYPE_RANK: u64 = 4;
+
+/// A metadata value from the GGUF key-value section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GgufValue {
+    Uint(u64),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Array { len: u64, items: Vec<GgufValue> },
+}
+
+impl GgufValue {
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            GgufValue::Uint(v) => Some(*v),
+            GgufValue::Int(v) if *v >= 0 => Some(*v as u64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            GgufValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            GgufValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GgufTensorInfo {
+    pub name: String,
+    pub dims: Vec<u64>,
+    pub ggml_type: u32,
+    pub offset: u64,
+}
+
+impl GgufTensorInfo {
+    pub fn element_count(&self) -> u64 {
+        self.dims.iter().product()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GgufHeader {
+    pub version: u32,
+    pub tensor_count: u64,
+    pub metadata: BTreeMap<String, GgufValue>,
+    pub tensors: Vec<GgufTensorInfo>,
+}
+
+impl GgufHeader {
+    pub fn get(&self, key: &str) -> Option<&GgufValue> {
+        self.metadata.get(key)
+    }
+
+    pub fn architecture(&self) -> Option<&str> {
+        self.get("general.architecture").and_then(|v| v.as_str())
+    }
+
+    /// Look up an architecture-scoped key such as `<arch>.context_length`
+    pub fn arch_u64(&self, suffix: &str) -> Option<u64> {
+        let arch = self.architecture()?;
+        self.get(&format!("{}.{}", arch, suffix)).and_then(|v| v.as_u64())
+    }
+
+    pub fn context_length(&self) -> Option<u64> {
+        self.arch_u64("context_length")
+    }
+
+    /// Inputs a multimodal projector (mmproj) adds, "vision" and/or "audio". Projectors
+    /// from before audio support have no `clip.has_*_encoder` keys and are vision only.
+    pub fn projector_modalities(&self) -> Vec<String> {
+        let has = |key: &str| self.get(key).and_then(|v| v.as_bool());
+        let (vision, audio) = (has("clip.has_vision_encoder"), has("clip.has_audio_encoder"));
+        let mut modalities = Vec::new();
+        if vision.unwrap_or(audio.is_none()) {
+            modalities.push("vision".to_string());
+        }
+        if audio == Some(true) {
+            modalities.push("audio".to_string());
+        }
+        modalities
+    }
+
+    /// The llama-server flag a model has to be served with when it can't chat: `--reranking`
+    /// for rerankers, `--embedding` for encoder-only architectures and for decoders
+    /// converted with a pooling type (Qwen3-Embedding and similar)
+    pub fn embedding_mode(&self) -> Option<&'static str> {
+        let pooling = self.arch_u64("pooling_type");
+        if pooling == Some(POOLING_TYPE_RANK) {
+            return Some("--reranking");
+        }
+        let architecture = self.architecture().unwrap_or("");
+        let embedding = EMBEDDING_ARCHITECTURES.contains(&architecture)
+            || pooling.is_some_and(|p| p != POOLING_TYPE_NONE);
+        embedding.then_some("--embedding")
+    }
+
+    /// Total parameter count summed over all tensors (requires tensor infos to be read)
+    pub fn parameter_count(&self) -> Option<u64> {
+        if self.tensors.is_empty() {
+            None
+        } else {
+            Some(self.tensors.iter().map(|t| t.element_count()).sum())
+        }
+    }
+}
+
+/// Modalities of a projector file on disk, empty when its header can't be read
+pub fn read_projector_modalities(path: &Path) -> Vec<String> {
+    std::fs::File::open(path)
+        .and_then(|file| read_header(&mut BufReader::new(file), false))
+        .map(|header| header.projector_modalities())
+        .unwrap_or_default()
+}
+
+/// `general.type` of a file ("model", "adapter", ...), stopping at the first key outside
+/// `general.*` so model headers with large tokenizer arrays aren't read in full
+pub fn read_general_type(path: &Path) -> Option<String> {
+    let mut reader = BufReader::new(std::fs::File::open(path).ok()?);
+    if &read_bytes::<_, 4>(&mut reader).ok()? != b"GGUF" {
+        return None;
+    }
+    let _version = read_u32(&mut reader).ok()?;
+    let _tensor_count = read_u64(&mut reader).ok()?;
+    let kv_count = read_u64(&mut reader).ok()?;
+    for _ in 0..kv_count {
+        let key = read_string(&mut reader, MAX_KEY_LEN).ok()?;
+        if !key.starts_with("general.") {
+            return None;
+        }
+        let value_type = read_u32(&mut reader).ok()?;
+        let value = read_value(&mut reader, value_type).ok()?;
+        if key == "general.type" {
+            return value.as_str().map(|s| s.to_string());
+        }
+    }
+    None
+}
+
+/// Parse the GGUF header, metadata and (optionally) tensor infos without touching tensor data
+pub fn read_header<R: Read>(reader: &mut R, read_tensors: bool) -> io::Result<GgufHeader> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"GGUF" {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Not a GGUF file"));
+    }
+
+    let version = read_u32(reader)?;
+    if version < 2 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Unsupported GGUF version {}", version)));
+    }
+    let tensor_count = read_u64(reader)?;
+    let kv_count = read_u64(reader)?;
+
+    let mut metadata = BTreeMap::new();
+    for _ in 0..kv_count {
+        let key = read_string(reader, MAX_KEY_LEN)?;
+        let value_type = read_u32(reader)?;
+        let value = read_value(reader, value_type)?;
+        metadata.insert(key, value);
+    }
+
+    let mut tensors = Vec::new();
+    if read_tensors {
+        for _ in 0..tensor_count {
+            let name = read_string(reader, MAX_KEY_LEN)?;
+            let n_dims = read_u32(reader)?;
+            if n_dims > MAX_TENSOR_DIMS {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Tensor {} has {} dimensions", name, n_dims)));
+            }
+            let mut dims = Vec::with_capacity(n_dims as usize);
+            for _ in 0..n_dims {
+                dims.push(read_u64(reader)?);
+            }
+            let ggml_type = read_u32(reader)?;
+            let offset = read_u64(reader)?;
+            tensors.push(GgufTensorInfo { name, dims, ggml_type, offset });
+        }
+    }
+
+    Ok(GgufHeader {
+        version,
+        tensor_count,
+        metadata,
+        tensors,
+    })
+}
+
+/// Human readable name for a ggml tensor type id
+pub fn ggml_type_name(ggml_type: u32) -> String {
+    let name = match ggml_type {
+        0 => "F32",
+        1 => "F16",
+        2 => "Q4_0",
+        3 => "Q4_1",
+        6 => "Q5_0",
+        7 => "Q5_1",
+        8 => "Q8_0",
+        9 => "Q8_1",
+        10 => "Q2_K",
+        11 => "Q3_K",
+        12 => "Q4_K",
+        13 => "Q5_K",
+        14 => "Q6_K",
+        15 => "Q8_K",
+        16 => "IQ2_XXS",
+        17 => "IQ2_XS",
+        18 => "IQ3_XXS",
+        19 => "IQ1_S",
+        20 => "IQ4_NL",
+        21 => "IQ3_S",
+        22 => "IQ2_S",
+        23 => "IQ4_XS",
+        24 => "I8",
+        25 => "I16",
+        26 => "I32",
+        27 => "I64",
+        28 => "F64",
+        29 => "IQ1_M",
+        30 => "BF16",
+        34 => "TQ1_0",
+        35 => "TQ2_0",
+        39 => "MXFP4",
+        other => return format!("UNKNOWN({})", other),
+    };
+    name.to_string()
+}
+
+/// Name of a `general.file_type` value (llama_ftype), as used in quantized file names
+pub fn file_type_name(file_type: u64) -> Option<&'static str> {
+    Some(match file_type {
+        0 => "F32",
+        1 => "F16",
+        2 => "Q4_0",
+        3 => "Q4_1",
+        7 => "Q8_0",
+        8 => "Q5_0",
+        9 => "Q5_1",
+        10 => "Q2_K",
+        11 => "Q3_K_S",
+        12 => "Q3_K_M",
+        13 => "Q3_K_L",
+        14 => "Q4_K_S",
+        15 => "Q4_K_M",
+        16 => "Q5_K_S",
+        17 => "Q5_K_M",
+        18 => "Q6_K",
+        19 => "IQ2_XXS",
+        20 => "IQ2_XS",
+        21 => "Q2_K_S",
+        22 => "IQ3_XS",
+        23 => "IQ3_XXS",
+        24 => "IQ1_S",
+        25 => "IQ4_NL",
+        26 => "IQ3_S",
+        27 => "IQ3_M",
+        28 => "IQ2_S",
+        29 => "IQ2_M",
+        30 => "IQ4_XS",
+        31 => "IQ1_M",
+        32 => "BF16",
+        36 => "TQ1_0",
+        37 => "TQ2_0",
+        38 => "MXFP4_MOE",
+        _ => return None,
+    })
+}
+
+/// Read one section of a local GGUF file for the inspector. Only the header region is
+/// streamed through a buffered reader, tensor data is never touched.
+pub fn preview_file(path: &Path, section: PreviewSection, hexdump_bytes: Option<usize>) -> io::Result<ModelFilePreview> {
+    let file = std::fs::File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+
+    let mut preview = ModelFilePreview {
+        path: path.to_string_lossy().to_string(),
+        file_size,
+        section,
+        gguf_version: None,
+        tensor_count: None,
+        metadata: None,
+        tensors: None,
+        header_hex: None,
+    };
+
+    match section {
+        PreviewSection::Header => {
+            let limit = hexdump_bytes.unwrap_or(DEFAULT_HEXDUMP_BYTES).min(MAX_HEXDUMP_BYTES);
+            let mut buf = Vec::with_capacity(limit);
+            reader.by_ref().take(limit as u64).read_to_end(&mut buf)?;
+            preview.header_hex = Some(hexdump(&buf));
+        }
+        PreviewSection::Metadata | PreviewSection::Tensors => {
+            let header = read_header(&mut reader, section == PreviewSection::Tensors)?;
+            preview.gguf_version = Some(header.version);
+            preview.tensor_count = Some(header.tensor_count);
+            if section == PreviewSection::Tensors {
+                preview.tensors = Some(
+                    header
+                        .tensors
+                        .into_iter()
+                        .map(|t| TensorPreview {
+                            elements: t.element_count(),
+                            dtype: ggml_type_name(t.ggml_type),
+                            name: t.name,
+                            shape: t.dims,
+                            offset: t.offset,
+                        })
+                        .collect(),
+                );
+            } else {
+                preview.metadata = Some(header.metadata);
+            }
+        }
+    }
+
+    Ok(preview)
+}
+
+/// Format bytes as `offset  hex bytes  |ascii|` lines, 16 bytes per line
+fn hexdump(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|", i * 16, hex.join(" "), ascii)
+        })
+        .collect()
+}
+
+fn read_value<R: Read>(reader: &mut R, value_type: u32) -> io::Result<GgufValue> {
+    read_nested_value(reader, value_type, 0)
+}
+
+fn read_nested_value<R: Read>(reader: &mut R, value_type: u32, depth: u32) -> io::Result<GgufValue> {
+    Ok(match value_type {
+        0 => GgufValue::Uint(read_bytes::<R, 1>(reader)?[0] as u64),
+        1 => GgufValue::Int(read_bytes::<R, 1>(reader)?[0] as i8 as i64),
+        2 => GgufValue::Uint(u16::from_le_bytes(read_bytes(reader)?) as u64),
+        3 => GgufValue::Int(i16::from_le_bytes(read_bytes(reader)?) as i64),
+        4 => GgufValue::Uint(read_u32(reader)? as u64),
+        5 => GgufValue::Int(i32::from_le_bytes(read_bytes(reader)?) as i64),
+        6 => GgufValue::Float(f32::from_le_bytes(read_bytes(reader)?) as f64),
+        7 => GgufValue::Bool(read_bytes::<R, 1>(reader)?[0] != 0),
+        8 => GgufValue::String(read_string(reader, MAX_STRING_LEN)?),
+        9 => {
+            if depth >= MAX_ARRAY_DEPTH {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "GGUF arrays nested too deeply"));
+            }
+            let item_type = read_u32(reader)?;
+            let len = read_u64(reader)?;
+            let mut items = Vec::new();
+            for i in 0..len {
+                let item = read_nested_value(reader, item_type, depth + 1)?;
+                if (i as usize) < MAX_ARRAY_ITEMS {
+                    items.push(item);
+                }
+            }
+            GgufValue::Array { len, items }
+        }
+        10 => GgufValue::Uint(read_u64(reader)?),
+        11 => GgufValue::Int(i64::from_le_bytes(read_bytes(reader)?)),
+        12 => GgufValue::Float(f64::from_le_bytes(read_bytes(reader)?)),
+        other => {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Unknown GGUF value type {}", other)));
+        }
+    })
+}
+
+fn read_bytes<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_string<R: Read>(reader: &mut R, max_len: u64) -> io::Result<String> {
+    let len = read_u64(reader)?;
+    if len > max_len {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("String length {} exceeds limit", len)));
+    }
+    let mut buf = vec![0u8; len as usize];
+    reader.read_exact(&mut buf)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
@@ -0,0 +1,104 @@
+use crate::models::*;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::Emitter;
+
+pub const SPACE_URL: &str = "https://huggingface.co/spaces/ggml-org/gguf-my-repo";
+
+// Conversions usually finish within a few minutes, large models can take much longer
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const POLL_ATTEMPTS: u32 = 120;
+
+// Models currently being watched, so repeated requests don't start duplicate pollers
+static WATCHED_MODELS: LazyLock<std::sync::Mutex<HashSet<String>>> = LazyLock::new(|| std::sync::Mutex::new(HashSet::new()));
+
+/// Find GGUF repos that declare `model_id` as their quantized base model
+pub async fn find_conversions(model_id: &str) -> Result<Vec<ModelBasic>, Box<dyn std::error::Error + Send + Sync>> {
+    let client = reqwest::Client::new();
+    let url = format!(
+        "https://huggingface.co/api/models?filter=base_model:quantized:{}&filter=gguf&sort=downloads&limit=50",
+        model_id
+    );
+
+    let response = crate::huggingface::authorize(client.get(&url), &url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(format!("API request failed with status: {}", response.status()).into());
+    }
+
+    let models: Vec<serde_json::Value> = response.json().await?;
+    Ok(models.iter().filter_map(crate::huggingface::parse_model_basic).collect())
+}
+
+/// Return existing conversions for a model, or open GGUF-my-repo and watch for the new repo.
+/// The space needs the user's Hugging Face login, so the conversion itself is submitted in the browser.
+pub async fn request_conversion(model_id: &str, app_handle: tauri::AppHandle) -> Result<GgufConversionStatus, String> {
+    let model_id = model_id.trim().trim_matches('/');
+    if model_id.split('/').filter(|part| !part.is_empty()).count() != 2 {
+        return Err(format!("Invalid model id: {}", model_id));
+    }
+
+    let existing = find_conversions(model_id)
+        .await
+        .map_err(|e| format!("Failed to look up conversions: {}", e))?;
+    if !existing.is_empty() {
+        return Ok(GgufConversionStatus {
+            model_id: model_id.to_string(),
+            status: "existing".to_string(),
+            repos: existing,
+            space_url: SPACE_URL.to_string(),
+        });
+    }
+
+    {
+        use tauri_plugin_opener::OpenerExt;
+        app_handle.opener()
+            .open_url(SPACE_URL, None::<String>)
+            .map_err(|e| format!("Failed to open URL: {}", e))?;
+    }
+
+    spawn_conversion_watch(model_id.to_string(), app_handle);
+
+    Ok(GgufConversionStatus {
+        model_id: model_id.to_string(),
+        status: "requested".to_string(),
+        repos: Vec::new(),
+        space_url: SPACE_URL.to_string(),
+    })
+}
+
+/// Poll the Hub until a GGUF repo for the model shows up, then emit `gguf-conversion-status`
+fn spawn_conversion_watch(model_id: String, app_handle: tauri::AppHandle) {
+    if !WATCHED_MODELS.lock().unwrap().insert(model_id.clone()) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut repos = Vec::new();
+        for _ in 0..POLL_ATTEMPTS {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            match find_conversions(&model_id).await.map_err(|e| e.to_string()) {
+                Ok(found) if !found.is_empty() => {
+                    repos = found;
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to poll conversions for {}: {}", model_id, e),
+            }
+        }
+        WATCHED_MODELS.lock().unwrap().remove(&model_id);
+
+        let status = if repos.is_empty() { "timed_out" } else { "completed" };
+        println!("GGUF conversion watch for {} finished: {}", model_id, status);
+        let _ = app_handle.emit("gguf-conversion-status", GgufConversionStatus {
+            model_id,
+            status: status.to_string(),
+            repos,
+            space_url: SPACE_URL.to_string(),
+        });
+    });
+}
@@ -0,0 +1,304 @@
+// Windows performance counter fallback for GPU metrics.
+//
+// NVML is missing on AMD/Intel machines and frequently fails to initialise on
+// older NVIDIA drivers or restricted accounts. The "GPU Engine" and "GPU Adapter
+// Memory" counters are fed by the WDDM kernel (D3DKMT) for every vendor, so they
+// keep the monitor populated in those cases. Adapter name and VRAM size come from
+// the display class registry keys since PDH does not expose them.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use windows_sys::Wdk::Graphics::Direct3D::{
+    D3DKMTCloseAdapter, D3DKMTEnumAdapters2, D3DKMTQueryAdapterInfo, D3DKMT_ADAPTERADDRESS,
+    D3DKMT_ADAPTERINFO, D3DKMT_CLOSEADAPTER, D3DKMT_ENUMADAPTERS2, D3DKMT_QUERYADAPTERINFO,
+    KMTQAITYPE_ADAPTERADDRESS,
+};
+use windows_sys::Win32::System::Performance::{
+    PdhAddEnglishCounterW, PdhCloseQuery, PdhCollectQueryData, PdhGetFormattedCounterArrayW,
+    PdhOpenQueryW, PDH_CSTATUS_NEW_DATA, PDH_CSTATUS_VALID_DATA, PDH_FMT_COUNTERVALUE_ITEM_W,
+    PDH_FMT_DOUBLE, PDH_MORE_DATA,
+};
+use windows_sys::Win32::System::Registry::{
+    RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_QWORD, RRF_RT_REG_SZ,
+};
+
+const UTILIZATION_COUNTER: &str = r"\GPU Engine(*)\Utilization Percentage";
+const DEDICATED_USAGE_COUNTER: &str = r"\GPU Adapter Memory(*)\Dedicated Usage";
+const PROCESS_USAGE_COUNTER: &str = r"\GPU Process Memory(*)\Dedicated Usage";
+const DISPLAY_CLASS_KEY: &str =
+    r"SYSTEM\CurrentControlSet\Control\Class\{4d36e968-e325-11ce-bfc1-08002be10318}";
+
+// Utilization is a rate counter, so the first sample needs a short baseline window
+const BASELINE_INTERVAL: Duration = Duration::from_millis(200);
+
+struct CounterQuery {
+    query: isize,
+    utilization: isize,
+    dedicated_usage: isize,
+    process_usage: Option<isize>, // Missing on builds older than Windows 10 1709
+}
+
+// The query is kept open between polls so each sample covers the interval since
+// the previous call instead of blocking for a fresh baseline every time.
+static QUERY: Mutex<Option<CounterQuery>> = Mutex::new(None);
+
+/// Returns (name, usage %, total VRAM GB, used VRAM GB) read from performance
+/// counters, or None when the counters are unavailable.
+pub fn get_gpu_info() -> Option<(String, f32, f32, f32)> {
+    let mut guard = QUERY.lock().ok()?;
+    if guard.is_none() {
+        *guard = open_query();
+    }
+    let counters = guard.as_ref()?;
+
+    if unsafe { PdhCollectQueryData(counters.query) } != 0 {
+        // The query went stale (driver reset, adapter removed); reopen on the next poll
+        unsafe { PdhCloseQuery(counters.query) };
+        *guard = None;
+        return None;
+    }
+
+    let engines = read_counter_array(counters.utilization);
+    let adapters = read_counter_array(counters.dedicated_usage);
+    if engines.is_empty() && adapters.is_empty() {
+        return None;
+    }
+
+    let gpu_usage = busiest_engine(&engines).min(100.0) as f32;
+    let used_bytes = adapters.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+    let (name, total_bytes) = adapter_from_registry()
+        .unwrap_or_else(|| ("GPU (performance counters)".to_string(), 0));
+
+    let gb = 1024.0 * 1024.0 * 1024.0;
+    Some((name, gpu_usage, (total_bytes as f64 / gb) as f32, (used_bytes / gb) as f32))
+}
+
+/// Dedicated VRAM per process id on each adapter, keyed by the adapter's LUID. WDDM
+/// drivers don't report per-process usage through NVML, so this fills in the VRAM
+/// breakdown on Windows.
+pub fn process_memory_usage() -> HashMap<u64, HashMap<u32, u64>> {
+    let mut usage: HashMap<u64, HashMap<u32, u64>> = HashMap::new();
+    let Ok(mut guard) = QUERY.lock() else {
+        return usage;
+    };
+    if guard.is_none() {
+        *guard = open_query();
+    }
+    let Some(counters) = guard.as_ref() else {
+        return usage;
+    };
+    let Some(process_usage) = counters.process_usage else {
+        return usage;
+    };
+    if unsafe { PdhCollectQueryData(counters.query) } != 0 {
+        return usage;
+    }
+
+    // Instances look like "pid_1234_luid_0x00000000_0x0000D1B5_phys_0", one per adapter
+    for (instance, value) in read_counter_array(process_usage) {
+        let parts: Vec<&str> = instance.split('_').collect();
+        let ["pid", pid, "luid", high, low, ..] = parts.as_slice() else {
+            continue;
+        };
+        let hex = |part: &str| u32::from_str_radix(part.trim_start_matches("0x").trim_start_matches("0X"), 16).ok();
+        let (Ok(pid), Some(high), Some(low)) = (pid.parse::<u32>(), hex(*high), hex(*low)) else {
+            continue;
+        };
+        let luid = ((high as u64) << 32) | low as u64;
+        *usage.entry(luid).or_default().entry(pid).or_insert(0) += value as u64;
+    }
+    usage
+}
+
+/// LUID of the WDDM adapter at a PCI bus/device/function, the key the per-process
+/// counters are filed under
+pub fn adapter_luid(bus: u32, device: u32, function: u32) -> Option<u64> {
+    let mut enumeration = D3DKMT_ENUMADAPTERS2 { NumAdapters: 0, pAdapters: std::ptr::null_mut() };
+    // The first call only reports how many adapters there are
+    if unsafe { D3DKMTEnumAdapters2(&mut enumeration) } != 0 {
+        return None;
+    }
+    let mut adapters: Vec<D3DKMT_ADAPTERINFO> = vec![unsafe { std::mem::zeroed() }; enumeration.NumAdapters as usize];
+    enumeration.pAdapters = adapters.as_mut_ptr();
+    if unsafe { D3DKMTEnumAdapters2(&mut enumeration) } != 0 {
+        return None;
+    }
+    adapters.truncate(enumeration.NumAdapters as usize);
+
+    // Every enumerated adapter comes back open and has to be closed again
+    let mut found = None;
+    for adapter in &adapters {
+        let mut address = D3DKMT_ADAPTERADDRESS { BusNumber: 0, DeviceNumber: 0, FunctionNumber: 0 };
+        let mut query = D3DKMT_QUERYADAPTERINFO {
+            hAdapter: adapter.hAdapter,
+            Type: KMTQAITYPE_ADAPTERADDRESS,
+            pPrivateDriverData: &mut address as *mut D3DKMT_ADAPTERADDRESS as *mut _,
+            PrivateDriverDataSize: std::mem::size_of::<D3DKMT_ADAPTERADDRESS>() as u32,
+        };
+        let matches = unsafe { D3DKMTQueryAdapterInfo(&mut query) } == 0
+            && address.BusNumber == bus
+            && address.DeviceNumber == device
+            && address.FunctionNumber == function;
+        if matches && found.is_none() {
+            let luid = adapter.AdapterLuid;
+            found = Some(((luid.HighPart as u32 as u64) << 32) | luid.LowPart as u64);
+        }
+        unsafe { D3DKMTCloseAdapter(&D3DKMT_CLOSEADAPTER { hAdapter: adapter.hAdapter }) };
+    }
+    found
+}
+
+fn open_query() -> Option<CounterQuery> {
+    let utilization_path = wide(UTILIZATION_COUNTER);
+    let dedicated_path = wide(DEDICATED_USAGE_COUNTER);
+    let process_path = wide(PROCESS_USAGE_COUNTER);
+
+    unsafe {
+        let mut query = 0isize;
+        if PdhOpenQueryW(std::ptr::null(), 0, &mut query) != 0 {
+            return None;
+        }
+
+        let mut utilization = 0isize;
+        let mut dedicated_usage = 0isize;
+        let added = PdhAddEnglishCounterW(query, utilization_path.as_ptr(), 0, &mut utilization) == 0
+            && PdhAddEnglishCounterW(query, dedicated_path.as_ptr(), 0, &mut dedicated_usage) == 0;
+        if !added || PdhCollectQueryData(query) != 0 {
+            PdhCloseQuery(query);
+            return None;
+        }
+
+        // Optional, the adapter counters still work without it
+        let mut process_usage = 0isize;
+        let process_usage = (PdhAddEnglishCounterW(query, process_path.as_ptr(), 0, &mut process_usage) == 0)
+            .then_some(process_usage);
+
+        std::thread::sleep(BASELINE_INTERVAL);
+        Some(CounterQuery { query, utilization, dedicated_usage, process_usage })
+    }
+}
+
+fn read_counter_array(counter: isize) -> Vec<(String, f64)> {
+    // Instances can appear between the size probe and the read, so retry a few times
+    for _ in 0..3 {
+        let mut buffer_size = 0u32;
+        let mut item_count = 0u32;
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(
+                counter,
+                PDH_FMT_DOUBLE,
+                &mut buffer_size,
+                &mut item_count,
+                std::ptr::null_mut(),
+            )
+        };
+        if status != PDH_MORE_DATA || buffer_size == 0 {
+            return Vec::new();
+        }
+
+        // u64 backing storage keeps the items 8-byte aligned
+        let mut buffer = vec![0u64; (buffer_size as usize).div_ceil(8)];
+        let items = buffer.as_mut_ptr() as *mut PDH_FMT_COUNTERVALUE_ITEM_W;
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(counter, PDH_FMT_DOUBLE, &mut buffer_size, &mut item_count, items)
+        };
+        if status == PDH_MORE_DATA {
+            continue;
+        }
+        if status != 0 {
+            return Vec::new();
+        }
+
+        let items = unsafe { std::slice::from_raw_parts(items, item_count as usize) };
+        return items
+            .iter()
+            .filter(|item| {
+                item.FmtValue.CStatus == PDH_CSTATUS_VALID_DATA
+                    || item.FmtValue.CStatus == PDH_CSTATUS_NEW_DATA
+            })
+            .map(|item| unsafe { (from_wide_ptr(item.szName), item.FmtValue.Anonymous.doubleValue) })
+            .collect();
+    }
+    Vec::new()
+}
+
+/// Mirrors Task Manager: sum each engine type across processes, then report the busiest.
+fn busiest_engine(engines: &[(String, f64)]) -> f64 {
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    for (instance, value) in engines {
+        // Instances look like "pid_1234_luid_0x0_0xD1B5_phys_0_eng_0_engtype_3D"
+        let engine_key = match instance.find("luid_") {
+            Some(start) => &instance[start..],
+            None => instance.as_str(),
+        };
+        *totals.entry(engine_key.to_string()).or_insert(0.0) += value;
+    }
+    totals.values().copied().fold(0.0, f64::max)
+}
+
+/// Picks the display adapter with the most dedicated memory from the registry.
+fn adapter_from_registry() -> Option<(String, u64)> {
+    let mut best: Option<(String, u64)> = None;
+
+    for index in 0..16 {
+        let subkey = wide(&format!(r"{}\{:04}", DISPLAY_CLASS_KEY, index));
+
+        let mut memory_size = 0u64;
+        let mut memory_len = std::mem::size_of::<u64>() as u32;
+        let memory_status = unsafe {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                subkey.as_ptr(),
+                wide("HardwareInformation.qwMemorySize").as_ptr(),
+                RRF_RT_REG_QWORD,
+                std::ptr::null_mut(),
+                &mut memory_size as *mut u64 as *mut _,
+                &mut memory_len,
+            )
+        };
+
+        let mut name_buffer = [0u16; 256];
+        let mut name_len = std::mem::size_of_val(&name_buffer) as u32;
+        let name_status = unsafe {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                subkey.as_ptr(),
+                wide("DriverDesc").as_ptr(),
+                RRF_RT_REG_SZ,
+                std::ptr::null_mut(),
+                name_buffer.as_mut_ptr() as *mut _,
+                &mut name_len,
+            )
+        };
+        if name_status != 0 {
+            continue;
+        }
+
+        let name_end = name_buffer.iter().position(|&c| c == 0).unwrap_or(name_buffer.len());
+        let name = String::from_utf16_lossy(&name_buffer[..name_end]);
+        let memory = if memory_status == 0 { memory_size } else { 0 };
+
+        if best.as_ref().is_none_or(|(_, best_memory)| memory > *best_memory) {
+            best = Some((name, memory));
+        }
+    }
+
+    best
+}
+
+fn wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+unsafe fn from_wide_ptr(ptr: *const u16) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+    String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+}
@@ -0,0 +1,173 @@
+use std::sync::LazyLock;
+use nvml_wrapper::enums::device::GpuLockedClocksSetting;
+use nvml_wrapper::Nvml;
+
+use crate::models::GpuPowerProfile;
+use crate::AppState;
+
+// Device settings captured before the first profile was applied, restored once everything stops
+#[derive(Debug, Clone)]
+struct SavedDeviceState {
+    index: u32,
+    power_limit_mw: Option<u32>,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    persistence_mode: Option<bool>,
+}
+
+static SAVED_STATE: LazyLock<std::sync::Mutex<Option<Vec<SavedDeviceState>>>> = LazyLock::new(|| std::sync::Mutex::new(None));
+
+/// Apply a power profile to every NVIDIA device. Returns per-device warnings for settings
+/// the driver refused (usually missing admin/root permissions).
+pub fn apply_profile(profile: &GpuPowerProfile) -> Result<Vec<String>, String> {
+    let nvml = Nvml::init().map_err(|e| format!("NVML not available: {}", e))?;
+    let count = nvml.device_count().map_err(|e| format!("Failed to enumerate GPUs: {}", e))?;
+
+    let mut saved = SAVED_STATE.lock().unwrap();
+    let capture = saved.is_none();
+    let mut captured = Vec::new();
+    let mut warnings = Vec::new();
+
+    for index in 0..count {
+        let mut device = match nvml.device_by_index(index) {
+            Ok(device) => device,
+            Err(e) => {
+                warnings.push(format!("GPU {}: {}", index, e));
+                continue;
+            }
+        };
+
+        if capture {
+            #[cfg(target_os = "linux")]
+            let persistence_mode = device.is_in_persistent_mode().ok();
+            #[cfg(not(target_os = "linux"))]
+            let persistence_mode = None;
+            captured.push(SavedDeviceState {
+                index,
+                power_limit_mw: device.power_management_limit().ok(),
+                persistence_mode,
+            });
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(enabled) = profile.persistence_mode {
+            if let Err(e) = device.set_persistent(enabled) {
+                warnings.push(format!("GPU {}: failed to set persistence mode: {}", index, e));
+            }
+        }
+
+        if let Some(watts) = profile.power_limit_watts {
+            // Clamp to what the board allows instead of failing outright
+            let mut limit_mw = watts.saturating_mul(1000);
+            if let Ok(constraints) = device.power_management_limit_constraints() {
+                limit_mw = limit_mw.clamp(constraints.min_limit, constraints.max_limit);
+            }
+            if let Err(e) = device.set_power_management_limit(limit_mw) {
+                warnings.push(format!("GPU {}: failed to set power limit: {}", index, e));
+            }
+        }
+
+        match (profile.min_clock_mhz, profile.max_clock_mhz) {
+            (Some(min_clock_mhz), Some(max_clock_mhz)) => {
+                if let Err(e) = device.set_gpu_locked_clocks(GpuLockedClocksSetting::Numeric { min_clock_mhz, max_clock_mhz }) {
+                    warnings.push(format!("GPU {}: failed to lock clocks: {}", index, e));
+                }
+            }
+            _ => {
+                let _ = device.reset_gpu_locked_clocks();
+            }
+        }
+    }
+
+    if capture {
+        *saved = Some(captured);
+    }
+    Ok(warnings)
+}
+
+/// Put every device back to the settings it had before the first profile was applied
+pub fn restore_saved_state() {
+    let saved = match SAVED_STATE.lock().unwrap().take() {
+        Some(saved) => saved,
+        None => return,
+    };
+    let nvml = match Nvml::init() {
+        Ok(nvml) => nvml,
+        Err(e) => {
+            eprintln!("Failed to restore GPU settings, NVML not available: {}", e);
+            return;
+        }
+    };
+
+    for device_state in saved {
+        let mut device = match nvml.device_by_index(device_state.index) {
+            Ok(device) => device,
+            Err(_) => continue,
+        };
+        if let Some(limit_mw) = device_state.power_limit_mw {
+            if let Err(e) = device.set_power_management_limit(limit_mw) {
+                eprintln!("GPU {}: failed to restore power limit: {}", device_state.index, e);
+            }
+        }
+        let _ = device.reset_gpu_locked_clocks();
+        #[cfg(target_os = "linux")]
+        if let Some(enabled) = device_state.persistence_mode {
+            let _ = device.set_persistent(enabled);
+        }
+    }
+    println!("Restored GPU power settings");
+}
+
+fn find_profile(state_profiles: &[GpuPowerProfile], name: &Option<String>) -> Option<GpuPowerProfile> {
+    let name = name.as_ref()?;
+    state_profiles.iter().find(|p| &p.name == name).cloned()
+}
+
+/// Switch to the generation profile when a model server starts
+pub async fn on_model_started(state: &AppState) {
+    let profile = {
+        let config = state.config.lock().await;
+        if !config.gpu_power.enabled {
+            return;
+        }
+        find_profile(&config.gpu_power.profiles, &config.gpu_power.generation_profile)
+    };
+
+    if let Some(profile) = profile {
+        let name = profile.name.clone();
+        match tokio::task::spawn_blocking(move || apply_profile(&profile)).await {
+            Ok(Ok(warnings)) => {
+                println!("Applied GPU power profile '{}'", name);
+                for warning in warnings {
+                    eprintln!("{}", warning);
+                }
+            }
+            Ok(Err(e)) => eprintln!("Failed to apply GPU power profile '{}': {}", name, e),
+            Err(e) => eprintln!("GPU power task failed: {}", e),
+        }
+    }
+}
+
+/// Once the last model server is gone, restore the original settings or apply the idle profile
+pub async fn on_model_stopped(state: &AppState) {
+    if state.processes.has_children().await {
+        return;
+    }
+
+    let (enabled, idle_profile) = {
+        let config = state.config.lock().await;
+        (config.gpu_power.enabled, find_profile(&config.gpu_power.profiles, &config.gpu_power.idle_profile))
+    };
+    if !enabled && SAVED_STATE.lock().unwrap().is_none() {
+        return;
+    }
+
+    let _ = tokio::task::spawn_blocking(move || {
+        restore_saved_state();
+        if let Some(profile) = idle_profile {
+            if let Err(e) = apply_profile(&profile) {
+                eprintln!("Failed to apply idle GPU power profile '{}': {}", profile.name, e);
+            }
+        }
+    })
+    .await;
+}
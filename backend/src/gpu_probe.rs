@@ -0,0 +1,99 @@
+// GPU probes behind the system monitor. Each vendor backend answers for the GPUs it knows
+// about; the monitor asks them in order and shows the first one that reports. The Cargo
+// features (`gpu-nvidia`, `gpu-intel`, `gpu-apple`) pick which probes the monitor asks, while
+// the Windows performance counters stay as the vendor-neutral last resort. They don't remove
+// NVML from the build, the VRAM, power and telemetry features use it directly.
+
+/// One reading of a GPU, memory in bytes. `shared_memory` marks integrated GPUs whose
+/// memory is system RAM, where the totals are what the driver lets the GPU use.
+#[derive(Debug, Clone)]
+pub struct GpuSample {
+    pub name: String,
+    pub usage_percent: f32,
+    pub memory_total_bytes: u64,
+    pub memory_used_bytes: u64,
+    pub shared_memory: bool,
+}
+
+pub trait GpuProbe: Send + Sync {
+    /// Short name for logs
+    fn vendor(&self) -> &'static str;
+
+    /// The probe's primary GPU, None when it has none or can't read it. May block briefly
+    fn sample(&self) -> Option<GpuSample>;
+}
+
+#[cfg(feature = "gpu-nvidia")]
+struct NvmlProbe;
+
+#[cfg(feature = "gpu-nvidia")]
+impl GpuProbe for NvmlProbe {
+    fn vendor(&self) -> &'static str {
+        "nvidia"
+    }
+
+    fn sample(&self) -> Option<GpuSample> {
+        let nvml = nvml_wrapper::Nvml::init().ok()?;
+        if nvml.device_count().ok()? == 0 {
+            return None;
+        }
+        let device = nvml.device_by_index(0).ok()?;
+        let (memory_total_bytes, memory_used_bytes) = device.memory_info()
+            .map(|info| (info.total, info.used))
+            .unwrap_or((0, 0));
+        Some(GpuSample {
+            name: device.name().unwrap_or_else(|_| "NVIDIA GPU".to_string()),
+            usage_percent: device.utilization_rates().map(|util| util.gpu as f32).unwrap_or(0.0),
+            memory_total_bytes,
+            memory_used_bytes,
+            shared_memory: false,
+        })
+    }
+}
+
+/// WDDM performance counters, fed by the kernel for every vendor's driver
+#[cfg(windows)]
+struct CountersProbe;
+
+#[cfg(windows)]
+impl GpuProbe for CountersProbe {
+    fn vendor(&self) -> &'static str {
+        "counters"
+    }
+
+    fn sample(&self) -> Option<GpuSample> {
+        let (name, usage_percent, total_gb, used_gb) = crate::gpu_counters::get_gpu_info()?;
+        let gb = 1024.0 * 1024.0 * 1024.0;
+        Some(GpuSample {
+            name,
+            usage_percent,
+            memory_total_bytes: (total_gb as f64 * gb) as u64,
+            memory_used_bytes: (used_gb as f64 * gb) as u64,
+            shared_memory: false,
+        })
+    }
+}
+
+/// Probes compiled into this build, most specific first
+fn probes() -> &'static [Box<dyn GpuProbe>] {
+    static PROBES: std::sync::LazyLock<Vec<Box<dyn GpuProbe>>> = std::sync::LazyLock::new(|| {
+        let probes: Vec<Box<dyn GpuProbe>> = vec![
+            #[cfg(feature = "gpu-nvidia")]
+            Box::new(NvmlProbe),
+            #[cfg(all(feature = "gpu-intel", target_os = "linux"))]
+            Box::new(crate::intel_gpu::IntelProbe),
+            #[cfg(all(feature = "gpu-apple", target_os = "macos"))]
+            Box::new(crate::apple_silicon::AppleGpuProbe),
+            #[cfg(windows)]
+            Box::new(CountersProbe),
+        ];
+        println!("GPU probes: {}", probes.iter().map(|probe| probe.vendor()).collect::<Vec<_>>().join(", "));
+        probes
+    });
+    &PROBES
+}
+
+/// The first GPU any probe reports
+pub fn primary() -> Option<GpuSample> {
+    probes().iter().find_map(|probe| probe.sample())
+}
@@ -0,0 +1,181 @@
+// GPU state sampled while a benchmark runs. A score that drops because the card hit its
+// power cap or got hot says nothing about the llama.cpp build, so runs carry clocks,
+// temperatures and NVML throttle reasons along with their results.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use nvml_wrapper::bitmasks::device::ThrottleReasons;
+use nvml_wrapper::enum_wrappers::device::{Clock, TemperatureSensor};
+use nvml_wrapper::Nvml;
+use serde::{Deserialize, Serialize};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+// Reasons that lower clocks below what the workload asked for. Idle, sync boost and
+// display clocks are normal and not reported.
+const PERFORMANCE_LIMITS: [(ThrottleReasons, &str); 5] = [
+    (ThrottleReasons::SW_POWER_CAP, "power_cap"),
+    (ThrottleReasons::HW_POWER_BRAKE_SLOWDOWN, "power_brake"),
+    (ThrottleReasons::HW_SLOWDOWN, "hw_slowdown"),
+    (ThrottleReasons::SW_THERMAL_SLOWDOWN, "thermal"),
+    (ThrottleReasons::HW_THERMAL_SLOWDOWN, "hw_thermal"),
+];
+// Clocks held by application or locked clock settings, e.g. a GPU power profile
+const CLOCK_LOCKS: ThrottleReasons = ThrottleReasons::APPLICATIONS_CLOCKS_SETTING;
+
+/// Summary of one GPU over a benchmark run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuRunTelemetry {
+    pub index: u32,
+    pub name: String,
+    pub samples: usize,
+    pub max_temperature_c: Option<u32>,
+    pub avg_graphics_clock_mhz: Option<f64>,
+    pub min_graphics_clock_mhz: Option<u32>,
+    pub max_graphics_clock_mhz: Option<u32>,
+    pub avg_memory_clock_mhz: Option<f64>,
+    pub max_power_watts: Option<f64>,
+    pub throttled_samples: usize, // Samples with any of the reasons below
+    pub throttle_reasons: Vec<String>, // "power_cap", "thermal", ... seen at least once
+    pub clocks_locked: bool,
+}
+
+impl GpuRunTelemetry {
+    /// Share of the run spent throttled, 0.0 to 1.0
+    pub fn throttled_fraction(&self) -> f64 {
+        if self.samples == 0 { 0.0 } else { self.throttled_samples as f64 / self.samples as f64 }
+    }
+}
+
+struct DeviceSamples {
+    name: String,
+    samples: usize,
+    temperatures: Vec<u32>,
+    graphics_clocks: Vec<u32>,
+    memory_clocks: Vec<u32>,
+    power_mw: Vec<u32>,
+    throttled_samples: usize,
+    reasons: ThrottleReasons,
+}
+
+impl DeviceSamples {
+    fn summarize(self, index: u32) -> GpuRunTelemetry {
+        let average = |values: &[u32]| (!values.is_empty()).then(|| values.iter().map(|&v| v as f64).sum::<f64>() / values.len() as f64);
+        GpuRunTelemetry {
+            index,
+            name: self.name,
+            samples: self.samples,
+            max_temperature_c: self.temperatures.iter().copied().max(),
+            avg_graphics_clock_mhz: average(&self.graphics_clocks),
+            min_graphics_clock_mhz: self.graphics_clocks.iter().copied().min(),
+            max_graphics_clock_mhz: self.graphics_clocks.iter().copied().max(),
+            avg_memory_clock_mhz: average(&self.memory_clocks),
+            max_power_watts: self.power_mw.iter().copied().max().map(|mw| mw as f64 / 1000.0),
+            throttled_samples: self.throttled_samples,
+            throttle_reasons: PERFORMANCE_LIMITS
+                .iter()
+                .filter(|(reason, _)| self.reasons.contains(*reason))
+                .map(|(_, name)| name.to_string())
+                .collect(),
+            clocks_locked: self.reasons.intersects(CLOCK_LOCKS),
+        }
+    }
+}
+
+/// Samples every NVIDIA GPU on a blocking thread until finished or dropped, so a
+/// benchmark that times out doesn't leave the thread sampling
+pub struct TelemetrySampler {
+    stop: Arc<AtomicBool>,
+    task: Option<tokio::task::JoinHandle<Vec<GpuRunTelemetry>>>,
+}
+
+impl TelemetrySampler {
+    pub fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let task = tokio::task::spawn_blocking(move || sample_until(&stop_flag));
+        Self { stop, task: Some(task) }
+    }
+
+    /// Stop sampling and summarize. Empty without NVML or NVIDIA GPUs.
+    pub async fn finish(mut self) -> Vec<GpuRunTelemetry> {
+        self.stop.store(true, Ordering::Release);
+        match self.task.take() {
+            Some(task) => task.await.unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Drop for TelemetrySampler {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+    }
+}
+
+fn sample_until(stop: &AtomicBool) -> Vec<GpuRunTelemetry> {
+    let Ok(nvml) = Nvml::init() else {
+        return Vec::new();
+    };
+    let count = nvml.device_count().unwrap_or(0);
+    let mut devices: Vec<DeviceSamples> = (0..count)
+        .map(|index| DeviceSamples {
+            name: nvml.device_by_index(index).and_then(|d| d.name()).unwrap_or_else(|_| format!("GPU {}", index)),
+            samples: 0,
+            temperatures: Vec::new(),
+            graphics_clocks: Vec::new(),
+            memory_clocks: Vec::new(),
+            power_mw: Vec::new(),
+            throttled_samples: 0,
+            reasons: ThrottleReasons::empty(),
+        })
+        .collect();
+
+    let limits = PERFORMANCE_LIMITS.iter().fold(ThrottleReasons::empty(), |all, (reason, _)| all | *reason);
+    loop {
+        for (index, samples) in devices.iter_mut().enumerate() {
+            let Ok(device) = nvml.device_by_index(index as u32) else {
+                continue;
+            };
+            samples.samples += 1;
+            samples.temperatures.extend(device.temperature(TemperatureSensor::Gpu).ok());
+            samples.graphics_clocks.extend(device.clock_info(Clock::Graphics).ok());
+            samples.memory_clocks.extend(device.clock_info(Clock::Memory).ok());
+            samples.power_mw.extend(device.power_usage().ok());
+            if let Ok(reasons) = device.current_throttle_reasons() {
+                if reasons.intersects(limits) {
+                    samples.throttled_samples += 1;
+                }
+                samples.reasons |= reasons;
+            }
+        }
+        if stop.load(Ordering::Acquire) {
+            break;
+        }
+        std::thread::sleep(SAMPLE_INTERVAL);
+    }
+
+    devices
+        .into_iter()
+        .enumerate()
+        .map(|(index, samples)| samples.summarize(index as u32))
+        .collect()
+}
+
+/// Short explanation when throttling may account for a score, None for a clean run
+pub fn throttling_note(gpus: &[GpuRunTelemetry]) -> Option<String> {
+    let throttled: Vec<String> = gpus
+        .iter()
+        .filter(|gpu| gpu.throttled_samples > 0)
+        .map(|gpu| format!(
+            "{} throttled {:.0}% of the run ({}{})",
+            gpu.name,
+            gpu.throttled_fraction() * 100.0,
+            gpu.throttle_reasons.join(", "),
+            gpu.max_temperature_c.map(|t| format!(", peak {}°C", t)).unwrap_or_default(),
+        ))
+        .collect();
+    (!throttled.is_empty()).then(|| throttled.join("; "))
+}
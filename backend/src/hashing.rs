@@ -0,0 +1,350 @@
+// Library hashing jobs: SHA-256 of every GGUF, grouped into duplicates or checked
+// against the LFS hashes the Hub publishes. Files are handed out to a small pool of
+// blocking workers that read in large chunks, so a terabyte library keeps the disk
+// busy without tying up the async runtime. Progress is emitted as `hash-progress`
+// and the outcome as `hash-complete`; jobs can be cancelled between chunks.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::Emitter;
+
+use crate::models::QuantAlternative;
+
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+// Disks, not cores, are the limit; more readers than this just seek against each other
+const MAX_WORKERS: usize = 4;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+// Cancel flags of running jobs
+static JOBS: LazyLock<std::sync::Mutex<HashMap<String, Arc<AtomicBool>>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashJobKind {
+    Hash,
+    Dedup,
+    Verify,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HashProgress {
+    pub job_id: String,
+    pub kind: HashJobKind,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub bytes_per_second: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HashedFile {
+    pub path: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub sha256: String,
+    pub size_bytes: u64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyResult {
+    pub path: String,
+    pub repo_id: Option<String>,
+    pub expected: Option<String>,
+    pub actual: String,
+    pub status: String, // "ok", "mismatch" or "unknown" when the Hub has no hash for it
+    pub alternatives: Vec<QuantAlternative>, // Other quantizations once a file has mismatched repeatedly
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HashJobResult {
+    pub job_id: String,
+    pub kind: HashJobKind,
+    pub cancelled: bool,
+    pub files: Vec<HashedFile>,
+    pub duplicates: Vec<DuplicateGroup>,
+    pub verification: Vec<VerifyResult>,
+    pub errors: Vec<String>,
+}
+
+/// Start a job over the given files, or every GGUF in the models directory
+pub async fn start_job(
+    kind: HashJobKind,
+    paths: Option<Vec<String>>,
+    models_directory: String,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let files: Vec<PathBuf> = match paths {
+        Some(paths) => paths.into_iter().map(PathBuf::from).collect(),
+        None => {
+            if models_directory.is_empty() || !Path::new(&models_directory).is_dir() {
+                return Err("Models directory is not set".to_string());
+            }
+            let pattern = format!("{}/**/*.gguf", models_directory);
+            glob::glob(&pattern)
+                .map_err(|e| format!("Invalid models directory: {}", e))?
+                .flatten()
+                .collect()
+        }
+    };
+
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    JOBS.lock().unwrap().insert(job_id.clone(), cancel.clone());
+
+    let id = job_id.clone();
+    tokio::spawn(async move {
+        let result = run_job(&id, kind, files, &models_directory, cancel, &app_handle).await;
+        JOBS.lock().unwrap().remove(&id);
+        println!(
+            "Hash job {} finished: {} files, {} errors{}",
+            id,
+            result.files.len(),
+            result.errors.len(),
+            if result.cancelled { ", cancelled" } else { "" }
+        );
+        let _ = app_handle.emit("hash-complete", &result);
+    });
+    Ok(job_id)
+}
+
+pub fn cancel_job(job_id: &str) -> Result<(), String> {
+    match JOBS.lock().unwrap().get(job_id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err("Hash job not found".to_string()),
+    }
+}
+
+async fn run_job(
+    job_id: &str,
+    kind: HashJobKind,
+    files: Vec<PathBuf>,
+    models_directory: &str,
+    cancel: Arc<AtomicBool>,
+    app_handle: &tauri::AppHandle,
+) -> HashJobResult {
+    let mut errors = Vec::new();
+    let mut queue: Vec<(PathBuf, u64)> = Vec::new();
+    for path in files {
+        match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() => queue.push((path, metadata.len())),
+            Ok(_) => errors.push(format!("{}: not a file", path.display())),
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    // Largest first so one huge file doesn't start last and leave the other workers idle
+    queue.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+    let files_total = queue.len();
+    let bytes_total: u64 = queue.iter().map(|(_, size)| size).sum();
+    let queue = Arc::new(std::sync::Mutex::new(VecDeque::from(queue)));
+    let bytes_done = Arc::new(AtomicU64::new(0));
+    let files_done = Arc::new(AtomicUsize::new(0));
+
+    let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1).clamp(1, MAX_WORKERS).min(files_total.max(1));
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let (queue, bytes_done, files_done, cancel) = (queue.clone(), bytes_done.clone(), files_done.clone(), cancel.clone());
+            tokio::task::spawn_blocking(move || worker(&queue, &bytes_done, &files_done, &cancel))
+        })
+        .collect();
+
+    let started = Instant::now();
+    let progress = |bytes: u64, files: usize| HashProgress {
+        job_id: job_id.to_string(),
+        kind,
+        files_done: files,
+        files_total,
+        bytes_done: bytes,
+        bytes_total,
+        bytes_per_second: bytes as f64 / started.elapsed().as_secs_f64().max(0.001),
+    };
+    while !handles.iter().all(|h| h.is_finished()) {
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+        let _ = app_handle.emit("hash-progress", progress(bytes_done.load(Ordering::Relaxed), files_done.load(Ordering::Relaxed)));
+    }
+
+    let mut hashed = Vec::new();
+    for handle in handles {
+        match handle.await {
+            Ok((files, worker_errors)) => {
+                hashed.extend(files);
+                errors.extend(worker_errors);
+            }
+            Err(e) => errors.push(format!("Hash worker failed: {}", e)),
+        }
+    }
+    hashed.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let cancelled = cancel.load(Ordering::Relaxed);
+    let duplicates = if kind == HashJobKind::Dedup && !cancelled { find_duplicates(&hashed) } else { Vec::new() };
+    let verification = if kind == HashJobKind::Verify && !cancelled {
+        verify_against_hub(&hashed, models_directory, &mut errors).await
+    } else {
+        Vec::new()
+    };
+
+    HashJobResult {
+        job_id: job_id.to_string(),
+        kind,
+        cancelled,
+        files: hashed,
+        duplicates,
+        verification,
+        errors,
+    }
+}
+
+fn worker(
+    queue: &std::sync::Mutex<VecDeque<(PathBuf, u64)>>,
+    bytes_done: &AtomicU64,
+    files_done: &AtomicUsize,
+    cancel: &AtomicBool,
+) -> (Vec<HashedFile>, Vec<String>) {
+    let mut hashed = Vec::new();
+    let mut errors = Vec::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let Some((path, size)) = queue.lock().unwrap().pop_front() else { break };
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        match hash_file(&path, &mut buffer, bytes_done, cancel) {
+            Ok(Some(sha256)) => hashed.push(HashedFile { path: path.to_string_lossy().to_string(), size_bytes: size, sha256 }),
+            Ok(None) => break, // Cancelled mid-file
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+        files_done.fetch_add(1, Ordering::Relaxed);
+    }
+    (hashed, errors)
+}
+
+fn hash_file(path: &Path, buffer: &mut [u8], bytes_done: &AtomicU64, cancel: &AtomicBool) -> std::io::Result<Option<String>> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let read = file.read(buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        bytes_done.fetch_add(read as u64, Ordering::Relaxed);
+    }
+    Ok(Some(format!("{:x}", hasher.finalize())))
+}
+
+fn find_duplicates(hashed: &[HashedFile]) -> Vec<DuplicateGroup> {
+    let mut by_hash: HashMap<&str, Vec<&HashedFile>> = HashMap::new();
+    for file in hashed {
+        by_hash.entry(&file.sha256).or_default().push(file);
+    }
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(sha256, files)| DuplicateGroup {
+            sha256: sha256.to_string(),
+            size_bytes: files[0].size_bytes,
+            paths: files.iter().map(|f| f.path.clone()).collect(),
+        })
+        .collect();
+    // Most space wasted first
+    groups.sort_by_key(|g| std::cmp::Reverse(g.size_bytes * (g.paths.len() as u64 - 1)));
+    groups
+}
+
+/// Compare against the LFS hashes of the repo a file was downloaded from, following
+/// the downloader's models_directory/author/model layout
+async fn verify_against_hub(hashed: &[HashedFile], models_directory: &str, errors: &mut Vec<String>) -> Vec<VerifyResult> {
+    let mut repo_hashes: HashMap<String, Option<HashMap<String, String>>> = HashMap::new();
+    let mut results = Vec::new();
+    for file in hashed {
+        let path = Path::new(&file.path);
+        let repo_id = repo_for(path, models_directory);
+        let expected = match &repo_id {
+            Some(repo_id) => {
+                if !repo_hashes.contains_key(repo_id) {
+                    let hashes = match fetch_lfs_hashes(repo_id).await {
+                        Ok(hashes) => Some(hashes),
+                        Err(e) => {
+                            errors.push(format!("{}: {}", repo_id, e));
+                            None
+                        }
+                    };
+                    repo_hashes.insert(repo_id.clone(), hashes);
+                }
+                let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                repo_hashes[repo_id].as_ref().and_then(|hashes| hashes.get(&file_name).cloned())
+            }
+            None => None,
+        };
+        let status = match &expected {
+            Some(expected) if expected == &file.sha256 => "ok",
+            Some(_) => "mismatch",
+            None => "unknown",
+        };
+        let mut alternatives = Vec::new();
+        if let (Some(repo_id), "mismatch") = (&repo_id, status) {
+            let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if crate::downloader::record_file_failure(repo_id, &file_name) {
+                alternatives = crate::huggingface::quantization_alternatives(repo_id, &[file_name]).await;
+            }
+        }
+        results.push(VerifyResult {
+            path: file.path.clone(),
+            repo_id,
+            expected,
+            actual: file.sha256.clone(),
+            status: status.to_string(),
+            alternatives,
+        });
+    }
+    results
+}
+
+fn repo_for(path: &Path, models_directory: &str) -> Option<String> {
+    let relative = path.strip_prefix(models_directory).ok()?;
+    let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    (parts.len() == 3).then(|| format!("{}/{}", parts[0], parts[1]))
+}
+
+/// File name -> SHA-256 for every LFS file in the repo
+async fn fetch_lfs_hashes(repo_id: &str) -> Result<HashMap<String, String>, String> {
+    let url = format!("https://huggingface.co/api/models/{}/tree/main?recursive=true", repo_id);
+    let client = reqwest::Client::new();
+    let response = crate::huggingface::authorize(client.get(&url), &url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("Hub returned status {}", response.status()));
+    }
+    let entries: Vec<serde_json::Value> = response.json().await.map_err(|e| e.to_string())?;
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            let path = entry["path"].as_str()?;
+            let oid = entry["lfs"]["oid"].as_str()?;
+            let name = path.rsplit('/').next().unwrap_or(path);
+            Some((name.to_string(), oid.to_string()))
+        })
+        .collect())
+}
@@ -0,0 +1,447 @@
+// Local conversion for repos that only publish safetensors. A job downloads the original
+// weights with their config and tokenizer through the downloader, converts them with llama.cpp's
+// convert_hf_to_gguf.py and quantizes the result with llama-quantize from the active
+// version. The convert script isn't part of llama.cpp releases: it comes from a llama.cpp
+// checkout, with its Python requirements installed
+// (`pip install -r requirements/requirements-convert_hf_to_gguf.txt`).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use regex::Regex;
+use tauri::Emitter;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command as TokioCommand;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::benchmark::tool_path;
+use crate::config::get_app_data_path;
+use crate::downloader::{DownloadConfig, DownloadState};
+use crate::models::{ConversionJob, ConversionStep};
+use crate::AppState;
+
+const CONVERT_SCRIPT: &str = "convert_hf_to_gguf.py";
+const PROGRESS_EVENT: &str = "hf-conversion-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+// Weights, config and tokenizer files the convert script reads from the repo root
+const REPO_FILES: &[&str] = &["*.safetensors", "*.json", "*.model", "*.tiktoken", "*.txt"];
+
+// Types convert_hf_to_gguf.py writes itself, anything else is quantized from an F16 file
+const DIRECT_TYPES: &[&str] = &["F32", "F16", "BF16", "Q8_0"];
+
+// llama-quantize types that work without an importance matrix
+const QUANT_TYPES: &[&str] = &[
+    "Q2_K", "Q3_K_S", "Q3_K_M", "Q3_K_L", "Q4_0", "Q4_1", "Q4_K_S", "Q4_K_M",
+    "Q5_0", "Q5_1", "Q5_K_S", "Q5_K_M", "Q6_K", "IQ4_NL", "IQ4_XS",
+];
+
+// tqdm bar of the convert script, `Writing:  45%|████▌     | ...`
+static CONVERT_PROGRESS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d{1,3})%\|").unwrap());
+// Tensor counter of llama-quantize, `[  12/ 291] blk.0.attn_k.weight ...`
+static QUANTIZE_PROGRESS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\s*(\d+)/\s*(\d+)\]").unwrap());
+
+static JOBS: LazyLock<std::sync::Mutex<HashMap<String, ConversionJob>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+// Running job tasks, aborting one kills its child process through kill_on_drop
+static TASKS: LazyLock<std::sync::Mutex<HashMap<String, tokio::task::AbortHandle>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+// Weight downloads by job id, cancelled along with their job
+static DOWNLOADS: LazyLock<std::sync::Mutex<HashMap<String, String>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Tools a conversion needs, resolved before anything is downloaded
+struct Toolchain {
+    python: String,
+    convert_script: PathBuf,
+    quantize: Option<PathBuf>,
+}
+
+fn update(app_handle: &tauri::AppHandle, job_id: &str, change: impl FnOnce(&mut ConversionJob)) {
+    let job = {
+        let mut jobs = JOBS.lock().unwrap();
+        let Some(job) = jobs.get_mut(job_id) else {
+            return;
+        };
+        // A cancelled job keeps its state, the task may still report before it is aborted
+        if job.step == ConversionStep::Cancelled {
+            return;
+        }
+        change(job);
+        job.clone()
+    };
+    let _ = app_handle.emit(PROGRESS_EVENT, job);
+}
+
+fn set_step(app_handle: &tauri::AppHandle, job_id: &str, step: ConversionStep, message: String) {
+    update(app_handle, job_id, |job| {
+        job.step = step;
+        job.step_progress = 0.0;
+        job.message = message;
+    });
+}
+
+fn normalize_quantization(quantization: &str) -> Result<String, String> {
+    let quantization = quantization.trim().to_uppercase();
+    if DIRECT_TYPES.contains(&quantization.as_str()) || QUANT_TYPES.contains(&quantization.as_str()) {
+        Ok(quantization)
+    } else {
+        Err(format!("Unsupported quantization type: {}", quantization))
+    }
+}
+
+async fn resolve_toolchain(state: &AppState, quantization: &str) -> Result<Toolchain, String> {
+    let config = state.config.lock().await.clone();
+    let version_dir = crate::process::resolve_llama_server_path_with_fallback(state, &config).await
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or("No llama.cpp version installed")?;
+
+    let convert_script = if config.convert_script_path.trim().is_empty() {
+        version_dir.join(CONVERT_SCRIPT)
+    } else {
+        PathBuf::from(config.convert_script_path.trim())
+    };
+    if !convert_script.is_file() {
+        return Err(format!(
+            "{} not found at {:?}. Set the path to the script in a llama.cpp checkout and install its requirements \
+             (pip install -r requirements/requirements-convert_hf_to_gguf.txt)",
+            CONVERT_SCRIPT, convert_script
+        ));
+    }
+
+    let python = if config.python_path.trim().is_empty() {
+        if cfg!(windows) { "python" } else { "python3" }.to_string()
+    } else {
+        config.python_path.trim().to_string()
+    };
+
+    let quantize = if DIRECT_TYPES.contains(&quantization) {
+        None
+    } else {
+        let quantize = tool_path(&version_dir, "llama-quantize");
+        if !quantize.exists() {
+            return Err(format!("llama-quantize not found at: {:?}", quantize));
+        }
+        Some(quantize)
+    };
+
+    Ok(Toolchain { python, convert_script, quantize })
+}
+
+/// Start converting a Hub repo's safetensors into a GGUF of the given type in the models folder.
+/// Progress goes out as `hf-conversion-progress` events carrying the whole job.
+pub async fn start_conversion(
+    model_id: &str,
+    quantization: &str,
+    revision: Option<String>,
+    state: &AppState,
+    app_handle: tauri::AppHandle,
+) -> Result<ConversionJob, String> {
+    let model_id = model_id.trim().trim_matches('/').to_string();
+    let Some((_, repo_name)) = model_id.split_once('/') else {
+        return Err(format!("Invalid model id: {}", model_id));
+    };
+    if state.config.lock().await.offline_mode {
+        return Err("Offline mode is on, conversions need to download the weights".to_string());
+    }
+    let quantization = normalize_quantization(quantization)?;
+    let toolchain = resolve_toolchain(state, &quantization).await?;
+
+    let models_directory = state.config.lock().await.models_directory.clone();
+    let output = Path::new(&models_directory)
+        .join(repo_name)
+        .join(format!("{}-{}.gguf", repo_name, quantization));
+    if output.exists() {
+        return Err(format!("{} already exists", output.display()));
+    }
+    let busy = JOBS.lock().unwrap().values().any(|job| {
+        job.output_path.as_deref() == Some(&*output.to_string_lossy())
+            && !matches!(job.step, ConversionStep::Completed | ConversionStep::Failed | ConversionStep::Cancelled)
+    });
+    if busy {
+        return Err(format!("{} is already being converted to {}", model_id, quantization));
+    }
+
+    let job = ConversionJob {
+        id: Uuid::new_v4().to_string(),
+        model_id: model_id.clone(),
+        revision: revision.clone(),
+        quantization: quantization.clone(),
+        step: ConversionStep::Downloading,
+        step_progress: 0.0,
+        message: "Listing repository files".to_string(),
+        output_path: Some(output.to_string_lossy().to_string()),
+        error: None,
+        started_at: Utc::now(),
+    };
+    JOBS.lock().unwrap().insert(job.id.clone(), job.clone());
+
+    let job_id = job.id.clone();
+    let state = state.clone();
+    let task = tokio::spawn(async move {
+        let staging = match get_app_data_path("conversions").await {
+            Ok(dir) => dir.join(&job_id),
+            Err(e) => {
+                finish(&app_handle, &job_id, Err(e.to_string()));
+                return;
+            }
+        };
+        let revision = revision.as_deref().unwrap_or("main");
+        let result = run_job(&state, &app_handle, &job_id, &model_id, revision, &quantization, &toolchain, &staging, &output).await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&output).await;
+        }
+        let _ = tokio::fs::remove_dir_all(&staging).await;
+        finish(&app_handle, &job_id, result);
+    });
+    TASKS.lock().unwrap().insert(job.id.clone(), task.abort_handle());
+
+    println!("Started conversion {} of {} to {}", job.id, job.model_id, job.quantization);
+    Ok(job)
+}
+
+fn finish(app_handle: &tauri::AppHandle, job_id: &str, result: Result<(), String>) {
+    TASKS.lock().unwrap().remove(job_id);
+    DOWNLOADS.lock().unwrap().remove(job_id);
+    match result {
+        Ok(()) => set_step(app_handle, job_id, ConversionStep::Completed, "Conversion finished".to_string()),
+        Err(e) => {
+            eprintln!("Conversion {} failed: {}", job_id, e);
+            update(app_handle, job_id, |job| {
+                job.step = ConversionStep::Failed;
+                job.message = "Conversion failed".to_string();
+                job.error = Some(e);
+            });
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_job(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    model_id: &str,
+    revision: &str,
+    quantization: &str,
+    toolchain: &Toolchain,
+    staging: &Path,
+    output: &Path,
+) -> Result<(), String> {
+    let weights_dir = staging.join("weights");
+    tokio::fs::create_dir_all(&weights_dir).await.map_err(|e| format!("Failed to create {}: {}", weights_dir.display(), e))?;
+    if let Some(parent) = output.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    download_weights(state, app_handle, job_id, model_id, revision, &weights_dir).await?;
+
+    // Types the script writes directly go straight to the output, the rest through an F16 file
+    let converted = if toolchain.quantize.is_some() { staging.join("model-F16.gguf") } else { output.to_path_buf() };
+    let out_type = if toolchain.quantize.is_some() { "f16".to_string() } else { quantization.to_lowercase() };
+    set_step(app_handle, job_id, ConversionStep::Converting, format!("Converting to {}", out_type.to_uppercase()));
+    let mut convert = TokioCommand::new(&toolchain.python);
+    convert.arg(&toolchain.convert_script)
+        .arg(&weights_dir)
+        .arg("--outfile").arg(&converted)
+        .arg("--outtype").arg(&out_type);
+    run_tool(app_handle, job_id, convert, &CONVERT_PROGRESS).await
+        .map_err(|e| format!("{} failed: {}", CONVERT_SCRIPT, e))?;
+
+    if let Some(quantize_tool) = &toolchain.quantize {
+        // The weights aren't needed anymore, free the space before writing another copy
+        let _ = tokio::fs::remove_dir_all(&weights_dir).await;
+        set_step(app_handle, job_id, ConversionStep::Quantizing, format!("Quantizing to {}", quantization));
+        let mut quantize = TokioCommand::new(quantize_tool);
+        quantize.arg(&converted).arg(output).arg(quantization);
+        run_tool(app_handle, job_id, quantize, &QUANTIZE_PROGRESS).await
+            .map_err(|e| format!("llama-quantize failed: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Download the weights at the revision through the downloader, so its speed limit,
+/// retries and resume apply, and wait for them while mirroring its progress in the job
+async fn download_weights(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    model_id: &str,
+    revision: &str,
+    target: &Path,
+) -> Result<(), String> {
+    let patterns: Vec<String> = REPO_FILES.iter().map(|p| p.to_string()).collect();
+    // Subfolders hold alternative formats (`original/` checkpoints and the like), the script reads the root
+    let files: Vec<(String, u64)> = crate::huggingface::resolve_file_patterns(model_id, Some(revision), &patterns, &[]).await?
+        .into_iter()
+        .filter(|(path, _)| !path.contains('/'))
+        .collect();
+    if !files.iter().any(|(f, _)| f.ends_with(".safetensors")) {
+        return Err(format!("{} has no safetensors weights in its root folder", model_id));
+    }
+
+    // The converted copy is about as large as the weights and is written next to them
+    let sizes = files.clone();
+    let destination = target.to_path_buf();
+    let shortfall = tokio::task::spawn_blocking(move || {
+        let needed = crate::downloader::remaining_bytes(&sizes, &destination) * 2;
+        let available = crate::downloader::free_space_for(&destination)?;
+        (needed > 0 && needed + crate::DISK_SPACE_RESERVE_BYTES > available).then_some((needed, available))
+    })
+    .await
+    .ok()
+    .flatten();
+    if let Some((needed, available)) = shortfall {
+        return Err(format!(
+            "Not enough disk space to convert {}: {:.1} GB needed, {:.1} GB available",
+            model_id,
+            needed as f64 / 1024.0 / 1024.0 / 1024.0,
+            available as f64 / 1024.0 / 1024.0 / 1024.0,
+        ));
+    }
+
+    let config = DownloadConfig {
+        base_url: format!("https://huggingface.co/{}/resolve/{}", model_id, revision),
+        destination_folder: target.to_string_lossy().to_string(),
+        auto_extract: false,
+        create_subfolder: None,
+        files: files.into_iter().map(|(file, _)| file).collect(),
+        custom_headers: Some(HashMap::from([("User-Agent".to_string(), "Llama-OS-Tauri/1.0".to_string())])),
+    };
+    let download_id = crate::downloader::start_download(config, state, app_handle.clone()).await
+        .map_err(|e| format!("Failed to start downloading the weights: {}", e))?
+        .download_id;
+    DOWNLOADS.lock().unwrap().insert(job_id.to_string(), download_id.clone());
+
+    loop {
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+        let Some(status) = state.downloads.status(&download_id).await else {
+            return Err("The weights download disappeared".to_string());
+        };
+        match status.status {
+            DownloadState::Completed => return Ok(()),
+            DownloadState::Failed => {
+                return Err(format!("Failed to download the weights: {}", status.error.unwrap_or_default()));
+            }
+            DownloadState::Cancelled => return Err("The weights download was cancelled".to_string()),
+            _ => update(app_handle, job_id, |job| {
+                job.message = format!(
+                    "Downloading {} ({}/{})",
+                    status.current_file,
+                    (status.files_completed + 1).min(status.total_files),
+                    status.total_files,
+                );
+                job.step_progress = status.progress as f64 / 100.0;
+            }),
+        }
+    }
+}
+
+/// Forward a reader's output line by line. tqdm redraws its bar with `\r`, so that ends a line too
+fn forward_lines(mut reader: impl AsyncRead + Unpin + Send + 'static, lines: mpsc::UnboundedSender<String>) {
+    tokio::spawn(async move {
+        let mut buffer = [0u8; 4096];
+        let mut line = Vec::new();
+        while let Ok(read) = reader.read(&mut buffer).await {
+            if read == 0 {
+                break;
+            }
+            for &byte in &buffer[..read] {
+                if byte == b'\n' || byte == b'\r' {
+                    if !line.is_empty() {
+                        let _ = lines.send(String::from_utf8_lossy(&line).to_string());
+                        line.clear();
+                    }
+                } else {
+                    line.push(byte);
+                }
+            }
+        }
+        if !line.is_empty() {
+            let _ = lines.send(String::from_utf8_lossy(&line).to_string());
+        }
+    });
+}
+
+/// Run a conversion tool, turning the progress it prints into the job's step progress.
+/// The last line printed is kept for the error when it fails.
+async fn run_tool(app_handle: &tauri::AppHandle, job_id: &str, mut cmd: TokioCommand, progress: &Regex) -> Result<(), String> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    // Hide console window on Windows release builds
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, sender.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, sender);
+    }
+
+    let mut last_line = String::new();
+    let mut last_report = Instant::now();
+    while let Some(line) = receiver.recv().await {
+        if let Some(captures) = progress.captures(&line) {
+            let fraction = match (captures.get(1), captures.get(2)) {
+                (Some(done), Some(total)) => done.as_str().parse::<f64>().ok()
+                    .zip(total.as_str().parse::<f64>().ok().filter(|t| *t > 0.0))
+                    .map(|(done, total)| done / total),
+                (Some(percent), None) => percent.as_str().parse::<f64>().ok().map(|p| p / 100.0),
+                _ => None,
+            };
+            if let Some(fraction) = fraction {
+                if last_report.elapsed() >= PROGRESS_INTERVAL {
+                    last_report = Instant::now();
+                    update(app_handle, job_id, |job| job.step_progress = fraction.min(1.0));
+                }
+            }
+        }
+        if !line.trim().is_empty() {
+            last_line = line;
+        }
+    }
+
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("exited with {}: {}", status, last_line.trim()))
+    }
+}
+
+/// Every job of this session, newest first
+pub fn list_jobs() -> Vec<ConversionJob> {
+    let mut jobs: Vec<ConversionJob> = JOBS.lock().unwrap().values().cloned().collect();
+    jobs.sort_by_key(|job| std::cmp::Reverse(job.started_at));
+    jobs
+}
+
+/// Stop a running job and remove its partial files
+pub async fn cancel_conversion(job_id: &str, state: &AppState, app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let task = TASKS.lock().unwrap().remove(job_id).ok_or("Conversion is not running")?;
+    task.abort();
+    let download_id = DOWNLOADS.lock().unwrap().remove(job_id);
+    if let Some(download_id) = download_id {
+        let _ = state.downloads.cancel(&download_id).await;
+    }
+
+    set_step(app_handle, job_id, ConversionStep::Cancelled, "Conversion cancelled".to_string());
+    let output = JOBS.lock().unwrap().get(job_id).and_then(|job| job.output_path.clone());
+    if let Some(output) = output {
+        let _ = tokio::fs::remove_file(&output).await;
+    }
+    let conversions = get_app_data_path("conversions").await.ok();
+    if let Some(conversions) = conversions {
+        let _ = tokio::fs::remove_dir_all(conversions.join(job_id)).await;
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use crate::models::*;
+use crate::AppState;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+use tauri::Emitter;
+use tokio::sync::Mutex;
+
+const METADATA_FILE: &str = "hf_metadata.json";
+
+// Entries younger than this are not fetched again
+const REFRESH_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+// How often the job wakes up to look for stale entries
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Delay before the first pass so startup traffic isn't competing with it
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+// Gap between API requests to stay well below the anonymous Hub rate limit
+const REQUEST_SPACING: Duration = Duration::from_secs(2);
+
+// Cached metadata keyed by repo id, loaded from disk on first use
+static METADATA: LazyLock<Mutex<Option<HashMap<String, UpstreamMetadata>>>> = LazyLock::new(|| Mutex::new(None));
+
+async fn load_cache() -> HashMap<String, UpstreamMetadata> {
+    let path = match crate::config::get_app_data_path(METADATA_FILE).await.map_err(|e| e.to_string()) {
+        Ok(path) => path,
+        Err(_) => return HashMap::new(),
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+async fn save_cache(metadata: &HashMap<String, UpstreamMetadata>) -> Result<(), String> {
+    let path = crate::config::get_app_data_path(METADATA_FILE).await.map_err(|e| e.to_string())?;
+    let contents = serde_json::to_string_pretty(metadata).map_err(|e| e.to_string())?;
+    tokio::fs::write(&path, contents).await.map_err(|e| e.to_string())
+}
+
+/// Current cached metadata for all installed repos
+pub async fn get_metadata() -> HashMap<String, UpstreamMetadata> {
+    let mut cache = METADATA.lock().await;
+    if cache.is_none() {
+        *cache = Some(load_cache().await);
+    }
+    cache.clone().unwrap_or_default()
+}
+
+/// Installed repos following the downloader's models_directory/author/model layout,
+/// with the newest local modification time of their GGUF files
+async fn installed_repos(models_directory: &str) -> HashMap<String, SystemTime> {
+    let mut repos = HashMap::new();
+    let Ok(mut authors) = tokio::fs::read_dir(models_directory).await else {
+        return repos;
+    };
+    while let Ok(Some(author)) = authors.next_entry().await {
+        if !author.path().is_dir() {
+            continue;
+        }
+        let Ok(mut models) = tokio::fs::read_dir(author.path()).await else {
+            continue;
+        };
+        while let Ok(Some(model)) = models.next_entry().await {
+            if let Some(modified) = newest_gguf(&model.path()).await {
+                let repo_id = format!("{}/{}", author.file_name().to_string_lossy(), model.file_name().to_string_lossy());
+                repos.insert(repo_id, modified);
+            }
+        }
+    }
+    repos
+}
+
+async fn newest_gguf(dir: &Path) -> Option<SystemTime> {
+    let mut entries = tokio::fs::read_dir(dir).await.ok()?;
+    let mut newest = None;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("gguf")) {
+            if let Ok(modified) = entry.metadata().await.and_then(|m| m.modified()) {
+                newest = newest.max(Some(modified));
+            }
+        }
+    }
+    newest
+}
+
+enum FetchOutcome {
+    Found(serde_json::Value),
+    Missing,
+    RateLimited(Duration),
+}
+
+async fn fetch_repo(client: &reqwest::Client, repo_id: &str) -> Result<FetchOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let url = format!("https://huggingface.co/api/models/{}", repo_id);
+    let response = crate::huggingface::authorize(client.get(&url), &url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await?;
+
+    match response.status().as_u16() {
+        200..=299 => Ok(FetchOutcome::Found(response.json().await?)),
+        404 | 410 => Ok(FetchOutcome::Missing),
+        429 => {
+            let retry_after = response.headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(CHECK_INTERVAL.as_secs());
+            Ok(FetchOutcome::RateLimited(Duration::from_secs(retry_after)))
+        }
+        status => Err(format!("API request failed with status: {}", status).into()),
+    }
+}
+
+fn is_stale(entry: Option<&UpstreamMetadata>) -> bool {
+    match entry {
+        Some(entry) => (chrono::Utc::now() - entry.refreshed_at).to_std().map_or(true, |age| age >= REFRESH_AGE),
+        None => true,
+    }
+}
+
+/// Refresh stale (or all, when forced) installed repos. Returns how long the caller
+/// should wait before the next pass when the Hub asked us to back off.
+pub async fn refresh(state: &AppState, force: bool) -> Result<Option<Duration>, String> {
+    let (models_directory, offline) = {
+        let config = state.config.lock().await;
+        (config.models_directory.clone(), config.offline_mode)
+    };
+    if offline {
+        return Err("Offline mode is enabled".to_string());
+    }
+
+    let installed = installed_repos(&models_directory).await;
+    let mut metadata = get_metadata().await;
+    // Forget repos that are no longer installed
+    metadata.retain(|repo_id, _| installed.contains_key(repo_id));
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(20))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut backoff = None;
+    let mut first = true;
+
+    for (repo_id, local_modified) in &installed {
+        if !force && !is_stale(metadata.get(repo_id)) {
+            continue;
+        }
+        if !first {
+            tokio::time::sleep(REQUEST_SPACING).await;
+        }
+        first = false;
+
+        let outcome = fetch_repo(&client, repo_id).await.map_err(|e| e.to_string());
+        let entry = match outcome {
+            Ok(FetchOutcome::Found(data)) => {
+                let last_modified = data.get("lastModified").and_then(|v| v.as_str()).map(|s| s.to_string());
+                let upstream_updated = last_modified.as_deref()
+                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                    .is_some_and(|upstream| SystemTime::from(upstream) > *local_modified);
+                UpstreamMetadata {
+                    repo_id: repo_id.clone(),
+                    downloads: data.get("downloads").and_then(|v| v.as_u64()).unwrap_or(0),
+                    likes: data.get("likes").and_then(|v| v.as_u64()).unwrap_or(0),
+                    last_modified,
+                    missing: false,
+                    disabled: data.get("disabled").and_then(|v| v.as_bool()).unwrap_or(false),
+                    upstream_updated,
+                    refreshed_at: chrono::Utc::now(),
+                }
+            }
+            Ok(FetchOutcome::Missing) => UpstreamMetadata {
+                repo_id: repo_id.clone(),
+                downloads: 0,
+                likes: 0,
+                last_modified: None,
+                missing: true,
+                disabled: false,
+                upstream_updated: false,
+                refreshed_at: chrono::Utc::now(),
+            },
+            Ok(FetchOutcome::RateLimited(wait)) => {
+                println!("Hugging Face rate limit hit, pausing metadata refresh for {}s", wait.as_secs());
+                backoff = Some(wait);
+                break;
+            }
+            Err(e) => {
+                eprintln!("Failed to refresh metadata for {}: {}", repo_id, e);
+                continue;
+            }
+        };
+        metadata.insert(repo_id.clone(), entry);
+    }
+
+    *METADATA.lock().await = Some(metadata.clone());
+    save_cache(&metadata).await?;
+    Ok(backoff)
+}
+
+/// Periodically refresh installed repos and emit `hf-metadata-updated` after each pass
+pub async fn run_refresh_job(state: AppState, app_handle: tauri::AppHandle) {
+    tokio::time::sleep(STARTUP_DELAY).await;
+    loop {
+        let wait = match refresh(&state, false).await {
+            Ok(backoff) => {
+                let _ = app_handle.emit("hf-metadata-updated", get_metadata().await);
+                backoff.unwrap_or(CHECK_INTERVAL).max(CHECK_INTERVAL)
+            }
+            Err(_) => CHECK_INTERVAL,
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
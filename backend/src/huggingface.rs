@@ -1,10 +1,43 @@
 use crate::models::*;
 use serde_json::{json, Value};
+use regex::Regex;
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::LazyLock;
 use tokio::fs;
 
-/// Cleanup leftover .download files from interrupted downloads during startup
+// Access token for private and gated repos, mirrored from the settings
+static ACCESS_TOKEN: LazyLock<std::sync::RwLock<Option<String>>> = LazyLock::new(|| std::sync::RwLock::new(None));
+
+// Last details fetched per repo, used to suggest other quantizations when a download fails
+static MODEL_DETAILS_CACHE: LazyLock<std::sync::Mutex<HashMap<String, ModelDetails>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+pub fn set_access_token(token: &str) {
+    let token = token.trim();
+    *ACCESS_TOKEN.write().unwrap() = (!token.is_empty()).then(|| token.to_string());
+}
+
+pub fn access_token() -> Option<String> {
+    ACCESS_TOKEN.read().unwrap().clone()
+}
+
+/// Send the access token with a request to the Hub. Mirrors and other hosts never see it.
+pub fn authorize(request: reqwest::RequestBuilder, url: &str) -> reqwest::RequestBuilder {
+    match access_token() {
+        Some(token) if is_hub_url(url) => request.bearer_auth(token),
+        _ => request,
+    }
+}
+
+fn is_hub_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host == "huggingface.co" || host == "hf.co" || host.ends_with(".huggingface.co")))
+        .unwrap_or(false)
+}
+
+/// Cleanup leftover .part files from interrupted downloads during startup.
+/// Partial files of downloads that can still be resumed are kept.
 pub async fn cleanup_leftover_downloads(models_directory: &str) -> Result<usize, Box<dyn std::error::Error>> {
     if models_directory.is_empty() {
         return Ok(0);
@@ -16,6 +49,7 @@ pub async fn cleanup_leftover_downloads(models_directory: &str) -> Result<usize,
     }
     
     let mut cleaned_count = 0;
+    let keep = crate::downloader::pending_partial_files().await;
     
     // Recursively walk through the models directory
     let mut entries = fs::read_dir(models_path).await?;
@@ -24,12 +58,12 @@ pub async fn cleanup_leftover_downloads(models_directory: &str) -> Result<usize,
         
         if path.is_dir() {
             // Recursively clean subdirectories (author folders, model folders)
-            if let Ok(count) = cleanup_directory_downloads(&path).await {
+            if let Ok(count) = cleanup_directory_downloads(&path, &keep).await {
                 cleaned_count += count;
             }
         } else if path.is_file() {
-            // Check if it's a .gguf.download file
-            if is_gguf_download_file(&path) {
+            // Check if it's an unfinished download
+            if is_gguf_download_file(&path) && !keep.contains(&path) {
                 match fs::remove_file(&path).await {
                     Ok(_) => {
                         println!("Cleaned up leftover download file: {:?}", path);
@@ -44,14 +78,14 @@ pub async fn cleanup_leftover_downloads(models_directory: &str) -> Result<usize,
     }
     
     if cleaned_count > 0 {
-        println!("Startup cleanup: removed {} leftover .gguf.download files", cleaned_count);
+        println!("Startup cleanup: removed {} leftover partial download files", cleaned_count);
     }
     
     Ok(cleaned_count)
 }
 
-/// Recursively clean .gguf.download files from a directory
-fn cleanup_directory_downloads(dir_path: &Path) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<usize, Box<dyn std::error::Error>>> + Send + '_>> {
+/// Recursively clean partial download files from a directory
+fn cleanup_directory_downloads<'a>(dir_path: &'a Path, keep: &'a std::collections::HashSet<std::path::PathBuf>) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<usize, Box<dyn std::error::Error>>> + Send + 'a>> {
     Box::pin(async move {
         let mut cleaned_count = 0;
         
@@ -61,12 +95,12 @@ fn cleanup_directory_downloads(dir_path: &Path) -> std::pin::Pin<Box<dyn std::fu
             
             if path.is_dir() {
                 // Recursively clean subdirectories
-                if let Ok(count) = cleanup_directory_downloads(&path).await {
+                if let Ok(count) = cleanup_directory_downloads(&path, keep).await {
                     cleaned_count += count;
                 }
             } else if path.is_file() {
-                // Check if it's a .gguf.download file
-                if is_gguf_download_file(&path) {
+                // Check if it's an unfinished download
+                if is_gguf_download_file(&path) && !keep.contains(&path) {
                     match fs::remove_file(&path).await {
                         Ok(_) => {
                             println!("Cleaned up leftover download file: {:?}", path);
@@ -84,27 +118,43 @@ fn cleanup_directory_downloads(dir_path: &Path) -> std::pin::Pin<Box<dyn std::fu
     })
 }
 
-/// Strictly check if a file is a .gguf.download file
-/// Only files ending with exactly ".gguf.download" will be considered for removal
+/// Strictly check if a file is an unfinished GGUF download
+/// Only files ending with exactly ".gguf.part", its ".gguf.part.chunks" range map (or the older ".gguf.download") will be considered for removal
 fn is_gguf_download_file(path: &Path) -> bool {
     if let Some(file_name) = path.file_name() {
         if let Some(file_str) = file_name.to_str() {
-            // Must end with exactly ".gguf.download" - case insensitive for safety
-            return file_str.to_lowercase().ends_with(".gguf.download");
+            // Case insensitive for safety
+            let lower = file_str.to_lowercase();
+            return lower.ends_with(&format!(".gguf{}", crate::downloader::PARTIAL_SUFFIX))
+                || lower.ends_with(&format!(".gguf{}{}", crate::downloader::PARTIAL_SUFFIX, crate::downloader::CHUNK_MAP_SUFFIX))
+                || lower.ends_with(".gguf.download");
         }
     }
     false
 }
 
+/// Parameter range in billions for a search size bucket
+fn parameter_bucket(bucket: &str) -> Option<(u64, Option<u64>)> {
+    match bucket {
+        "tiny" => Some((0, Some(3))),
+        "small" => Some((3, Some(9))),
+        "medium" => Some((9, Some(35))),
+        "large" => Some((35, Some(100))),
+        "huge" => Some((100, None)),
+        _ => None,
+    }
+}
+
 pub async fn search_models(
     query: String,
     limit: usize,
     sort_by: String,
+    filters: &HubSearchFilters,
 ) -> Result<SearchResult, Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
     
     // Build search URL with parameters - add full parameter to get complete model information
-    let url = format!(
+    let mut url = format!(
         "https://huggingface.co/api/models?search={}&filter=gguf&sort={}&limit={}&full=true",
         urlencoding::encode(&query),
         match sort_by.as_str() {
@@ -115,11 +165,29 @@ pub async fn search_models(
         },
         limit
     );
+
+    if let Some(pipeline_tag) = filters.pipeline_tag.as_deref().filter(|t| !t.is_empty()) {
+        // Embedding models are tagged feature-extraction on the Hub
+        let pipeline_tag = if pipeline_tag == "embedding" { "feature-extraction" } else { pipeline_tag };
+        url.push_str(&format!("&pipeline_tag={}", urlencoding::encode(pipeline_tag)));
+    }
+    if let Some(license) = filters.license.as_deref().filter(|l| !l.is_empty()) {
+        url.push_str(&format!("&filter=license:{}", urlencoding::encode(license)));
+    }
+    let bucket = match filters.parameter_size.as_deref().filter(|s| !s.is_empty()) {
+        Some(size) => Some(parameter_bucket(size).ok_or_else(|| format!("Unknown parameter size '{}'", size))?),
+        None => None,
+    };
+    if let Some((min, max)) = bucket {
+        url.push_str(&format!("&num_parameters=min:{}B", min));
+        if let Some(max) = max {
+            url.push_str(&format!(",max:{}B", max));
+        }
+    }
     
     println!("Searching with URL: {}", url);
     
-    let response = client
-        .get(&url)
+    let response = authorize(client.get(&url), &url)
         .header("User-Agent", "Llama-OS-Tauri/1.0")
         .send()
         .await?;
@@ -139,6 +207,17 @@ pub async fn search_models(
             models.push(model);
         }
     }
+
+    // The Hub only knows parameter counts for some repos, also drop known mismatches here
+    if let Some((min, max)) = bucket {
+        models.retain(|model| match model.parameters {
+            Some(parameters) => {
+                let billions = parameters as f64 / 1e9;
+                billions >= min as f64 && max.is_none_or(|max| billions < max as f64)
+            }
+            None => true,
+        });
+    }
     
     let total = models.len();
     
@@ -149,7 +228,7 @@ pub async fn search_models(
     })
 }
 
-fn parse_model_basic(data: &Value) -> Option<ModelBasic> {
+pub fn parse_model_basic(data: &Value) -> Option<ModelBasic> {
     let id = data.get("id")?.as_str()?.to_string();
     let name = id.clone(); // Use ID as name for now
     let author = id.split('/').next().unwrap_or("unknown").to_string();
@@ -169,24 +248,52 @@ fn parse_model_basic(data: &Value) -> Option<ModelBasic> {
     //println!("Model {}: lastModified from API = {:?}", id, last_modified);
     
     Some(ModelBasic {
-        id,
         name,
         author,
         downloads: data.get("downloads").and_then(|v| v.as_u64()).unwrap_or(0),
         likes: data.get("likes").and_then(|v| v.as_u64()).unwrap_or(0),
         last_modified,
+        pipeline_tag: data.get("pipeline_tag").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        license: data.get("tags")
+            .and_then(|v| v.as_array())
+            .and_then(|tags| tags.iter().filter_map(|t| t.as_str()).find_map(|t| t.strip_prefix("license:")))
+            .map(|s| s.to_string()),
+        parameters: data.get("gguf").and_then(|g| g.get("total")).and_then(|v| v.as_u64())
+            .or_else(|| data.get("safetensors").and_then(|s| s.get("total")).and_then(|v| v.as_u64()))
+            .or_else(|| parameters_from_name(&id)),
+        id,
     })
 }
 
+/// Guess the parameter count from size labels in a repo name, e.g. "Qwen2.5-0.5B", "Mixtral-8x7B", "SmolLM-135M"
+fn parameters_from_name(name: &str) -> Option<u64> {
+    let name = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+    name.split(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+        .find_map(|token| {
+            let (scale, number) = if let Some(n) = token.strip_suffix('b') {
+                (1e9, n)
+            } else if let Some(n) = token.strip_suffix('m') {
+                (1e6, n)
+            } else {
+                return None;
+            };
+            let (experts, size) = match number.split_once('x') {
+                Some((experts, size)) => (experts.parse::<f64>().ok()?, size),
+                None => (1.0, number),
+            };
+            let size: f64 = size.parse().ok()?;
+            Some((experts * size * scale) as u64)
+        })
+}
+
 pub async fn get_huggingface_model_details(
     model_id: String,
 ) -> Result<ModelDetails, Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
     
-    // Get model info
-    let model_url = format!("https://huggingface.co/api/models/{}", model_id);
-    let model_response = client
-        .get(&model_url)
+    // Get model info (blobs=true adds sizes to the sibling list used as a fallback below)
+    let model_url = format!("https://huggingface.co/api/models/{}?blobs=true", model_id);
+    let model_response = authorize(client.get(&model_url), &model_url)
         .header("User-Agent", "Llama-OS-Tauri/1.0")
         .send()
         .await?;
@@ -197,19 +304,8 @@ pub async fn get_huggingface_model_details(
     
     let model_data: Value = model_response.json().await?;
     
-    // Get file tree to find GGUF files
-    let files_url = format!("https://huggingface.co/api/models/{}/tree/main", model_id);
-    let files_response = client
-        .get(&files_url)
-        .header("User-Agent", "Llama-OS-Tauri/1.0")
-        .send()
-        .await?;
-    
-    let files_data: Value = if files_response.status().is_success() {
-        files_response.json().await?
-    } else {
-        json!([])
-    };
+    // Get file tree to find GGUF files, including ones inside per-quant folders
+    let files_data = fetch_file_tree(&client, &model_id, "main", &model_data).await?;
     
     // Parse the model details
     let id = model_data.get("id").and_then(|v| v.as_str()).unwrap_or(&model_id).to_string();
@@ -218,34 +314,52 @@ pub async fn get_huggingface_model_details(
     let description = model_data.get("description").and_then(|v| v.as_str()).map(|s| s.to_string());
     let downloads = model_data.get("downloads").and_then(|v| v.as_u64()).unwrap_or(0);
     let likes = model_data.get("likes").and_then(|v| v.as_u64()).unwrap_or(0);
+    let gated = gated_mode(&model_data);
+    let revision = model_data.get("sha").and_then(|v| v.as_str()).map(|s| s.to_string());
     
     // Find and organize GGUF files
     let mut gguf_files = HashMap::new();
+    let mut gguf_paths = HashMap::new();
     let mut total_files = 0;
     
     if let Some(files_array) = files_data.as_array() {
         for file in files_array {
+            // Directories show up in recursive listings, only count files
+            if file.get("type").and_then(|v| v.as_str()) == Some("directory") {
+                continue;
+            }
             if let Some(file_path) = file.get("path").and_then(|v| v.as_str()) {
                 // Count all files
                 total_files += 1;
                 
                 // Process GGUF files specifically
                 if file_path.to_lowercase().ends_with(".gguf") {
-                    let size = file.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
+                    // LFS files report their real size under lfs.size
+                    let size = file.get("lfs").and_then(|l| l.get("size")).and_then(|v| v.as_u64())
+                        .or_else(|| file.get("size").and_then(|v| v.as_u64()))
+                        .unwrap_or(0);
                     let filename = file_path.split('/').last().unwrap_or(file_path).to_string();
                     let quantization_type = extract_quantization_type(file_path);
                     
+                    gguf_paths.insert(filename.clone(), file_path.to_string());
                     gguf_files.insert(filename.clone(), GgufFileInfo {
                         filename: filename.clone(),
                         size,
                         quantization_type,
+                        parts: vec![file_path.to_string()],
+                        is_mmproj: is_mmproj_file(&filename),
+                        modalities: Vec::new(),
                     });
                 }
             }
         }
     }
     
-    Ok(ModelDetails {
+    resolve_quantization_from_headers(&model_id, &mut gguf_files, &gguf_paths).await;
+    resolve_projector_modalities(&model_id, &mut gguf_files, &gguf_paths).await;
+    let gguf_files = group_shards(gguf_files);
+    
+    let details = ModelDetails {
         id,
         name,
         author,
@@ -254,77 +368,480 @@ pub async fn get_huggingface_model_details(
         likes,
         total_files,
         gguf_files,
-    })
+        gated,
+        revision,
+    };
+    MODEL_DETAILS_CACHE.lock().unwrap().insert(model_id, details.clone());
+    Ok(details)
 }
 
-fn extract_quantization_type(filename: &str) -> Option<String> {
-    // Find .gguf extension first, then search backwards for the first dash or dot
-    let filename_lower = filename.to_lowercase();
+/// Fold split shards into one entry keyed by the first shard, so the whole set is
+/// picked and downloaded together
+fn group_shards(files: HashMap<String, GgufFileInfo>) -> HashMap<String, GgufFileInfo> {
+    let mut groups: HashMap<String, Vec<GgufFileInfo>> = HashMap::new();
+    for info in files.into_values() {
+        let path = info.parts.first().cloned().unwrap_or_else(|| info.filename.clone());
+        let stem = path.strip_suffix(".gguf").unwrap_or(&path);
+        let key = if SHARD_SUFFIX.is_match(stem) { SHARD_SUFFIX.replace(stem, "").to_string() } else { path.clone() };
+        groups.entry(key).or_default().push(info);
+    }
     
-    if let Some(gguf_pos) = filename_lower.find(".gguf") {
-        let base_part = &filename[..gguf_pos];
-        
-        // Search backwards from the .gguf position to find the first dash or dot
-        let mut last_separator_pos = None;
-        
-        // Check for dash first
-        if let Some(dash_pos) = base_part.rfind('-') {
-            last_separator_pos = Some(dash_pos);
+    groups
+        .into_values()
+        .filter_map(|mut shards| {
+            shards.sort_by(|a, b| a.filename.cmp(&b.filename));
+            let size = shards.iter().map(|s| s.size).sum();
+            let parts = shards.iter().flat_map(|s| s.parts.clone()).collect();
+            let first = shards.into_iter().next()?;
+            Some((first.filename.clone(), GgufFileInfo { size, parts, ..first }))
+        })
+        .collect()
+}
+
+/// Projectors are published as `mmproj-<name>-<quant>.gguf`, for audio encoders too
+pub fn is_mmproj_file(filename: &str) -> bool {
+    filename.rsplit(['/', '\\']).next().unwrap_or(filename).to_lowercase().contains("mmproj")
+}
+
+/// Details from the cache when the repo was browsed this session, fetched otherwise
+pub async fn cached_model_details(model_id: &str) -> Result<ModelDetails, String> {
+    let cached = MODEL_DETAILS_CACHE.lock().unwrap().get(model_id).cloned();
+    match cached {
+        Some(details) => Ok(details),
+        None => get_huggingface_model_details(model_id.to_string()).await.map_err(|e| e.to_string()),
+    }
+}
+
+/// The projector to download with a vision model, F16 when offered since quantizing
+/// it saves little and costs accuracy, otherwise the smallest one
+pub async fn preferred_mmproj(model_id: &str) -> Option<String> {
+    let details = match cached_model_details(model_id).await {
+        Ok(details) => details,
+        Err(e) => {
+            eprintln!("Couldn't look up the projector for {}: {}", model_id, e);
+            return None;
+        }
+    };
+    let projectors: Vec<&GgufFileInfo> = details.gguf_files.values().filter(|info| info.is_mmproj).collect();
+    projectors
+        .iter()
+        .find(|info| info.quantization_type.as_deref() == Some("F16"))
+        .or_else(|| projectors.iter().min_by_key(|info| info.size))
+        .map(|info| info.parts.first().cloned().unwrap_or_else(|| info.filename.clone()))
+}
+
+/// Other quantizations in the repo, closest in size to the failed files first. Uses the
+/// cached details when the repo was browsed this session.
+pub async fn quantization_alternatives(model_id: &str, failed_files: &[String]) -> Vec<QuantAlternative> {
+    let details = match cached_model_details(model_id).await {
+        Ok(details) => details,
+        Err(e) => {
+            eprintln!("Couldn't list alternatives for {}: {}", model_id, e);
+            return Vec::new();
+        }
+    };
+    
+    // Files are repo paths, or bare names for downloads started before shards were grouped
+    let failed: Vec<&GgufFileInfo> = details.gguf_files
+        .values()
+        .filter(|info| failed_files.iter().any(|f| f == &info.filename || info.parts.contains(f)))
+        .collect();
+    let failed_quants: Vec<String> = failed.iter().filter_map(|info| info.quantization_type.clone())
+        .chain(failed_files.iter().filter_map(|f| extract_quantization_type(f)))
+        .collect();
+    let failed_size: u64 = failed.iter().map(|info| info.size).sum();
+    
+    let mut alternatives: Vec<QuantAlternative> = details.gguf_files
+        .values()
+        .filter(|info| !failed.iter().any(|f| f.filename == info.filename))
+        .filter(|info| !info.is_mmproj)
+        .filter(|info| !info.quantization_type.as_ref().is_some_and(|q| failed_quants.contains(q)))
+        .map(|info| QuantAlternative {
+            model_id: model_id.to_string(),
+            quantization_type: info.quantization_type.clone(),
+            files: if info.parts.is_empty() { vec![info.filename.clone()] } else { info.parts.clone() },
+            size: info.size,
+        })
+        .collect();
+    alternatives.sort_by_key(|a| a.size.abs_diff(failed_size));
+    alternatives
+}
+
+/// The `gated` field is `false` for open repos and "auto"/"manual" for gated ones
+fn gated_mode(model_data: &Value) -> Option<String> {
+    match model_data.get("gated") {
+        Some(Value::String(mode)) => Some(mode.clone()),
+        Some(Value::Bool(true)) => Some("auto".to_string()),
+        _ => None,
+    }
+}
+
+// Markdown link/image targets and HTML src/href attributes in model cards
+static MARKDOWN_LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(!?\[[^\]]*\]\()([^)\s]+)").unwrap());
+static HTML_LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)((?:src|href)\s*=\s*["'])([^"']+)"#).unwrap());
+
+/// The repo's README.md with relative image and link targets pointing at the Hub
+pub async fn get_model_readme(model_id: String) -> Result<String, Box<dyn std::error::Error>> {
+    let client = reqwest::Client::new();
+    let url = format!("https://huggingface.co/{}/resolve/main/README.md", model_id);
+    let response = authorize(client.get(&url), &url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await?;
+    
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("{} has no model card", model_id).into());
+    }
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch model card: {}", response.status()).into());
+    }
+    
+    let readme = response.text().await?;
+    Ok(resolve_readme_links(&model_id, &readme))
+}
+
+/// Images resolve to the raw file, other links to the Hub's file view
+fn resolve_readme_links(model_id: &str, readme: &str) -> String {
+    let resolve = |target: &str, image: bool| -> String {
+        if target.starts_with('#') || target.starts_with("//") || target.contains(':') {
+            target.to_string() // Anchors, absolute URLs, mailto: and data: URIs
+        } else if target.starts_with('/') {
+            format!("https://huggingface.co{}", target)
+        } else {
+            let kind = if image { "resolve" } else { "blob" };
+            format!("https://huggingface.co/{}/{}/main/{}", model_id, kind, target.trim_start_matches("./"))
         }
+    };
+    
+    let readme = MARKDOWN_LINK.replace_all(readme, |caps: &regex::Captures| {
+        format!("{}{}", &caps[1], resolve(&caps[2], caps[1].starts_with('!')))
+    });
+    HTML_LINK.replace_all(&readme, |caps: &regex::Captures| {
+        format!("{}{}", &caps[1], resolve(&caps[2], caps[1].to_lowercase().starts_with("src")))
+    }).into_owned()
+}
+
+/// Check that the current token can download a file from a gated repo.
+/// Returns the repo page when access is missing. Network errors let the download
+/// go ahead and fail on its own, gating is only reported when the Hub says so.
+pub async fn check_gated_access(model_id: &str, filename: &str) -> Option<String> {
+    let client = reqwest::Client::new();
+    let model_url = format!("https://huggingface.co/api/models/{}", model_id);
+    let model_data: Value = authorize(client.get(&model_url), &model_url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    gated_mode(&model_data)?;
+
+    let file_url = format!("https://huggingface.co/{}/resolve/main/{}", model_id, filename);
+    let response = authorize(client.head(&file_url), &file_url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await
+        .ok()?;
+    match response.status().as_u16() {
+        401 | 403 => Some(format!("https://huggingface.co/{}", model_id)),
+        _ => None,
+    }
+}
+
+/// Recursive file listing of a repo at a branch or commit. The tree endpoint sometimes fails or
+/// comes back empty for large repos, the sibling list from the model info has the same paths.
+async fn fetch_file_tree(client: &reqwest::Client, model_id: &str, revision: &str, model_data: &Value) -> Result<Value, reqwest::Error> {
+    let files_url = format!("https://huggingface.co/api/models/{}/tree/{}?recursive=true", model_id, revision);
+    let files_response = authorize(client.get(&files_url), &files_url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await?;
+    
+    let files_data: Value = if files_response.status().is_success() {
+        files_response.json().await.unwrap_or_else(|_| json!([]))
+    } else {
+        json!([])
+    };
+    
+    Ok(match files_data.as_array() {
+        Some(files) if !files.is_empty() => files_data,
+        _ => siblings_as_tree(model_data),
+    })
+}
+
+/// Files of a repo selected by glob patterns matched case-insensitively against their
+/// path, e.g. `*Q4_K_M*` or `*.safetensors`. No include patterns selects every file,
+/// excludes are applied after. Returns paths with their size, 0 when the Hub doesn't list one.
+pub async fn resolve_file_patterns(
+    model_id: &str,
+    revision: Option<&str>,
+    include: &[String],
+    exclude: &[String],
+) -> Result<Vec<(String, u64)>, String> {
+    let compile = |patterns: &[String]| -> Result<Vec<glob::Pattern>, String> {
+        patterns
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| glob::Pattern::new(p.trim()).map_err(|e| format!("Invalid pattern '{}': {}", p, e)))
+            .collect()
+    };
+    let include = compile(include)?;
+    let exclude = compile(exclude)?;
+    let options = glob::MatchOptions { case_sensitive: false, ..Default::default() };
+    
+    let client = reqwest::Client::new();
+    let model_url = match revision {
+        Some(revision) => format!("https://huggingface.co/api/models/{}/revision/{}", model_id, revision),
+        None => format!("https://huggingface.co/api/models/{}", model_id),
+    };
+    let response = authorize(client.get(&model_url), &model_url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch model info: {}", response.status()));
+    }
+    let model_data: Value = response.json().await.map_err(|e| e.to_string())?;
+    let tree = fetch_file_tree(&client, model_id, revision.unwrap_or("main"), &model_data).await.map_err(|e| e.to_string())?;
+    
+    let mut files: Vec<(String, u64)> = tree
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.get("type").and_then(|v| v.as_str()) != Some("directory"))
+        .filter_map(|entry| {
+            let path = entry.get("path").and_then(|v| v.as_str())?;
+            let size = entry.get("lfs").and_then(|l| l.get("size")).and_then(|v| v.as_u64())
+                .or_else(|| entry.get("size").and_then(|v| v.as_u64()))
+                .unwrap_or(0);
+            Some((path, size))
+        })
+        .filter(|(path, _)| include.is_empty() || include.iter().any(|p| p.matches_with(path, options)))
+        .filter(|(path, _)| !exclude.iter().any(|p| p.matches_with(path, options)))
+        .map(|(path, size)| (path.to_string(), size))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Convert the `siblings` list from the model info into the file tree format
+fn siblings_as_tree(model_data: &Value) -> Value {
+    let siblings = match model_data.get("siblings").and_then(|v| v.as_array()) {
+        Some(siblings) => siblings,
+        None => return json!([]),
+    };
+    
+    Value::Array(
+        siblings
+            .iter()
+            .filter_map(|s| {
+                let path = s.get("rfilename").and_then(|v| v.as_str())?;
+                let size = s.get("lfs").and_then(|l| l.get("size")).and_then(|v| v.as_u64())
+                    .or_else(|| s.get("size").and_then(|v| v.as_u64()));
+                Some(json!({ "type": "file", "path": path, "size": size }))
+            })
+            .collect(),
+    )
+}
+
+// Range sizes used when peeking at a remote GGUF header
+const PEEK_INITIAL_BYTES: usize = 2 * 1024 * 1024;
+const PEEK_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Read the GGUF header of a remote file through range requests, without downloading the model.
+/// Starts with a small range and doubles it until the header and tensor infos fit.
+pub async fn peek_remote_gguf(url: &str) -> Result<RemoteGgufInfo, Box<dyn std::error::Error + Send + Sync>> {
+    let client = reqwest::Client::new();
+    let (header, bytes_fetched) = fetch_remote_header(&client, url, true).await?;
+    
+    Ok(RemoteGgufInfo {
+        url: url.to_string(),
+        gguf_version: header.version,
+        architecture: header.architecture().map(|s| s.to_string()),
+        name: header.get("general.name").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        context_length: header.context_length(),
+        parameter_count: header.parameter_count(),
+        block_count: header.arch_u64("block_count"),
+        embedding_length: header.arch_u64("embedding_length"),
+        file_type: header.get("general.file_type").and_then(|v| v.as_u64()),
+        tensor_count: header.tensor_count,
+        bytes_fetched,
+    })
+}
+
+/// Fetch and parse a remote GGUF header, returning it with the number of bytes downloaded
+async fn fetch_remote_header(client: &reqwest::Client, url: &str, read_tensors: bool) -> Result<(crate::gguf::GgufHeader, usize), Box<dyn std::error::Error + Send + Sync>> {
+    let mut range_size = PEEK_INITIAL_BYTES;
+    
+    loop {
+        let bytes = fetch_range(client, url, range_size).await?;
+        let mut cursor = std::io::Cursor::new(&bytes);
         
-        // Check for dot, but only if it's after the last dash (or if no dash found)
-        if let Some(dot_pos) = base_part.rfind('.') {
-            if let Some(dash_pos) = last_separator_pos {
-                // If dot is after dash, use dot
-                if dot_pos > dash_pos {
-                    last_separator_pos = Some(dot_pos);
+        match crate::gguf::read_header(&mut cursor, read_tensors) {
+            Ok(header) => return Ok((header, bytes.len())),
+            // Header continues past what we fetched, try a larger range
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof && bytes.len() >= range_size => {
+                if range_size >= PEEK_MAX_BYTES {
+                    return Err(format!("GGUF header is larger than {} MB", PEEK_MAX_BYTES / (1024 * 1024)).into());
                 }
-            } else {
-                // No dash found, use dot
-                last_separator_pos = Some(dot_pos);
+                range_size *= 2;
+                println!("GGUF header did not fit, retrying peek with {} bytes", range_size);
             }
+            Err(e) => return Err(format!("Failed to parse GGUF header: {}", e).into()),
         }
-        
-        if let Some(separator_pos) = last_separator_pos {
-            let quant_part = &base_part[separator_pos + 1..];
-            if !quant_part.is_empty() {
-                let quant_upper = quant_part.to_uppercase();
-                // Return the extracted quantization part
-                return Some(quant_upper);
+    }
+}
+
+/// Fetch at most `size` bytes from the start of a URL.
+/// The body is streamed and cut off in case the server ignores the Range header.
+async fn fetch_range(client: &reqwest::Client, url: &str, size: usize) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    use futures_util::StreamExt;
+    
+    let response = authorize(client.get(url), url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .header("Range", format!("bytes=0-{}", size - 1))
+        .send()
+        .await?;
+    
+    if !response.status().is_success() {
+        return Err(format!("Range request failed with status: {}", response.status()).into());
+    }
+    
+    let mut bytes = Vec::with_capacity(size);
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        let remaining = size - bytes.len();
+        bytes.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+        if bytes.len() >= size {
+            break;
+        }
+    }
+    
+    Ok(bytes)
+}
+
+// Quantization names as they appear in GGUF file and folder names
+static QUANT_TOKEN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:I?Q[1-8](?:_[0-9A-Z]{1,3}){0,3}|TQ[12]_0|MXFP4(?:_MOE)?|BF16|FP16|F16|F32|FP32)$").unwrap()
+});
+
+// Split shards are named `<name>-00001-of-00003.gguf`
+static SHARD_SUFFIX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)-\d{5}-of-\d{5}$").unwrap());
+
+/// Work out the quantization from a repo file path. The file name is checked first and
+/// then its folders (repos often group shards as `Q4_K_M/<name>-00001-of-00002.gguf`).
+/// Returns None when nothing looks like a quantization, so callers can fall back to the header.
+fn extract_quantization_type(file_path: &str) -> Option<String> {
+    let mut components: Vec<&str> = file_path.split('/').collect();
+    let file_name = components.pop()?;
+    let lower = file_name.to_lowercase();
+    let base = match lower.rfind(".gguf") {
+        Some(pos) => &file_name[..pos],
+        None => file_name,
+    };
+    let base = SHARD_SUFFIX.replace(base, "");
+    
+    find_quant_token(&base).or_else(|| components.iter().rev().find_map(|dir| find_quant_token(dir)))
+}
+
+// Tokens are separated by '-' or '.', and the quant is normally the last one that matches.
+// Underscores are kept since they are part of names like Q4_K_M, but a name such as
+// `model_q4_k_m` is also tried on its underscore-separated tail.
+fn find_quant_token(name: &str) -> Option<String> {
+    for token in name.rsplit(['-', '.']) {
+        if QUANT_TOKEN.is_match(token) {
+            return Some(normalize_quant(token));
+        }
+        let parts: Vec<&str> = token.split('_').collect();
+        for start in 1..parts.len() {
+            let tail = parts[start..].join("_");
+            if QUANT_TOKEN.is_match(&tail) {
+                return Some(normalize_quant(&tail));
             }
         }
-        
-        // If no separator found, return the whole base name
-        return Some(base_part.to_string());
+    }
+    None
+}
+
+fn normalize_quant(token: &str) -> String {
+    match token.to_uppercase().as_str() {
+        "FP16" => "F16".to_string(),
+        "FP32" => "F32".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// Header lookups are a couple of MB each, so only a handful are made per repo
+const MAX_HEADER_LOOKUPS: usize = 6;
+
+/// Fill in quantization types the file names didn't reveal by reading `general.file_type`
+/// from the remote GGUF headers. Only the first shard of each file is checked.
+async fn resolve_quantization_from_headers(model_id: &str, gguf_files: &mut HashMap<String, GgufFileInfo>, paths: &HashMap<String, String>) {
+    let unresolved: Vec<String> = gguf_files
+        .values()
+        .filter(|f| f.quantization_type.is_none())
+        .map(|f| f.filename.clone())
+        .filter(|name| !SHARD_SUFFIX.is_match(name.trim_end_matches(".gguf")) || name.contains("-00001-of-"))
+        .take(MAX_HEADER_LOOKUPS)
+        .collect();
+    if unresolved.is_empty() {
+        return;
     }
     
-    // Fallback: use the original pattern matching for edge cases
-    let patterns = [
-        // IQ formats
-        "iq4_nl", "iq4_xs", "iq3_xxs", "iq3_xs", "iq3_s", "iq3_m",
-        "iq2_xxs", "iq2_xs", "iq2_s", "iq2_m",
-        "iq1_s", "iq1_m",
-        
-        // Q formats with K variants (most specific first)
-        "q8_k_m", "q8_k_s", "q8_k",
-        "q6_k_m", "q6_k_s", "q6_k", 
-        "q5_k_m", "q5_k_s", "q5_k",
-        "q4_k_m", "q4_k_s", "q4_k",
-        "q3_k_m", "q3_k_s", "q3_k_l", "q3_k",
-        "q2_k_m", "q2_k_s", "q2_k",
-        
-        // Standard Q formats
-        "q8_0", "q6_0", "q5_1", "q5_0", "q4_1", "q4_0", "q3_0", "q2_0",
-        
-        // Float formats
-        "fp16", "f16", "f32", "bf16"
-    ];
+    let client = reqwest::Client::new();
+    let lookups = unresolved.iter().map(|filename| {
+        let path = paths.get(filename).cloned().unwrap_or_else(|| filename.clone());
+        let url = format!("https://huggingface.co/{}/resolve/main/{}", model_id, path);
+        let client = client.clone();
+        async move {
+            let file_type = fetch_remote_header(&client, &url, false)
+                .await
+                .ok()
+                .and_then(|(header, _)| header.get("general.file_type").and_then(|v| v.as_u64()));
+            file_type.and_then(crate::gguf::file_type_name)
+        }
+    });
+    let results = futures_util::future::join_all(lookups).await;
     
-    for pattern in &patterns {
-        if filename_lower.contains(pattern) {
-            return Some(pattern.to_uppercase());
+    for (filename, quant) in unresolved.iter().zip(results) {
+        if let (Some(info), Some(quant)) = (gguf_files.get_mut(filename), quant) {
+            info.quantization_type = Some(quant.to_string());
         }
     }
     
-    Some("UNKNOWN".to_string())
+    // Remaining shards take the type resolved for their first shard
+    let shard_types: HashMap<String, String> = gguf_files
+        .values()
+        .filter_map(|f| {
+            let base = SHARD_SUFFIX.replace(f.filename.trim_end_matches(".gguf"), "").to_string();
+            f.quantization_type.clone().map(|q| (base, q))
+        })
+        .collect();
+    for info in gguf_files.values_mut().filter(|f| f.quantization_type.is_none()) {
+        let base = SHARD_SUFFIX.replace(info.filename.trim_end_matches(".gguf"), "").to_string();
+        info.quantization_type = shard_types.get(&base).cloned();
+    }
+}
+
+/// Tell vision projectors from audio encoder ones by their `clip.has_*_encoder` keys.
+/// The quants of a repo's projector share an encoder, so one header answers for all.
+async fn resolve_projector_modalities(model_id: &str, gguf_files: &mut HashMap<String, GgufFileInfo>, paths: &HashMap<String, String>) {
+    let Some(smallest) = gguf_files.values().filter(|f| f.is_mmproj).min_by_key(|f| f.size).map(|f| f.filename.clone()) else {
+        return;
+    };
+    let path = paths.get(&smallest).cloned().unwrap_or_else(|| smallest.clone());
+    let url = format!("https://huggingface.co/{}/resolve/main/{}", model_id, path);
+    let modalities = match fetch_remote_header(&reqwest::Client::new(), &url, false).await {
+        Ok((header, _)) => header.projector_modalities(),
+        Err(e) => {
+            eprintln!("Could not read projector header of {}: {}", smallest, e);
+            return;
+        }
+    };
+    for info in gguf_files.values_mut().filter(|f| f.is_mmproj) {
+        info.modalities = modalities.clone();
+    }
 }
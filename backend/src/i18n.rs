@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+// Catalogs are bundled into the binary, English is the fallback for missing keys
+const BUNDLED_CATALOGS: [(&str, &str); 3] = [
+    ("en", include_str!("../locales/en.json")),
+    ("es", include_str!("../locales/es.json")),
+    ("pt-BR", include_str!("../locales/pt-BR.json")),
+];
+
+static CATALOGS: LazyLock<HashMap<&'static str, HashMap<String, String>>> = LazyLock::new(|| {
+    BUNDLED_CATALOGS
+        .iter()
+        .map(|(locale, json)| {
+            let catalog = serde_json::from_str(json).unwrap_or_else(|e| {
+                eprintln!("Failed to parse bundled catalog {}: {}", locale, e);
+                HashMap::new()
+            });
+            (*locale, catalog)
+        })
+        .collect()
+});
+
+pub fn available_locales() -> Vec<String> {
+    BUNDLED_CATALOGS.iter().map(|(locale, _)| locale.to_string()).collect()
+}
+
+/// Match a requested locale against the bundled ones ("pt-br" -> "pt-BR", "es-MX" -> "es")
+pub fn resolve_locale(locale: &str) -> &'static str {
+    let requested = locale.replace('_', "-").to_lowercase();
+    let language = requested.split('-').next().unwrap_or("");
+    BUNDLED_CATALOGS
+        .iter()
+        .map(|(l, _)| *l)
+        .find(|l| l.to_lowercase() == requested)
+        .or_else(|| BUNDLED_CATALOGS.iter().map(|(l, _)| *l).find(|l| l.to_lowercase().split('-').next() == Some(language)))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Full catalog for a locale with English entries filling any gaps
+pub fn get_catalog(locale: &str) -> HashMap<String, String> {
+    let mut catalog = CATALOGS.get(DEFAULT_LOCALE).cloned().unwrap_or_default();
+    if let Some(localized) = CATALOGS.get(resolve_locale(locale)) {
+        catalog.extend(localized.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    catalog
+}
+
+/// Translate a key and substitute `{name}` placeholders
+pub fn translate(locale: &str, key: &str, args: &[(&str, &str)]) -> String {
+    let template = CATALOGS
+        .get(resolve_locale(locale))
+        .and_then(|c| c.get(key))
+        .or_else(|| CATALOGS.get(DEFAULT_LOCALE).and_then(|c| c.get(key)))
+        .cloned()
+        .unwrap_or_else(|| key.to_string());
+    
+    args.iter().fold(template, |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+#[tauri::command]
+pub async fn get_translations(locale: Option<String>) -> Result<HashMap<String, String>, String> {
+    Ok(get_catalog(locale.as_deref().unwrap_or(DEFAULT_LOCALE)))
+}
+
+#[tauri::command]
+pub async fn get_available_locales() -> Result<Vec<String>, String> {
+    Ok(available_locales())
+}
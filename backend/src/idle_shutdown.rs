@@ -0,0 +1,129 @@
+// Idle shutdown of local servers. Models with an idle timeout are stopped once they
+// have gone that long without a request, to give their VRAM back, and are listed as
+// sleeping until they are launched again. Activity is read from the server itself:
+// task ids in `/slots` or the token counters in `/metrics` move with every request,
+// whichever client sent it.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A model stopped for being idle, until it is launched again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepingModel {
+    pub model_path: String,
+    pub model_name: String,
+    pub idle_minutes: u32,
+    pub slept_at: DateTime<Utc>,
+}
+
+// Process id -> (last activity counter, when it last changed)
+static ACTIVITY: LazyLock<std::sync::Mutex<HashMap<String, (u64, Instant)>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+// Model path -> sleeping model
+static SLEEPING: LazyLock<std::sync::Mutex<HashMap<String, SleepingModel>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+pub async fn run_idle_job(state: AppState, app_handle: tauri::AppHandle) {
+    let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        eprintln!("Idle shutdown disabled, failed to create HTTP client");
+        return;
+    };
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let running: Vec<_> = state.processes.list().await
+            .into_iter()
+            .filter(|p| p.remote_host.is_none() && matches!(p.status, ProcessStatus::Running))
+            .collect();
+        ACTIVITY.lock().unwrap().retain(|id, _| running.iter().any(|p| &p.id == id));
+
+        for process in running {
+            let idle_minutes = state.library.config_for(&process.model_path).await.idle_timeout_minutes;
+            if idle_minutes == 0 {
+                continue;
+            }
+            // Servers with both /slots and /metrics disabled can't be judged, leave them running
+            let Some((counter, busy)) = read_activity(&client, &process.client_url()).await else {
+                continue;
+            };
+
+            let idle_for = {
+                let mut activity = ACTIVITY.lock().unwrap();
+                let entry = activity.entry(process.id.clone()).or_insert((counter, Instant::now()));
+                if entry.0 != counter || busy {
+                    *entry = (counter, Instant::now());
+                }
+                entry.1.elapsed()
+            };
+            if idle_for < Duration::from_secs(idle_minutes as u64 * 60) {
+                continue;
+            }
+
+            println!("Stopping {} after {} idle minutes", process.model_name, idle_minutes);
+            if let Err(e) = crate::process::terminate_process(process.id.clone(), &state).await {
+                eprintln!("Failed to stop idle server {}: {}", process.id, e);
+                continue;
+            }
+            ACTIVITY.lock().unwrap().remove(&process.id);
+            let sleeping = SleepingModel {
+                model_path: process.model_path.clone(),
+                model_name: process.model_name,
+                idle_minutes,
+                slept_at: Utc::now(),
+            };
+            SLEEPING.lock().unwrap().insert(process.model_path, sleeping.clone());
+            let _ = app_handle.emit("model-sleeping", &sleeping);
+        }
+    }
+}
+
+/// A number that changes with every request the server handles, and whether one is in
+/// flight right now so a long generation isn't mistaken for silence
+async fn read_activity(client: &reqwest::Client, base: &str) -> Option<(u64, bool)> {
+
+    if let Ok(response) = client.get(format!("{}/slots", base)).send().await {
+        if response.status().is_success() {
+            if let Ok(slots) = response.json::<Vec<serde_json::Value>>().await {
+                let tasks: u64 = slots.iter().filter_map(|s| s.get("id_task").and_then(|v| v.as_u64())).sum();
+                let busy = slots.iter().any(|s| s.get("is_processing").and_then(|v| v.as_bool()).unwrap_or(false));
+                return Some((tasks, busy));
+            }
+        }
+    }
+
+    let response = client.get(format!("{}/metrics", base)).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let text = response.text().await.ok()?;
+    let metric = |name: &str| {
+        text.lines()
+            .find(|l| l.starts_with(name))
+            .and_then(|l| l.split_whitespace().last())
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0)
+    };
+    let tokens = metric("llamacpp:prompt_tokens_total") + metric("llamacpp:tokens_predicted_total");
+    Some((tokens as u64, metric("llamacpp:requests_processing") > 0.0))
+}
+
+pub fn sleeping_models() -> Vec<SleepingModel> {
+    let mut models: Vec<SleepingModel> = SLEEPING.lock().unwrap().values().cloned().collect();
+    models.sort_by_key(|m| std::cmp::Reverse(m.slept_at));
+    models
+}
+
+/// Launching a model wakes it, whether from the desktop, a chat or a stack
+pub fn wake(model_path: &str) {
+    SLEEPING.lock().unwrap().remove(model_path);
+}
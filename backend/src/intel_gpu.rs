@@ -0,0 +1,208 @@
+// Intel GPU monitoring on Linux, for Arc cards and integrated graphics alike.
+//
+// Neither i915 nor xe has an NVML equivalent, but both publish per-client statistics in
+// /proc/<pid>/fdinfo for every open DRM file (the same source nvtop and intel_gpu_top
+// read). Busy time per engine class gives utilization between two samples and the
+// resident memory per region gives VRAM use. The card itself is found through sysfs.
+// On Windows Intel adapters are covered by the WDDM counters in gpu_counters.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::gpu_probe::{GpuProbe, GpuSample};
+
+const INTEL_VENDOR_ID: &str = "0x8086";
+
+// Engine time is cumulative, so the first reading needs a second one to compare with
+const BASELINE_INTERVAL: Duration = Duration::from_millis(200);
+
+pub struct IntelProbe;
+
+struct Card {
+    device_dir: PathBuf,
+    pci_slot: String, // Matches drm-pdev in fdinfo
+    vram_total: Option<u64>,
+}
+
+/// Engine counters summed over every client of the card
+#[derive(Default)]
+struct Snapshot {
+    engine_ns: HashMap<String, u64>,          // i915: busy nanoseconds per engine class
+    engine_capacity: HashMap<String, u64>,    // Engines per class, when more than one
+    engine_cycles: HashMap<String, u64>,      // xe: busy GPU cycles per engine class
+    engine_total_cycles: HashMap<String, u64>, // xe: elapsed GPU cycles per engine class
+    vram_resident: u64,
+    system_resident: u64,
+}
+
+// The previous reading, so each poll covers the time since the last one
+static LAST: Mutex<Option<(Instant, Snapshot)>> = Mutex::new(None);
+
+impl GpuProbe for IntelProbe {
+    fn vendor(&self) -> &'static str {
+        "intel"
+    }
+
+    fn sample(&self) -> Option<GpuSample> {
+        let card = find_card()?;
+        let mut last = LAST.lock().ok()?;
+        let (previous_at, previous) = match last.take() {
+            Some(previous) => previous,
+            None => {
+                let baseline = (Instant::now(), read_snapshot(&card.pci_slot));
+                std::thread::sleep(BASELINE_INTERVAL);
+                baseline
+            }
+        };
+        let now = Instant::now();
+        let current = read_snapshot(&card.pci_slot);
+        let usage_percent = utilization(&previous, &current, now.duration_since(previous_at));
+
+        let sample = GpuSample {
+            name: card_name(&card),
+            usage_percent,
+            memory_total_bytes: card.vram_total.unwrap_or(0),
+            memory_used_bytes: if card.vram_total.is_some() { current.vram_resident } else { current.system_resident },
+            shared_memory: card.vram_total.is_none(),
+        };
+        *last = Some((now, current));
+        Some(sample)
+    }
+}
+
+/// The Intel card to report, a discrete one when there is both
+fn find_card() -> Option<Card> {
+    let mut cards: Vec<Card> = std::fs::read_dir("/sys/class/drm").ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // cardN, not the connector entries like card0-DP-1
+            name.strip_prefix("card").is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|entry| {
+            let device_dir = entry.path().join("device");
+            let vendor = std::fs::read_to_string(device_dir.join("vendor")).ok()?;
+            if vendor.trim() != INTEL_VENDOR_ID {
+                return None;
+            }
+            let pci_slot = std::fs::canonicalize(&device_dir).ok()?
+                .file_name()?
+                .to_string_lossy()
+                .to_string();
+            let vram_total = read_number(&device_dir.join("tile0").join("physical_vram_size_bytes"))
+                .or_else(|| read_number(&device_dir.join("lmem_total_bytes")));
+            Some(Card { device_dir, pci_slot, vram_total })
+        })
+        .collect();
+    cards.sort_by_key(|card| card.vram_total.is_none());
+    cards.into_iter().next()
+}
+
+fn card_name(card: &Card) -> String {
+    let device_id = std::fs::read_to_string(card.device_dir.join("device"))
+        .map(|id| id.trim().trim_start_matches("0x").to_uppercase())
+        .unwrap_or_default();
+    let family = if card.vram_total.is_some() { "Intel Arc GPU" } else { "Intel Graphics" };
+    if device_id.is_empty() { family.to_string() } else { format!("{} [{}]", family, device_id) }
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// `1234 KiB` style fdinfo sizes in bytes
+fn parse_size(value: &str) -> u64 {
+    let mut parts = value.split_whitespace();
+    let number: u64 = parts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+    let unit = match parts.next() {
+        Some("KiB") => 1024,
+        Some("MiB") => 1024 * 1024,
+        Some("GiB") => 1024 * 1024 * 1024,
+        _ => 1,
+    };
+    number * unit
+}
+
+/// Sum the fdinfo of every DRM client on the card. A client can be open through several
+/// file descriptors, or shared between processes, so each client id only counts once.
+fn read_snapshot(pci_slot: &str) -> Snapshot {
+    let mut snapshot = Snapshot::default();
+    let mut seen_clients = HashSet::new();
+    let Ok(processes) = std::fs::read_dir("/proc") else {
+        return snapshot;
+    };
+
+    for process in processes.flatten() {
+        let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+            continue; // Other users' processes aren't readable, they don't count
+        };
+        for fd in fds.flatten() {
+            let is_drm = std::fs::read_link(fd.path())
+                .is_ok_and(|target| target.starts_with("/dev/dri"));
+            if !is_drm {
+                continue;
+            }
+            let fdinfo = process.path().join("fdinfo").join(fd.file_name());
+            let Ok(contents) = std::fs::read_to_string(fdinfo) else {
+                continue;
+            };
+            let fields: HashMap<&str, &str> = contents
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .collect();
+            if fields.get("drm-pdev") != Some(&pci_slot) {
+                continue;
+            }
+            let Some(client_id) = fields.get("drm-client-id") else {
+                continue;
+            };
+            if !seen_clients.insert(client_id.to_string()) {
+                continue;
+            }
+            add_client(&mut snapshot, &fields);
+        }
+    }
+    snapshot
+}
+
+fn add_client(snapshot: &mut Snapshot, fields: &HashMap<&str, &str>) {
+    for (key, value) in fields {
+        if let Some(class) = key.strip_prefix("drm-engine-capacity-") {
+            snapshot.engine_capacity.insert(class.to_string(), value.parse().unwrap_or(1));
+        } else if let Some(class) = key.strip_prefix("drm-engine-") {
+            *snapshot.engine_ns.entry(class.to_string()).or_default() += parse_size(value);
+        } else if let Some(class) = key.strip_prefix("drm-total-cycles-") {
+            // Every client reads the same GPU clock, the largest one covers the interval
+            let cycles: u64 = value.parse().unwrap_or(0);
+            let total = snapshot.engine_total_cycles.entry(class.to_string()).or_default();
+            *total = (*total).max(cycles);
+        } else if let Some(class) = key.strip_prefix("drm-cycles-") {
+            *snapshot.engine_cycles.entry(class.to_string()).or_default() += value.parse::<u64>().unwrap_or(0);
+        } else if key.starts_with("drm-resident-local") || key.starts_with("drm-resident-vram") {
+            snapshot.vram_resident += parse_size(value);
+        } else if key.starts_with("drm-resident-system") {
+            snapshot.system_resident += parse_size(value);
+        }
+    }
+}
+
+/// Busiest engine class between two snapshots, like the WDDM counters report it
+fn utilization(previous: &Snapshot, current: &Snapshot, elapsed: Duration) -> f32 {
+    let elapsed_ns = elapsed.as_nanos().max(1) as f64;
+    let from_time = current.engine_ns.iter().map(|(class, busy)| {
+        let delta = busy.saturating_sub(previous.engine_ns.get(class).copied().unwrap_or(0)) as f64;
+        let capacity = current.engine_capacity.get(class).copied().unwrap_or(1).max(1) as f64;
+        delta / (elapsed_ns * capacity)
+    });
+    let from_cycles = current.engine_cycles.iter().filter_map(|(class, busy)| {
+        let delta = busy.saturating_sub(previous.engine_cycles.get(class).copied().unwrap_or(0)) as f64;
+        let total = current.engine_total_cycles.get(class)?
+            .saturating_sub(previous.engine_total_cycles.get(class).copied().unwrap_or(0));
+        let capacity = current.engine_capacity.get(class).copied().unwrap_or(1).max(1) as f64;
+        (total > 0).then(|| delta / (total as f64 * capacity))
+    });
+    (from_time.chain(from_cycles).fold(0.0, f64::max) * 100.0).min(100.0) as f32
+}
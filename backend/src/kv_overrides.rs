@@ -0,0 +1,121 @@
+// Structured `--override-kv` entries. Overrides are checked against the model's own
+// metadata when saved, so a typo in a key or a wrong value type is caught in the
+// settings window instead of making llama-server refuse to load the model.
+
+use std::collections::HashSet;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::gguf::{read_header, GgufHeader, GgufValue};
+use crate::models::{KvOverride, KvOverrideType, OverridableKey};
+
+// llama.cpp stores string overrides in a fixed 128 byte buffer
+const MAX_STR_VALUE_LEN: usize = 127;
+
+fn load_header(model_path: &str) -> Result<GgufHeader, String> {
+    let file = std::fs::File::open(Path::new(model_path)).map_err(|e| format!("Failed to open model: {}", e))?;
+    read_header(&mut BufReader::new(file), false).map_err(|e| format!("Failed to read GGUF header: {}", e))
+}
+
+/// Type tag as written in the override argument
+fn type_name(value_type: KvOverrideType) -> &'static str {
+    match value_type {
+        KvOverrideType::Int => "int",
+        KvOverrideType::Float => "float",
+        KvOverrideType::Bool => "bool",
+        KvOverrideType::Str => "str",
+    }
+}
+
+/// Override type for a metadata value, None for arrays which can't be overridden
+fn override_type(value: &GgufValue) -> Option<KvOverrideType> {
+    match value {
+        GgufValue::Uint(_) | GgufValue::Int(_) => Some(KvOverrideType::Int),
+        GgufValue::Float(_) => Some(KvOverrideType::Float),
+        GgufValue::Bool(_) => Some(KvOverrideType::Bool),
+        GgufValue::String(_) => Some(KvOverrideType::Str),
+        GgufValue::Array { .. } => None,
+    }
+}
+
+fn display_value(value: &GgufValue) -> String {
+    match value {
+        GgufValue::Uint(v) => v.to_string(),
+        GgufValue::Int(v) => v.to_string(),
+        GgufValue::Float(v) => v.to_string(),
+        GgufValue::Bool(v) => v.to_string(),
+        GgufValue::String(v) => v.clone(),
+        GgufValue::Array { len, .. } => format!("[{} items]", len),
+    }
+}
+
+/// Metadata keys of a model that can be overridden, with their current values
+pub fn overridable_keys(model_path: &str) -> Result<Vec<OverridableKey>, String> {
+    let header = load_header(model_path)?;
+    Ok(header.metadata
+        .iter()
+        .filter_map(|(key, value)| {
+            override_type(value).map(|value_type| OverridableKey {
+                key: key.clone(),
+                value_type,
+                current_value: display_value(value),
+            })
+        })
+        .collect())
+}
+
+/// Check overrides against the model: the key must exist, the type must match
+/// the stored value and the value must parse as that type
+pub fn validate(model_path: &str, overrides: &[KvOverride]) -> Result<(), String> {
+    if overrides.is_empty() {
+        return Ok(());
+    }
+    let header = load_header(model_path)?;
+
+    let mut seen = HashSet::new();
+    for entry in overrides {
+        if !seen.insert(entry.key.as_str()) {
+            return Err(format!("{} is overridden more than once", entry.key));
+        }
+        let value = header.get(&entry.key)
+            .ok_or_else(|| format!("{} is not a metadata key of this model", entry.key))?;
+        let expected = override_type(value)
+            .ok_or_else(|| format!("{} is an array and can't be overridden", entry.key))?;
+        if entry.value_type != expected {
+            return Err(format!("{} holds a {} value, not {}", entry.key, type_name(expected), type_name(entry.value_type)));
+        }
+        validate_value(entry)?;
+    }
+    Ok(())
+}
+
+fn validate_value(entry: &KvOverride) -> Result<(), String> {
+    let value = entry.value.trim();
+    let valid = match entry.value_type {
+        KvOverrideType::Int => value.parse::<i64>().is_ok(),
+        KvOverrideType::Float => value.parse::<f64>().is_ok(),
+        KvOverrideType::Bool => value == "true" || value == "false",
+        KvOverrideType::Str => entry.value.len() <= MAX_STR_VALUE_LEN,
+    };
+    if valid {
+        Ok(())
+    } else if entry.value_type == KvOverrideType::Str {
+        Err(format!("{}: string overrides are limited to {} bytes", entry.key, MAX_STR_VALUE_LEN))
+    } else {
+        Err(format!("{}: '{}' is not a valid {} value", entry.key, entry.value, type_name(entry.value_type)))
+    }
+}
+
+/// Launch arguments for the overrides
+pub fn build_args(overrides: &[KvOverride]) -> Vec<String> {
+    overrides
+        .iter()
+        .flat_map(|entry| {
+            let value = match entry.value_type {
+                KvOverrideType::Str => entry.value.as_str(),
+                _ => entry.value.trim(),
+            };
+            ["--override-kv".to_string(), format!("{}={}:{}", entry.key, type_name(entry.value_type), value)]
+        })
+        .collect()
+}
@@ -0,0 +1,118 @@
+// First-class launch settings for the flags nearly every model sets. They are turned into
+// llama-server arguments ahead of the custom ones, so a flag typed into custom_args still
+// wins. Saved custom arguments are lifted into the fields where they map cleanly.
+
+use crate::models::{FlashAttention, LaunchSettings};
+use crate::presets::{group_args, same_flag, ArgGroup};
+use crate::process::quote_arg;
+
+fn flash_attention_value(mode: FlashAttention) -> &'static str {
+    match mode {
+        FlashAttention::Auto => "auto",
+        FlashAttention::On => "on",
+        FlashAttention::Off => "off",
+    }
+}
+
+fn parse_flash_attention(value: &str) -> Option<FlashAttention> {
+    match value.to_ascii_lowercase().as_str() {
+        "auto" => Some(FlashAttention::Auto),
+        "on" | "1" | "true" => Some(FlashAttention::On),
+        "off" | "0" | "false" => Some(FlashAttention::Off),
+        _ => None,
+    }
+}
+
+/// Server arguments for the fields that are set
+pub fn to_args(settings: &LaunchSettings) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut push = |flag: &str, value: Option<String>| {
+        if let Some(value) = value {
+            args.push(flag.to_string());
+            args.push(value);
+        }
+    };
+    push("-c", settings.context_size.map(|v| v.to_string()));
+    push("-ngl", settings.gpu_layers.map(|v| v.to_string()));
+    push("-t", settings.threads.map(|v| v.to_string()));
+    push("-fa", settings.flash_attention.map(|v| flash_attention_value(v).to_string()));
+    push("-b", settings.batch_size.map(|v| v.to_string()));
+    push("-ub", settings.ubatch_size.map(|v| v.to_string()));
+    push("--temp", settings.temperature.map(|v| v.to_string()));
+    args
+}
+
+pub fn validate(settings: &LaunchSettings) -> Result<(), String> {
+    if settings.threads == Some(0) {
+        return Err("Threads must be at least 1".to_string());
+    }
+    if settings.batch_size == Some(0) || settings.ubatch_size == Some(0) {
+        return Err("Batch sizes must be at least 1".to_string());
+    }
+    if let (Some(batch), Some(ubatch)) = (settings.batch_size, settings.ubatch_size) {
+        if ubatch > batch {
+            return Err(format!("Micro-batch size {} is larger than the batch size {}", ubatch, batch));
+        }
+    }
+    if let Some(temperature) = settings.temperature {
+        if !temperature.is_finite() || temperature < 0.0 {
+            return Err(format!("Invalid temperature: {}", temperature));
+        }
+    }
+    Ok(())
+}
+
+/// The flag's single value, from either `--flag value` or `--flag=value`
+fn single_value(group: &ArgGroup) -> Option<&str> {
+    let (flag, values) = group;
+    match (flag.split_once('='), values.as_slice()) {
+        (Some((_, value)), []) => Some(value),
+        (None, [value]) => Some(value.as_str()),
+        _ => None,
+    }
+}
+
+/// Move the flags the settings cover out of `custom_args` and into `settings`, returning the
+/// arguments left over. A flag is only lifted when it appears once with a value that parses,
+/// anything unusual (a bare `-fa`, a repeated flag) stays in the custom arguments.
+pub fn lift(settings: &mut LaunchSettings, custom_args: &str) -> String {
+    let groups = group_args(custom_args);
+    let mut lifted = vec![false; groups.len()];
+
+    for (index, group) in groups.iter().enumerate() {
+        let flag = &group.0;
+        if flag.is_empty() || groups.iter().filter(|(other, _)| same_flag(flag, other)).count() > 1 {
+            continue;
+        }
+        let Some(value) = single_value(group) else {
+            continue;
+        };
+        lifted[index] = if same_flag(flag, "-c") {
+            value.parse().map(|v| settings.context_size = Some(v)).is_ok()
+        } else if same_flag(flag, "-ngl") {
+            value.parse().map(|v| settings.gpu_layers = Some(v)).is_ok()
+        } else if same_flag(flag, "-t") {
+            value.parse().map(|v| settings.threads = Some(v)).is_ok()
+        } else if same_flag(flag, "-fa") {
+            parse_flash_attention(value).map(|v| settings.flash_attention = Some(v)).is_some()
+        } else if same_flag(flag, "-b") {
+            value.parse().map(|v| settings.batch_size = Some(v)).is_ok()
+        } else if same_flag(flag, "-ub") {
+            value.parse().map(|v| settings.ubatch_size = Some(v)).is_ok()
+        } else if same_flag(flag, "--temp") {
+            value.parse().map(|v| settings.temperature = Some(v)).is_ok()
+        } else {
+            false
+        };
+    }
+
+    groups
+        .into_iter()
+        .zip(lifted)
+        .filter(|(_, lifted)| !lifted)
+        .flat_map(|((flag, values), _)| std::iter::once(flag).chain(values))
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| quote_arg(&arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{Manager, Listener};
 use tokio::sync::Mutex;
 use std::sync::Arc;
@@ -12,16 +12,76 @@ mod huggingface;
 mod downloader;
 mod llamacpp_manager;
 mod system_monitor;
+mod stacks;
+mod benchmark;
+mod gguf;
+mod themes;
+mod download_log;
+mod i18n;
+mod chat;
+mod chat_store;
+mod gpu_power;
+mod batch;
+mod capabilities;
+mod remote;
+mod fixups;
+mod sorting;
+mod gguf_my_repo;
+mod hf_metadata;
+mod local_socket;
+mod mirrors;
+mod managers;
+mod share;
+mod context_fit;
+mod kv_overrides;
+mod version_args;
+mod encryption;
+mod archive_stream;
+mod vram;
+mod trial;
+mod process_gc;
+mod starter_catalog;
+mod version_test;
+mod chat_analytics;
+mod hashing;
+mod firewall;
+mod request_log;
+mod app_status;
+mod conversion;
+mod model_import;
+mod gpu_telemetry;
+mod audio_input;
+mod manifest;
+mod scan_cache;
+mod readiness;
+mod idle_shutdown;
+mod process_log;
+mod output_stream;
+mod presets;
+mod launch_settings;
+mod cli_session;
+mod hf_conversion;
+mod gpu_probe;
+mod process_stats;
+#[cfg(all(feature = "gpu-intel", target_os = "linux"))]
+mod intel_gpu;
+#[cfg(target_os = "macos")]
+mod apple_silicon;
+#[cfg(windows)]
+mod gpu_counters;
 
 use config::*;
 use process::*;
 use process::launch_model_external as launch_model_external_impl;
 use scanner::*;
 use huggingface::*;
-use models::{GlobalConfig, ModelConfig, ProcessInfo, SessionState, WindowState, ProcessOutput, SearchResult, ModelDetails, DownloadStartResult};
-use downloader::{DownloadManager, DownloadStatus};
+use models::{GlobalConfig, ModelConfig, ModelConfigSnapshot, BatchResult, SessionState, WindowState, TerminalState, TerminalHistory, ProcessInfo, ProcessMetrics, ProcessResourceUsage, ChatState, ProcessOutput, SearchResult, HubSearchFilters, ModelDetails, DownloadStartResult, DownloadError, StarterModel, RecentModel, LaunchOverrides, LaunchCommandPreview, StackDefinition, StackStatus, GpuPowerSettings, AcceleratedTransferSettings, PortRange, RemoteHostConfig, RemoteGgufInfo, GgufConversionStatus, ConversionJob, UpstreamMetadata, MirrorHealth, ShareLink, ManagedFirewallRule, GpuVramBreakdown, ModelMemoryEstimate, OverridableKey, VersionArgRule, PreviewSection, ModelFilePreview, LoraAdapterInfo, ModelTag, DesktopFolder, ArgumentPreset};
+use downloader::DownloadStatus;
 use llamacpp_manager::{LlamaCppReleaseFrontend as LlamaCppRelease, LlamaCppAssetFrontend as LlamaCppAsset};
 use system_monitor::*;
+use themes::*;
+use download_log::get_bandwidth_usage;
+use i18n::{get_translations, get_available_locales};
 
 // Import ProcessHandle from process module
 use process::ProcessHandle;
@@ -30,11 +90,14 @@ use process::ProcessHandle;
 #[derive(Debug)]
 pub struct AppState {
     pub config: Arc<Mutex<GlobalConfig>>,
-    pub model_configs: Arc<Mutex<HashMap<String, ModelConfig>>>,
-    pub running_processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
-    pub child_processes: Arc<Mutex<HashMap<String, Arc<Mutex<ProcessHandle>>>>>, // Simplified process tracking
+    pub processes: managers::ProcessManager,
+    pub library: managers::ModelLibrary,
+    pub downloads: managers::DownloadService,
+    pub versions: managers::VersionManager,
     pub session_state: Arc<Mutex<SessionState>>,
-    pub download_manager: Arc<Mutex<DownloadManager>>,
+    pub stacks: Arc<Mutex<HashMap<String, StackDefinition>>>,
+    pub stack_processes: Arc<Mutex<HashMap<String, Vec<String>>>>, // Stack name -> launched process ids
+    pub presets: Arc<Mutex<HashMap<String, ArgumentPreset>>>, // Preset name -> preset
 }
 
 // Implement Clone manually to avoid derive issues with Child
@@ -42,51 +105,84 @@ impl Clone for AppState {
     fn clone(&self) -> Self {
         Self {
             config: self.config.clone(),
-            model_configs: self.model_configs.clone(),
-            running_processes: self.running_processes.clone(),
-            child_processes: self.child_processes.clone(),
+            processes: self.processes.clone(),
+            library: self.library.clone(),
+            downloads: self.downloads.clone(),
+            versions: self.versions.clone(),
             session_state: self.session_state.clone(),
-            download_manager: self.download_manager.clone(),
+            stacks: self.stacks.clone(),
+            stack_processes: self.stack_processes.clone(),
+            presets: self.presets.clone(),
         }
     }
 }
 
 impl AppState {
     pub fn new() -> Self {
+        let config = Arc::new(Mutex::new(GlobalConfig::default()));
         Self {
-            config: Arc::new(Mutex::new(GlobalConfig::default())),
-            model_configs: Arc::new(Mutex::new(HashMap::new())),
-            running_processes: Arc::new(Mutex::new(HashMap::new())),
-            child_processes: Arc::new(Mutex::new(HashMap::new())),
+            versions: managers::VersionManager::new(config.clone()),
+            config,
+            processes: managers::ProcessManager::default(),
+            library: managers::ModelLibrary::default(),
+            downloads: managers::DownloadService::default(),
             session_state: Arc::new(Mutex::new(SessionState::default())),
-            download_manager: Arc::new(Mutex::new(DownloadManager::new())),
+            stacks: Arc::new(Mutex::new(HashMap::new())),
+            stack_processes: Arc::new(Mutex::new(HashMap::new())),
+            presets: Arc::new(Mutex::new(HashMap::new())),
         }
     }
     
-    // Method to cleanup all child processes when app exits
-    pub async fn cleanup_all_processes(&self) {
+    // Method to cleanup all child processes when app exits.
+    // Processes are asked to exit and awaited up to the configured shutdown timeout,
+    // anything still alive after that is force killed. Returns how many processes were cleaned up.
+    pub async fn cleanup_all_processes(&self) -> usize {
         println!("Starting cleanup of all child processes...");
+        share::revoke_all();
         
-        let process_count = {
-            let child_processes = self.child_processes.lock().await;
-            child_processes.len()
+        let (timeout_ms, skip_wait) = {
+            let config = self.config.lock().await;
+            (config.shutdown_timeout_ms, config.skip_shutdown_wait)
         };
         
-        println!("Found {} processes to clean up", process_count);
+        let handles: Vec<(String, Arc<Mutex<ProcessHandle>>)> = {
+            let mut child_processes = self.processes.children.lock().await;
+            child_processes.drain().collect()
+        };
+        
+        println!("Found {} processes to clean up", handles.len());
+        
+        let mut children = Vec::new();
+        for (process_id, handle_arc) in handles {
+            let mut handle_guard = handle_arc.lock().await;
+            match handle_guard.take_child() {
+                Some(child) => children.push((process_id, child)),
+                None => println!("Process {} already terminated", process_id),
+            }
+        }
+        let process_count = children.len();
         
         if process_count == 0 {
             println!("No processes to clean up");
-            return;
+            self.processes.running.lock().await.clear();
+            return 0;
         }
         
-        let mut child_processes = self.child_processes.lock().await;
-        let mut running_processes = self.running_processes.lock().await;
+        // Ask every process to exit first so they can shut down in parallel. The timeout only
+        // applies when a request went out, otherwise there is nothing to wait for
+        let mut requested = false;
+        if !skip_wait && timeout_ms > 0 {
+            for (process_id, child) in children.iter() {
+                println!("Requesting termination of process: {}", process_id);
+                requested |= request_process_exit(child);
+            }
+        }
         
-        for (process_id, handle_arc) in child_processes.drain() {
-            println!("Terminating process: {}", process_id);
-            let mut handle_guard = handle_arc.lock().await;
-            if let Some(mut child) = handle_guard.take_child() {
-                match child.kill().await {
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_millis(if requested { timeout_ms } else { 0 });
+        for (process_id, mut child) in children {
+            match tokio::time::timeout_at(deadline, child.wait()).await {
+                Ok(_) => println!("Process {} exited", process_id),
+                Err(_) => match child.kill().await {
                     Ok(_) => println!("Successfully killed process: {}", process_id),
                     Err(e) => {
                         eprintln!("Failed to kill process {}: {}", process_id, e);
@@ -101,24 +197,25 @@ impl AppState {
                             }
                         }
                     }
-                }
-            } else {
-                println!("Process {} already terminated", process_id);
+                },
             }
         }
         
         // Clear the running processes list
-        running_processes.clear();
+        self.processes.running.lock().await.clear();
+        let _ = tokio::task::spawn_blocking(gpu_power::restore_saved_state).await;
         println!("Process cleanup completed");
+        process_count
     }
     
     // Force cleanup that drops all child processes immediately
     // This relies on kill_on_drop(true) to terminate the processes
     pub fn force_cleanup_all_processes(&self) {
         println!("Force cleaning up all child processes (synchronous)...");
+        share::revoke_all();
         
         // Use try_lock to avoid blocking if already locked
-        if let Ok(mut child_processes) = self.child_processes.try_lock() {
+        if let Ok(mut child_processes) = self.processes.children.try_lock() {
             let count = child_processes.len();
             if count == 0 {
                 println!("No processes to clean up");
@@ -171,7 +268,7 @@ impl AppState {
             println!("Could not acquire lock for force cleanup, relying on kill_on_drop");
         }
         
-        if let Ok(mut running_processes) = self.running_processes.try_lock() {
+        if let Ok(mut running_processes) = self.processes.running.try_lock() {
             running_processes.clear();
         }
         
@@ -179,6 +276,26 @@ impl AppState {
     }
 }
 
+/// Ask a child process to shut down on its own, true when the request was sent.
+/// On Unix this sends SIGTERM.
+#[cfg(not(windows))]
+fn request_process_exit(child: &tokio::process::Child) -> bool {
+    let Some(pid) = child.id() else {
+        return false;
+    };
+    std::process::Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Windows console servers have no SIGTERM equivalent a GUI process can send them, so
+/// nothing is requested and they are killed straight away instead of after the timeout.
+#[cfg(windows)]
+fn request_process_exit(_child: &tokio::process::Child) -> bool {
+    false
+}
+
 // Implement Drop trait for emergency cleanup
 // Note: This will only be called when the entire application is shutting down
 impl Drop for AppState {
@@ -190,7 +307,7 @@ impl Drop for AppState {
         // If you want to be extra safe, you could do this:
         /*
         let has_processes = {
-            if let Ok(child_processes) = self.child_processes.try_lock() {
+            if let Ok(child_processes) = self.processes.children.try_lock() {
                 !child_processes.is_empty()
             } else {
                 false
@@ -204,6 +321,27 @@ impl Drop for AppState {
     }
 }
 
+// Locale used for error messages produced in the backend
+async fn current_locale(state: &AppState) -> String {
+    let config = state.config.lock().await;
+    config.locale.clone()
+}
+
+/// The running server that has a file open, with a message naming it. Checked before
+/// deleting, moving or overwriting models, where Windows would only report a sharing
+/// violation.
+pub(crate) async fn model_in_use(path: &str, state: &AppState) -> Option<(ProcessInfo, String)> {
+    let process = state.processes.using_file(path).await?;
+    let locale = current_locale(state).await;
+    let file = Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string());
+    let message = i18n::translate(&locale, "error.model_in_use", &[
+        ("file", &file),
+        ("model", &process.model_name),
+        ("process", &process.id),
+    ]);
+    Some((process, message))
+}
+
 // Tauri commands
 #[tauri::command]
 async fn get_config(state: tauri::State<'_, AppState>) -> Result<GlobalConfig, String> {
@@ -212,36 +350,40 @@ async fn get_config(state: tauri::State<'_, AppState>) -> Result<GlobalConfig, S
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn save_config(
     models_directory: String,
     executable_folder: String,
     theme_color: String,
     background_color: String,
     theme_is_synced: bool,
+    hf_token: Option<String>,
+    extra_model_directories: Option<Vec<String>>,
     state: tauri::State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
     println!("Saving config: models_dir={}, exec_folder={}, theme={}, background={}, synced={}", models_directory, executable_folder, theme_color, background_color, theme_is_synced);
     
-    // Preserve existing active executable folder
-    let (existing_active_path, existing_active_version) = {
-        let cfg = state.config.lock().await;
-        (cfg.active_executable_folder.clone(), cfg.active_executable_version.clone())
-    };
-    let config = GlobalConfig {
-        models_directory: models_directory.clone(),
-        executable_folder,
-        active_executable_folder: existing_active_path,
-        active_executable_version: existing_active_version,
-        theme_color,
-        background_color,
-        theme_is_synced,
-    };
-    
-    // Update global config
-    {
+    // Update global config, preserving settings not managed by this form (active version, etc.)
+    let config = {
         let mut global_config = state.config.lock().await;
-        *global_config = config.clone();
-    }
+        global_config.models_directory = models_directory.clone();
+        global_config.executable_folder = executable_folder;
+        global_config.theme_color = theme_color;
+        global_config.background_color = background_color;
+        global_config.theme_is_synced = theme_is_synced;
+        if let Some(token) = hf_token {
+            global_config.hf_token = token.trim().to_string();
+            huggingface::set_access_token(&global_config.hf_token);
+        }
+        if let Some(directories) = extra_model_directories {
+            global_config.extra_model_directories = directories
+                .into_iter()
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty())
+                .collect();
+        }
+        global_config.clone()
+    };
     
     // Save to file
     if let Err(e) = save_settings(&state).await {
@@ -258,7 +400,7 @@ async fn save_config(
     }
     
     // Scan models with new directory
-    match scan_models(&models_directory).await {
+    match scan_library(&config, None).await {
         Ok(models) => {
             println!("Successfully scanned {} models", models.len());
             Ok(serde_json::json!({
@@ -279,9 +421,10 @@ async fn save_config(
 #[tauri::command]
 async fn scan_models_command(
     state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
 ) -> Result<serde_json::Value, String> {
-    let config = state.config.lock().await;
-    let models = scan_models(&config.models_directory).await
+    let config = state.config.lock().await.clone();
+    let models = scan_library(&config, Some(&app_handle)).await
         .map_err(|e| format!("Failed to scan models: {}", e))?;
     
     Ok(serde_json::json!({
@@ -290,15 +433,85 @@ async fn scan_models_command(
     }))
 }
 
+#[tauri::command]
+async fn get_sorted_models(
+    sort_type: sorting::SortStrategy,
+    direction: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<sorting::SortedModel>, String> {
+    let config = state.config.lock().await.clone();
+    let models = scan_library(&config, None).await
+        .map_err(|e| format!("Failed to scan models: {}", e))?;
+    
+    let usage = state.library.usage().await;
+    let descending = direction.as_deref() == Some("desc");
+    Ok(sorting::sort_models(models, sort_type, descending, &usage))
+}
+
+#[tauri::command]
+async fn set_model_tags(
+    model_path: String,
+    tags: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModelConfig, String> {
+    let config = state.library.set_labels(&model_path, Some(tags), None).await;
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(config)
+}
+
+#[tauri::command]
+async fn set_model_favorite(
+    model_path: String,
+    favorite: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModelConfig, String> {
+    let config = state.library.set_labels(&model_path, None, Some(favorite)).await;
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(config)
+}
+
+/// Every tag in use with its model count, most used first
+#[tauri::command]
+async fn get_model_tags(state: tauri::State<'_, AppState>) -> Result<Vec<ModelTag>, String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for config in state.library.all_configs().await.values() {
+        for tag in &config.tags {
+            *counts.entry(tag.clone()).or_default() += 1;
+        }
+    }
+    let mut tags: Vec<ModelTag> = counts.into_iter().map(|(tag, count)| ModelTag { tag, count }).collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    Ok(tags)
+}
+
+/// Paths of the models carrying a tag, "favorites" selects the favorite models
+#[tauri::command]
+async fn get_models_with_tag(
+    tag: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let tag = tag.trim().to_lowercase();
+    Ok(state.library.all_configs().await
+        .into_iter()
+        .filter(|(_, config)| if tag == "favorites" { config.favorite } else { config.tags.contains(&tag) })
+        .map(|(path, _)| path)
+        .collect())
+}
+
+#[tauri::command]
+async fn scan_lora_adapters(state: tauri::State<'_, AppState>) -> Result<Vec<LoraAdapterInfo>, String> {
+    let config = state.config.lock().await.clone();
+    Ok(scanner::scan_lora_adapters(&config).await)
+}
+
 #[tauri::command]
 async fn get_model_settings(
     model_path: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<ModelConfig, String> {
-    let model_configs = state.model_configs.lock().await;
-    Ok(model_configs.get(&model_path)
-        .cloned()
-        .unwrap_or_else(|| ModelConfig::new(model_path)))
+    Ok(state.library.config_for(&model_path).await)
 }
 
 #[tauri::command]
@@ -307,22 +520,173 @@ async fn update_model_settings(
     config: ModelConfig,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
-    {
-        let mut model_configs = state.model_configs.lock().await;
-        model_configs.insert(model_path, config);
-    }
+    kv_overrides::validate(&model_path, &config.kv_overrides)?;
+    process::validate_env_vars(&config.env_vars)?;
+    let mut config = config;
+    config.custom_args = launch_settings::lift(&mut config.launch_settings, &config.custom_args);
+    launch_settings::validate(&config.launch_settings)?;
+    state.library.set_config(&model_path, config).await;
     
     save_settings(&state).await
         .map_err(|e| format!("Failed to save settings: {}", e))
 }
 
+#[tauri::command]
+async fn get_vram_breakdown(state: tauri::State<'_, AppState>) -> Result<Vec<GpuVramBreakdown>, String> {
+    vram::get_breakdown(&state).await
+}
+
+#[tauri::command]
+async fn estimate_model_memory(
+    model_path: String,
+    context_size: Option<u64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModelMemoryEstimate, String> {
+    let model_config = state.library.config_for(&model_path).await;
+    let model_files = read_model_file_args(&model_config).await;
+    let args = build_server_args(&model_config, model_config.server_port, &model_files);
+    let auto_context = model_config.auto_context && context_size.is_none();
+    
+    tokio::task::spawn_blocking(move || {
+        let model_path = first_shard(&model_path);
+        let free_vram = free_vram_bytes();
+        // With auto context the launch sizes the context to free VRAM, estimate at that size
+        let context_size = match (auto_context, free_vram) {
+            (true, Some(free)) => context_fit::fit_context(Path::new(&model_path), &args, free).ok(),
+            _ => context_size,
+        };
+        context_fit::estimate_memory(Path::new(&model_path), &args, context_size, free_vram, total_vram_bytes())
+    })
+    .await
+    .map_err(|e| format!("Memory estimate task failed: {}", e))?
+    .map_err(|e| format!("Failed to estimate memory: {}", e))
+}
+
+#[tauri::command]
+async fn get_overridable_keys(model_path: String) -> Result<Vec<OverridableKey>, String> {
+    tokio::task::spawn_blocking(move || kv_overrides::overridable_keys(&model_path))
+        .await
+        .map_err(|e| format!("Failed to read model metadata: {}", e))?
+}
+
+#[tauri::command]
+async fn list_applied_fixups(
+    model_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<fixups::AppliedFixup>, String> {
+    let model_config = state.library.config_for(&model_path).await;
+    tokio::task::spawn_blocking(move || fixups::find_fixups(&model_config))
+        .await
+        .map_err(|e| format!("Failed to check fixups: {}", e))
+}
+
+#[tauri::command]
+async fn get_model_config_history(
+    model_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ModelConfigSnapshot>, String> {
+    Ok(state.library.history(&model_path).await)
+}
+
+#[tauri::command]
+async fn rollback_model_config(
+    model_path: String,
+    version: u32,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModelConfig, String> {
+    let mut restored = state.library.snapshot(&model_path, version).await
+        .ok_or_else(|| format!("No saved version {} for this model", version))?;
+    
+    // Tags and favorites aren't versioned, an old snapshot shouldn't bring back old ones
+    let current = state.library.config_for(&model_path).await;
+    restored.tags = current.tags;
+    restored.favorite = current.favorite;
+    
+    // The settings being replaced go into the history too, so a rollback can be undone
+    state.library.set_config(&model_path, restored.clone()).await;
+    
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(restored)
+}
+
 #[tauri::command]
 async fn launch_model(
     model_path: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
+    let locale = current_locale(&state).await;
     let result = launch_model_server(model_path, &state).await
-        .map_err(|e| format!("Failed to launch model: {}", e))?;
+        .map_err(|e| i18n::translate(&locale, "error.launch_failed", &[("error", &e.to_string())]))?;
+    
+    Ok(serde_json::json!({
+        "success": true,
+        "process_id": result.process_id,
+        "model_name": result.model_name,
+        "server_host": result.server_host,
+        "server_port": result.server_port,
+        "computed_context": result.computed_context
+    }))
+}
+
+/// Start llama-cli for a model with its stdin piped, for a REPL in the terminal window
+#[tauri::command]
+async fn launch_model_cli(
+    model_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let locale = current_locale(&state).await;
+    let result = cli_session::launch_cli_session(model_path, &state).await
+        .map_err(|e| i18n::translate(&locale, "error.launch_failed", &[("error", &e.to_string())]))?;
+    
+    Ok(serde_json::json!({
+        "success": true,
+        "process_id": result.process_id,
+        "model_name": result.model_name
+    }))
+}
+
+#[tauri::command]
+async fn send_process_input(
+    process_id: String,
+    input: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    cli_session::send_input(&process_id, &input, &state).await
+        .map_err(|e| format!("Failed to send input: {}", e))
+}
+
+#[tauri::command]
+async fn try_model_from_hub(
+    model_id: String,
+    filename: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let result = trial::launch_trial(&model_id, &filename, &state).await?;
+    
+    Ok(serde_json::json!({
+        "success": true,
+        "process_id": result.process_id,
+        "model_name": result.model_name,
+        "server_host": result.server_host,
+        "server_port": result.server_port,
+        "message": result.message
+    }))
+}
+
+#[tauri::command]
+async fn clear_trial_cache(state: tauri::State<'_, AppState>) -> Result<u64, String> {
+    trial::clear_cache(&state).await
+}
+
+#[tauri::command]
+async fn relaunch_last(
+    model_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let locale = current_locale(&state).await;
+    let result = process::relaunch_last(model_path, &state).await
+        .map_err(|e| i18n::translate(&locale, "error.launch_failed", &[("error", &e.to_string())]))?;
     
     Ok(serde_json::json!({
         "success": true,
@@ -338,8 +702,9 @@ async fn launch_model_external(
     model_path: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
+    let locale = current_locale(&state).await;
     let result = launch_model_external_impl(model_path, &state).await
-        .map_err(|e| format!("Failed to launch model externally: {}", e))?;
+        .map_err(|e| i18n::translate(&locale, "error.launch_external_failed", &[("error", &e.to_string())]))?;
     
     Ok(serde_json::json!({
         "success": true,
@@ -347,6 +712,27 @@ async fn launch_model_external(
     }))
 }
 
+#[tauri::command]
+async fn preview_launch_command(
+    model_path: String,
+    overrides: Option<LaunchOverrides>,
+    state: tauri::State<'_, AppState>,
+) -> Result<LaunchCommandPreview, String> {
+    process::preview_launch_command(model_path, overrides, &state).await
+        .map_err(|e| format!("Failed to preview launch command: {}", e))
+}
+
+/// What launching a model with its saved settings would run, to debug arguments or run it by hand.
+/// Local socket launches swap in their socket address when started, it isn't known beforehand
+#[tauri::command]
+async fn get_launch_command(
+    model_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<LaunchCommandPreview, String> {
+    process::preview_launch_command(model_path, None, &state).await
+        .map_err(|e| format!("Failed to get launch command: {}", e))
+}
+
 #[tauri::command]
 async fn delete_model_file(
     model_path: String,
@@ -356,11 +742,12 @@ async fn delete_model_file(
     use std::fs;
     
     // Security checks - scope the config lock
-    let (models_dir, model_file) = {
+    let (models_dir, model_file, read_only) = {
         let config = state.config.lock().await;
         let models_dir = PathBuf::from(&config.models_directory);
         let model_file = PathBuf::from(&model_path);
-        (models_dir, model_file)
+        let read_only = in_extra_directory(&model_file, &config.extra_model_directories);
+        (models_dir, model_file, read_only)
     }; // Config lock is dropped here
     
     // Check if file exists before deletion
@@ -371,6 +758,15 @@ async fn delete_model_file(
         }));
     }
     
+    // Extra model directories are only referenced, their files belong to someone else
+    if read_only {
+        return Ok(serde_json::json!({
+            "success": false,
+            "code": "read_only",
+            "error": "Models in extra model directories are read-only"
+        }));
+    }
+    
     // Ensure the file is within the models directory
     if !model_file.starts_with(&models_dir) {
         return Ok(serde_json::json!({
@@ -387,14 +783,21 @@ async fn delete_model_file(
         }));
     }
     
+    if let Some((process, message)) = model_in_use(&model_path, &state).await {
+        return Ok(serde_json::json!({
+            "success": false,
+            "code": "model_in_use",
+            "process_id": process.id,
+            "model_name": process.model_name,
+            "error": message
+        }));
+    }
+    
     // Delete the file
     match fs::remove_file(&model_path) {
         Ok(_) => {
-            // Remove from model configs - scope the lock
-            {
-                let mut model_configs = state.model_configs.lock().await;
-                model_configs.remove(&model_path);
-            } // Model configs lock is dropped here
+            // Remove from model configs
+            state.library.forget(&model_path).await;
             
             // Save settings
             if let Err(e) = save_settings(&state).await {
@@ -424,91 +827,683 @@ async fn delete_model_file(
     }
 }
 
+// Count generations in progress on the given processes (all running ones when None)
+async fn count_requests_in_flight(state: &AppState, process_id: Option<&str>) -> u64 {
+    let servers = state.processes.active_endpoints(process_id).await;
+    
+    let counts = futures_util::future::join_all(
+        servers.iter().map(|url| capabilities::requests_in_flight(url))
+    ).await;
+    counts.into_iter().flatten().sum()
+}
+
+fn in_flight_warning(count: u64) -> serde_json::Value {
+    serde_json::json!({
+        "success": false,
+        "requests_in_flight": count,
+        "warning": format!("{} request{} in flight", count, if count == 1 { "" } else { "s" })
+    })
+}
+
 #[tauri::command]
 async fn kill_process(
     process_id: String,
+    force: Option<bool>,
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<serde_json::Value, String> {
+    // Don't cut off a response another app is waiting on unless asked to
+    if !force.unwrap_or(false) {
+        let count = count_requests_in_flight(&state, Some(&process_id)).await;
+        if count > 0 {
+            return Ok(in_flight_warning(count));
+        }
+    }
+    
+    let locale = current_locale(&state).await;
     terminate_process(process_id, &state).await
-        .map_err(|e| format!("Failed to kill process: {}", e))
+        .map_err(|e| i18n::translate(&locale, "error.process_kill_failed", &[("error", &e.to_string())]))?;
+    Ok(serde_json::json!({ "success": true }))
 }
 
 #[tauri::command]
-async fn get_process_output(
+async fn get_sleeping_models() -> Result<Vec<idle_shutdown::SleepingModel>, String> {
+    Ok(idle_shutdown::sleeping_models())
+}
+
+#[tauri::command]
+async fn get_server_capabilities(
     process_id: String,
+    refresh: Option<bool>,
     state: tauri::State<'_, AppState>,
-) -> Result<ProcessOutput, String> {
-    get_process_logs(process_id, &state).await
-        .map_err(|e| format!("Failed to get process output: {}", e))
+) -> Result<capabilities::ServerCapabilities, String> {
+    if !refresh.unwrap_or(false) {
+        if let Some(cached) = state.processes.capabilities(&process_id).await {
+            return Ok(cached);
+        }
+    }
+    capabilities::refresh(&process_id, &state).await
+        .map_err(|e| format!("Failed to probe server capabilities: {}", e))
 }
 
 #[tauri::command]
-async fn browse_folder(
-    initial_dir: Option<String>,
-    app: tauri::AppHandle,
-) -> Result<Option<String>, String> {
-    use tauri_plugin_dialog::DialogExt;
-    
-    let dialog = app.dialog();
-    let mut file_dialog = dialog.file();
-    
-    if let Some(initial) = initial_dir {
-        file_dialog = file_dialog.set_directory(initial);
-    }
-    
-    // Use a channel to convert callback to async
-    let (tx, rx) = tokio::sync::oneshot::channel();
-    
-    file_dialog.pick_folder(move |path| {
-        let result = path.map(|p| p.to_string());
-        let _ = tx.send(result);
-    });
-    
-    match rx.await {
-        Ok(result) => Ok(result),
-        Err(_) => Err("Dialog was cancelled or failed".to_string()),
-    }
+async fn create_share_link(
+    process_id: String,
+    expires_in_minutes: Option<u32>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<ShareLink, String> {
+    share::create(&process_id, expires_in_minutes, &state, app_handle).await
+        .map_err(|e| format!("Failed to create share link: {}", e))
 }
 
 #[tauri::command]
-async fn open_url(url: String, app: tauri::AppHandle) -> Result<(), String> {
-    use tauri_plugin_opener::OpenerExt;
-    
-    // Open URL in default browser using opener plugin
-    app.opener()
-        .open_url(url, None::<String>)
-        .map_err(|e| format!("Failed to open URL: {}", e))
+async fn revoke_share_link(share_id: String) -> Result<(), String> {
+    if share::revoke(&share_id) {
+        Ok(())
+    } else {
+        Err("Share link not found or already expired".to_string())
+    }
 }
 
 #[tauri::command]
-async fn search_huggingface(
-    query: String,
-    limit: Option<usize>,
-    sort_by: Option<String>,
-) -> Result<SearchResult, String> {
-    search_models(query, limit.unwrap_or(100), sort_by.unwrap_or_else(|| "relevance".to_string()))
-        .await
-        .map_err(|e| format!("Search failed: {}", e))
+async fn set_share_tunnel_path(
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.share_tunnel_path = path.trim().to_string();
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
 }
 
 #[tauri::command]
-async fn get_model_details(
-    model_id: String,
-) -> Result<ModelDetails, String> {
-    get_huggingface_model_details(model_id)
-        .await
-        .map_err(|e| format!("Failed to get model details: {}", e))
+async fn list_stacks(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<StackDefinition>, String> {
+    let stacks = state.stacks.lock().await;
+    let mut list: Vec<StackDefinition> = stacks.values().cloned().collect();
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(list)
 }
 
 #[tauri::command]
-async fn download_model(
-    model_id: String,
-    _filename: String,
-    files: Vec<String>,
+async fn save_stack(
+    stack: StackDefinition,
     state: tauri::State<'_, AppState>,
-   app_handle: tauri::AppHandle,
-) -> Result<DownloadStartResult, String> {
-    use crate::downloader::{DownloadConfig, start_download};
+) -> Result<(), String> {
+    if stack.name.trim().is_empty() {
+        return Err("Stack name cannot be empty".to_string());
+    }
+    // Reject definitions that can never be launched
+    stacks::resolve_launch_order(&stack)?;
+    
+    {
+        let mut stacks = state.stacks.lock().await;
+        stacks.insert(stack.name.clone(), stack);
+    }
+    
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn delete_stack(
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut stacks = state.stacks.lock().await;
+        stacks.remove(&name);
+    }
+    
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn list_argument_presets(state: tauri::State<'_, AppState>) -> Result<Vec<ArgumentPreset>, String> {
+    Ok(presets::list(&state).await)
+}
+
+#[tauri::command]
+async fn save_argument_preset(
+    preset: ArgumentPreset,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    presets::save(preset, &state).await
+}
+
+#[tauri::command]
+async fn delete_argument_preset(
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    presets::delete(&name, &state).await
+}
+
+#[tauri::command]
+async fn apply_argument_preset(
+    name: String,
+    model_paths: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    presets::apply(&name, &model_paths, &state).await
+}
+
+#[tauri::command]
+async fn get_desktop_folders(state: tauri::State<'_, AppState>) -> Result<Vec<DesktopFolder>, String> {
+    Ok(state.session_state.lock().await.desktop_state.folders.clone())
+}
+
+/// Folder names are shown as labels, so two folders can't share one
+fn validate_folder_name(name: &str, folders: &[DesktopFolder], except_id: Option<&str>) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Folder name cannot be empty".to_string());
+    }
+    if folders.iter().any(|f| Some(f.id.as_str()) != except_id && f.name.eq_ignore_ascii_case(name)) {
+        return Err(format!("A folder named '{}' already exists", name));
+    }
+    Ok(name.to_string())
+}
+
+#[tauri::command]
+async fn create_desktop_folder(
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<DesktopFolder, String> {
+    let folder = {
+        let mut session = state.session_state.lock().await;
+        let folders = &mut session.desktop_state.folders;
+        let folder = DesktopFolder {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: validate_folder_name(&name, folders, None)?,
+            models: Vec::new(),
+        };
+        folders.push(folder.clone());
+        folder
+    };
+    
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(folder)
+}
+
+#[tauri::command]
+async fn rename_desktop_folder(
+    folder_id: String,
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut session = state.session_state.lock().await;
+        let folders = &mut session.desktop_state.folders;
+        let name = validate_folder_name(&name, folders, Some(&folder_id))?;
+        let folder = folders.iter_mut().find(|f| f.id == folder_id).ok_or("Folder not found")?;
+        folder.name = name;
+    }
+    
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Remove a folder, its models go back onto the desktop
+#[tauri::command]
+async fn delete_desktop_folder(
+    folder_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut session = state.session_state.lock().await;
+        session.desktop_state.folders.retain(|f| f.id != folder_id);
+    }
+    
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Move models into a folder, or back onto the desktop when no folder is given
+#[tauri::command]
+async fn assign_models_to_folder(
+    model_paths: Vec<String>,
+    folder_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DesktopFolder>, String> {
+    let folders = {
+        let mut session = state.session_state.lock().await;
+        let folders = &mut session.desktop_state.folders;
+        if let Some(id) = &folder_id {
+            if !folders.iter().any(|f| &f.id == id) {
+                return Err("Folder not found".to_string());
+            }
+        }
+        for folder in folders.iter_mut() {
+            folder.models.retain(|m| !model_paths.contains(m));
+            if Some(&folder.id) == folder_id.as_ref() {
+                folder.models.extend(model_paths.iter().cloned());
+            }
+        }
+        folders.clone()
+    };
+    
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(folders)
+}
+
+#[tauri::command]
+async fn launch_stack(
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<StackStatus, String> {
+    stacks::launch_stack(&name, &state).await
+        .map_err(|e| format!("Failed to launch stack: {}", e))
+}
+
+#[tauri::command]
+async fn stop_stack(
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<StackStatus, String> {
+    stacks::stop_stack(&name, &state).await
+        .map_err(|e| format!("Failed to stop stack: {}", e))
+}
+
+#[tauri::command]
+async fn get_stack_status(
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<StackStatus, String> {
+    stacks::get_stack_status(&name, &state).await
+        .map_err(|e| format!("Failed to get stack status: {}", e))
+}
+
+#[tauri::command]
+async fn get_process_output(
+    process_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ProcessOutput, String> {
+    get_process_logs(process_id, &state).await
+        .map_err(|e| format!("Failed to get process output: {}", e))
+}
+
+#[tauri::command]
+async fn get_process_metrics(
+    process_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ProcessMetrics, String> {
+    state.processes.get(&process_id).await
+        .map(|process| process.metrics)
+        .ok_or_else(|| "Process not found".to_string())
+}
+
+/// CPU, memory and VRAM of one running server, or all of them without a process id
+#[tauri::command]
+async fn get_process_stats(
+    process_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ProcessResourceUsage>, String> {
+    process_stats::sample(&state, process_id.as_deref()).await
+}
+
+#[tauri::command]
+async fn browse_folder(
+    initial_dir: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+    
+    let dialog = app.dialog();
+    let mut file_dialog = dialog.file();
+    
+    if let Some(initial) = initial_dir {
+        file_dialog = file_dialog.set_directory(initial);
+    }
+    
+    // Use a channel to convert callback to async
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    
+    file_dialog.pick_folder(move |path| {
+        let result = path.map(|p| p.to_string());
+        let _ = tx.send(result);
+    });
+    
+    match rx.await {
+        Ok(result) => Ok(result),
+        Err(_) => Err("Dialog was cancelled or failed".to_string()),
+    }
+}
+
+#[tauri::command]
+async fn open_url(url: String, app: tauri::AppHandle) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
+    
+    // Open URL in default browser using opener plugin
+    app.opener()
+        .open_url(url, None::<String>)
+        .map_err(|e| format!("Failed to open URL: {}", e))
+}
+
+#[tauri::command]
+async fn search_huggingface(
+    query: String,
+    limit: Option<usize>,
+    sort_by: Option<String>,
+    filters: Option<HubSearchFilters>,
+) -> Result<SearchResult, String> {
+    search_models(query, limit.unwrap_or(100), sort_by.unwrap_or_else(|| "relevance".to_string()), &filters.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Search failed: {}", e))
+}
+
+#[tauri::command]
+async fn get_model_details(
+    model_id: String,
+) -> Result<ModelDetails, String> {
+    get_huggingface_model_details(model_id)
+        .await
+        .map_err(|e| format!("Failed to get model details: {}", e))
+}
+
+#[tauri::command]
+async fn get_model_readme(
+    model_id: String,
+) -> Result<String, String> {
+    huggingface::get_model_readme(model_id)
+        .await
+        .map_err(|e| format!("Failed to get model card: {}", e))
+}
+
+#[tauri::command]
+async fn peek_remote_gguf(
+    url: String,
+) -> Result<RemoteGgufInfo, String> {
+    huggingface::peek_remote_gguf(&url)
+        .await
+        .map_err(|e| format!("Failed to read remote GGUF header: {}", e))
+}
+
+/// Convert a safetensors-only repo to GGUF on this machine, see `hf_conversion`
+#[tauri::command]
+async fn start_hf_conversion(
+    model_id: String,
+    quantization: String,
+    revision: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<ConversionJob, String> {
+    hf_conversion::start_conversion(&model_id, &quantization, revision, &state, app_handle).await
+}
+
+#[tauri::command]
+async fn list_hf_conversions() -> Result<Vec<ConversionJob>, String> {
+    Ok(hf_conversion::list_jobs())
+}
+
+#[tauri::command]
+async fn cancel_hf_conversion(
+    job_id: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    hf_conversion::cancel_conversion(&job_id, &state, &app_handle).await
+}
+
+#[tauri::command]
+async fn set_conversion_tools(
+    convert_script_path: String,
+    python_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.convert_script_path = convert_script_path.trim().to_string();
+        cfg.python_path = python_path.trim().to_string();
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn request_gguf_conversion(
+    model_id: String,
+    app_handle: tauri::AppHandle,
+) -> Result<GgufConversionStatus, String> {
+    gguf_my_repo::request_conversion(&model_id, app_handle).await
+}
+
+#[tauri::command]
+async fn get_upstream_metadata() -> Result<HashMap<String, UpstreamMetadata>, String> {
+    Ok(hf_metadata::get_metadata().await)
+}
+
+#[tauri::command]
+async fn refresh_upstream_metadata(
+    state: tauri::State<'_, AppState>,
+) -> Result<HashMap<String, UpstreamMetadata>, String> {
+    hf_metadata::refresh(&state, true).await?;
+    Ok(hf_metadata::get_metadata().await)
+}
+
+#[tauri::command]
+async fn set_offline_mode(
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.offline_mode = enabled;
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn set_accelerated_transfer(
+    settings: AcceleratedTransferSettings,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.accelerated_transfer = settings;
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn set_port_range(
+    port_range: PortRange,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if port_range.start == 0 || port_range.start > port_range.end {
+        return Err(format!("Invalid port range {}-{}", port_range.start, port_range.end));
+    }
+    state.config.lock().await.port_range = port_range;
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn set_manage_firewall_rules(
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.manage_firewall_rules = enabled;
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn list_managed_firewall_rules(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ManagedFirewallRule>, String> {
+    Ok(firewall::list_rules(&state).await)
+}
+
+#[tauri::command]
+async fn remove_managed_firewall_rule(
+    name: String,
+) -> Result<(), String> {
+    firewall::remove_rule(&name)
+        .await
+        .map_err(|e| format!("Failed to remove firewall rule: {}", e))
+}
+
+#[tauri::command]
+async fn set_download_speed_limit(
+    bytes_per_second: u64,
+    download_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state.downloads.manager.lock().await
+        .set_speed_limit(download_id.as_deref(), bytes_per_second)
+        .map_err(|e| format!("Failed to set download speed limit: {}", e))?;
+    // Per-download limits end with the download, only the global one is a setting
+    if download_id.is_none() {
+        state.config.lock().await.download_speed_limit = bytes_per_second;
+        save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn start_hash_job(
+    kind: hashing::HashJobKind,
+    paths: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let models_directory = state.config.lock().await.models_directory.clone();
+    hashing::start_job(kind, paths, models_directory, app_handle).await
+}
+
+#[tauri::command]
+async fn cancel_hash_job(job_id: String) -> Result<(), String> {
+    hashing::cancel_job(&job_id)
+}
+
+#[tauri::command]
+async fn get_mirror_health(
+    refresh: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<MirrorHealth>, String> {
+    if refresh.unwrap_or(false) {
+        return Ok(mirrors::check_all(&state).await);
+    }
+    Ok(mirrors::get_health().await)
+}
+
+#[tauri::command]
+async fn set_download_mirrors(
+    mirrors: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<MirrorHealth>, String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.download_mirrors = mirrors::configured_endpoints(&mirrors).into_iter().skip(1).collect();
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(mirrors::check_all(&state).await)
+}
+
+#[tauri::command]
+async fn preview_model_file(
+    path: String,
+    section: PreviewSection,
+    bytes: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModelFilePreview, String> {
+    let (models_dir, extra_directories) = {
+        let config = state.config.lock().await;
+        (PathBuf::from(&config.models_directory), config.extra_model_directories.clone())
+    };
+    
+    // Only files inside the models directory (or an extra one) can be inspected
+    let models_dir = models_dir.canonicalize()
+        .map_err(|e| format!("Failed to resolve models directory: {}", e))?;
+    let file_path = PathBuf::from(&path).canonicalize()
+        .map_err(|e| format!("Failed to resolve file: {}", e))?;
+    let in_library = file_path.starts_with(&models_dir)
+        || extra_directories.iter().filter_map(|d| Path::new(d).canonicalize().ok()).any(|d| file_path.starts_with(d));
+    if !in_library || !file_path.is_file() {
+        return Err("Only files inside the models directory can be previewed".to_string());
+    }
+    
+    tokio::task::spawn_blocking(move || gguf::preview_file(&file_path, section, bytes))
+        .await
+        .map_err(|e| format!("Preview task failed: {}", e))?
+        .map_err(|e| format!("Failed to read model file: {}", e))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn download_model(
+    model_id: String,
+    _filename: String,
+    mut files: Vec<String>,
+    force: Option<bool>,
+    include_mmproj: Option<bool>,
+    include_patterns: Option<Vec<String>>,
+    exclude_patterns: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+   app_handle: tauri::AppHandle,
+) -> Result<DownloadStartResult, DownloadError> {
+    // Globs pick files from the repo tree on top of the ones listed explicitly
+    let include_patterns = include_patterns.unwrap_or_default();
+    let exclude_patterns = exclude_patterns.unwrap_or_default();
+    if !include_patterns.is_empty() || !exclude_patterns.is_empty() {
+        let matched = huggingface::resolve_file_patterns(&model_id, None, &include_patterns, &exclude_patterns)
+            .await
+            .map_err(|e| format!("Failed to resolve file patterns: {}", e))?;
+        if matched.is_empty() && files.is_empty() {
+            let locale = current_locale(&state).await;
+            return Err(DownloadError {
+                code: "no_match".to_string(),
+                message: i18n::translate(&locale, "error.no_files_matched", &[("model", &model_id)]),
+                url: None,
+            });
+        }
+        for (file, _) in matched {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    // Vision models need their projector to accept images, fetch it with the quant
+    if include_mmproj.unwrap_or(false) && !files.iter().any(|f| huggingface::is_mmproj_file(f)) {
+        if let Some(mmproj) = huggingface::preferred_mmproj(&model_id).await {
+            files.push(mmproj);
+        }
+    }
+    start_hub_download(model_id, files, None, force.unwrap_or(false), &state, app_handle).await
+}
+
+/// Download files of a Hub repo into models_directory/author/model_name/, at a
+/// pinned commit when a revision is given. Unless forced, refuses when the files
+/// won't fit on the models drive.
+async fn start_hub_download(
+    model_id: String,
+    files: Vec<String>,
+    revision: Option<String>,
+    force: bool,
+    state: &AppState,
+    app_handle: tauri::AppHandle,
+) -> Result<DownloadStartResult, DownloadError> {
+    use crate::downloader::{DownloadConfig, start_download};
+    
+    let locale = current_locale(state).await;
+    
+    // Gated repos answer 401/403 until the terms are accepted with the token's account
+    if let Some(first_file) = files.first() {
+        if let Some(repo_url) = huggingface::check_gated_access(&model_id, first_file).await {
+            let message_key = if huggingface::access_token().is_some() { "error.model_gated" } else { "error.model_gated_no_token" };
+            return Err(DownloadError {
+                code: "gated".to_string(),
+                message: i18n::translate(&locale, message_key, &[("model", &model_id)]),
+                url: Some(repo_url),
+            });
+        }
+    }
     
     // Get models directory from config
     let models_directory = {
@@ -521,9 +1516,31 @@ async fn download_model(
     let model_name = model_id.split('/').nth(1).unwrap_or(&model_id);
     let destination_folder = format!("{}/{}/{}", models_directory, author, model_name);
     
+    // Re-downloading over a model that is being served would fail when the file is moved into place
+    for file in &files {
+        let target = Path::new(&destination_folder).join(file.rsplit('/').next().unwrap_or(file));
+        if let Some((_, message)) = model_in_use(&target.to_string_lossy(), state).await {
+            return Err(DownloadError { code: "model_in_use".to_string(), message, url: None });
+        }
+    }
+    
+    if !force {
+        if let Some((needed, available)) = disk_space_shortfall(&model_id, &files, &destination_folder).await {
+            return Err(DownloadError {
+                code: "insufficient_space".to_string(),
+                message: i18n::translate(&locale, "error.insufficient_disk_space", &[
+                    ("model", &model_id),
+                    ("needed", &format!("{:.1}", needed as f64 / 1024.0 / 1024.0 / 1024.0)),
+                    ("available", &format!("{:.1}", available as f64 / 1024.0 / 1024.0 / 1024.0)),
+                ]),
+                url: None,
+            });
+        }
+    }
+    
     // Create download configuration
     let config = DownloadConfig {
-        base_url: format!("https://huggingface.co/{}/resolve/main", model_id),
+        base_url: format!("https://huggingface.co/{}/resolve/{}", model_id, revision.as_deref().unwrap_or("main")),
         destination_folder,
         auto_extract: false, // GGUF files don't need extraction
         create_subfolder: None, // We already created the subfolder structure
@@ -534,10 +1551,67 @@ async fn download_model(
             headers
         }),
     };
-    
-    start_download(config, &state, app_handle)
+    
+    start_download(config, state, app_handle)
+        .await
+        .map_err(|e| i18n::translate(&locale, "error.download_start_failed", &[("error", &e.to_string())]).into())
+}
+
+// Headroom left on the drive so a finished download doesn't leave it completely full
+const DISK_SPACE_RESERVE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// (needed, available) bytes when the files don't fit, None when they do or sizes are unknown
+async fn disk_space_shortfall(model_id: &str, files: &[String], destination_folder: &str) -> Option<(u64, u64)> {
+    let details = match huggingface::cached_model_details(model_id).await {
+        Ok(details) => details,
+        Err(e) => {
+            eprintln!("Skipping disk space check for {}, file sizes unavailable: {}", model_id, e);
+            return None;
+        }
+    };
+    let sizes: Vec<(String, u64)> = files
+        .iter()
+        .map(|file| {
+            // Split models list their total size on the first shard
+            let size = details.gguf_files.get(file)
+                .or_else(|| details.gguf_files.values().find(|info| info.parts.first() == Some(file)))
+                .or_else(|| details.gguf_files.values().find(|info| info.filename.rsplit('/').next() == file.rsplit('/').next()))
+                .map(|info| info.size)
+                .unwrap_or(0);
+            (file.clone(), size)
+        })
+        .collect();
+
+    let destination = std::path::PathBuf::from(destination_folder);
+    tokio::task::spawn_blocking(move || {
+        let needed = downloader::remaining_bytes(&sizes, &destination);
+        let available = downloader::free_space_for(&destination)?;
+        (needed > 0 && needed + DISK_SPACE_RESERVE_BYTES > available).then_some((needed, available))
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+#[tauri::command]
+async fn get_starter_catalog() -> Result<Vec<StarterModel>, String> {
+    tokio::task::spawn_blocking(starter_catalog::resolve_catalog)
+        .await
+        .map_err(|e| format!("Failed to resolve starter catalog: {}", e))
+}
+
+#[tauri::command]
+async fn download_starter_model(
+    entry_id: String,
+    force: Option<bool>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<DownloadStartResult, DownloadError> {
+    let plan = tokio::task::spawn_blocking(move || starter_catalog::download_plan(&entry_id))
         .await
-        .map_err(|e| format!("Failed to start download: {}", e))
+        .map_err(|e| format!("Failed to resolve starter model: {}", e))?;
+    let (model_id, files) = plan?;
+    start_hub_download(model_id, files, None, force.unwrap_or(false), &state, app_handle).await
 }
 
 #[tauri::command]
@@ -545,9 +1619,7 @@ async fn get_download_status(
     download_id: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<DownloadStatus, String> {
-    let download_manager = state.download_manager.lock().await;
-    download_manager.get_status(&download_id)
-        .map(|status| status.clone())
+    state.downloads.status(&download_id).await
         .ok_or_else(|| "Download not found".to_string())
 }
 
@@ -555,8 +1627,7 @@ async fn get_download_status(
 async fn get_all_downloads(
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<DownloadStatus>, String> {
-    let download_manager = state.download_manager.lock().await;
-    Ok(download_manager.downloads.values().cloned().collect())
+    Ok(state.downloads.active().await)
 }
 
 #[tauri::command]
@@ -564,147 +1635,542 @@ async fn cancel_download(
     download_id: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<DownloadStatus>, String> {
-    let mut download_manager = state.download_manager.lock().await;
-    download_manager.cancel_download(&download_id).map_err(|e| format!("Failed to cancel download: {}", e))?;
-    Ok(download_manager.downloads.values().cloned().collect())
+    state.downloads.cancel(&download_id).await
+        .map_err(|e| format!("Failed to cancel download: {}", e))
+}
+
+#[tauri::command]
+async fn pause_download(
+    download_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DownloadStatus>, String> {
+    state.downloads.pause(&download_id).await
+        .map_err(|e| format!("Failed to pause download: {}", e))
+}
+
+#[tauri::command]
+async fn resume_download(
+    download_id: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<DownloadStatus>, String> {
+    // Downloads interrupted by a restart have no task left to unpause
+    let restarted = downloader::restart_interrupted_download(&download_id, &state, app_handle).await
+        .map_err(|e| format!("Failed to resume download: {}", e))?;
+    if restarted {
+        return Ok(state.downloads.active().await);
+    }
+    state.downloads.resume(&download_id).await
+        .map_err(|e| format!("Failed to resume download: {}", e))
+}
+
+#[tauri::command]
+async fn inspect_model_format(
+    path: String,
+) -> Result<conversion::ModelFormatReport, String> {
+    tokio::task::spawn_blocking(move || conversion::inspect_format(std::path::Path::new(&path)))
+        .await
+        .map_err(|e| format!("Failed to inspect model: {}", e))?
+}
+
+#[tauri::command]
+async fn merge_split_model(
+    path: String,
+    output: Option<String>,
+    delete_shards: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    conversion::merge_split_gguf(std::path::Path::new(&path), output.map(PathBuf::from), delete_shards.unwrap_or(false), &state)
+        .await
+        .map_err(|e| format!("Failed to merge model: {}", e))
+}
+
+#[tauri::command]
+async fn get_app_status(
+    state: tauri::State<'_, AppState>,
+) -> Result<app_status::AppStatus, String> {
+    Ok(app_status::collect(&state).await)
+}
+
+#[tauri::command]
+async fn get_all_downloads_and_history(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DownloadStatus>, String> {
+    Ok(state.downloads.all_with_history().await)
+}
+
+#[tauri::command]
+async fn clear_download_history(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DownloadStatus>, String> {
+    Ok(state.downloads.clear_history().await)
+}
+
+#[tauri::command]
+async fn delete_model(
+    model_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    use std::fs;
+    
+    // Security checks
+    let config = state.config.lock().await;
+    let models_dir = PathBuf::from(&config.models_directory);
+    let model_file = PathBuf::from(&model_path);
+    
+    if in_extra_directory(&model_file, &config.extra_model_directories) {
+        return Err("Models in extra model directories are read-only".to_string());
+    }
+    
+    // Ensure the file is within the models directory
+    if !model_file.starts_with(&models_dir) {
+        return Err("Cannot delete files outside of models directory".to_string());
+    }
+    
+    // Ensure it's a .gguf file
+    if !model_path.to_lowercase().ends_with(".gguf") {
+        return Err("Only .gguf files can be deleted".to_string());
+    }
+    drop(config);
+    
+    if let Some((_, message)) = model_in_use(&model_path, &state).await {
+        return Err(message);
+    }
+    
+    // Delete the file
+    fs::remove_file(&model_path)
+        .map_err(|e| format!("Failed to delete file: {}", e))?;
+    
+    // Remove from model configs
+    state.library.forget(&model_path).await;
+    
+    // Save settings
+    save_settings(&state).await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+async fn delete_models(
+    paths: Vec<String>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BatchResult, String> {
+    batch::delete_models(paths, &state, &app_handle).await
+}
+
+#[tauri::command]
+async fn move_models(
+    paths: Vec<String>,
+    destination: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BatchResult, String> {
+    batch::move_models(paths, destination, &state, &app_handle).await
+}
+
+#[tauri::command]
+async fn import_model_files(
+    paths: Vec<String>,
+    mode: model_import::ImportMode,
+    on_conflict: Option<model_import::ImportConflict>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<model_import::ImportResult, String> {
+    model_import::import_model_files(paths, mode, on_conflict.unwrap_or_default(), &state, &app_handle).await
+}
+
+#[tauri::command]
+async fn export_manifest(
+    path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<manifest::SetupManifest, String> {
+    match path {
+        Some(path) => manifest::export_to_file(&path, &state).await,
+        None => manifest::export(&state).await,
+    }
+    .map_err(|e| format!("Failed to export manifest: {}", e))
+}
+
+#[tauri::command]
+async fn apply_manifest(
+    path: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<manifest::ManifestApplyResult, String> {
+    manifest::apply(&path, &state, &app_handle).await
+        .map_err(|e| format!("Failed to apply manifest: {}", e))
+}
+
+/// Re-running an LM Studio import skips what is already there unless told otherwise
+#[tauri::command]
+async fn import_lm_studio_models(
+    directory: Option<String>,
+    mode: model_import::ImportMode,
+    on_conflict: Option<model_import::ImportConflict>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<model_import::ImportResult, String> {
+    model_import::import_lm_studio(directory, mode, on_conflict.unwrap_or(model_import::ImportConflict::Skip), &state, &app_handle).await
+}
+
+#[tauri::command]
+async fn apply_preset_bulk(
+    paths: Vec<String>,
+    preset: LaunchOverrides,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BatchResult, String> {
+    batch::apply_preset_bulk(paths, preset, &state, &app_handle).await
+}
+
+#[tauri::command]
+async fn get_session_state(
+    state: tauri::State<'_, AppState>,
+) -> Result<SessionState, String> {
+    let session = state.session_state.lock().await;
+    Ok(session.clone())
+}
+
+#[tauri::command]
+async fn save_window_state(
+    window_id: String,
+    window_state: WindowState,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut session = state.session_state.lock().await;
+    session.windows.insert(window_id, window_state);
+    Ok(())
+}
+
+#[tauri::command]
+async fn save_chat_state(
+    chat_id: String,
+    chat_state: ChatState,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let chat_state = {
+        let mut session = state.session_state.lock().await;
+        // Keep a title generated earlier if the frontend doesn't know about it yet
+        let title = session.chats.get(&chat_id).and_then(|c| c.title.clone());
+        let mut chat_state = chat_state;
+        if chat_state.title.is_none() {
+            chat_state.title = title;
+        }
+        session.chats.insert(chat_id.clone(), chat_state.clone());
+        chat_state
+    };
+    
+    if let Err(e) = chat_store::store_chat(chat_id.clone(), chat_state.clone()).await {
+        eprintln!("{}", e);
+    }
+    
+    if chat::needs_title(&chat_state) {
+        chat::spawn_title_generation(chat_id, chat_state, state.inner().clone(), app_handle);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn remove_chat_state(
+    chat_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut session = state.session_state.lock().await;
+        session.chats.remove(&chat_id);
+    }
+    chat_store::delete_chat(chat_id).await
+}
+
+#[tauri::command]
+async fn search_chats(
+    query: String,
+    filters: Option<chat_store::ChatSearchFilters>,
+) -> Result<Vec<chat_store::ChatSearchHit>, String> {
+    chat_store::search_chats(query, filters.unwrap_or_default()).await
+}
+
+/// Warning from the startup check of the active llama.cpp version, once the UI has loaded
+#[tauri::command]
+async fn get_startup_version_warning() -> Result<Option<VersionChangeWarning>, String> {
+    Ok(take_startup_version_warning())
+}
+
+#[tauri::command]
+async fn get_recent_models(
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<RecentModel>, String> {
+    Ok(recent_models(&state, limit.unwrap_or(10)).await)
+}
+
+#[tauri::command]
+async fn pin_model(
+    path: String,
+    pinned: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<RecentModel>, String> {
+    set_model_pinned(&state, &path, pinned.unwrap_or(true)).await?;
+    Ok(recent_models(&state, 10).await)
+}
+
+#[tauri::command]
+async fn open_chat_for_model(
+    model_path: String,
+    initial_prompt: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<chat::OpenChatRequest, String> {
+    chat::open_chat_for_model(model_path, initial_prompt, &state, &app_handle).await
+        .map_err(|e| format!("Failed to open chat: {}", e))
+}
+
+#[tauri::command]
+async fn record_chat_request(
+    sample: chat_analytics::ChatRequestSample,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    chat_analytics::record_request(sample, &state).await
+        .map_err(|e| format!("Failed to record chat request: {}", e))
+}
+
+#[tauri::command]
+async fn stage_audio_input(path: String) -> Result<audio_input::StagedAudio, String> {
+    audio_input::stage(&path).await
+        .map_err(|e| format!("Failed to stage audio: {}", e))
+}
+
+#[tauri::command]
+async fn log_inference_request(
+    sample: request_log::InferenceRequestSample,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    request_log::record(sample, &state).await
+        .map_err(|e| format!("Failed to log request: {}", e))
+}
+
+#[tauri::command]
+async fn get_request_log(
+    limit: Option<usize>,
+    model: Option<String>,
+) -> Result<Vec<request_log::RequestLogEntry>, String> {
+    request_log::list(limit.unwrap_or(100), model).await
+        .map_err(|e| format!("Failed to read request log: {}", e))
+}
+
+#[tauri::command]
+async fn replay_request(
+    log_id: String,
+    target_model: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<request_log::RequestLogEntry, String> {
+    request_log::replay(&log_id, &target_model, &state).await
+}
+
+#[tauri::command]
+async fn set_request_logging(
+    enabled: bool,
+    redact_patterns: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if let Some(patterns) = &redact_patterns {
+        request_log::validate_patterns(patterns)?;
+    }
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.request_log.enabled = enabled;
+        if let Some(patterns) = redact_patterns {
+            cfg.request_log.redact_patterns = patterns;
+        }
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn get_chat_analytics(
+    model: Option<String>,
+    period: Option<String>,
+) -> Result<chat_analytics::ChatAnalytics, String> {
+    chat_analytics::get_analytics(model, period).await
+}
+
+#[tauri::command]
+async fn set_encryption_enabled(
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    if enabled {
+        // Fail before touching any data when the keyring can't hold the key
+        tokio::task::spawn_blocking(encryption::ensure_key)
+            .await
+            .map_err(|e| e.to_string())??;
+    }
+    
+    // Flip the switch first so chats saved during the conversion come out the same way
+    encryption::set_enabled(enabled);
+    let converted = match chat_store::convert_all(enabled).await {
+        Ok(converted) => converted,
+        Err(e) => {
+            encryption::set_enabled(!enabled);
+            return Err(e);
+        }
+    };
+    // The request log holds prompts as well
+    let converted = match request_log::convert_all(enabled).await {
+        Ok(logged) => converted + logged,
+        Err(e) => {
+            encryption::set_enabled(!enabled);
+            return Err(e);
+        }
+    };
+    
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.encrypt_at_rest = enabled;
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(converted)
+}
+
+#[tauri::command]
+async fn restart_application(
+    force: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    println!("Application restart requested via command");
+    
+    if !force.unwrap_or(false) {
+        let count = count_requests_in_flight(&state, None).await;
+        if count > 0 {
+            return Ok(in_flight_warning(count));
+        }
+    }
+    
+    // Perform cleanup but don't exit, this waits until the processes are gone
+    let cleaned = state.cleanup_all_processes().await;
+    
+    println!("Application restart cleanup completed ({} processes) - frontend will reload", cleaned);
+    
+    // Don't exit - let the frontend handle the reload
+    Ok(serde_json::json!({ "success": true }))
 }
 
 #[tauri::command]
-async fn pause_download(
-    download_id: String,
+async fn graceful_exit(
+    force: Option<bool>,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<DownloadStatus>, String> {
-    let mut download_manager = state.download_manager.lock().await;
-    download_manager.pause_download(&download_id).map_err(|e| format!("Failed to pause download: {}", e))?;
-    Ok(download_manager.downloads.values().cloned().collect())
+    app: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    println!("Graceful exit requested via command");
+    
+    if !force.unwrap_or(false) {
+        let count = count_requests_in_flight(&state, None).await;
+        if count > 0 {
+            return Ok(in_flight_warning(count));
+        }
+    }
+    
+    // Perform cleanup, this waits until the processes are gone
+    let cleaned = state.cleanup_all_processes().await;
+    
+    println!("Graceful exit cleanup completed ({} processes)", cleaned);
+    
+    // Exit the application
+    app.exit(0);
+    
+    Ok(serde_json::json!({ "success": true }))
 }
 
 #[tauri::command]
-async fn resume_download(
-    download_id: String,
+async fn set_locale(
+    locale: String,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<DownloadStatus>, String> {
-    let mut download_manager = state.download_manager.lock().await;
-    download_manager.resume_download(&download_id).map_err(|e| format!("Failed to resume download: {}", e))?;
-    Ok(download_manager.downloads.values().cloned().collect())
+) -> Result<String, String> {
+    let resolved = i18n::resolve_locale(&locale).to_string();
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.locale = resolved.clone();
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(resolved)
 }
 
 #[tauri::command]
-async fn get_all_downloads_and_history(
+async fn set_shutdown_settings(
+    timeout_ms: u64,
+    skip_wait: bool,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<DownloadStatus>, String> {
-    let download_manager = state.download_manager.lock().await;
-    let mut all_downloads = download_manager.downloads.values().cloned().collect::<Vec<_>>();
-    all_downloads.extend(download_manager.download_history.clone());
-    Ok(all_downloads)
+) -> Result<(), String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.shutdown_timeout_ms = timeout_ms;
+        cfg.skip_shutdown_wait = skip_wait;
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
 }
 
 #[tauri::command]
-async fn clear_download_history(
+async fn get_remote_host_settings(
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<DownloadStatus>, String> {
-    let mut download_manager = state.download_manager.lock().await;
-    download_manager.clear_download_history();
-    let mut all_downloads = download_manager.downloads.values().cloned().collect::<Vec<_>>();
-    all_downloads.extend(download_manager.download_history.clone());
-    Ok(all_downloads)
+) -> Result<RemoteHostConfig, String> {
+    let config = state.config.lock().await;
+    Ok(config.remote_host.clone())
 }
 
 #[tauri::command]
-async fn delete_model(
-    model_path: String,
+async fn set_remote_host_settings(
+    settings: RemoteHostConfig,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
-    use std::fs;
-    
-    // Security checks
-    let config = state.config.lock().await;
-    let models_dir = PathBuf::from(&config.models_directory);
-    let model_file = PathBuf::from(&model_path);
-    
-    // Ensure the file is within the models directory
-    if !model_file.starts_with(&models_dir) {
-        return Err("Cannot delete files outside of models directory".to_string());
+    if settings.enabled && settings.host.trim().is_empty() {
+        return Err("A host is required to enable remote execution".to_string());
     }
-    
-    // Ensure it's a .gguf file
-    if !model_path.to_lowercase().ends_with(".gguf") {
-        return Err("Only .gguf files can be deleted".to_string());
-    }
-    
-    // Delete the file
-    fs::remove_file(&model_path)
-        .map_err(|e| format!("Failed to delete file: {}", e))?;
-    
-    // Remove from model configs
     {
-        let mut model_configs = state.model_configs.lock().await;
-        model_configs.remove(&model_path);
+        let mut cfg = state.config.lock().await;
+        cfg.remote_host = settings;
     }
-    
-    // Save settings
-    save_settings(&state).await
-        .map_err(|e| format!("Failed to save settings: {}", e))?;
-    
-    Ok(())
-}
-
-#[tauri::command]
-async fn get_session_state(
-    state: tauri::State<'_, AppState>,
-) -> Result<SessionState, String> {
-    let session = state.session_state.lock().await;
-    Ok(session.clone())
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
 }
 
 #[tauri::command]
-async fn save_window_state(
-    window_id: String,
-    window_state: WindowState,
+async fn list_remote_models(
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    let mut session = state.session_state.lock().await;
-    session.windows.insert(window_id, window_state);
-    Ok(())
+) -> Result<Vec<String>, String> {
+    let remote = state.config.lock().await.remote_host.clone();
+    remote::list_remote_models(&remote).await
+        .map_err(|e| format!("Failed to list remote models: {}", e))
 }
 
 #[tauri::command]
-async fn restart_application(
+async fn get_gpu_power_settings(
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    println!("Application restart requested via command");
-    
-    // Perform cleanup but don't exit
-    state.cleanup_all_processes().await;
-    
-    // Give time for cleanup to complete
-    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-    
-    println!("Application restart cleanup completed - frontend will reload");
-    
-    // Don't exit - let the frontend handle the reload
-    Ok(())
+) -> Result<GpuPowerSettings, String> {
+    let config = state.config.lock().await;
+    Ok(config.gpu_power.clone())
 }
 
 #[tauri::command]
-async fn graceful_exit(
+async fn set_gpu_power_settings(
+    settings: GpuPowerSettings,
     state: tauri::State<'_, AppState>,
-    app: tauri::AppHandle,
 ) -> Result<(), String> {
-    println!("Graceful exit requested via command");
-    
-    // Perform cleanup
-    state.cleanup_all_processes().await;
-    
-    // Give time for cleanup to complete
-    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-    
-    println!("Graceful exit cleanup completed");
-    
-    // Exit the application
-    app.exit(0);
-    
-    Ok(())
+    for name in [&settings.generation_profile, &settings.idle_profile].into_iter().flatten() {
+        if !settings.profiles.iter().any(|p| &p.name == name) {
+            return Err(format!("Unknown GPU power profile: {}", name));
+        }
+    }
+    let disabled = !settings.enabled;
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.gpu_power = settings;
+    }
+    if disabled {
+        let _ = tokio::task::spawn_blocking(gpu_power::restore_saved_state).await;
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
 }
 
 #[tauri::command]
@@ -736,6 +2202,74 @@ async fn check_file_exists(
     Ok(file_path.exists())
 }
 
+#[tauri::command]
+async fn save_terminal_state(
+    terminal_id: String,
+    terminal_state: TerminalState,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut session = state.session_state.lock().await;
+    let mut terminal_state = terminal_state;
+    // Input is recorded as it is sent, a snapshot without any keeps what was recorded
+    if terminal_state.history.is_empty() {
+        if let Some(existing) = session.terminals.get(&terminal_id) {
+            terminal_state.history = existing.history.clone();
+        }
+    }
+    terminal_state.trim();
+    session.terminals.insert(terminal_id, terminal_state);
+    Ok(())
+}
+
+#[tauri::command]
+async fn record_terminal_input(
+    terminal_id: String,
+    input: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut session = state.session_state.lock().await;
+    let terminal = session.terminals.get_mut(&terminal_id).ok_or("Terminal not found")?;
+    terminal.push_history(&input);
+    Ok(())
+}
+
+/// Replace the input history, for editing or clearing it
+#[tauri::command]
+async fn set_terminal_history(
+    terminal_id: String,
+    history: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut session = state.session_state.lock().await;
+    let terminal = session.terminals.get_mut(&terminal_id).ok_or("Terminal not found")?;
+    terminal.history = history;
+    terminal.trim();
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_terminal_history(
+    terminal_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<TerminalHistory, String> {
+    let session = state.session_state.lock().await;
+    let terminal = session.terminals.get(&terminal_id).ok_or("Terminal not found")?;
+    Ok(TerminalHistory {
+        output: terminal.output.clone(),
+        history: terminal.history.clone(),
+    })
+}
+
+#[tauri::command]
+async fn remove_terminal_state(
+    terminal_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut session = state.session_state.lock().await;
+    session.terminals.remove(&terminal_id);
+    Ok(())
+}
+
 #[tauri::command]
 async fn remove_window_state(
     window_id: String,
@@ -766,9 +2300,10 @@ async fn download_from_url(
         custom_headers: None,
     };
     
+    let locale = current_locale(&state).await;
     start_download(config, &state, app_handle)
         .await
-        .map_err(|e| format!("Failed to start download: {}", e))
+        .map_err(|e| i18n::translate(&locale, "error.download_start_failed", &[("error", &e.to_string())]))
 }
 
 #[tauri::command]
@@ -785,6 +2320,30 @@ async fn get_llamacpp_commit_info(tag_name: String) -> Result<llamacpp_manager::
         .map_err(|e| format!("Failed to fetch commit info: {}", e))
 }
 
+/// OS and architecture tokens of this machine in llama.cpp asset names
+#[tauri::command]
+async fn get_llamacpp_platform() -> Result<llamacpp_manager::HostPlatform, String> {
+    Ok(llamacpp_manager::host_platform())
+}
+
+/// Commits between two releases. Without `from_tag` the active build is compared, which needs
+/// it to be installed under its release tag
+#[tauri::command]
+async fn get_release_changes(
+    from_tag: Option<String>,
+    to_tag: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<llamacpp_manager::ReleaseChanges, String> {
+    let from_tag = match from_tag {
+        Some(tag) => tag,
+        None => state.versions.active().await.version
+            .ok_or("No active llama.cpp version to compare with")?,
+    };
+    llamacpp_manager::fetch_release_changes(&from_tag, &to_tag)
+        .await
+        .map_err(|e| format!("Failed to fetch release changes: {}", e))
+}
+
 #[tauri::command]
 async fn download_llamacpp_asset(
     asset: LlamaCppAsset,
@@ -873,15 +2432,8 @@ async fn list_llamacpp_versions(state: tauri::State<'_, AppState>) -> Result<Vec
     use std::fs;
     use std::time::SystemTime;
 
-    let (base_exec, active_path, active_version) = {
-        let cfg = state.config.lock().await;
-        (
-            cfg.executable_folder.clone(),
-            cfg.active_executable_folder.clone(),
-            cfg.active_executable_version.clone(),
-        )
-    };
-    let versions_dir = std::path::Path::new(&base_exec).join("versions");
+    let versions_dir = state.versions.versions_dir().await;
+    let managers::ActiveVersion { folder: active_path, version: active_version } = state.versions.active().await;
     let mut out = Vec::new();
     if versions_dir.exists() {
         if let Ok(read_dir) = fs::read_dir(&versions_dir) {
@@ -924,15 +2476,7 @@ async fn list_llamacpp_versions(state: tauri::State<'_, AppState>) -> Result<Vec
     if out.len() == 1 && !has_active {
         if let Some(only) = out.get(0) {
             // Update config with this single version as active
-            {
-                let mut cfg = state.config.lock().await;
-                cfg.active_executable_folder = Some(only.path.clone());
-                cfg.active_executable_version = Some(std::path::Path::new(&only.path)
-                    .file_name()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("")
-                    .to_string());
-            }
+            state.versions.set_active(&only.path).await;
             // Best-effort save; if it fails, we still return the list
             if let Err(e) = save_settings(&state).await {
                 eprintln!("Failed to save settings after auto-activating version: {}", e);
@@ -946,17 +2490,179 @@ async fn list_llamacpp_versions(state: tauri::State<'_, AppState>) -> Result<Vec
     Ok(out)
 }
 
+/// Outcome of switching llama.cpp builds. A regression found by the optional benchmark
+/// leaves the previous build active until the switch is confirmed.
+#[derive(serde::Serialize, Debug)]
+struct VersionActivation {
+    activated: bool,
+    regression_check: Option<benchmark::RegressionReport>,
+    cuda_runtime: Option<llamacpp_manager::CudaRuntimeStatus>,
+}
+
+#[tauri::command]
+async fn set_active_llamacpp_version(
+    path: String,
+    force: Option<bool>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<VersionActivation, String> {
+    use tauri::Emitter;
+    
+    let previous_path = state.versions.active().await.folder;
+    let (bench_enabled, reference_model) = {
+        let cfg = state.config.lock().await;
+        (cfg.benchmark_on_version_change, cfg.benchmark_reference_model.clone())
+    };
+    
+    // Optionally benchmark the new build against the active one before switching to it
+    let mut regression_check = None;
+    if let (true, Some(model_path), false) = (bench_enabled, reference_model, force.unwrap_or(false)) {
+        if previous_path.as_deref() != Some(path.as_str()) {
+            let baseline = previous_path.map(PathBuf::from);
+            match benchmark::check_regression(&model_path, Path::new(&path), baseline.as_deref()).await {
+                Ok(report) => {
+                    println!("Version benchmark: {}", report.message);
+                    let _ = app_handle.emit("benchmark-regression-check", &report);
+                    regression_check = Some(report);
+                }
+                Err(e) => eprintln!("Version benchmark failed: {}", e),
+            }
+        }
+    }
+    if regression_check.as_ref().is_some_and(|report| report.regression) {
+        return Ok(VersionActivation { activated: false, regression_check, cuda_runtime: None });
+    }
+    
+    // Save both path and derived version name
+    state.versions.set_active(&path).await;
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    // A CUDA build without its runtime DLLs can't start a server, report them with the activation
+    let folder = PathBuf::from(&path);
+    let tag = folder.file_name().map(|name| name.to_string_lossy().to_string());
+    let cuda_runtime = llamacpp_manager::check_cuda_runtime(&folder, tag.as_deref()).await;
+    Ok(VersionActivation { activated: true, regression_check, cuda_runtime })
+}
+
+/// Download the matching cudart archive and extract it into the version folder
+#[tauri::command]
+async fn install_cuda_runtime(
+    path: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<DownloadStartResult, String> {
+    use crate::downloader::{DownloadConfig, start_download};
+
+    let folder = PathBuf::from(&path);
+    let tag = folder.file_name().map(|name| name.to_string_lossy().to_string());
+    let status = llamacpp_manager::check_cuda_runtime(&folder, tag.as_deref())
+        .await
+        .ok_or("The CUDA runtime is already available for this version")?;
+    let asset = status.asset
+        .ok_or_else(|| format!("No CUDA {} runtime archive found for this release", status.cuda_version.unwrap_or_default()))?;
+
+    let config = DownloadConfig {
+        base_url: asset.download_url,
+        destination_folder: path,
+        auto_extract: true,
+        create_subfolder: None,
+        files: Vec::new(),
+        custom_headers: Some({
+            let mut headers = std::collections::HashMap::new();
+            headers.insert("User-Agent".to_string(), "Llama-OS-Tauri/1.0".to_string());
+            headers
+        }),
+    };
+    start_download(config, &state, app_handle)
+        .await
+        .map_err(|e| format!("Failed to download the CUDA runtime: {}", e))
+}
+
+#[tauri::command]
+async fn set_benchmark_settings(
+    enabled: bool,
+    reference_model: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.benchmark_on_version_change = enabled;
+        cfg.benchmark_reference_model = reference_model;
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+async fn check_version_regression(
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<benchmark::RegressionReport, String> {
+    let active_path = state.versions.active().await.folder;
+    let reference_model = state.config.lock().await.benchmark_reference_model.clone();
+    let model_path = reference_model.ok_or("No benchmark reference model configured")?;
+    
+    // Compare against the active version unless the candidate is the active one
+    let baseline = active_path
+        .filter(|p| p != &path)
+        .map(PathBuf::from);
+    
+    benchmark::check_regression(&model_path, std::path::Path::new(&path), baseline.as_deref())
+        .await
+        .map_err(|e| format!("Benchmark failed: {}", e))
+}
+
+/// Benchmark a model with llama-bench from the llama.cpp version launches use
+#[tauri::command]
+async fn run_benchmark(
+    model_path: String,
+    options: Option<benchmark::BenchOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<benchmark::BenchmarkRecord, String> {
+    let global_config = state.config.lock().await.clone();
+    let server_path = resolve_llama_server_path_with_fallback(&state, &global_config).await;
+    let version_dir = server_path.parent().ok_or("No llama.cpp version installed")?;
+    
+    benchmark::run_benchmark(&first_shard(&model_path), version_dir, options.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Benchmark failed: {}", e))
+}
+
+#[tauri::command]
+async fn get_benchmark_history(model_path: Option<String>) -> Result<Vec<benchmark::BenchmarkRecord>, String> {
+    Ok(benchmark::history(model_path.map(|p| first_shard(&p)).as_deref()).await)
+}
+
+#[tauri::command]
+async fn test_llamacpp_version(
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<version_test::VersionTestReport, String> {
+    let offline = state.config.lock().await.offline_mode;
+    version_test::test_version(std::path::Path::new(&path), offline).await
+}
+
+#[tauri::command]
+async fn get_version_default_args(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<VersionArgRule>, String> {
+    Ok(state.config.lock().await.version_default_args.clone())
+}
+
 #[tauri::command]
-async fn set_active_llamacpp_version(path: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+async fn set_version_default_args(
+    rules: Vec<VersionArgRule>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    for rule in &rules {
+        if let (Some(min), Some(max)) = (rule.min_build, rule.max_build) {
+            if min > max {
+                return Err(format!("Build range b{}-b{} is empty", min, max));
+            }
+        }
+    }
     {
         let mut cfg = state.config.lock().await;
-        // Save both path and derived version name
-        let version_name = std::path::Path::new(&path)
-            .file_name()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_string());
-        cfg.active_executable_folder = Some(path);
-        cfg.active_executable_version = version_name;
+        cfg.version_default_args = rules;
     }
     save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
 }
@@ -966,12 +2672,7 @@ async fn delete_llamacpp_version(path: String, state: tauri::State<'_, AppState>
     use std::fs;
     use std::path::Path;
 
-    let base_exec = {
-        let cfg = state.config.lock().await;
-        cfg.executable_folder.clone()
-    };
-
-    let versions_root = Path::new(&base_exec).join("versions");
+    let versions_root = state.versions.versions_dir().await;
     let path_buf = Path::new(&path).to_path_buf();
     // Ensure deletion target is under versions root
     if !path_buf.starts_with(&versions_root) {
@@ -981,12 +2682,7 @@ async fn delete_llamacpp_version(path: String, state: tauri::State<'_, AppState>
         fs::remove_dir_all(&path_buf).map_err(|e| format!("Failed to delete version: {}", e))?;
     }
     // Clear active if it pointed here
-    {
-        let mut cfg = state.config.lock().await;
-        if cfg.active_executable_folder.as_deref() == Some(&path) {
-            cfg.active_executable_folder = None;
-        }
-    }
+    state.versions.clear_if_active(&path).await;
     save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
 }
 
@@ -994,6 +2690,11 @@ async fn delete_llamacpp_version(path: String, state: tauri::State<'_, AppState>
 async fn initialize_app_state() -> Result<AppState, Box<dyn std::error::Error>> {
     let state = AppState::new();
     load_settings(&state).await?;
+    encryption::set_enabled(state.config.lock().await.encrypt_at_rest);
+    {
+        let speed_limit = state.config.lock().await.download_speed_limit;
+        let _ = state.downloads.manager.lock().await.set_speed_limit(None, speed_limit);
+    }
 
     // Create models and executable directories if they don't exist
     {
@@ -1019,6 +2720,9 @@ async fn initialize_app_state() -> Result<AppState, Box<dyn std::error::Error>>
         }
     }
     
+    // Bring back downloads the last session didn't finish, before their .part files are cleaned up
+    downloader::restore_pending_downloads(&state.downloads.manager).await;
+
     // Cleanup leftover download files from previous sessions
     {
         let config = state.config.lock().await;
@@ -1050,6 +2754,12 @@ pub fn run() {
             
             println!("Application started, process tracking enabled with kill_on_drop");
             
+            // Verify the active llama.cpp version before the first launch attempt
+            if let Some(warning) = rt.block_on(verify_active_llamacpp_version(&state)) {
+                eprintln!("Warning: {}", warning.message);
+                set_startup_version_warning(warning);
+            }
+            
             // Handle main window close event specifically
             if let Some(main_window) = app.get_webview_window("main") {
                 let version = env!("CARGO_PKG_VERSION");
@@ -1092,6 +2802,21 @@ pub fn run() {
                 });
             });
             
+            // Keep installed repos' Hub metadata fresh for update badges
+            tauri::async_runtime::spawn(hf_metadata::run_refresh_job(state.clone(), app.handle().clone()));
+            tauri::async_runtime::spawn(mirrors::run_health_job(state.clone()));
+            // Drop registry entries for servers that died without us noticing
+            tauri::async_runtime::spawn(process_gc::run_reconcile_job(state.clone(), app.handle().clone()));
+            // GPU power samples for the chat energy estimates
+            tauri::async_runtime::spawn(chat_analytics::run_power_sampler(state.clone()));
+            // Headline status for the taskbar
+            tauri::async_runtime::spawn(app_status::run_status_job(state.clone(), app.handle().clone()));
+            output_stream::init(app.handle().clone());
+            tauri::async_runtime::spawn(readiness::run_readiness_job(state.clone(), app.handle().clone()));
+            tauri::async_runtime::spawn(idle_shutdown::run_idle_job(state.clone(), app.handle().clone()));
+            // Micro model for test_llamacpp_version, so the check doesn't depend on being online
+            tauri::async_runtime::spawn(version_test::prefetch_micro_model(state.clone()));
+            
             app.manage(state);
             Ok(())
         })
@@ -1099,22 +2824,95 @@ pub fn run() {
             get_config,
             save_config,
             scan_models_command,
+            scan_lora_adapters,
+            estimate_model_memory,
+            get_sleeping_models,
+            get_process_metrics,
+            get_process_stats,
+            get_desktop_folders,
+            create_desktop_folder,
+            rename_desktop_folder,
+            delete_desktop_folder,
+            assign_models_to_folder,
+            set_model_tags,
+            set_model_favorite,
+            get_model_tags,
+            get_models_with_tag,
             get_model_settings,
+            get_sorted_models,
             update_model_settings,
+            list_applied_fixups,
+            get_overridable_keys,
+            get_model_config_history,
+            rollback_model_config,
             launch_model,
+            relaunch_last,
+            try_model_from_hub,
+            clear_trial_cache,
             launch_model_external,
+            preview_launch_command,
+            get_launch_command,
+            list_argument_presets,
+            save_argument_preset,
+            delete_argument_preset,
+            apply_argument_preset,
             delete_model_file,
+            delete_models,
+            move_models,
+            import_model_files,
+            import_lm_studio_models,
+            export_manifest,
+            apply_manifest,
+            apply_preset_bulk,
             delete_model,
             kill_process,
+            get_server_capabilities,
             get_process_output,
+            send_process_input,
+            launch_model_cli,
+            list_stacks,
+            save_stack,
+            delete_stack,
+            launch_stack,
+            stop_stack,
+            get_stack_status,
             browse_folder,
             open_url,
             search_huggingface,
             get_model_details,
+            get_model_readme,
+            peek_remote_gguf,
+            request_gguf_conversion,
+            start_hf_conversion,
+            list_hf_conversions,
+            cancel_hf_conversion,
+            set_conversion_tools,
+            get_upstream_metadata,
+            refresh_upstream_metadata,
+            set_offline_mode,
+            set_manage_firewall_rules,
+            list_managed_firewall_rules,
+            remove_managed_firewall_rule,
+            set_download_speed_limit,
+            set_accelerated_transfer,
+            set_port_range,
+            start_hash_job,
+            cancel_hash_job,
+            get_mirror_health,
+            set_download_mirrors,
+            create_share_link,
+            revoke_share_link,
+            set_share_tunnel_path,
+            preview_model_file,
             download_model,
+            get_starter_catalog,
+            download_starter_model,
             get_download_status,
             get_all_downloads,
             get_all_downloads_and_history,
+            get_app_status,
+            inspect_model_format,
+            merge_split_model,
             cancel_download,
             pause_download,
             resume_download,
@@ -1122,19 +2920,67 @@ pub fn run() {
             download_from_url,
             get_llamacpp_releases,
             get_llamacpp_commit_info,
+            get_release_changes,
+            get_llamacpp_platform,
             download_llamacpp_asset,
             download_llamacpp_asset_to_version,
             list_llamacpp_versions,
+            test_llamacpp_version,
             set_active_llamacpp_version,
+            install_cuda_runtime,
+            set_benchmark_settings,
+            run_benchmark,
+            get_benchmark_history,
+            check_version_regression,
             delete_llamacpp_version,
+            get_version_default_args,
+            set_version_default_args,
             get_session_state,
+            save_chat_state,
+            remove_chat_state,
+            search_chats,
+            get_recent_models,
+            get_startup_version_warning,
+            pin_model,
+            open_chat_for_model,
+            record_chat_request,
+            stage_audio_input,
+            log_inference_request,
+            get_request_log,
+            replay_request,
+            set_request_logging,
+            get_chat_analytics,
+            set_encryption_enabled,
             save_window_state,
             remove_window_state,
+            save_terminal_state,
+            record_terminal_input,
+            set_terminal_history,
+            get_terminal_history,
+            remove_terminal_state,
             restart_application,
             graceful_exit,
+            set_shutdown_settings,
+            get_remote_host_settings,
+            set_remote_host_settings,
+            list_remote_models,
+            get_gpu_power_settings,
+            set_gpu_power_settings,
+            set_locale,
+            get_translations,
+            get_available_locales,
             get_app_version,
             check_file_exists,
-            get_system_stats
+            get_system_stats,
+            get_vram_breakdown,
+            import_wallpaper_command,
+            list_wallpapers_command,
+            get_wallpaper_data_command,
+            remove_wallpaper_command,
+            list_custom_themes_command,
+            save_custom_theme_command,
+            delete_custom_theme_command,
+            get_bandwidth_usage
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use std::sync::Mutex;
 use std::sync::LazyLock;
@@ -11,6 +12,25 @@ static RELEASES_CACHE: LazyLock<Mutex<Option<(Vec<LlamaCppReleaseFrontend>, Inst
 // We'll cache for 10 minutes to be conservative
 const CACHE_DURATION: Duration = Duration::from_secs(600);
 
+// Compared ranges don't change once both tags exist, so they're kept for the whole session
+static CHANGES_CACHE: LazyLock<Mutex<HashMap<(String, String), ReleaseChanges>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// The compare API pages commits 100 at a time, more than this many pages is a long way back
+const COMPARE_PAGE_SIZE: usize = 100;
+const MAX_COMPARE_PAGES: usize = 5;
+
+// Commit message phrases that suggest an upgrade needs attention
+const BREAKING_MARKERS: &[&str] = &["breaking", "deprecat", "removed", "renamed", "no longer"];
+
+// CUDA version in llama.cpp's archive names, `cuda-12.4` or `cuda-cu12.4`
+pub static CUDA_ARCHIVE_VERSION: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"cuda-(?:cu)?(\d+\.\d+)").unwrap());
+
+// The cudart DLL ggml-cuda.dll imports, which carries the CUDA major version
+static CUDART_IMPORT: LazyLock<regex::bytes::Regex> =
+    LazyLock::new(|| regex::bytes::Regex::new(r"(?i)cudart64_(\d+)\.dll").unwrap());
+
 // Llama.cpp specific types - updated to match GitHub API response
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LlamaCppRelease {
@@ -62,6 +82,30 @@ pub struct CommitInfo {
     pub html_url: String,
 }
 
+/// One commit between two releases
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReleaseChange {
+    pub sha: String,
+    pub title: String,
+    pub area: Option<String>, // `server`, `sampling`, `CUDA`... from the `area : title` convention
+    pub breaking: bool,
+    pub author: String,
+    pub date: String,
+    pub html_url: String,
+}
+
+/// Commits between two releases, oldest first
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReleaseChanges {
+    pub from_tag: String,
+    pub to_tag: String,
+    pub downgrade: bool, // `to_tag` is older than `from_tag`, the changes are the ones that would be lost
+    pub total_commits: u64,
+    pub truncated: bool,
+    pub changes: Vec<ReleaseChange>,
+    pub compare_url: String,
+}
+
 // Frontend-facing release struct
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LlamaCppReleaseFrontend {
@@ -338,4 +382,232 @@ pub async fn fetch_commit_info(tag_name: &str) -> Result<CommitInfo, Box<dyn std
         date,
         html_url,
     })
-}
\ No newline at end of file
+}
+
+/// Area prefix of a llama.cpp commit title, e.g. `server` in `server : fix slot reuse (#123)`
+fn commit_area(title: &str) -> Option<String> {
+    let (area, rest) = title.split_once(':')?;
+    let area = area.trim();
+    (!area.is_empty() && !rest.trim().is_empty() && area.len() <= 40 && area.split_whitespace().count() <= 3)
+        .then(|| area.to_string())
+}
+
+fn parse_change(commit: &serde_json::Value) -> ReleaseChange {
+    let message = commit["commit"]["message"].as_str().unwrap_or("");
+    let title = message.lines().next().unwrap_or("").trim().to_string();
+    let lower = message.to_lowercase();
+    ReleaseChange {
+        sha: commit["sha"].as_str().unwrap_or("").to_string(),
+        area: commit_area(&title),
+        breaking: BREAKING_MARKERS.iter().any(|marker| lower.contains(marker)),
+        title,
+        author: commit["commit"]["author"]["name"].as_str().unwrap_or("").to_string(),
+        date: commit["commit"]["author"]["date"].as_str().unwrap_or("").to_string(),
+        html_url: commit["html_url"].as_str().unwrap_or("").to_string(),
+    }
+}
+
+async fn fetch_compare_page(
+    client: &reqwest::Client,
+    base: &str,
+    head: &str,
+    page: usize,
+) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+    let url = format!(
+        "https://api.github.com/repos/ggerganov/llama.cpp/compare/{}...{}?per_page={}&page={}",
+        base, head, COMPARE_PAGE_SIZE, page
+    );
+    let response = client
+        .get(&url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .header("Accept", "application/vnd.github+json")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .send()
+        .await?;
+
+    match response.status().as_u16() {
+        200 => Ok(response.json().await?),
+        403 | 429 => Err("Rate limit exceeded. Please try again later.".into()),
+        404 => Err(format!("Release {} or {} not found", base, head).into()),
+        _ => Err(format!("Failed to compare releases: {}", response.status()).into()),
+    }
+}
+
+/// Commit messages between two release tags, to check what an upgrade (or downgrade) brings
+/// before installing it
+pub async fn fetch_release_changes(from_tag: &str, to_tag: &str) -> Result<ReleaseChanges, Box<dyn std::error::Error + Send + Sync>> {
+    let key = (from_tag.to_string(), to_tag.to_string());
+    if let Some(cached) = CHANGES_CACHE.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+        return Ok(cached);
+    }
+
+    let client = reqwest::Client::new();
+    let mut first = fetch_compare_page(&client, from_tag, to_tag, 1).await?;
+    // Going back, the commits that would be lost are the ones from the target up to the installed build
+    let downgrade = first["status"].as_str() == Some("behind");
+    let (base, head) = if downgrade { (to_tag, from_tag) } else { (from_tag, to_tag) };
+    if downgrade {
+        first = fetch_compare_page(&client, base, head, 1).await?;
+    }
+
+    let total_commits = first["total_commits"].as_u64().unwrap_or(0);
+    let compare_url = first["html_url"].as_str().unwrap_or("").to_string();
+    let mut commits: Vec<serde_json::Value> = first["commits"].as_array().cloned().unwrap_or_default();
+    let mut page = 1;
+    while (commits.len() as u64) < total_commits && page < MAX_COMPARE_PAGES {
+        page += 1;
+        let next = fetch_compare_page(&client, base, head, page).await?;
+        match next["commits"].as_array() {
+            Some(more) if !more.is_empty() => commits.extend(more.iter().cloned()),
+            _ => break,
+        }
+    }
+    println!("Compared llama.cpp {}...{}: {} commits", base, head, total_commits);
+
+    let changes = ReleaseChanges {
+        from_tag: from_tag.to_string(),
+        to_tag: to_tag.to_string(),
+        downgrade,
+        total_commits,
+        truncated: (commits.len() as u64) < total_commits,
+        changes: commits.iter().map(parse_change).collect(),
+        compare_url,
+    };
+    if let Ok(mut cache) = CHANGES_CACHE.lock() {
+        cache.insert(key, changes.clone());
+    }
+    Ok(changes)
+}
+
+/// OS and architecture as llama.cpp release archives name them (`win`, `macos`, `ubuntu`;
+/// `x64`, `arm64`), so the release list can tell which assets run here
+#[derive(Debug, Serialize, Clone)]
+pub struct HostPlatform {
+    pub os: String,
+    pub arch: String,
+}
+
+pub fn host_platform() -> HostPlatform {
+    let (os, arch) = crate::manifest::platform_tokens();
+    HostPlatform { os: os.trim_matches('-').to_string(), arch: arch.to_string() }
+}
+
+/// The macOS and Ubuntu archives keep their binaries under `build/bin`, while version folders
+/// are expected to hold `llama-server` at the top. Moves them up after extraction and makes
+/// sure the executables can run. Folders already laid out flat are left alone.
+pub fn normalize_build_layout(dir: &std::path::Path) -> std::io::Result<()> {
+    let server_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+    let nested = dir.join("build").join("bin");
+    if !dir.join(server_name).exists() && nested.join(server_name).exists() {
+        for entry in std::fs::read_dir(&nested)?.flatten() {
+            std::fs::rename(entry.path(), dir.join(entry.file_name()))?;
+        }
+        let _ = std::fs::remove_dir(&nested);
+        let _ = std::fs::remove_dir(dir.join("build"));
+        println!("Moved llama.cpp binaries out of build/bin in {:?}", dir);
+    }
+
+    // Archives written without Unix modes extract as plain files
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        for entry in std::fs::read_dir(dir)?.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            if path.is_file() && (name.starts_with("llama-") || name.ends_with(".dylib") || name.ends_with(".so")) {
+                let mut permissions = std::fs::metadata(&path)?.permissions();
+                if permissions.mode() & 0o111 == 0 {
+                    permissions.set_mode(permissions.mode() | 0o755);
+                    std::fs::set_permissions(&path, permissions)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Assets of one release, looked up by tag
+pub async fn fetch_release_assets(tag: &str) -> Result<Vec<LlamaCppAsset>, String> {
+    let url = format!("https://api.github.com/repos/ggerganov/llama.cpp/releases/tags/{}", tag);
+    let response = reqwest::Client::new()
+        .get(&url)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub returned status {} for release {}", response.status(), tag));
+    }
+    let release: LlamaCppRelease = response.json().await.map_err(|e| e.to_string())?;
+    Ok(release.assets)
+}
+
+/// CUDA runtime libraries a Windows CUDA build loads, missing from both its folder and PATH
+#[derive(Debug, Serialize, Clone)]
+pub struct CudaRuntimeStatus {
+    pub cuda_version: Option<String>, // `12.4` from the build's archive name, or just the major version
+    pub missing: Vec<String>,
+    pub asset: Option<LlamaCppAssetFrontend>, // The release's matching cudart archive, when it has one
+}
+
+/// CUDA version of the build in `folder`. The archive it was installed from names the full
+/// version, otherwise the cudart import inside ggml-cuda.dll gives at least the major one
+async fn build_cuda_version(folder: &std::path::Path) -> Option<String> {
+    let from_log = crate::manifest::installed_asset_url(folder).await
+        .and_then(|url| CUDA_ARCHIVE_VERSION.captures(&url.to_lowercase()).map(|c| c[1].to_string()));
+    if from_log.is_some() {
+        return from_log;
+    }
+    let library = std::fs::read(folder.join("ggml-cuda.dll")).ok()?;
+    CUDART_IMPORT
+        .captures(&library)
+        .map(|c| String::from_utf8_lossy(&c[1]).to_string())
+}
+
+fn on_path(file_name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(file_name).exists()))
+        .unwrap_or(false)
+}
+
+/// For a CUDA build on Windows, which runtime DLLs can't be found. None when the build
+/// isn't CUDA, the platform isn't Windows or nothing is missing. Without the toolkit
+/// installed these only come from the release's separate cudart archive.
+pub async fn check_cuda_runtime(folder: &std::path::Path, release_tag: Option<&str>) -> Option<CudaRuntimeStatus> {
+    if !cfg!(windows) || !folder.join("ggml-cuda.dll").exists() {
+        return None;
+    }
+    let cuda_version = build_cuda_version(folder).await;
+    let major = cuda_version.as_deref().and_then(|v| v.split('.').next()).unwrap_or("12").to_string();
+    let missing: Vec<String> = ["cudart64", "cublas64", "cublasLt64"]
+        .iter()
+        .map(|library| format!("{}_{}.dll", library, major))
+        .filter(|dll| !folder.join(dll).exists() && !on_path(dll))
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+
+    let asset = match release_tag {
+        Some(tag) => match fetch_release_assets(tag).await {
+            Ok(assets) => {
+                let full_version = cuda_version.as_deref().filter(|v| v.contains('.'));
+                let matching = |asset: &&LlamaCppAsset| {
+                    let name = asset.name.to_lowercase();
+                    name.starts_with("cudart") && name.contains("x64") && match full_version {
+                        Some(version) => name.contains(version),
+                        None => name.contains(&format!("cuda-{}.", major)) || name.contains(&format!("cu{}.", major)),
+                    }
+                };
+                assets.iter().find(matching).cloned().map(LlamaCppAssetFrontend::from)
+            }
+            Err(e) => {
+                eprintln!("Failed to look up the CUDA runtime for {}: {}", tag, e);
+                None
+            }
+        },
+        None => None,
+    };
+    Some(CudaRuntimeStatus { cuda_version, missing, asset })
+}
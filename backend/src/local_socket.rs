@@ -0,0 +1,124 @@
+// Local-only serving: llama-server listens on a Unix domain socket and a loopback
+// bridge on an OS-assigned port forwards chat traffic to it, so no configured port
+// can collide. Windows has no socket support in llama-server, so it gets a
+// loopback-only bind on an OS-assigned port instead.
+
+use crate::models::ModelConfig;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+struct Bridge {
+    task: Option<tokio::task::AbortHandle>,
+    socket_path: Option<PathBuf>,
+}
+
+// Active bridges keyed by process id
+static BRIDGES: LazyLock<std::sync::Mutex<HashMap<String, Bridge>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Launch settings for a local-only server
+pub struct LocalLaunch {
+    pub server_config: ModelConfig, // Config used to build the llama-server arguments
+    pub port: u16,                  // Loopback port clients connect to
+    pub bridge: BridgeGuard,
+}
+
+/// Stops the bridge when dropped, so a launch that fails after preparing it doesn't leave
+/// the listener behind. `keep` hands it over to the running process
+pub struct BridgeGuard {
+    process_id: Option<String>,
+}
+
+impl BridgeGuard {
+    fn new(process_id: &str) -> Self {
+        BridgeGuard { process_id: Some(process_id.to_string()) }
+    }
+
+    pub fn keep(mut self) {
+        self.process_id = None;
+    }
+}
+
+impl Drop for BridgeGuard {
+    fn drop(&mut self) {
+        if let Some(process_id) = self.process_id.take() {
+            stop_bridge(&process_id);
+        }
+    }
+}
+
+/// Socket files live in the temp dir because Unix socket paths are limited to ~100 bytes
+pub fn socket_path(process_id: &str) -> PathBuf {
+    let short_id: String = process_id.chars().filter(|c| *c != '-').take(12).collect();
+    std::env::temp_dir().join(format!("llama-os-{}.sock", short_id))
+}
+
+#[cfg(unix)]
+pub async fn prepare_launch(process_id: &str, model_config: &ModelConfig) -> std::io::Result<LocalLaunch> {
+    let socket_path = socket_path(process_id);
+    // A leftover socket from a crashed session would make the bind fail
+    let _ = std::fs::remove_file(&socket_path);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+
+    let bridge_socket = socket_path.clone();
+    let task = tokio::spawn(async move {
+        loop {
+            let (mut client, _) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    eprintln!("Local socket bridge stopped accepting: {}", e);
+                    break;
+                }
+            };
+            let socket = bridge_socket.clone();
+            tokio::spawn(async move {
+                match tokio::net::UnixStream::connect(&socket).await {
+                    Ok(mut server) => {
+                        let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+                    }
+                    Err(e) => eprintln!("Failed to connect to {:?}: {}", socket, e),
+                }
+            });
+        }
+    });
+
+    BRIDGES.lock().unwrap().insert(process_id.to_string(), Bridge {
+        task: Some(task.abort_handle()),
+        socket_path: Some(socket_path.clone()),
+    });
+
+    // llama-server treats a --host ending in .sock as a Unix socket path
+    let mut server_config = model_config.clone();
+    server_config.server_host = socket_path.to_string_lossy().to_string();
+    Ok(LocalLaunch { server_config, port, bridge: BridgeGuard::new(process_id) })
+}
+
+#[cfg(not(unix))]
+pub async fn prepare_launch(process_id: &str, model_config: &ModelConfig) -> std::io::Result<LocalLaunch> {
+    // Let the OS pick a free loopback port; nothing outside this machine can reach it
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+
+    BRIDGES.lock().unwrap().insert(process_id.to_string(), Bridge {
+        task: None,
+        socket_path: None,
+    });
+
+    let mut server_config = model_config.clone();
+    server_config.server_host = "127.0.0.1".to_string();
+    Ok(LocalLaunch { server_config, port, bridge: BridgeGuard::new(process_id) })
+}
+
+/// Stop the bridge for a process and remove its socket file
+pub fn stop_bridge(process_id: &str) {
+    let bridge = BRIDGES.lock().unwrap().remove(process_id);
+    if let Some(bridge) = bridge {
+        if let Some(task) = bridge.task {
+            task.abort();
+        }
+        if let Some(path) = bridge.socket_path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,361 @@
+// Per-domain state managers. Each one owns the locks for its domain and exposes
+// methods for it, so commands ask a manager for what they need instead of
+// locking several AppState fields in the right order themselves. The lock fields
+// stay public for the hot paths (output capture, download progress) that update
+// state in place.
+
+use crate::capabilities::ServerCapabilities;
+use crate::downloader::{DownloadManager, DownloadStatus};
+use crate::models::*;
+use crate::process::ProcessHandle;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// Number of previous settings versions kept per model
+const MAX_CONFIG_HISTORY: usize = 20;
+
+/// The llama.cpp build launches currently use
+#[derive(Debug, Clone, Default)]
+pub struct ActiveVersion {
+    pub folder: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Running servers, their child handles and probed capabilities
+#[derive(Debug, Clone, Default)]
+pub struct ProcessManager {
+    pub running: Arc<Mutex<HashMap<String, ProcessInfo>>>,
+    pub children: Arc<Mutex<HashMap<String, Arc<Mutex<ProcessHandle>>>>>, // Simplified process tracking
+    pub capabilities: Arc<Mutex<HashMap<String, ServerCapabilities>>>, // Process id -> probed features
+}
+
+impl ProcessManager {
+    pub async fn get(&self, process_id: &str) -> Option<ProcessInfo> {
+        self.running.lock().await.get(process_id).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<ProcessInfo> {
+        self.running.lock().await.values().cloned().collect()
+    }
+
+    pub async fn register(&self, info: ProcessInfo, handle: Arc<Mutex<ProcessHandle>>) {
+        let process_id = info.id.clone();
+        self.running.lock().await.insert(process_id.clone(), info);
+        self.children.lock().await.insert(process_id, handle);
+    }
+
+    /// Stop tracking the child handle, keeping the process info for its final output
+    pub async fn release_child(&self, process_id: &str) -> Option<Arc<Mutex<ProcessHandle>>> {
+        self.capabilities.lock().await.remove(process_id);
+        self.children.lock().await.remove(process_id)
+    }
+
+    pub async fn forget(&self, process_id: &str) {
+        self.running.lock().await.remove(process_id);
+        self.children.lock().await.remove(process_id);
+        self.capabilities.lock().await.remove(process_id);
+    }
+
+    pub async fn has_children(&self) -> bool {
+        !self.children.lock().await.is_empty()
+    }
+
+    /// OS process id of each live local server, keyed by process id
+    pub async fn child_pids(&self) -> HashMap<String, u32> {
+        let handles: Vec<(String, Arc<Mutex<ProcessHandle>>)> = self.children.lock().await
+            .iter()
+            .map(|(id, handle)| (id.clone(), handle.clone()))
+            .collect();
+        let mut pids = HashMap::new();
+        for (process_id, handle) in handles {
+            if let Some(pid) = handle.lock().await.get_child_id() {
+                pids.insert(process_id, pid);
+            }
+        }
+        pids
+    }
+
+    /// Client URLs of live servers, optionally restricted to one process. Interactive
+    /// llama-cli sessions have no server to reach and are left out.
+    pub async fn active_endpoints(&self, process_id: Option<&str>) -> Vec<String> {
+        self.running.lock().await
+            .values()
+            .filter(|p| process_id.is_none_or(|id| p.id == id))
+            .filter(|p| !p.interactive && p.port != 0)
+            .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running))
+            .map(|p| p.client_url())
+            .collect()
+    }
+
+    pub async fn capabilities(&self, process_id: &str) -> Option<ServerCapabilities> {
+        self.capabilities.lock().await.get(process_id).cloned()
+    }
+
+    pub async fn set_capabilities(&self, process_id: &str, capabilities: ServerCapabilities) {
+        self.capabilities.lock().await.insert(process_id.to_string(), capabilities);
+    }
+
+    /// Move a starting server to Running once its model is loaded, false if it is no longer starting
+    pub async fn mark_ready(&self, process_id: &str) -> bool {
+        match self.running.lock().await.get_mut(process_id) {
+            Some(process) if matches!(process.status, ProcessStatus::Starting) => {
+                process.status = ProcessStatus::Running;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Ports of servers that are up or coming back up, an exited server no longer holds one
+    pub async fn held_ports(&self) -> HashSet<u16> {
+        self.running.lock().await.values()
+            .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running | ProcessStatus::Restarting))
+            .map(|p| p.port)
+            .collect()
+    }
+
+    /// The live local server holding a file open: as its model, another shard of a
+    /// split model or an argument such as a projector or LoRA adapter
+    pub async fn using_file(&self, path: &str) -> Option<ProcessInfo> {
+        let target = comparable_path(path);
+        self.running.lock().await
+            .values()
+            .filter(|p| p.remote_host.is_none() && matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running))
+            .find(|p| {
+                comparable_path(&p.model_path) == target
+                    || same_split_model(&p.model_path, path)
+                    || p.command.iter().any(|arg| comparable_path(arg) == target)
+            })
+            .cloned()
+    }
+}
+
+// Paths as typed by users and stored in settings mix separators, and Windows
+// ignores case
+fn comparable_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    if cfg!(windows) { path.to_lowercase() } else { path }
+}
+
+fn same_split_model(a: &str, b: &str) -> bool {
+    let split_base = |path: &str| {
+        let path = std::path::Path::new(path);
+        let name = path.file_name()?.to_string_lossy().to_string();
+        let base = crate::scanner::SPLIT_FILE.captures(&name)?.get(1)?.as_str().to_string();
+        Some(comparable_path(&path.with_file_name(base).to_string_lossy()))
+    };
+    matches!((split_base(a), split_base(b)), (Some(a), Some(b)) if a == b)
+}
+
+/// Per-model settings, their history and launch statistics
+#[derive(Debug, Clone, Default)]
+pub struct ModelLibrary {
+    pub configs: Arc<Mutex<HashMap<String, ModelConfig>>>,
+    pub history: Arc<Mutex<HashMap<String, Vec<ModelConfigSnapshot>>>>,
+    pub usage: Arc<Mutex<HashMap<String, ModelUsage>>>,
+}
+
+impl ModelLibrary {
+    /// Record the settings a model had before they get replaced, skipping no-op saves
+    pub async fn record_history(&self, previous: Option<ModelConfig>, new_config: &ModelConfig) {
+        let previous = match previous {
+            Some(previous) if &previous != new_config => previous,
+            _ => return,
+        };
+
+        let mut history = self.history.lock().await;
+        let entries = history.entry(previous.model_path.clone()).or_default();
+        let version = entries.last().map(|s| s.version + 1).unwrap_or(1);
+        entries.push(ModelConfigSnapshot {
+            version,
+            saved_at: chrono::Utc::now(),
+            config: previous,
+        });
+        if entries.len() > MAX_CONFIG_HISTORY {
+            let excess = entries.len() - MAX_CONFIG_HISTORY;
+            entries.drain(0..excess);
+        }
+    }
+
+    pub async fn config_for(&self, model_path: &str) -> ModelConfig {
+        self.configs.lock().await
+            .get(model_path)
+            .cloned()
+            .unwrap_or_else(|| ModelConfig::new(model_path.to_string()))
+    }
+
+    pub async fn all_configs(&self) -> HashMap<String, ModelConfig> {
+        self.configs.lock().await.clone()
+    }
+
+    /// Replace a model's settings, keeping the previous ones in its history
+    pub async fn set_config(&self, model_path: &str, config: ModelConfig) {
+        let previous = self.configs.lock().await.insert(model_path.to_string(), config.clone());
+        self.record_history(previous, &config).await;
+    }
+
+    pub async fn forget(&self, model_path: &str) {
+        self.configs.lock().await.remove(model_path);
+        self.history.lock().await.remove(model_path);
+    }
+
+    pub async fn history(&self, model_path: &str) -> Vec<ModelConfigSnapshot> {
+        self.history.lock().await.get(model_path).cloned().unwrap_or_default()
+    }
+
+    pub async fn snapshot(&self, model_path: &str, version: u32) -> Option<ModelConfig> {
+        self.history.lock().await
+            .get(model_path)
+            .and_then(|entries| entries.iter().find(|s| s.version == version))
+            .map(|s| s.config.clone())
+    }
+
+    pub async fn usage(&self) -> HashMap<String, ModelUsage> {
+        self.usage.lock().await.clone()
+    }
+
+    pub async fn last_launch(&self, model_path: &str) -> Option<LaunchRecord> {
+        self.usage.lock().await.get(model_path).and_then(|u| u.last_launch.clone())
+    }
+
+    /// Note a launch for the "last used" ordering.
+    /// Local launches also pass their effective invocation so it can be repeated.
+    pub async fn record_launch(&self, model_path: &str, launch: Option<LaunchRecord>) {
+        let mut usage = self.usage.lock().await;
+        let entry = usage.entry(model_path.to_string()).or_default();
+        entry.last_used = Some(chrono::Utc::now());
+        entry.launch_count += 1;
+        if launch.is_some() {
+            entry.last_launch = launch;
+        }
+    }
+
+    pub async fn set_pinned(&self, model_path: &str, pinned: bool) {
+        let mut usage = self.usage.lock().await;
+        let entry = usage.entry(model_path.to_string()).or_default();
+        // Re-pinning keeps the original position
+        if !pinned {
+            entry.pinned_at = None;
+        } else if entry.pinned_at.is_none() {
+            entry.pinned_at = Some(chrono::Utc::now());
+        }
+    }
+
+    /// Change tags and the favorite flag in place, they are not launch settings so no history is kept
+    pub async fn set_labels(&self, model_path: &str, tags: Option<Vec<String>>, favorite: Option<bool>) -> ModelConfig {
+        let mut configs = self.configs.lock().await;
+        let config = configs
+            .entry(model_path.to_string())
+            .or_insert_with(|| ModelConfig::new(model_path.to_string()));
+        if let Some(tags) = tags {
+            let mut normalized: Vec<String> = Vec::new();
+            for tag in tags.iter().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()) {
+                if !normalized.contains(&tag) {
+                    normalized.push(tag);
+                }
+            }
+            config.tags = normalized;
+        }
+        if let Some(favorite) = favorite {
+            config.favorite = favorite;
+        }
+        config.clone()
+    }
+}
+
+/// Active downloads and finished download history
+#[derive(Debug, Clone)]
+pub struct DownloadService {
+    pub manager: Arc<Mutex<DownloadManager>>,
+}
+
+impl Default for DownloadService {
+    fn default() -> Self {
+        Self { manager: Arc::new(Mutex::new(DownloadManager::new())) }
+    }
+}
+
+impl DownloadService {
+    pub async fn status(&self, download_id: &str) -> Option<DownloadStatus> {
+        self.manager.lock().await.get_status(download_id).cloned()
+    }
+
+    pub async fn active(&self) -> Vec<DownloadStatus> {
+        self.manager.lock().await.downloads.values().cloned().collect()
+    }
+
+    pub async fn all_with_history(&self) -> Vec<DownloadStatus> {
+        let manager = self.manager.lock().await;
+        let mut all_downloads = manager.downloads.values().cloned().collect::<Vec<_>>();
+        all_downloads.extend(manager.download_history.clone());
+        all_downloads
+    }
+
+    pub async fn pause(&self, download_id: &str) -> Result<Vec<DownloadStatus>, String> {
+        self.manager.lock().await.pause_download(download_id)?;
+        crate::downloader::save_pending_downloads(&self.manager).await;
+        Ok(self.active().await)
+    }
+
+    pub async fn resume(&self, download_id: &str) -> Result<Vec<DownloadStatus>, String> {
+        self.manager.lock().await.resume_download(download_id)?;
+        crate::downloader::save_pending_downloads(&self.manager).await;
+        Ok(self.active().await)
+    }
+
+    pub async fn cancel(&self, download_id: &str) -> Result<Vec<DownloadStatus>, String> {
+        self.manager.lock().await.cancel_download(download_id)?;
+        crate::downloader::save_pending_downloads(&self.manager).await;
+        Ok(self.active().await)
+    }
+
+    pub async fn clear_history(&self) -> Vec<DownloadStatus> {
+        self.manager.lock().await.clear_download_history();
+        self.all_with_history().await
+    }
+}
+
+/// Installed llama.cpp builds and which one is active. Shares the global config
+/// lock since the selection is persisted as part of it.
+#[derive(Debug, Clone)]
+pub struct VersionManager {
+    config: Arc<Mutex<GlobalConfig>>,
+}
+
+impl VersionManager {
+    pub fn new(config: Arc<Mutex<GlobalConfig>>) -> Self {
+        Self { config }
+    }
+
+    pub async fn versions_dir(&self) -> PathBuf {
+        PathBuf::from(&self.config.lock().await.executable_folder).join("versions")
+    }
+
+    pub async fn active(&self) -> ActiveVersion {
+        let config = self.config.lock().await;
+        ActiveVersion {
+            folder: config.active_executable_folder.clone(),
+            version: config.active_executable_version.clone(),
+        }
+    }
+
+    /// Activate a version folder, deriving the version name from it
+    pub async fn set_active(&self, folder: &str) {
+        let version_name = std::path::Path::new(folder)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string());
+        let mut config = self.config.lock().await;
+        config.active_executable_folder = Some(folder.to_string());
+        config.active_executable_version = version_name;
+    }
+
+    pub async fn clear_if_active(&self, folder: &str) {
+        let mut config = self.config.lock().await;
+        if config.active_executable_folder.as_deref() == Some(folder) {
+            config.active_executable_folder = None;
+        }
+    }
+}
@@ -0,0 +1,372 @@
+// Reproducible description of a desktop setup: which Hub files the models came from and
+// at which commit, the llama.cpp build in use and every model's launch settings.
+// `apply` recreates it on another machine by downloading whatever is missing there.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::save_settings;
+use crate::models::{DownloadStartResult, ModelConfig};
+use crate::downloader::DownloadState;
+use crate::AppState;
+
+pub const MANIFEST_FORMAT: u32 = 1;
+
+// ggml backend libraries shipped with a build, the first one found names its backend
+const BACKEND_LIBRARIES: &[(&str, &str)] = &[
+    ("ggml-cuda", "cuda"),
+    ("ggml-hip", "hip"),
+    ("ggml-vulkan", "vulkan"),
+    ("ggml-sycl", "sycl"),
+    ("ggml-opencl", "opencl"),
+    ("ggml-metal", "metal"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetupManifest {
+    pub format: u32,
+    pub created_at: DateTime<Utc>,
+    pub app_version: String,
+    pub llama_cpp: Option<LlamaCppEntry>,
+    pub models: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlamaCppEntry {
+    pub version: String, // Release tag, also the version folder name
+    pub backend: String, // "cuda", "hip", "vulkan", "sycl", "opencl", "metal" or "cpu"
+    #[serde(default)]
+    pub asset_url: Option<String>, // Archive it was installed from, when the download log still knows
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelEntry {
+    pub path: String, // Relative to the models directory, `/` separated
+    pub size_bytes: u64,
+    #[serde(default)]
+    pub repo_id: Option<String>, // None for files that didn't come from the Hub
+    #[serde(default)]
+    pub revision: Option<String>, // Hub commit whose files match the local ones
+    #[serde(default)]
+    pub files: Vec<String>, // Repo paths to fetch, every shard of a split model
+    #[serde(default)]
+    pub config: Option<ModelConfig>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManifestApplyResult {
+    pub downloads: Vec<DownloadStartResult>,
+    pub present: Vec<String>,  // Models already on this machine
+    pub configured: usize,     // Models whose launch settings were restored
+    pub skipped: Vec<String>,  // Models that can't be fetched, with the reason
+    pub llama_cpp: Option<String>,
+}
+
+fn relative_path(path: &Path, models_dir: &Path) -> Option<String> {
+    let relative = path.strip_prefix(models_dir).ok()?;
+    Some(relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect::<Vec<_>>().join("/"))
+}
+
+/// Model files of the library, a split model as its first shard plus the rest
+fn library_files(models_dir: &Path) -> Vec<(PathBuf, Vec<PathBuf>)> {
+    let pattern = format!("{}/**/*.gguf", models_dir.display());
+    let Ok(paths) = glob::glob(&pattern) else { return Vec::new() };
+    let mut entries = Vec::new();
+    for path in paths.flatten() {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        match crate::scanner::SPLIT_FILE.captures(&name) {
+            Some(captures) => {
+                if captures.get(2).map(|m| m.as_str()) != Some("00001") {
+                    continue;
+                }
+                let base = captures.get(1).unwrap().as_str();
+                let count: usize = captures.get(3).unwrap().as_str().parse().unwrap_or(0);
+                let shards = (1..=count).map(|i| path.with_file_name(format!("{}-{:05}-of-{:05}.gguf", base, i, count))).collect();
+                entries.push((path, shards));
+            }
+            None => entries.push((path.clone(), vec![path])),
+        }
+    }
+    entries.sort();
+    entries
+}
+
+fn detect_backend(folder: &Path) -> String {
+    let names: Vec<String> = std::fs::read_dir(folder)
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_lowercase()).collect())
+        .unwrap_or_default();
+    BACKEND_LIBRARIES
+        .iter()
+        .find(|(library, _)| names.iter().any(|name| name.contains(library)))
+        .map(|(_, backend)| backend.to_string())
+        .unwrap_or_else(|| if cfg!(target_os = "macos") { "metal" } else { "cpu" }.to_string())
+}
+
+async fn llama_cpp_entry(state: &AppState) -> Option<LlamaCppEntry> {
+    let active = state.versions.active().await;
+    let folder = PathBuf::from(active.folder?);
+    let version = active.version.or_else(|| folder.file_name().map(|n| n.to_string_lossy().to_string()))?;
+    let asset_url = installed_asset_url(&folder).await;
+    Some(LlamaCppEntry { backend: detect_backend(&folder), version, asset_url })
+}
+
+/// Archive the llama.cpp build in `folder` was installed from, when the download log still knows.
+/// A CUDA runtime installed into the folder afterwards is logged there too and isn't the build
+pub async fn installed_asset_url(folder: &Path) -> Option<String> {
+    crate::download_log::read_entries().await
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .filter(|entry| entry.outcome == "Completed" && Path::new(&entry.destination) == folder)
+        .map(|entry| entry.source_url)
+        .find(|url| !url.rsplit('/').next().unwrap_or(url).to_lowercase().starts_with("cudart"))
+}
+
+pub async fn export(state: &AppState) -> Result<SetupManifest, String> {
+    let (models_directory, offline) = {
+        let config = state.config.lock().await;
+        (config.models_directory.clone(), config.offline_mode)
+    };
+    if models_directory.is_empty() {
+        return Err("Models directory is not set".to_string());
+    }
+    let models_dir = PathBuf::from(&models_directory);
+    let configs = state.library.all_configs().await;
+
+    let mut details: HashMap<String, Option<crate::models::ModelDetails>> = HashMap::new();
+    let mut models = Vec::new();
+    for (path, shards) in library_files(&models_dir) {
+        let Some(relative) = relative_path(&path, &models_dir) else { continue };
+        let size_bytes: u64 = shards.iter().filter_map(|s| std::fs::metadata(s).ok()).map(|m| m.len()).sum();
+        let path_string = path.to_string_lossy().to_string();
+        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+        // Hub downloads live in <author>/<model>/<file>
+        let parts: Vec<&str> = relative.split('/').collect();
+        let repo_id = (parts.len() == 3).then(|| format!("{}/{}", parts[0], parts[1]));
+        let (mut files, mut revision) = (Vec::new(), None);
+        if let Some(repo_id) = &repo_id {
+            if !offline && !details.contains_key(repo_id) {
+                let fetched = crate::huggingface::get_huggingface_model_details(repo_id.clone()).await
+                    .map_err(|e| e.to_string());
+                if let Err(e) = &fetched {
+                    eprintln!("Manifest: no Hub details for {}: {}", repo_id, e);
+                }
+                details.insert(repo_id.clone(), fetched.ok());
+            }
+            match details.get(repo_id).and_then(|d| d.as_ref()).and_then(|d| d.gguf_files.get(&file_name).map(|info| (d, info))) {
+                Some((repo, info)) => {
+                    files = info.parts.clone();
+                    // A size that differs from upstream means the files are from another commit
+                    revision = repo.revision.clone().filter(|_| info.size == size_bytes);
+                }
+                None => files = shards.iter().filter_map(|s| s.file_name()).map(|n| n.to_string_lossy().to_string()).collect(),
+            }
+        }
+
+        models.push(ModelEntry {
+            path: relative,
+            size_bytes,
+            repo_id,
+            revision,
+            files,
+            config: configs.get(&path_string).cloned(),
+        });
+    }
+
+    Ok(SetupManifest {
+        format: MANIFEST_FORMAT,
+        created_at: Utc::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        llama_cpp: llama_cpp_entry(state).await,
+        models,
+    })
+}
+
+pub async fn export_to_file(path: &str, state: &AppState) -> Result<SetupManifest, String> {
+    let manifest = export(state).await?;
+    let contents = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    tokio::fs::write(path, contents).await.map_err(|e| e.to_string())?;
+    Ok(manifest)
+}
+
+/// OS and architecture parts of llama.cpp release archive names for this machine
+pub fn platform_tokens() -> (&'static str, &'static str) {
+    let os = if cfg!(windows) { "-win-" } else if cfg!(target_os = "macos") { "-macos-" } else { "-ubuntu-" };
+    let arch = if cfg!(target_arch = "aarch64") { "arm64" } else { "x64" };
+    (os, arch)
+}
+
+fn asset_fits_platform(url: &str) -> bool {
+    let name = url.rsplit('/').next().unwrap_or(url).to_lowercase();
+    let (os, arch) = platform_tokens();
+    name.contains(os) && name.contains(arch)
+}
+
+/// Asset of a llama.cpp release for this OS and architecture built for the backend,
+/// plus the CUDA runtime archive Windows CUDA builds need next to them
+fn pick_assets(assets: &[crate::llamacpp_manager::LlamaCppAsset], backend: &str) -> Vec<String> {
+    let (os, arch) = platform_tokens();
+    let other_backends: Vec<&str> = BACKEND_LIBRARIES
+        .iter()
+        .map(|(_, b)| *b)
+        .filter(|b| *b != backend && *b != "metal")
+        .chain(["cpu", "kompute", "cudart", "openvino"].into_iter().filter(|b| *b != backend))
+        .collect();
+    let main = assets.iter().find(|asset| {
+        let name = asset.name.to_lowercase();
+        name.contains(os)
+            && name.contains(arch)
+            && (matches!(backend, "cpu" | "metal") || name.contains(backend))
+            && !other_backends.iter().any(|other| name.contains(other))
+    });
+    let Some(main) = main else { return Vec::new() };
+    let mut urls = vec![main.download_url.clone()];
+    if backend == "cuda" && cfg!(windows) {
+        let cuda_version = crate::llamacpp_manager::CUDA_ARCHIVE_VERSION
+            .captures(&main.name.to_lowercase())
+            .map(|c| c[1].to_string());
+        let runtime = assets.iter().find(|asset| {
+            let name = asset.name.to_lowercase();
+            name.starts_with("cudart") && name.contains(arch) && cuda_version.as_ref().is_some_and(|v| name.contains(v.as_str()))
+        });
+        if let Some(runtime) = runtime {
+            urls.push(runtime.download_url.clone());
+        }
+    }
+    urls
+}
+
+/// Install the manifest's llama.cpp build into versions/<tag> unless it is there, and
+/// make it the active one once it has arrived
+async fn apply_llama_cpp(entry: &LlamaCppEntry, state: &AppState, app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let folder = state.versions.versions_dir().await.join(&entry.version);
+    let folder_string = folder.to_string_lossy().to_string();
+    let server_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+    if folder.join(server_name).exists() {
+        state.versions.set_active(&folder_string).await;
+        return Ok(format!("llama.cpp {} is installed and now active", entry.version));
+    }
+
+    // The recorded archive only fits when this machine is the same platform
+    let urls = match entry.asset_url.as_ref().filter(|url| asset_fits_platform(url)) {
+        Some(url) => vec![url.clone()],
+        None => pick_assets(&crate::llamacpp_manager::fetch_release_assets(&entry.version).await?, &entry.backend),
+    };
+    if urls.is_empty() {
+        return Err(format!("No {} build of llama.cpp {} for this platform", entry.backend, entry.version));
+    }
+
+    let mut download_ids = Vec::new();
+    for url in urls {
+        let config = crate::downloader::DownloadConfig {
+            base_url: url,
+            destination_folder: folder_string.clone(),
+            auto_extract: true,
+            create_subfolder: None,
+            files: Vec::new(),
+            custom_headers: Some(HashMap::from([("User-Agent".to_string(), "Llama-OS-Tauri/1.0".to_string())])),
+        };
+        let started = crate::downloader::start_download(config, state, app_handle.clone()).await
+            .map_err(|e| format!("Failed to download llama.cpp: {}", e))?;
+        download_ids.push(started.download_id);
+    }
+
+    let state = state.clone();
+    let version = entry.version.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            let mut finished = true;
+            for id in &download_ids {
+                match state.downloads.status(id).await.map(|s| s.status) {
+                    Some(DownloadState::Completed) => {}
+                    Some(DownloadState::Failed | DownloadState::Cancelled) | None => return,
+                    Some(_) => finished = false,
+                }
+            }
+            if finished {
+                break;
+            }
+        }
+        state.versions.set_active(&folder_string).await;
+        match save_settings(&state).await {
+            Ok(()) => println!("llama.cpp {} from the manifest is installed and active", version),
+            Err(e) => eprintln!("Failed to save settings after installing llama.cpp {}: {}", version, e),
+        }
+    });
+    Ok(format!("Downloading llama.cpp {} ({}), it becomes active once installed", entry.version, entry.backend))
+}
+
+/// Recreate a setup: fetch missing models at their recorded commits, restore their
+/// launch settings and install the llama.cpp build
+pub async fn apply(path: &str, state: &AppState, app_handle: &tauri::AppHandle) -> Result<ManifestApplyResult, String> {
+    let contents = tokio::fs::read_to_string(path).await.map_err(|e| e.to_string())?;
+    let manifest: SetupManifest = serde_json::from_str(&contents).map_err(|e| format!("Invalid manifest: {}", e))?;
+    if manifest.format > MANIFEST_FORMAT {
+        return Err(format!("Manifest format {} needs a newer version of Llama-OS", manifest.format));
+    }
+    let models_directory = state.config.lock().await.models_directory.clone();
+    if models_directory.is_empty() {
+        return Err("Set a models directory before applying a manifest".to_string());
+    }
+    let models_dir = PathBuf::from(&models_directory);
+
+    let mut result = ManifestApplyResult::default();
+    for entry in &manifest.models {
+        let local = entry.path.split('/').fold(models_dir.clone(), |path, part| path.join(part));
+        let local_string = local.to_string_lossy().to_string();
+
+        if let Some(mut config) = entry.config.clone() {
+            // Projectors and adapters picked from the same library move along with it
+            let old_root = Path::new(&config.model_path)
+                .ancestors()
+                .nth(entry.path.split('/').count())
+                .map(Path::to_path_buf);
+            let relocate = |path: &str| match old_root.as_deref().and_then(|root| Path::new(path).strip_prefix(root).ok()) {
+                Some(rest) if !path.is_empty() => models_dir.join(rest).to_string_lossy().to_string(),
+                _ => path.to_string(),
+            };
+            config.model_path = local_string.clone();
+            config.mmproj_path = relocate(&config.mmproj_path);
+            config.lora_paths = config.lora_paths.iter().map(|path| relocate(path)).collect();
+            config.lora_scales = config.lora_scales.iter().map(|(path, scale)| (relocate(path), *scale)).collect();
+            state.library.set_config(&local_string, config).await;
+            result.configured += 1;
+        }
+
+        if local.is_file() {
+            result.present.push(entry.path.clone());
+            continue;
+        }
+        let Some(repo_id) = &entry.repo_id else {
+            result.skipped.push(format!("{}: not from Hugging Face, copy it over by hand", entry.path));
+            continue;
+        };
+        let files = if entry.files.is_empty() { vec![entry.path.rsplit('/').next().unwrap_or(&entry.path).to_string()] } else { entry.files.clone() };
+        match crate::start_hub_download(repo_id.clone(), files, entry.revision.clone(), false, state, app_handle.clone()).await {
+            Ok(started) => result.downloads.push(started),
+            Err(e) => result.skipped.push(format!("{}: {}", entry.path, e.message)),
+        }
+    }
+
+    if let Some(entry) = &manifest.llama_cpp {
+        result.llama_cpp = Some(match apply_llama_cpp(entry, state, app_handle).await {
+            Ok(message) => message,
+            Err(e) => format!("llama.cpp {} not installed: {}", entry.version, e),
+        });
+    }
+
+    save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    println!(
+        "Applied manifest {}: {} downloads, {} present, {} skipped",
+        path,
+        result.downloads.len(),
+        result.present.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
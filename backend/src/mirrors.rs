@@ -0,0 +1,154 @@
+use crate::models::MirrorHealth;
+use crate::AppState;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+pub const PRIMARY_ENDPOINT: &str = "https://huggingface.co";
+
+// Small stable file for latency and a larger one for a throughput sample
+const LATENCY_PROBE_PATH: &str = "openai-community/gpt2/resolve/main/config.json";
+const THROUGHPUT_PROBE_PATH: &str = "openai-community/gpt2/resolve/main/model.safetensors";
+const THROUGHPUT_SAMPLE_BYTES: u64 = 1024 * 1024;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+// Results older than this are measured again before ranking
+const HEALTH_MAX_AGE: Duration = Duration::from_secs(30 * 60);
+
+static HEALTH: LazyLock<Mutex<Vec<MirrorHealth>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Primary Hub endpoint followed by the configured mirrors, without duplicates
+pub fn configured_endpoints(mirrors: &[String]) -> Vec<String> {
+    let mut endpoints = vec![PRIMARY_ENDPOINT.to_string()];
+    for mirror in mirrors {
+        let mirror = mirror.trim().trim_end_matches('/').to_string();
+        if !mirror.is_empty() && !endpoints.contains(&mirror) {
+            endpoints.push(mirror);
+        }
+    }
+    endpoints
+}
+
+async fn probe_endpoint(client: &reqwest::Client, endpoint: &str) -> MirrorHealth {
+    let mut health = MirrorHealth {
+        endpoint: endpoint.to_string(),
+        latency_ms: None,
+        throughput_bps: None,
+        healthy: false,
+        error: None,
+        checked_at: chrono::Utc::now(),
+    };
+
+    let started = Instant::now();
+    match client.get(format!("{}/{}", endpoint, LATENCY_PROBE_PATH)).send().await {
+        Ok(response) if response.status().is_success() => {
+            health.latency_ms = Some(started.elapsed().as_millis() as u64);
+            health.healthy = true;
+        }
+        Ok(response) => health.error = Some(format!("HTTP {}", response.status())),
+        Err(e) => health.error = Some(e.to_string()),
+    }
+    if !health.healthy {
+        return health;
+    }
+
+    let started = Instant::now();
+    let sample = client
+        .get(format!("{}/{}", endpoint, THROUGHPUT_PROBE_PATH))
+        .header(reqwest::header::RANGE, format!("bytes=0-{}", THROUGHPUT_SAMPLE_BYTES - 1))
+        .send()
+        .await;
+    if let Ok(response) = sample {
+        if let Ok(bytes) = response.bytes().await {
+            let elapsed = started.elapsed().as_secs_f64();
+            if elapsed > 0.0 && !bytes.is_empty() {
+                health.throughput_bps = Some(bytes.len() as f64 / elapsed);
+            }
+        }
+    }
+    health
+}
+
+/// Measure every configured endpoint and cache the results
+pub async fn check_all(state: &AppState) -> Vec<MirrorHealth> {
+    let mirrors = state.config.lock().await.download_mirrors.clone();
+    let client = match reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .user_agent("Llama-OS-Tauri/1.0")
+        .build()
+    {
+        Ok(client) => client,
+        Err(_) => return Vec::new(),
+    };
+
+    let probes = configured_endpoints(&mirrors)
+        .into_iter()
+        .map(|endpoint| {
+            let client = client.clone();
+            async move { probe_endpoint(&client, &endpoint).await }
+        });
+    let results = futures_util::future::join_all(probes).await;
+
+    *HEALTH.lock().await = results.clone();
+    results
+}
+
+/// Last measured health of every endpoint
+pub async fn get_health() -> Vec<MirrorHealth> {
+    HEALTH.lock().await.clone()
+}
+
+/// Endpoints ordered fastest first: healthy by throughput then latency, unmeasured next,
+/// failing ones last so they are still tried as a final fallback
+pub async fn ranked_endpoints(state: &AppState) -> Vec<String> {
+    let (mirrors, offline) = {
+        let config = state.config.lock().await;
+        (config.download_mirrors.clone(), config.offline_mode)
+    };
+    let endpoints = configured_endpoints(&mirrors);
+    // A single endpoint has nothing to rank against
+    if endpoints.len() == 1 || offline {
+        return endpoints;
+    }
+
+    let mut health = get_health().await;
+    let stale = health.iter().map(|h| &h.endpoint).ne(endpoints.iter())
+        || health.iter().any(|h| (chrono::Utc::now() - h.checked_at).to_std().map_or(true, |age| age > HEALTH_MAX_AGE));
+    if stale {
+        health = check_all(state).await;
+    }
+
+    let mut ranked = endpoints;
+    let rank = |endpoint: &String| -> (u8, i64, u64) {
+        match health.iter().find(|h| &h.endpoint == endpoint) {
+            Some(h) if h.healthy => (0, -(h.throughput_bps.unwrap_or(0.0) as i64), h.latency_ms.unwrap_or(u64::MAX)),
+            Some(_) => (2, 0, 0),
+            None => (1, 0, 0),
+        }
+    };
+    ranked.sort_by_key(rank);
+    ranked
+}
+
+/// Candidate URLs for a download in endpoint order. Only Hub URLs can be served by mirrors.
+pub fn candidate_urls(url: &str, endpoints: &[String]) -> Vec<String> {
+    match url.strip_prefix(PRIMARY_ENDPOINT) {
+        Some(path) if path.starts_with('/') => endpoints.iter().map(|endpoint| format!("{}{}", endpoint, path)).collect(),
+        _ => vec![url.to_string()],
+    }
+}
+
+/// Re-measure endpoints periodically so the ranking is ready when a download starts
+pub async fn run_health_job(state: AppState) {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+    loop {
+        let (has_mirrors, offline) = {
+            let config = state.config.lock().await;
+            (!config.download_mirrors.is_empty(), config.offline_mode)
+        };
+        if has_mirrors && !offline {
+            check_all(&state).await;
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
@@ -0,0 +1,361 @@
+// Import of GGUF files dropped onto the window or found in an LM Studio library. Files
+// are checked for the GGUF magic, then copied, moved or linked into the models
+// directory, so they show up on the desktop without the user managing folders by hand.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::AppState;
+
+const IMPORT_FOLDER: &str = "imported";
+const COPY_BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    Copy,
+    Move,
+    Symlink,
+}
+
+/// What to do when the models directory already has a file with the same name
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflict {
+    #[default]
+    Rename, // Import as `name (2).gguf`
+    Skip,
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub file: String,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedFile {
+    pub source: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub source: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub imported: Vec<ImportedFile>,
+    pub skipped: Vec<SkippedFile>,
+}
+
+fn is_gguf(path: &Path) -> std::io::Result<bool> {
+    use std::io::Read;
+    let mut magic = [0u8; 4];
+    let mut file = std::fs::File::open(path)?;
+    Ok(file.read_exact(&mut magic).is_ok() && &magic == b"GGUF")
+}
+
+/// Dropping one shard of a split model brings the rest of the set along
+fn with_sibling_shards(paths: Vec<String>) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut expanded = Vec::new();
+    for path in paths {
+        let path = PathBuf::from(path);
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        let shards = match (crate::scanner::SPLIT_FILE.captures(&name), path.parent()) {
+            (Some(captures), Some(folder)) => {
+                let base = captures.get(1).unwrap().as_str();
+                let count: usize = captures.get(3).unwrap().as_str().parse().unwrap_or(0);
+                (1..=count).map(|i| folder.join(format!("{}-{:05}-of-{:05}.gguf", base, i, count))).collect()
+            }
+            _ => vec![path],
+        };
+        for shard in shards {
+            if seen.insert(shard.clone()) {
+                expanded.push(shard);
+            }
+        }
+    }
+    expanded
+}
+
+/// `name (2).gguf`, `name (3).gguf`, ... until one is free
+fn free_name(target: &Path) -> PathBuf {
+    let stem = target.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let extension = target.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+    (2..)
+        .map(|n| target.with_file_name(format!("{} ({}).{}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+async fn copy_with_progress(source: &Path, target: &Path, progress: &mut ImportProgress, app_handle: &tauri::AppHandle) -> std::io::Result<()> {
+    let mut reader = fs::File::open(source).await?;
+    let partial = crate::downloader::partial_path(target);
+    let mut writer = fs::File::create(&partial).await?;
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let result = async {
+        loop {
+            let read = reader.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read]).await?;
+            progress.copied_bytes += read as u64;
+            let _ = app_handle.emit("model-import-progress", progress.clone());
+        }
+        writer.flush().await?;
+        drop(writer);
+        fs::rename(&partial, target).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = fs::remove_file(&partial).await;
+    }
+    result
+}
+
+async fn link_file(source: &Path, target: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    return fs::symlink(source, target).await;
+    #[cfg(windows)]
+    return fs::symlink_file(source, target).await;
+}
+
+/// Import one file, returning the path it ended up at
+async fn import_file(source: &Path, target: &Path, mode: ImportMode, progress: &mut ImportProgress, app_handle: &tauri::AppHandle) -> Result<(), String> {
+    match mode {
+        ImportMode::Copy => copy_with_progress(source, target, progress, app_handle).await
+            .map_err(|e| format!("Failed to copy: {}", e)),
+        ImportMode::Move => {
+            if fs::rename(source, target).await.is_ok() {
+                progress.copied_bytes = progress.total_bytes;
+                let _ = app_handle.emit("model-import-progress", progress.clone());
+                return Ok(());
+            }
+            // Different drive, copy then remove the original
+            copy_with_progress(source, target, progress, app_handle).await
+                .map_err(|e| format!("Failed to move: {}", e))?;
+            fs::remove_file(source).await
+                .map_err(|e| format!("Copied but could not remove the original: {}", e))
+        }
+        ImportMode::Symlink => {
+            let source = source.canonicalize().map_err(|e| format!("Failed to resolve: {}", e))?;
+            // Creating symlinks on Windows needs Developer Mode or admin rights
+            link_file(&source, target).await
+                .map_err(|e| format!("Failed to create link ({}), try copying instead", e))?;
+            progress.copied_bytes = progress.total_bytes;
+            let _ = app_handle.emit("model-import-progress", progress.clone());
+            Ok(())
+        }
+    }
+}
+
+async fn models_directory(state: &AppState) -> Result<PathBuf, String> {
+    let models_dir = PathBuf::from(state.config.lock().await.models_directory.clone());
+    if models_dir.as_os_str().is_empty() {
+        return Err("Set a models directory before importing".to_string());
+    }
+    Ok(models_dir)
+}
+
+/// Import dropped files into `<models_directory>/imported/`
+pub async fn import_model_files(
+    paths: Vec<String>,
+    mode: ImportMode,
+    on_conflict: ImportConflict,
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+) -> Result<ImportResult, String> {
+    let models_dir = models_directory(state).await?;
+    let destination = models_dir.join(IMPORT_FOLDER);
+    let files = with_sibling_shards(paths).into_iter().map(|source| (source, destination.clone())).collect();
+    import_files(files, &models_dir, mode, on_conflict, state, app_handle).await
+}
+
+/// Where LM Studio keeps its models: `~/.lmstudio/models`, or `~/.cache/lm-studio/models`
+/// for installs from before 0.3
+fn default_lm_studio_directory() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    [home.join(".lmstudio").join("models"), home.join(".cache").join("lm-studio").join("models")]
+        .into_iter()
+        .find(|dir| dir.is_dir())
+}
+
+/// GGUF files of an LM Studio library, each with the `publisher/repo` folder it sits in
+fn lm_studio_files(root: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let subfolders = |dir: &Path| -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+            .unwrap_or_default()
+    };
+    let mut files = Vec::new();
+    for publisher in subfolders(root) {
+        for repo in subfolders(&publisher) {
+            let Ok(entries) = std::fs::read_dir(&repo) else { continue };
+            let relative = repo.strip_prefix(root).unwrap_or(&repo).to_path_buf();
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.is_file() && path.extension().is_some_and(|e| e.eq_ignore_ascii_case("gguf")) {
+                    files.push((path, relative.clone()));
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Import an LM Studio library (`models/<publisher>/<repo>/<file>.gguf`) into the same
+/// `<models_directory>/<publisher>/<repo>/` layout Hub downloads use, so the files count
+/// as downloaded for their repo. Linking keeps them in place for LM Studio too.
+pub async fn import_lm_studio(
+    directory: Option<String>,
+    mode: ImportMode,
+    on_conflict: ImportConflict,
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+) -> Result<ImportResult, String> {
+    let models_dir = models_directory(state).await?;
+    let root = match directory.filter(|d| !d.trim().is_empty()) {
+        Some(directory) => PathBuf::from(directory),
+        None => default_lm_studio_directory().ok_or("No LM Studio models folder found, choose it manually")?,
+    };
+    if !root.is_dir() {
+        return Err(format!("{} is not a folder", root.display()));
+    }
+    println!("Importing LM Studio models from {}", root.display());
+    let files = lm_studio_files(&root)
+        .into_iter()
+        .map(|(source, relative)| (source, models_dir.join(relative)))
+        .collect();
+    import_files(files, &models_dir, mode, on_conflict, state, app_handle).await
+}
+
+/// Import each source file into the folder paired with it
+async fn import_files(
+    files: Vec<(PathBuf, PathBuf)>,
+    models_dir: &Path,
+    mode: ImportMode,
+    on_conflict: ImportConflict,
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+) -> Result<ImportResult, String> {
+    let models_dir = models_dir.canonicalize().unwrap_or(models_dir.to_path_buf());
+    let extra_directories = state.config.lock().await.extra_model_directories.clone();
+    let total = files.len();
+    let mut result = ImportResult::default();
+    let mut skip = |source: &Path, reason: String| {
+        println!("Skipping import of {}: {}", source.display(), reason);
+        result.skipped.push(SkippedFile { source: source.to_string_lossy().to_string(), reason });
+    };
+    let mut imported = Vec::new();
+
+    for (index, (source, destination)) in files.iter().enumerate() {
+        if !source.is_file() {
+            skip(source, "File not found".to_string());
+            continue;
+        }
+        if source.canonicalize().is_ok_and(|p| p.starts_with(&models_dir)) {
+            skip(source, "Already in the models directory".to_string());
+            continue;
+        }
+        match is_gguf(source) {
+            Ok(true) => {}
+            Ok(false) => {
+                skip(source, "Not a GGUF model file".to_string());
+                continue;
+            }
+            Err(e) => {
+                skip(source, format!("Failed to read: {}", e));
+                continue;
+            }
+        }
+
+        // Extra model directories are referenced in place, moving out of one would empty it
+        if matches!(mode, ImportMode::Move) && crate::scanner::in_extra_directory(source, &extra_directories) {
+            skip(source, "In a read-only extra model directory, copy or link it instead".to_string());
+            continue;
+        }
+
+        // Moving a file out from under a running server fails on Windows
+        if matches!(mode, ImportMode::Move) {
+            if let Some((_, message)) = crate::model_in_use(&source.to_string_lossy(), state).await {
+                skip(source, message);
+                continue;
+            }
+        }
+
+        if let Err(e) = fs::create_dir_all(destination).await {
+            skip(source, format!("Failed to create {}: {}", destination.display(), e));
+            continue;
+        }
+        let mut target = destination.join(source.file_name().unwrap_or_default());
+        // A link made by an earlier import already points here
+        if target.canonicalize().ok().zip(source.canonicalize().ok()).is_some_and(|(a, b)| a == b) {
+            skip(source, "Already imported".to_string());
+            continue;
+        }
+        if target.exists() || target.is_symlink() {
+            match on_conflict {
+                ImportConflict::Skip => {
+                    skip(source, format!("{} already exists", target.display()));
+                    continue;
+                }
+                // A renamed shard wouldn't be found by llama.cpp next to the others
+                ImportConflict::Rename if crate::scanner::SPLIT_FILE.is_match(&target.file_name().unwrap_or_default().to_string_lossy()) => {
+                    skip(source, format!("{} already exists", target.display()));
+                    continue;
+                }
+                ImportConflict::Rename => target = free_name(&target),
+                ImportConflict::Overwrite => {
+                    if let Some((_, message)) = crate::model_in_use(&target.to_string_lossy(), state).await {
+                        skip(source, message);
+                        continue;
+                    }
+                    if let Err(e) = fs::remove_file(&target).await {
+                        skip(source, format!("Failed to replace {}: {}", target.display(), e));
+                        continue;
+                    }
+                }
+            }
+        }
+
+        let mut progress = ImportProgress {
+            file: source.to_string_lossy().to_string(),
+            copied_bytes: 0,
+            total_bytes: std::fs::metadata(source).map(|m| m.len()).unwrap_or(0),
+            completed: index,
+            total,
+        };
+        let _ = app_handle.emit("model-import-progress", progress.clone());
+        match import_file(source, &target, mode, &mut progress, app_handle).await {
+            Ok(()) => imported.push(ImportedFile {
+                source: source.to_string_lossy().to_string(),
+                path: target.to_string_lossy().to_string(),
+            }),
+            Err(e) => skip(source, e),
+        }
+    }
+
+    result.imported = imported;
+    let _ = app_handle.emit("model-import-progress", ImportProgress {
+        file: String::new(),
+        copied_bytes: 0,
+        total_bytes: 0,
+        completed: total,
+        total,
+    });
+    Ok(result)
+}
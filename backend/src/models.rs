@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +15,48 @@ pub struct GlobalConfig {
     pub background_color: String,
     #[serde(default = "default_theme_is_synced")]
     pub theme_is_synced: bool,
+    #[serde(default)]
+    pub benchmark_on_version_change: bool,
+    #[serde(default)]
+    pub benchmark_reference_model: Option<String>,
+    #[serde(default = "default_shutdown_timeout_ms")]
+    pub shutdown_timeout_ms: u64,
+    #[serde(default)]
+    pub skip_shutdown_wait: bool,
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    #[serde(default)]
+    pub gpu_power: GpuPowerSettings,
+    #[serde(default)]
+    pub remote_host: RemoteHostConfig,
+    #[serde(default)]
+    pub offline_mode: bool,
+    #[serde(default)]
+    pub download_mirrors: Vec<String>, // Hub mirror base URLs, e.g. https://hf-mirror.com
+    #[serde(default)]
+    pub share_tunnel_path: String, // cloudflared executable, empty uses the one on PATH
+    #[serde(default)]
+    pub version_default_args: Vec<VersionArgRule>,
+    #[serde(default)]
+    pub encrypt_at_rest: bool, // Seal chats and secrets with a key from the OS keyring
+    #[serde(default)]
+    pub hf_token: String, // Hugging Face access token for private and gated repos, empty when unset
+    #[serde(default)]
+    pub download_speed_limit: u64, // Bytes per second shared by all downloads, 0 for unlimited
+    #[serde(default)]
+    pub manage_firewall_rules: bool, // Open the firewall for servers bound to a LAN address (Windows)
+    #[serde(default)]
+    pub request_log: RequestLogSettings,
+    #[serde(default)]
+    pub accelerated_transfer: AcceleratedTransferSettings,
+    #[serde(default)]
+    pub extra_model_directories: Vec<String>, // Read-only locations (NAS share, second drive) listed next to models_directory
+    #[serde(default)]
+    pub port_range: PortRange,
+    #[serde(default)]
+    pub convert_script_path: String, // llama.cpp's convert_hf_to_gguf.py, empty looks in the active version folder
+    #[serde(default)]
+    pub python_path: String, // Interpreter for the convert script, empty uses python3 (python on Windows) from PATH
 }
 
 fn default_background_color() -> String {
@@ -25,6 +67,14 @@ fn default_theme_is_synced() -> bool {
     true
 }
 
+fn default_shutdown_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_locale() -> String {
+    crate::i18n::DEFAULT_LOCALE.to_string()
+}
+
 impl Default for GlobalConfig {
     fn default() -> Self {
         let base_dir = dirs::home_dir().unwrap_or_default().join(".llama-os");
@@ -36,16 +86,86 @@ impl Default for GlobalConfig {
             theme_color: "dark-gray".to_string(),
             background_color: "dark-gray".to_string(),
             theme_is_synced: true,
+            benchmark_on_version_change: false,
+            benchmark_reference_model: None,
+            shutdown_timeout_ms: default_shutdown_timeout_ms(),
+            skip_shutdown_wait: false,
+            locale: default_locale(),
+            gpu_power: GpuPowerSettings::default(),
+            remote_host: RemoteHostConfig::default(),
+            offline_mode: false,
+            download_mirrors: Vec::new(),
+            share_tunnel_path: String::new(),
+            version_default_args: Vec::new(),
+            encrypt_at_rest: false,
+            hf_token: String::new(),
+            download_speed_limit: 0,
+            manage_firewall_rules: false,
+            request_log: RequestLogSettings::default(),
+            accelerated_transfer: AcceleratedTransferSettings::default(),
+            extra_model_directories: Vec::new(),
+            port_range: PortRange::default(),
+            convert_script_path: String::new(),
+            python_path: String::new(),
         }
     }
 }
 
+/// Ports local servers are moved to when the one they ask for is taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16, // Inclusive
+}
+
+impl Default for PortRange {
+    fn default() -> Self {
+        Self { start: 8080, end: 8179 }
+    }
+}
+
+/// Default arguments for a range of llama.cpp builds (`b<number>` version folders)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionArgRule {
+    #[serde(default)]
+    pub min_build: Option<u32>, // Inclusive, None for no lower bound
+    #[serde(default)]
+    pub max_build: Option<u32>, // Inclusive, None for no upper bound
+    pub args: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ModelConfig {
     pub custom_args: String,
     pub server_host: String,
     pub server_port: u16,
     pub model_path: String,
+    #[serde(default)]
+    pub disable_fixups: bool, // Opt out of automatic EOS/template corrections
+    #[serde(default)]
+    pub local_socket: bool, // Serve over a Unix socket (loopback-only port on Windows)
+    #[serde(default)]
+    pub auto_context: bool, // Size -c to the free VRAM at launch instead of a fixed value
+    #[serde(default)]
+    pub kv_overrides: Vec<KvOverride>, // Passed as --override-kv, validated against the model metadata
+    #[serde(default)]
+    pub mmproj_path: String, // Vision projector for --mmproj, empty uses an mmproj*.gguf next to the model
+    #[serde(default)]
+    pub lora_paths: Vec<String>, // LoRA adapters attached at launch, in order
+    #[serde(default)]
+    pub lora_scales: HashMap<String, f32>, // Scale per adapter path, passed with --lora-scaled when not 1.0
+    #[serde(default)]
+    pub tags: Vec<String>, // User labels such as "coding" or "roleplay", lowercase
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default)]
+    pub restart_policy: RestartPolicy, // What happens when the server exits without being stopped
+    #[serde(default)]
+    pub idle_timeout_minutes: u32, // Stop the server after this long without requests, 0 keeps it running
+    #[serde(default)]
+    pub env_vars: HashMap<String, String>, // Set on the server process, e.g. GGML_CUDA_FORCE_MMQ or HSA_OVERRIDE_GFX_VERSION
+    #[serde(default)]
+    pub launch_settings: LaunchSettings,
 }
 
 impl ModelConfig {
@@ -55,10 +175,307 @@ impl ModelConfig {
             server_host: "127.0.0.1".to_string(),
             server_port: 8080,
             model_path,
+            disable_fixups: false,
+            local_socket: false,
+            auto_context: false,
+            kv_overrides: Vec::new(),
+            mmproj_path: String::new(),
+            lora_paths: Vec::new(),
+            lora_scales: HashMap::new(),
+            tags: Vec::new(),
+            favorite: false,
+            restart_policy: RestartPolicy::default(),
+            idle_timeout_minutes: 0,
+            env_vars: HashMap::new(),
+            launch_settings: LaunchSettings::default(),
+        }
+    }
+}
+
+/// Which exits of a server that wasn't stopped from the app lead to a restart
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    #[default]
+    Never,
+    OnCrash, // Non-zero exit codes and signals
+    Always,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestartPolicy {
+    #[serde(default)]
+    pub mode: RestartMode,
+    #[serde(default = "default_max_restarts")]
+    pub max_retries: u32, // Consecutive restarts before giving up
+    #[serde(default = "default_restart_backoff")]
+    pub backoff_secs: u64, // Wait before the first restart, doubled for each further one
+}
+
+fn default_max_restarts() -> u32 {
+    3
+}
+
+fn default_restart_backoff() -> u64 {
+    5
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            max_retries: default_max_restarts(),
+            backoff_secs: default_restart_backoff(),
+        }
+    }
+}
+
+/// Common llama-server flags kept as fields, None leaves the server default.
+/// Anything else goes in `custom_args`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaunchSettings {
+    #[serde(default)]
+    pub context_size: Option<u32>,
+    #[serde(default)]
+    pub gpu_layers: Option<u32>,
+    #[serde(default)]
+    pub threads: Option<u32>,
+    #[serde(default)]
+    pub flash_attention: Option<FlashAttention>,
+    #[serde(default)]
+    pub batch_size: Option<u32>,
+    #[serde(default)]
+    pub ubatch_size: Option<u32>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlashAttention {
+    Auto,
+    On,
+    Off,
+}
+
+/// Value types llama.cpp accepts in `--override-kv key=type:value`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KvOverrideType {
+    Int,
+    Float,
+    Bool,
+    Str,
+}
+
+/// A GGUF metadata value replaced at load time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvOverride {
+    pub key: String,
+    pub value_type: KvOverrideType,
+    pub value: String,
+}
+
+/// A metadata key of a model and the override type that matches it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverridableKey {
+    pub key: String,
+    pub value_type: KvOverrideType,
+    pub current_value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgress {
+    pub operation: String,
+    pub completed: usize,
+    pub total: usize,
+    pub current: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub processed: usize,
+    pub paths: Vec<String>, // Resulting paths (new locations for moves)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub last_used: Option<DateTime<Utc>>,
+    pub launch_count: u64,
+    #[serde(default)]
+    pub last_launch: Option<LaunchRecord>,
+    #[serde(default)]
+    pub pinned_at: Option<DateTime<Utc>>, // Set while the model is pinned to the launcher
+}
+
+/// A launcher entry from real usage: pinned models, then the most recently launched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentModel {
+    pub path: String,
+    pub name: String,
+    pub last_used: Option<DateTime<Utc>>,
+    pub launch_count: u64,
+    pub pinned: bool,
+    pub exists: bool, // Pins are kept for files that went missing, e.g. an unplugged drive
+}
+
+/// The effective invocation of a model's last local launch, after port reallocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchRecord {
+    pub executable: String,
+    pub args: Vec<String>,
+    pub host: String,
+    pub port: u16,
+    pub config: ModelConfig, // Settings the launch was built from
+    pub launched_at: DateTime<Utc>,
+}
+
+/// A previous version of a model's settings, kept so experiments can be rolled back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelConfigSnapshot {
+    pub version: u32,
+    pub saved_at: DateTime<Utc>,
+    pub config: ModelConfig,
+}
+
+/// Experimental: run servers on another machine over SSH, with models and binaries living there
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RemoteHostConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub user: Option<String>,
+    pub ssh_port: Option<u16>,
+    pub identity_file: Option<String>,
+    pub llama_server_path: String, // Path on the remote machine, defaults to llama-server on PATH
+    pub models_directory: String,  // Path on the remote machine
+}
+
+/// NVIDIA power/clock settings applied through NVML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuPowerProfile {
+    pub name: String,
+    pub power_limit_watts: Option<u32>,
+    pub persistence_mode: Option<bool>,
+    pub min_clock_mhz: Option<u32>,
+    pub max_clock_mhz: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestLogSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub redact_patterns: Vec<String>, // Regexes masked in prompts and responses, on top of the built-in ones
+}
+
+/// Download large files as many parallel range requests instead of one stream
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AcceleratedTransferSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub connections: usize, // Range requests in flight per file, 0 for the default
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuPowerSettings {
+    pub enabled: bool,
+    pub generation_profile: Option<String>, // Applied when a model server starts
+    pub idle_profile: Option<String>,       // Applied after the original settings are restored
+    pub profiles: Vec<GpuPowerProfile>,
+}
+
+/// Unsaved edits applied on top of a stored `ModelConfig` when previewing a launch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchOverrides {
+    #[serde(default)]
+    pub custom_args: Option<String>,
+    #[serde(default)]
+    pub server_host: Option<String>,
+    #[serde(default)]
+    pub server_port: Option<u16>,
+}
+
+impl LaunchOverrides {
+    pub fn apply_to(self, config: &mut ModelConfig) {
+        // Like the settings window, the overriding arguments replace the launch settings too
+        if let Some(custom_args) = self.custom_args {
+            config.launch_settings = LaunchSettings::default();
+            config.custom_args = crate::launch_settings::lift(&mut config.launch_settings, &custom_args);
+        }
+        if let Some(server_host) = self.server_host {
+            config.server_host = server_host;
+        }
+        if let Some(server_port) = self.server_port {
+            config.server_port = server_port;
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchCommandPreview {
+    pub executable: String,
+    pub executable_exists: bool,
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>, // The model's environment variables, on top of the app's own
+    pub command_line: String,
+    pub port: u16,
+    pub port_reassigned: bool,
+    #[serde(default)]
+    pub context_size: Option<u64>, // Context picked by auto context for the free VRAM right now
+    pub current_args: Vec<String>,
+    pub added_args: Vec<String>,
+    pub removed_args: Vec<String>,
+}
+
+/// Arguments saved under a name to merge into any model's custom arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgumentPreset {
+    pub name: String,
+    pub args: String, // e.g. "--ctx-size 16384 -ngl 99 -fa"
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A named group of models launched and stopped as a unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackDefinition {
+    pub name: String,
+    pub members: Vec<StackMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackMember {
+    pub model_path: String,
+    /// Model paths of other members that must be launched before this one
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackMemberStatus {
+    pub model_path: String,
+    pub process_id: Option<String>,
+    pub status: Option<ProcessStatus>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StackState {
+    Running,
+    Partial,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackStatus {
+    pub name: String,
+    pub state: StackState,
+    pub members: Vec<StackMemberStatus>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub path: String,
@@ -68,12 +485,54 @@ pub struct ModelInfo {
     pub model_name: String,
     pub quantization: String,
     pub date: i64,
+    #[serde(default)]
+    pub shards: usize, // Files of a split model, 1 otherwise
+    #[serde(default)]
+    pub incomplete: bool, // Split model with shards missing, won't load until they are there
+    #[serde(default)]
+    pub read_only: bool, // Found in one of the extra model directories, never deleted or moved
+    #[serde(default)]
+    pub context_length: Option<u64>, // Trained context from the GGUF metadata
+    #[serde(default)]
+    pub parameter_count: Option<u64>, // Summed over the tensors of every shard
+    #[serde(default)]
+    pub embedding_length: Option<u64>,
+    #[serde(default)]
+    pub block_count: Option<u64>, // Layers, the upper bound for -ngl
+    #[serde(default)]
+    pub chat_template: Option<String>, // Jinja template embedded by the converter
+    #[serde(default)]
+    pub embedding_model: bool, // Embedding or reranking model, launched without chat
+}
+
+/// A tag and how many models carry it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelTag {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// A LoRA adapter found in the models directory or an extra model directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoraAdapterInfo {
+    pub path: String,
+    pub name: String,
+    pub size_bytes: u64,
+    pub architecture: Option<String>, // Of the base model it was trained on
+    pub base_model: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GgufMetadata {
     pub architecture: String,
     pub name: String,
+    pub file_type: Option<String>, // `general.type`, "model" or "adapter" when the converter sets it
+    pub context_length: Option<u64>,
+    pub parameter_count: Option<u64>, // Tensors of this file only
+    pub embedding_length: Option<u64>,
+    pub block_count: Option<u64>,
+    pub chat_template: Option<String>,
+    pub embedding_model: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,13 +546,61 @@ pub struct ProcessInfo {
     pub status: ProcessStatus,
     pub output: Vec<String>,
     pub created_at: DateTime<Utc>,
-    pub last_sent_line: Option<usize>,
+    #[serde(default)]
+    pub remote_host: Option<RemoteHostConfig>, // SSH host the server was launched on, used to stop it there
+    #[serde(default)]
+    pub restarts: u32, // Automatic restarts in a row, reset once a run outlasts the crash window
+    #[serde(default)]
+    pub metrics: ProcessMetrics,
+    #[serde(default)]
+    pub interactive: bool, // llama-cli session fed through stdin, there is no server to reach
+    #[serde(default)]
+    pub resources: Option<ProcessResourceUsage>, // Last sample, refreshed by get_process_stats
+}
+
+impl ProcessInfo {
+    /// Base URL a client reaches the server at, e.g. `http://127.0.0.1:8080`
+    pub fn client_url(&self) -> String {
+        format!("http://{}:{}", client_host(&self.host), self.port)
+    }
+}
+
+/// Host to connect to for a server bound to `host`, wildcard binds are reached over loopback
+pub fn client_host(host: &str) -> &str {
+    match host {
+        "0.0.0.0" | "::" | "" => "127.0.0.1",
+        host => host,
+    }
+}
+
+/// CPU, memory and VRAM of one local server's OS process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessResourceUsage {
+    pub process_id: String,
+    pub pid: u32,
+    pub cpu_percent: f32, // Share of the whole machine, like the system CPU usage
+    pub memory_bytes: u64, // Resident set
+    pub vram_bytes: Option<u64>, // None when no GPU driver reports per-process usage
+    pub sampled_at: DateTime<Utc>,
+}
+
+/// Speeds of the last finished request, from the timings llama-server logs after each one
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessMetrics {
+    pub last_tokens_per_second: Option<f64>, // Generation
+    pub last_generated_tokens: Option<u64>,
+    pub prompt_eval_ms: Option<f64>,
+    pub prompt_tokens_per_second: Option<f64>,
+    pub last_prompt_tokens: Option<u64>,
+    pub completed_requests: u64,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProcessStatus {
     Starting,
     Running,
+    Restarting, // Exited by itself, waiting out the restart backoff
     Stopped,
     Failed,
 }
@@ -106,12 +613,81 @@ pub struct LaunchResult {
     pub server_port: u16,
     pub model_name: String,
     pub message: String,
+    #[serde(default)]
+    pub computed_context: Option<u64>, // Context picked by auto sizing, if it ran
+}
+
+/// A temporary public URL for a running model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareLink {
+    pub id: String,
+    pub process_id: String,
+    pub public_url: String,
+    pub api_key: String, // Bearer token clients must send
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// An inbound firewall rule created for a model server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedFirewallRule {
+    pub name: String,
+    pub process_id: String,
+    pub port: u16,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub active: bool, // Its server is still running, filled in when listing
+}
+
+/// A process holding memory on a GPU
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VramConsumer {
+    pub pid: u32,
+    pub name: String,
+    pub used_bytes: Option<u64>, // None when the driver doesn't report per-process usage
+    pub process_id: Option<String>, // Set for model servers started by Llama-OS
+    pub model_name: Option<String>,
+}
+
+/// What a model is expected to need before launching it, from its GGUF header and settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelMemoryEstimate {
+    pub model_path: String,
+    pub context_size: u64,
+    pub trained_context: Option<u64>,
+    pub quantization: Option<String>,
+    pub block_count: u64,
+    pub gpu_layers: u64, // Layers the settings offload
+    pub weights_bytes: u64,
+    pub kv_cache_bytes: u64, // All layers at context_size
+    pub vram_bytes: u64, // Offloaded layers, their KV cache and compute buffers
+    pub ram_bytes: u64, // Layers left on the CPU
+    pub gpu_total_bytes: Option<u64>,
+    pub gpu_free_bytes: Option<u64>,
+    pub layers_that_fit: Option<u64>, // None when no GPU reports its memory
+    pub fits_in_vram: Option<bool>,
+    pub ram_available_bytes: u64,
+}
+
+/// Memory use of one GPU, attributed to the processes holding it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuVramBreakdown {
+    pub index: u32,
+    pub name: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub consumers: Vec<VramConsumer>,
+    pub unattributed_bytes: u64, // Driver, display and processes without a reported size
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessOutput {
     pub output: Vec<String>,
+    #[serde(default)]
+    pub next_line: u64, // Sequence number of the first line not in `output`
     pub is_running: bool,
+    #[serde(default)]
+    pub ready: bool, // Model loaded and `/health` answering, not just the process alive
     pub return_code: Option<i32>,
 }
 
@@ -166,6 +742,41 @@ pub struct TerminalState {
     pub port: u16,
     pub status: String,
     pub output: Vec<String>,
+    #[serde(default)]
+    pub history: Vec<String>, // Input sent to interactive tools over stdin, oldest first
+}
+
+// Scrollback and input kept per terminal, older entries are dropped first
+const TERMINAL_OUTPUT_LINES: usize = 1000;
+const TERMINAL_HISTORY_ENTRIES: usize = 200;
+
+impl TerminalState {
+    /// Cap scrollback and history to what a reopened terminal shows
+    pub fn trim(&mut self) {
+        if self.output.len() > TERMINAL_OUTPUT_LINES {
+            self.output.drain(..self.output.len() - TERMINAL_OUTPUT_LINES);
+        }
+        if self.history.len() > TERMINAL_HISTORY_ENTRIES {
+            self.history.drain(..self.history.len() - TERMINAL_HISTORY_ENTRIES);
+        }
+    }
+
+    /// Add an input line, skipping blanks and repeats of the previous one like a shell does
+    pub fn push_history(&mut self, input: &str) {
+        let input = input.trim_end_matches(['\r', '\n']);
+        if input.trim().is_empty() || self.history.last().is_some_and(|last| last == input) {
+            return;
+        }
+        self.history.push(input.to_string());
+        self.trim();
+    }
+}
+
+/// What a reopened terminal restores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalHistory {
+    pub output: Vec<String>,
+    pub history: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -174,6 +785,8 @@ pub struct ChatState {
     pub host: String,
     pub port: u16,
     pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub title: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -193,6 +806,17 @@ pub struct DesktopState {
     pub background: String,
     #[serde(default = "default_theme_is_synced")]
     pub theme_synced: bool,
+    #[serde(default)]
+    pub folders: Vec<DesktopFolder>,
+}
+
+/// A named group of model icons on the desktop, the files themselves stay where they are
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DesktopFolder {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub models: Vec<String>, // Model paths, each model is in at most one folder
 }
 
 impl Default for DesktopState {
@@ -204,6 +828,7 @@ impl Default for DesktopState {
             theme: "dark-gray".to_string(),
             background: "dark-gray".to_string(),
             theme_synced: true,
+            folders: Vec::new(),
         }
     }
 }
@@ -225,6 +850,20 @@ pub struct ModelBasic {
     pub likes: u64,
     #[serde(rename = "lastModified")]
     pub last_modified: Option<String>,
+    #[serde(default)]
+    pub pipeline_tag: Option<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<u64>, // From the Hub's GGUF metadata, or guessed from the repo name
+}
+
+/// Optional filters for Hub searches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HubSearchFilters {
+    pub pipeline_tag: Option<String>,   // e.g. "text-generation", "embedding"
+    pub license: Option<String>,        // e.g. "apache-2.0", "mit"
+    pub parameter_size: Option<String>, // "tiny" (<3B), "small" (3-9B), "medium" (9-35B), "large" (35-100B), "huge" (100B+)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -237,13 +876,32 @@ pub struct ModelDetails {
     pub likes: u64,
     pub total_files: u32,
     pub gguf_files: HashMap<String, GgufFileInfo>,
+    #[serde(default)]
+    pub gated: Option<String>, // "auto" or "manual" when the repo requires accepting terms
+    #[serde(default)]
+    pub revision: Option<String>, // Commit the file listing was taken at
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GgufFileInfo {
     pub filename: String,
-    pub size: u64,
+    pub size: u64, // All parts together for split models
     pub quantization_type: Option<String>,
+    #[serde(default)]
+    pub parts: Vec<String>, // Repo paths to download, every shard in order for split models
+    #[serde(default)]
+    pub is_mmproj: bool, // Vision/audio projector for multimodal models, not a model by itself
+    #[serde(default)]
+    pub modalities: Vec<String>, // Inputs a projector adds, "vision" and/or "audio"
+}
+
+/// Another quantization of a repo, offered when the selected one can't be downloaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantAlternative {
+    pub model_id: String,
+    pub quantization_type: Option<String>,
+    pub files: Vec<String>, // All shards of the quantization
+    pub size: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -253,8 +911,139 @@ pub struct QuantizationInfo {
     pub primary_file: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteGgufInfo {
+    pub url: String,
+    pub gguf_version: u32,
+    pub architecture: Option<String>,
+    pub name: Option<String>,
+    pub context_length: Option<u64>,
+    pub parameter_count: Option<u64>,
+    pub block_count: Option<u64>,
+    pub embedding_length: Option<u64>,
+    pub file_type: Option<u64>,
+    pub tensor_count: u64,
+    pub bytes_fetched: usize,
+}
+
+/// Latest health measurement of a download endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorHealth {
+    pub endpoint: String,
+    pub latency_ms: Option<u64>,
+    pub throughput_bps: Option<f64>,
+    pub healthy: bool,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Hub metadata for an installed repo, refreshed in the background
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpstreamMetadata {
+    pub repo_id: String,
+    pub downloads: u64,
+    pub likes: u64,
+    pub last_modified: Option<String>,
+    pub missing: bool,
+    pub disabled: bool,
+    pub upstream_updated: bool,
+    pub refreshed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GgufConversionStatus {
+    pub model_id: String,
+    pub status: String, // "existing", "requested", "completed", "timed_out"
+    pub repos: Vec<ModelBasic>,
+    pub space_url: String,
+}
+
+/// Stage of a local safetensors to GGUF conversion
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversionStep {
+    Downloading,
+    Converting,
+    Quantizing,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// A local conversion job, sent with every `hf-conversion-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionJob {
+    pub id: String,
+    pub model_id: String,
+    pub revision: Option<String>, // Branch or commit the weights come from, main when unset
+    pub quantization: String,
+    pub step: ConversionStep,
+    pub step_progress: f64, // 0.0 to 1.0 within the current step
+    pub message: String,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewSection {
+    Metadata,
+    Tensors,
+    Header,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TensorPreview {
+    pub name: String,
+    pub shape: Vec<u64>,
+    pub dtype: String,
+    pub elements: u64,
+    pub offset: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelFilePreview {
+    pub path: String,
+    pub file_size: u64,
+    pub section: PreviewSection,
+    pub gguf_version: Option<u32>,
+    pub tensor_count: Option<u64>,
+    pub metadata: Option<std::collections::BTreeMap<String, crate::gguf::GgufValue>>,
+    pub tensors: Option<Vec<TensorPreview>>,
+    pub header_hex: Option<Vec<String>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadStartResult {
     pub download_id: String,
     pub message: String,
+}
+
+/// A curated model with the quant picked for this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarterModel {
+    pub id: String,
+    pub name: String,
+    pub use_case: String, // chat, code, embedding or vision
+    pub repo_id: String,
+    pub description: String,
+    pub quant: String,
+    pub files: Vec<String>, // Everything the download needs, including projectors
+    pub size_bytes: u64,
+    pub fits: bool, // False when even the smallest quant exceeds the memory budget
+}
+
+/// Why a download could not start, with a code the frontend can act on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadError {
+    pub code: String, // "gated", "insufficient_space", "no_match", "model_in_use" or "failed"
+    pub message: String,
+    pub url: Option<String>, // Page that resolves the problem, e.g. the repo to accept terms on
+}
+
+impl From<String> for DownloadError {
+    fn from(message: String) -> Self {
+        Self { code: "failed".to_string(), message, url: None }
+    }
 }
\ No newline at end of file
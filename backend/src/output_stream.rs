@@ -0,0 +1,62 @@
+// Server output pushed to the terminal windows as it is read, instead of each window
+// polling for it. Every line gets a sequence number per process id, which survives
+// restarts, so a window that loads a snapshot first can skip the lines it already has.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, OnceLock};
+
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::models::ProcessStatus;
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+// Process id -> sequence number of its next line
+static NEXT_LINE: LazyLock<std::sync::Mutex<HashMap<String, u64>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// A batch of output lines, shaped like `ProcessOutput` plus where the batch starts
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessOutputEvent {
+    pub process_id: String,
+    pub first_line: u64,
+    pub lines: Vec<String>,
+    pub is_running: bool,
+    pub ready: bool,
+    pub return_code: Option<i32>,
+}
+
+pub fn init(app_handle: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+/// Sequence number the next line of the process will get
+pub fn next_line(process_id: &str) -> u64 {
+    NEXT_LINE.lock().unwrap().get(process_id).copied().unwrap_or(0)
+}
+
+/// Send a batch to the frontend. Callers hold the process registry lock so the
+/// numbering matches the order lines land in the output buffer.
+pub fn emit(process_id: &str, lines: Vec<String>, status: &ProcessStatus, return_code: Option<i32>) {
+    let first_line = {
+        let mut next = NEXT_LINE.lock().unwrap();
+        let counter = next.entry(process_id.to_string()).or_insert(0);
+        let first_line = *counter;
+        *counter += lines.len() as u64;
+        first_line
+    };
+    let Some(app_handle) = APP_HANDLE.get() else {
+        return;
+    };
+    let _ = app_handle.emit("process-output", ProcessOutputEvent {
+        process_id: process_id.to_string(),
+        first_line,
+        lines,
+        is_running: matches!(status, ProcessStatus::Running | ProcessStatus::Starting | ProcessStatus::Restarting),
+        ready: matches!(status, ProcessStatus::Running),
+        return_code,
+    });
+}
+
+pub fn forget(process_id: &str) {
+    NEXT_LINE.lock().unwrap().remove(process_id);
+}
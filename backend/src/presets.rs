@@ -0,0 +1,107 @@
+// Named argument presets ("Low VRAM", "Long context") kept in settings.json next to the
+// model configs. Applying one merges its flags into a model's custom arguments: a flag
+// the preset sets replaces the model's own, spelled either way, and the rest are kept.
+
+use crate::config::save_settings;
+use crate::models::ArgumentPreset;
+use crate::process::{parse_custom_args, quote_arg};
+use crate::AppState;
+
+// Short and long spellings of the same llama-server flag
+const FLAG_ALIASES: &[&[&str]] = &[
+    &["-c", "--ctx-size"],
+    &["-ngl", "--gpu-layers", "--n-gpu-layers"],
+    &["-fa", "--flash-attn"],
+    &["-t", "--threads"],
+    &["-b", "--batch-size"],
+    &["-ub", "--ubatch-size"],
+    &["-np", "--parallel"],
+    &["-ctk", "--cache-type-k"],
+    &["-ctv", "--cache-type-v"],
+    &["-ts", "--tensor-split"],
+    &["-sm", "--split-mode"],
+];
+
+/// A flag followed by its values, e.g. `--ctx-size 16384` or a bare `--no-mmap`
+pub type ArgGroup = (String, Vec<String>);
+
+pub fn is_flag(arg: &str) -> bool {
+    // `-1` is a value (e.g. `-n -1`), not a flag
+    arg.starts_with('-') && arg.chars().nth(1).is_some_and(|c| c.is_ascii_alphabetic() || c == '-')
+}
+
+/// `--flag=value` counts as the flag, so it is matched like `--flag value`
+fn flag_name(flag: &str) -> &str {
+    flag.split('=').next().unwrap_or(flag)
+}
+
+pub fn same_flag(a: &str, b: &str) -> bool {
+    let (a, b) = (flag_name(a), flag_name(b));
+    a == b || FLAG_ALIASES.iter().any(|group| group.contains(&a) && group.contains(&b))
+}
+
+pub fn group_args(args: &str) -> Vec<ArgGroup> {
+    let mut groups: Vec<ArgGroup> = Vec::new();
+    for arg in parse_custom_args(args) {
+        match groups.last_mut() {
+            Some((_, values)) if !is_flag(&arg) => values.push(arg),
+            // Values before any flag are kept as they are
+            None if !is_flag(&arg) => groups.push((String::new(), vec![arg])),
+            _ => groups.push((arg, Vec::new())),
+        }
+    }
+    groups
+}
+
+/// The model's custom arguments with the preset's flags laid over them
+pub fn merge_args(custom_args: &str, preset_args: &str) -> String {
+    let preset = group_args(preset_args);
+    let kept: Vec<ArgGroup> = group_args(custom_args)
+        .into_iter()
+        .filter(|(flag, _)| flag.is_empty() || !preset.iter().any(|(preset_flag, _)| same_flag(flag, preset_flag)))
+        .collect();
+    kept.into_iter()
+        .chain(preset)
+        .flat_map(|(flag, values)| std::iter::once(flag).chain(values))
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| quote_arg(&arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub async fn list(state: &AppState) -> Vec<ArgumentPreset> {
+    let mut presets: Vec<ArgumentPreset> = state.presets.lock().await.values().cloned().collect();
+    presets.sort_by_key(|p| p.name.to_lowercase());
+    presets
+}
+
+pub async fn save(preset: ArgumentPreset, state: &AppState) -> Result<(), String> {
+    let name = preset.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    if group_args(&preset.args).is_empty() {
+        return Err("A preset needs at least one argument".to_string());
+    }
+    state.presets.lock().await.insert(name.clone(), ArgumentPreset { name, ..preset });
+    save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+pub async fn delete(name: &str, state: &AppState) -> Result<(), String> {
+    state.presets.lock().await.remove(name);
+    save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Merge a preset into each model's arguments, each change lands in the model's settings history
+pub async fn apply(name: &str, model_paths: &[String], state: &AppState) -> Result<usize, String> {
+    let preset = state.presets.lock().await.get(name).cloned()
+        .ok_or_else(|| format!("Preset {} not found", name))?;
+    for model_path in model_paths {
+        let mut config = state.library.config_for(model_path).await;
+        let custom_args = merge_args(&config.custom_args, &preset.args);
+        config.custom_args = crate::launch_settings::lift(&mut config.launch_settings, &custom_args);
+        state.library.set_config(model_path, config).await;
+    }
+    save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(model_paths.len())
+}
@@ -1,5 +1,5 @@
-use tokio::process::{Child, Command as TokioCommand};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command as TokioCommand};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use std::process::Stdio;
 use uuid::Uuid;
 use chrono::Utc;
@@ -8,17 +8,15 @@ use tokio::sync::Mutex;
 use crate::models::*;
 use crate::AppState;
 use crate::config::save_settings;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 
-async fn resolve_llama_server_path_with_fallback(
-    state: &AppState,
-    global_config: &GlobalConfig,
-) -> std::path::PathBuf {
-    use std::fs;
-    use std::time::SystemTime;
-
+/// Build the llama-server path for the configured active version, without any fallback
+fn preferred_llama_server_path(global_config: &GlobalConfig) -> std::path::PathBuf {
     let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
-    // First, build the preferred path using active version or active folder
-    let preferred = if let Some(version_name) = &global_config.active_executable_version {
+    if let Some(version_name) = &global_config.active_executable_version {
         std::path::Path::new(&global_config.executable_folder)
             .join("versions")
             .join(version_name)
@@ -27,13 +25,49 @@ async fn resolve_llama_server_path_with_fallback(
         std::path::Path::new(active_path).join(exe_name)
     } else {
         std::path::Path::new(&global_config.executable_folder).join(exe_name)
-    };
+    }
+}
+
+pub async fn resolve_llama_server_path_with_fallback(
+    state: &AppState,
+    global_config: &GlobalConfig,
+) -> std::path::PathBuf {
+    let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+    // First, build the preferred path using active version or active folder
+    let preferred = preferred_llama_server_path(global_config);
 
     if preferred.exists() {
         return preferred;
     }
 
-    // Fallback: look for the latest installed version under <exec>/versions having the server binary
+    if let Some(chosen_dir) = latest_installed_version_dir(global_config) {
+        // Update config to set this as active
+        {
+            let mut cfg = state.config.lock().await;
+            let path_str = chosen_dir.to_string_lossy().to_string();
+            let version_name = chosen_dir
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_string();
+            cfg.active_executable_folder = Some(path_str);
+            cfg.active_executable_version = Some(version_name);
+        }
+        if let Err(e) = save_settings(state).await {
+            eprintln!("Warning: failed to save settings after fallback activation: {}", e);
+        }
+        return chosen_dir.join(exe_name);
+    }
+
+    preferred
+}
+
+/// Find the most recently installed version under <exec>/versions that has the server binary
+fn latest_installed_version_dir(global_config: &GlobalConfig) -> Option<std::path::PathBuf> {
+    use std::fs;
+    use std::time::SystemTime;
+
+    let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
     let versions_dir = std::path::Path::new(&global_config.executable_folder).join("versions");
     let mut candidates: Vec<(std::path::PathBuf, Option<SystemTime>)> = Vec::new();
     if versions_dir.exists() {
@@ -62,26 +96,78 @@ async fn resolve_llama_server_path_with_fallback(
         (None, None) => b.0.cmp(&a.0),
     });
 
-    if let Some((chosen_dir, _)) = candidates.first() {
-        // Update config to set this as active
-        {
-            let mut cfg = state.config.lock().await;
-            let path_str = chosen_dir.to_string_lossy().to_string();
-            let version_name = chosen_dir
-                .file_name()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_string();
-            cfg.active_executable_folder = Some(path_str);
-            cfg.active_executable_version = Some(version_name);
-        }
-        if let Err(e) = save_settings(state).await {
-            eprintln!("Warning: failed to save settings after fallback activation: {}", e);
-        }
-        return chosen_dir.join(exe_name);
+    candidates.into_iter().next().map(|(dir, _)| dir)
+}
+
+/// Result of the startup integrity check when it had to switch versions
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VersionChangeWarning {
+    pub previous_version: Option<String>,
+    pub previous_path: Option<String>,
+    pub active_version: Option<String>,
+    pub active_path: Option<String>,
+    pub message: String,
+}
+
+// The check runs before any window has loaded, so the warning waits here until the UI asks
+static STARTUP_VERSION_WARNING: std::sync::Mutex<Option<VersionChangeWarning>> = std::sync::Mutex::new(None);
+
+pub fn set_startup_version_warning(warning: VersionChangeWarning) {
+    *STARTUP_VERSION_WARNING.lock().unwrap() = Some(warning);
+}
+
+/// The startup check's warning, handed out once so a reload doesn't show it again
+pub fn take_startup_version_warning() -> Option<VersionChangeWarning> {
+    STARTUP_VERSION_WARNING.lock().unwrap().take()
+}
+
+/// Verify at startup that the active llama.cpp version still exists and contains llama-server.
+/// If it does not, the fallback resolution runs eagerly so the problem surfaces as a warning
+/// instead of a failure on the first launch attempt.
+pub async fn verify_active_llamacpp_version(state: &AppState) -> Option<VersionChangeWarning> {
+    let global_config = {
+        let config = state.config.lock().await;
+        config.clone()
+    };
+
+    // Nothing configured yet, the version manager will pick one on first use
+    if global_config.active_executable_version.is_none() && global_config.active_executable_folder.is_none() {
+        return None;
     }
 
-    preferred
+    let preferred = preferred_llama_server_path(&global_config);
+    if preferred.exists() {
+        return None;
+    }
+
+    println!("Active llama.cpp version is missing llama-server at {:?}, resolving fallback", preferred);
+    let resolved = resolve_llama_server_path_with_fallback(state, &global_config).await;
+
+    let (active_version, active_path) = {
+        let config = state.config.lock().await;
+        (config.active_executable_version.clone(), config.active_executable_folder.clone())
+    };
+
+    let message = if resolved.exists() {
+        format!(
+            "Active llama.cpp version '{}' is missing or incomplete, switched to '{}'",
+            global_config.active_executable_version.as_deref().unwrap_or("unknown"),
+            active_version.as_deref().unwrap_or("unknown")
+        )
+    } else {
+        format!(
+            "Active llama.cpp version '{}' is missing or incomplete and no other installed version contains llama-server",
+            global_config.active_executable_version.as_deref().unwrap_or("unknown")
+        )
+    };
+
+    Some(VersionChangeWarning {
+        previous_version: global_config.active_executable_version,
+        previous_path: global_config.active_executable_folder,
+        active_version,
+        active_path,
+        message,
+    })
 }
 
 // Simple wrapper for child process that ensures cleanup
@@ -89,17 +175,29 @@ async fn resolve_llama_server_path_with_fallback(
 #[derive(Debug)]
 pub struct ProcessHandle {
     child: Option<Child>,
+    stdin: Option<ChildStdin>, // Only piped for interactive sessions
     process_id: String,
 }
 
 impl ProcessHandle {
-    fn new(child: Child, process_id: String) -> Self {
+    pub fn new(mut child: Child, process_id: String) -> Self {
         Self {
+            stdin: child.stdin.take(),
             child: Some(child),
             process_id,
         }
     }
     
+    /// Write one line to the process's stdin
+    pub async fn write_line(&mut self, line: &str) -> Result<(), String> {
+        let stdin = self.stdin.as_mut().ok_or("Process does not accept input")?;
+        let written = match stdin.write_all(format!("{}\n", line).as_bytes()).await {
+            Ok(()) => stdin.flush().await,
+            Err(e) => Err(e),
+        };
+        written.map_err(|e| format!("Failed to write to process: {}", e))
+    }
+    
     pub fn take_child(&mut self) -> Option<Child> {
         self.child.take()
     }
@@ -130,14 +228,17 @@ pub async fn launch_model_server(
     model_path: String,
     state: &AppState,
 ) -> Result<LaunchResult, Box<dyn std::error::Error>> {
-    let (global_config, model_config) = {
-        let config = state.config.lock().await;
-        let model_configs = state.model_configs.lock().await;
-        let model_config = model_configs.get(&model_path)
-            .cloned()
-            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
-        (config.clone(), model_config)
-    };
+    // A split model is launched, configured and tracked by its first shard
+    let model_path = crate::scanner::first_shard(&model_path);
+    let global_config = state.config.lock().await.clone();
+    let model_config = state.library.config_for(&model_path).await;
+    crate::idle_shutdown::wake(&model_path);
+    
+    if global_config.remote_host.enabled {
+        let result = crate::remote::launch_remote_model_server(model_config, &global_config.remote_host, state).await?;
+        crate::config::record_model_usage(state, &model_path, None).await;
+        return Ok(result);
+    }
     
     // Resolve server path with fallback to latest installed version if needed
     let executable_path = resolve_llama_server_path_with_fallback(state, &global_config).await;
@@ -146,22 +247,135 @@ pub async fn launch_model_server(
         return Err(format!("Server executable not found at: {:?}", executable_path).into());
     }
     
-    let requested_port = parse_port_from_args(&model_config.custom_args, model_config.server_port);
-    let actual_port = find_available_port(requested_port);
+    let process_id = Uuid::new_v4().to_string();
     
-    // If we had to change the port, update the model config for this session
-    let final_port = if actual_port != requested_port {
-        println!("Port {} was in use, using port {} instead", requested_port, actual_port);
-        actual_port
+    // Local-only servers skip port management and are always reached over loopback.
+    // The bridge is stopped again if anything below fails before the server is running
+    let (server_config, client_host, final_port, bridge) = if model_config.local_socket {
+        let local = crate::local_socket::prepare_launch(&process_id, &model_config).await?;
+        (local.server_config, "127.0.0.1".to_string(), local.port, Some(local.bridge))
     } else {
-        requested_port
+        let port = resolve_launch_port(state, &model_config).await?;
+        (model_config.clone(), model_config.server_host.clone(), port, None)
     };
     
+    // llama-server only reports a missing adapter after it has started loading the model
+    if let Some(missing) = model_config.lora_paths.iter().find(|p| !std::path::Path::new(p).is_file()) {
+        return Err(format!("LoRA adapter not found: {}", missing).into());
+    }
+    
     // Build command with custom args if any
-    let mut cmd = TokioCommand::new(&executable_path);
-    cmd.args(["-m", &model_config.model_path])
-       .args(["--host", &model_config.server_host])
-       .args(["--port", &final_port.to_string()])
+    let model_files = read_model_file_args(&server_config).await;
+    let mut server_args = build_server_args(&server_config, final_port, &model_files);
+    apply_version_defaults(&global_config, &executable_path, &mut server_args);
+    
+    // Size the context to the VRAM left after the offloaded weights
+    let computed_context = if model_config.auto_context {
+        let context = crate::context_fit::fit_for_launch(&model_path, &server_args).await?;
+        match context {
+            Some(context) => {
+                println!("Auto context for {}: {} tokens", model_path, context);
+                crate::context_fit::apply_context(&mut server_args, context);
+            }
+            None => println!("Free VRAM unavailable, keeping the configured context for {}", model_path),
+        }
+        context
+    } else {
+        None
+    };
+    
+    let mut result = spawn_server_process(state, process_id, &executable_path, &model_config, client_host.clone(), final_port, server_args.clone(), Vec::new()).await?;
+    if let Some(bridge) = bridge {
+        bridge.keep();
+    }
+    
+    let last_launch = LaunchRecord {
+        executable: executable_path.to_string_lossy().to_string(),
+        args: server_args,
+        host: client_host,
+        port: final_port,
+        config: model_config,
+        launched_at: Utc::now(),
+    };
+    crate::config::record_model_usage(state, &model_path, Some(last_launch)).await;
+    
+    result.computed_context = computed_context;
+    Ok(result)
+}
+
+/// Start a model again with the exact invocation of its previous local launch,
+/// ignoring any settings edited since then
+pub async fn relaunch_last(
+    model_path: String,
+    state: &AppState,
+) -> Result<LaunchResult, Box<dyn std::error::Error>> {
+    let record = state.library.last_launch(&model_path).await
+        .ok_or("No previous launch recorded for this model")?;
+    crate::idle_shutdown::wake(&model_path);
+    
+    let executable_path = std::path::PathBuf::from(&record.executable);
+    if !executable_path.exists() {
+        return Err(format!("Server executable from the previous launch no longer exists at: {:?}", executable_path).into());
+    }
+    
+    let process_id = Uuid::new_v4().to_string();
+    let mut server_args = record.args.clone();
+    let (client_host, final_port, bridge) = if record.config.local_socket {
+        // Socket paths are per process, only they are swapped out
+        let local = crate::local_socket::prepare_launch(&process_id, &record.config).await?;
+        set_arg_value(&mut server_args, "--host", local.server_config.server_host);
+        set_arg_value(&mut server_args, "--port", local.port.to_string());
+        ("127.0.0.1".to_string(), local.port, Some(local.bridge))
+    } else {
+        if !is_port_available(record.port) || state.processes.held_ports().await.contains(&record.port) {
+            return Err(format!("Port {} used by the previous launch is in use", record.port).into());
+        }
+        (record.host.clone(), record.port, None)
+    };
+    
+    let result = spawn_server_process(state, process_id, &executable_path, &record.config, client_host.clone(), final_port, server_args.clone(), Vec::new()).await?;
+    if let Some(bridge) = bridge {
+        bridge.keep();
+    }
+    
+    let last_launch = LaunchRecord {
+        args: server_args,
+        host: client_host,
+        port: final_port,
+        launched_at: Utc::now(),
+        ..record
+    };
+    crate::config::record_model_usage(state, &model_path, Some(last_launch)).await;
+    
+    Ok(result)
+}
+
+/// Replace the value following the first occurrence of a flag
+fn set_arg_value(args: &mut [String], flag: &str, value: String) {
+    if let Some(index) = args.iter().position(|a| a == flag) {
+        if let Some(slot) = args.get_mut(index + 1) {
+            *slot = value;
+        }
+    }
+}
+
+/// Spawn llama-server with resolved arguments and start tracking it
+#[allow(clippy::too_many_arguments)]
+pub async fn spawn_server_process(
+    state: &AppState,
+    process_id: String,
+    executable_path: &std::path::Path,
+    model_config: &ModelConfig,
+    client_host: String,
+    final_port: u16,
+    server_args: Vec<String>,
+    envs: Vec<(String, String)>,
+) -> Result<LaunchResult, Box<dyn std::error::Error>> {
+    // The launch's own variables win over the model's, trials rely on theirs
+    let mut cmd = TokioCommand::new(executable_path);
+    cmd.args(&server_args)
+       .envs(&model_config.env_vars)
+       .envs(envs)
        .stdout(Stdio::piped())
        .stderr(Stdio::piped())
        .kill_on_drop(true); // Ensure child process is killed when dropped
@@ -170,14 +384,13 @@ pub async fn launch_model_server(
     #[cfg(all(windows, not(debug_assertions)))]
     cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
     
-    // Add custom arguments if present
-    if !model_config.custom_args.trim().is_empty() {
-        let custom_args = parse_custom_args(&model_config.custom_args);
-        cmd.args(custom_args);
-    }
-    
-    let mut child = cmd.spawn()?;
-    let process_id = Uuid::new_v4().to_string();
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            crate::local_socket::stop_bridge(&process_id);
+            return Err(e.into());
+        }
+    };
     
     // Get stdout and stderr for output capture
     let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
@@ -193,27 +406,23 @@ pub async fn launch_model_server(
         id: process_id.clone(),
         model_path: model_config.model_path.clone(),
         model_name: model_name.clone(),
-        host: model_config.server_host.clone(),
+        host: client_host.clone(),
         port: final_port,
-        command: vec![executable_path.to_string_lossy().to_string()],
+        command: std::iter::once(executable_path.to_string_lossy().to_string()).chain(server_args).collect(),
         status: ProcessStatus::Starting,
         output: Vec::new(),
         created_at: Utc::now(),
-        last_sent_line: Some(0),
+        remote_host: None,
+        restarts: 0,
+        metrics: ProcessMetrics::default(),
+        interactive: false,
+        resources: None,
     };
     
-    // Store the process info and child
-    {
-        let mut processes = state.running_processes.lock().await;
-        processes.insert(process_id.clone(), process_info);
-    }
-    
-    // Store the child process using simplified wrapper
+    // Store the process info and the child using simplified wrapper
     let process_handle = Arc::new(Mutex::new(ProcessHandle::new(child, process_id.clone())));
-    {
-        let mut child_processes = state.child_processes.lock().await;
-        child_processes.insert(process_id.clone(), process_handle.clone());
-    }
+    state.processes.register(process_info, process_handle.clone()).await;
+    release_port_reservation(final_port);
     
     // Spawn task to handle output capture
     let state_clone = state.clone();
@@ -224,13 +433,18 @@ pub async fn launch_model_server(
         handle_process_output(state_clone, process_id_clone, handle_clone, stdout, stderr).await;
     });
     
+    crate::gpu_power::on_model_started(state).await;
+    crate::firewall::on_model_started(state, &process_id, &model_config.server_host, final_port).await;
+    crate::capabilities::spawn_startup_probe(process_id.clone(), state.clone());
+    
     Ok(LaunchResult {
         success: true,
         process_id,
-        server_host: model_config.server_host,
+        server_host: client_host,
         server_port: final_port,
         model_name,
         message: "Model server launched successfully".to_string(),
+        computed_context: None,
     })
 }
 
@@ -238,14 +452,9 @@ pub async fn launch_model_external(
     model_path: String,
     state: &AppState,
 ) -> Result<LaunchResult, Box<dyn std::error::Error>> {
-    let (global_config, model_config) = {
-        let config = state.config.lock().await;
-        let model_configs = state.model_configs.lock().await;
-        let model_config = model_configs.get(&model_path)
-            .cloned()
-            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
-        (config.clone(), model_config)
-    };
+    let model_path = crate::scanner::first_shard(&model_path);
+    let global_config = state.config.lock().await.clone();
+    let model_config = state.library.config_for(&model_path).await;
     
     // Resolve server path with fallback to latest installed version if needed
     let executable_path = resolve_llama_server_path_with_fallback(state, &global_config).await;
@@ -254,32 +463,12 @@ pub async fn launch_model_external(
         return Err(format!("Server executable not found at: {:?}", executable_path).into());
     }
     
-    let requested_port = parse_port_from_args(&model_config.custom_args, model_config.server_port);
-    let actual_port = find_available_port(requested_port);
-    
-    // If we had to change the port, update the model config for this session
-    let final_port = if actual_port != requested_port {
-        println!("Port {} was in use, using port {} instead", requested_port, actual_port);
-        actual_port
-    } else {
-        requested_port
-    };
+    let final_port = resolve_launch_port(state, &model_config).await?;
     
     // For external launch, spawn in a new terminal window
-    let mut cmd_args = vec![
-        "-m".to_string(),
-        model_config.model_path.clone(),
-        "--host".to_string(),
-        model_config.server_host.clone(),
-        "--port".to_string(),
-        final_port.to_string(),
-    ];
-    
-    // Add custom arguments if present
-    if !model_config.custom_args.trim().is_empty() {
-        let custom_args = parse_custom_args(&model_config.custom_args);
-        cmd_args.extend(custom_args);
-    }
+    let model_files = read_model_file_args(&model_config).await;
+    let mut cmd_args = build_server_args(&model_config, final_port, &model_files);
+    apply_version_defaults(&global_config, &executable_path, &mut cmd_args);
     
     // Launch in external terminal
     #[cfg(windows)]
@@ -287,29 +476,60 @@ pub async fn launch_model_external(
         let mut cmd = TokioCommand::new("cmd");
         cmd.args(["/c", "start", "cmd", "/k"])
            .arg(executable_path.to_string_lossy().to_string())
-           .args(&cmd_args);
+           .args(&cmd_args)
+           .envs(&model_config.env_vars);
         cmd.spawn()?;
     }
     
-    #[cfg(not(windows))]
+    // Terminal.app can't be handed a command line, so it opens a throwaway .command script
+    #[cfg(target_os = "macos")]
+    {
+        use crate::remote::shell_quote;
+        use std::os::unix::fs::PermissionsExt;
+
+        // The script removes itself straight away, sh keeps reading it through the open file
+        let mut script = String::from("#!/bin/sh\nrm -f -- \"$0\"\n");
+        for (key, value) in &model_config.env_vars {
+            script.push_str(&format!("export {}={}\n", key, shell_quote(value)));
+        }
+        script.push_str(&std::iter::once(executable_path.to_string_lossy().to_string())
+            .chain(cmd_args.iter().cloned())
+            .map(|arg| shell_quote(&arg))
+            .collect::<Vec<_>>()
+            .join(" "));
+        script.push('\n');
+
+        let script_path = std::env::temp_dir().join(format!("llama-os-{}.command", Uuid::new_v4()));
+        std::fs::write(&script_path, script)?;
+        std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755))?;
+        TokioCommand::new("open")
+            .args(["-a", "Terminal"])
+            .arg(&script_path)
+            .spawn()?;
+    }
+
+    #[cfg(all(not(windows), not(target_os = "macos")))]
     {
         let mut cmd = TokioCommand::new("x-terminal-emulator");
         cmd.args(["-e"])
            .arg(executable_path.to_string_lossy().to_string())
-           .args(&cmd_args);
+           .args(&cmd_args)
+           .envs(&model_config.env_vars);
         
         // Fallback to other terminal emulators if x-terminal-emulator fails
         if cmd.spawn().is_err() {
             let mut cmd = TokioCommand::new("gnome-terminal");
             cmd.args(["--"])
                .arg(executable_path.to_string_lossy().to_string())
-               .args(&cmd_args);
+               .args(&cmd_args)
+               .envs(&model_config.env_vars);
             
             if cmd.spawn().is_err() {
                 let mut cmd = TokioCommand::new("xterm");
                 cmd.args(["-e"])
                    .arg(executable_path.to_string_lossy().to_string())
-                   .args(&cmd_args);
+                   .args(&cmd_args)
+                   .envs(&model_config.env_vars);
                 cmd.spawn()?;
             }
         }
@@ -328,10 +548,89 @@ pub async fn launch_model_external(
         server_port: final_port,
         model_name,
         message: "Model launched in external terminal".to_string(),
+        computed_context: None,
+    })
+}
+
+/// Resolve the full command line a launch would use, applying unsaved overrides, without spawning
+pub async fn preview_launch_command(
+    model_path: String,
+    overrides: Option<LaunchOverrides>,
+    state: &AppState,
+) -> Result<LaunchCommandPreview, Box<dyn std::error::Error>> {
+    let model_path = crate::scanner::first_shard(&model_path);
+    let global_config = state.config.lock().await.clone();
+    let saved_config = state.library.config_for(&model_path).await;
+    
+    let mut model_config = saved_config.clone();
+    if let Some(overrides) = overrides {
+        overrides.apply_to(&mut model_config);
+    }
+    
+    // Same resolution as a real launch, but without persisting a fallback activation
+    let preferred = preferred_llama_server_path(&global_config);
+    let executable_path = if preferred.exists() {
+        preferred
+    } else {
+        let exe_name = preferred.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+        latest_installed_version_dir(&global_config)
+            .map(|dir| dir.join(exe_name))
+            .unwrap_or(preferred)
+    };
+    
+    let requested_port = parse_port_from_args(&model_config.custom_args, model_config.server_port);
+    let port = allocate_port(state, requested_port, false).await?;
+    let model_files = read_model_file_args(&model_config).await;
+    let mut args = build_server_args(&model_config, port, &model_files);
+    apply_version_defaults(&global_config, &executable_path, &mut args);
+    
+    // Diff against what the saved settings would produce on the same port
+    let saved_files = read_model_file_args(&saved_config).await;
+    let mut current_args = build_server_args(&saved_config, port, &saved_files);
+    apply_version_defaults(&global_config, &executable_path, &mut current_args);
+    let added_args = args.iter().filter(|a| !current_args.contains(a)).cloned().collect();
+    let removed_args = current_args.iter().filter(|a| !args.contains(a)).cloned().collect();
+    
+    // Fitted after the diff, the context follows free VRAM rather than the settings
+    let context_size = if model_config.auto_context {
+        let context = crate::context_fit::fit_for_launch(&model_path, &args).await?;
+        if let Some(context) = context {
+            crate::context_fit::apply_context(&mut args, context);
+        }
+        context
+    } else {
+        None
+    };
+    
+    let env: BTreeMap<String, String> = model_config.env_vars.clone().into_iter().collect();
+    let executable = executable_path.to_string_lossy().to_string();
+    let command = std::iter::once(&executable)
+        .chain(args.iter())
+        .map(|arg| quote_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let command_line = env_prefix(&env) + &command;
+    
+    Ok(LaunchCommandPreview {
+        executable,
+        executable_exists: executable_path.exists(),
+        args,
+        env,
+        command_line,
+        port,
+        port_reassigned: port != requested_port,
+        context_size,
+        current_args,
+        added_args,
+        removed_args,
     })
 }
 
-async fn handle_process_output(
+// Output is sent to the terminal windows every so often, or sooner once this many lines are waiting
+const OUTPUT_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+const OUTPUT_BATCH_LINES: usize = 200;
+
+pub async fn handle_process_output(
     state: AppState,
     process_id: String,
     process_handle: Arc<Mutex<ProcessHandle>>,
@@ -344,21 +643,21 @@ async fn handle_process_output(
     let mut stdout_lines = stdout_reader.lines();
     let mut stderr_lines = stderr_reader.lines();
     
-    // Update status to running
-    {
-        let mut processes = state.running_processes.lock().await;
-        if let Some(process_info) = processes.get_mut(&process_id) {
-            process_info.status = ProcessStatus::Running;
-        }
-    }
+    // Lines go out in batches, a model load prints hundreds of them at once
+    let mut pending: Vec<String> = Vec::new();
+    let mut flush = tokio::time::interval(OUTPUT_FLUSH_INTERVAL);
+    flush.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
     
+    // The status stays Starting until the readiness job sees `/health` answer 200
     loop {
         tokio::select! {
+            _ = flush.tick(), if !pending.is_empty() => {
+                add_output_lines(&state, &process_id, std::mem::take(&mut pending)).await;
+            },
             line = stdout_lines.next_line() => {
                 match line {
                     Ok(Some(line)) => {
-                        let formatted_line = format!("[OUT] {}", line);
-                        add_output_line(&state, &process_id, formatted_line).await;
+                        pending.push(format!("[OUT] {}", line));
                     },
                     Ok(None) => break, // EOF
                     Err(e) => {
@@ -370,8 +669,7 @@ async fn handle_process_output(
             line = stderr_lines.next_line() => {
                 match line {
                     Ok(Some(line)) => {
-                        let formatted_line = format!("[INFO] {}", line);
-                        add_output_line(&state, &process_id, formatted_line).await;
+                        pending.push(format!("[INFO] {}", line));
                     },
                     Ok(None) => break, // EOF
                     Err(e) => {
@@ -381,43 +679,204 @@ async fn handle_process_output(
                 }
             }
         }
+        if pending.len() >= OUTPUT_BATCH_LINES {
+            add_output_lines(&state, &process_id, std::mem::take(&mut pending)).await;
+        }
+    }
+    if !pending.is_empty() {
+        add_output_lines(&state, &process_id, pending).await;
     }
     
-    // Wait for process to finish and get exit code
-    let exit_code = {
+    // Wait for process to finish and get exit code. Without a child left it was stopped from the app
+    let (exit_code, exited_by_itself) = {
         let mut handle_guard = process_handle.lock().await;
         if let Some(mut child_process) = handle_guard.take_child() {
             match child_process.wait().await {
-                Ok(status) => status.code().unwrap_or(-1),
-                Err(_) => -1,
+                Ok(status) => (status.code().unwrap_or(-1), true),
+                Err(_) => (-1, true),
             }
         } else {
-            -1
+            (-1, false)
         }
     };
     
     // Update process status to stopped and clean up child process tracking
     {
-        let mut processes = state.running_processes.lock().await;
+        let mut processes = state.processes.running.lock().await;
         if let Some(process_info) = processes.get_mut(&process_id) {
             process_info.status = ProcessStatus::Stopped;
             let exit_msg = format!("Process exited with code: {}", exit_code);
+            crate::process_log::append(&process_id, &process_info.model_name, &exit_msg);
+            crate::output_stream::emit(&process_id, vec![exit_msg.clone()], &process_info.status, Some(exit_code));
             process_info.output.push(exit_msg);
         }
     }
+    crate::process_log::close(&process_id);
     
     // Remove from child process tracking since it has exited
+    state.processes.release_child(&process_id).await;
+    println!("Process {} exited naturally, removed from tracking", process_id);
+    crate::local_socket::stop_bridge(&process_id);
+    crate::share::revoke_for_process(&process_id);
+    crate::firewall::on_model_stopped(&process_id).await;
+    
+    crate::gpu_power::on_model_stopped(&state).await;
+    
+    if exited_by_itself {
+        restart_after_exit(state, process_id, exit_code).await;
+    }
+}
+
+// A run longer than this counts as stable, a crash after it starts the retry count over
+const RESTART_STABLE_SECS: i64 = 10 * 60;
+
+/// Start a server that exited by itself again when its model's restart policy asks for it.
+/// The process id, port and command are reused so open terminals and chats reconnect.
+async fn restart_after_exit(state: AppState, process_id: String, exit_code: i32) {
+    let Some(previous) = state.processes.get(&process_id).await else {
+        return;
+    };
+    // A remote server's exit is the ssh tunnel closing, the host may well be gone.
+    // A cli session ends when its user quits it
+    if previous.remote_host.is_some() || previous.interactive {
+        return;
+    }
+    let policy = state.library.config_for(&previous.model_path).await.restart_policy;
+    let wanted = match policy.mode {
+        RestartMode::Never => false,
+        RestartMode::OnCrash => exit_code != 0,
+        RestartMode::Always => true,
+    };
+    if !wanted {
+        return;
+    }
+    
+    let stable = (Utc::now() - previous.created_at).num_seconds() > RESTART_STABLE_SECS;
+    let attempt = if stable { 1 } else { previous.restarts + 1 };
+    if attempt > policy.max_retries {
+        add_output_line(&state, &process_id, format!("[INFO] Not restarting, the server failed {} restarts in a row", previous.restarts)).await;
+        return;
+    }
+    
+    let delay = policy.backoff_secs.saturating_mul(1u64 << (attempt - 1).min(10));
     {
-        let mut child_processes = state.child_processes.lock().await;
-        child_processes.remove(&process_id);
-        println!("Process {} exited naturally, removed from tracking", process_id);
+        let mut processes = state.processes.running.lock().await;
+        if let Some(process_info) = processes.get_mut(&process_id) {
+            process_info.status = ProcessStatus::Restarting;
+        }
     }
+    add_output_line(&state, &process_id, format!("[INFO] Restarting in {}s (attempt {} of {})", delay, attempt, policy.max_retries)).await;
+    tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+    
+    // Stopping it from the app during the backoff removes the entry
+    let Some(waiting) = state.processes.get(&process_id).await
+        .filter(|p| matches!(p.status, ProcessStatus::Restarting))
+    else {
+        return;
+    };
+    
+    match respawn(&state, &previous).await {
+        Ok(()) => {
+            println!("Restarted {} ({}), attempt {}", process_id, previous.model_name, attempt);
+            // Keep the earlier output in front of the new run's, the terminal reads on from where it was
+            let mut processes = state.processes.running.lock().await;
+            if let Some(process_info) = processes.get_mut(&process_id) {
+                let new_output = std::mem::take(&mut process_info.output);
+                let restarted = format!("[INFO] Restarted after exit code {}", exit_code);
+                crate::output_stream::emit(&process_id, vec![restarted.clone()], &process_info.status, None);
+                process_info.output = waiting.output;
+                process_info.output.push(restarted);
+                process_info.output.extend(new_output);
+                process_info.restarts = attempt;
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to restart {}: {}", process_id, e);
+            let mut processes = state.processes.running.lock().await;
+            if let Some(process_info) = processes.get_mut(&process_id) {
+                process_info.status = ProcessStatus::Failed;
+                let failed = format!("[INFO] Restart failed: {}", e);
+                crate::output_stream::emit(&process_id, vec![failed.clone()], &process_info.status, Some(exit_code));
+                process_info.output.push(failed);
+            }
+        }
+    }
+}
+
+/// Spawn the previous command again under the same process id. Boxed because spawning
+/// starts another `handle_process_output`, which would otherwise make the future recursive
+fn respawn<'a>(
+    state: &'a AppState,
+    previous: &'a ProcessInfo,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), String>> + Send + 'a>> {
+    Box::pin(async move {
+        let (executable, args) = previous.command.split_first().ok_or("No command recorded")?;
+        let executable_path = std::path::PathBuf::from(executable);
+        let model_config = state.library.config_for(&previous.model_path).await;
+        let mut server_args = args.to_vec();
+    
+        let (client_host, port, bridge) = if model_config.local_socket {
+            // The bridge was torn down with the old process, a new one may get another port
+            let local = crate::local_socket::prepare_launch(&previous.id, &model_config).await
+                .map_err(|e| e.to_string())?;
+            set_arg_value(&mut server_args, "--host", local.server_config.server_host);
+            set_arg_value(&mut server_args, "--port", local.port.to_string());
+            ("127.0.0.1".to_string(), local.port, Some(local.bridge))
+        } else {
+            (previous.host.clone(), previous.port, None)
+        };
+    
+        spawn_server_process(state, previous.id.clone(), &executable_path, &model_config, client_host, port, server_args, Vec::new()).await
+            .map_err(|e| e.to_string())?;
+        if let Some(bridge) = bridge {
+            bridge.keep();
+        }
+        Ok(())
+    })
 }
 
-async fn add_output_line(state: &AppState, process_id: &str, line: String) {
-    let mut processes = state.running_processes.lock().await;
+// Per-request timings, e.g. `prompt eval time = 123.45 ms / 42 tokens (2.94 ms per token, 340.21 tokens per second)`
+// and the same without `prompt` for generation
+static TIMING_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(prompt )?eval time =\s*([\d.]+) ms /\s*(\d+) (?:tokens|runs)\s*\(.*?([\d.]+) tokens per second\)").unwrap()
+});
+
+/// Take the speeds out of a timing line into the process metrics
+fn record_timing(metrics: &mut ProcessMetrics, line: &str) {
+    let Some(captures) = TIMING_LINE.captures(line) else {
+        return;
+    };
+    let millis = captures[2].parse::<f64>().ok();
+    let tokens = captures[3].parse::<u64>().ok();
+    let per_second = captures[4].parse::<f64>().ok();
+    if captures.get(1).is_some() {
+        metrics.prompt_eval_ms = millis;
+        metrics.last_prompt_tokens = tokens;
+        metrics.prompt_tokens_per_second = per_second;
+    } else {
+        // Generation is logged after the prompt, it closes the request
+        metrics.last_tokens_per_second = per_second;
+        metrics.last_generated_tokens = tokens;
+        metrics.completed_requests += 1;
+        metrics.updated_at = Some(Utc::now());
+    }
+}
+
+pub async fn add_output_line(state: &AppState, process_id: &str, line: String) {
+    add_output_lines(state, process_id, vec![line]).await;
+}
+
+async fn add_output_lines(state: &AppState, process_id: &str, lines: Vec<String>) {
+    let mut processes = state.processes.running.lock().await;
     if let Some(process_info) = processes.get_mut(process_id) {
-        process_info.output.push(line);
+        for line in &lines {
+            if line.contains("eval time") {
+                record_timing(&mut process_info.metrics, line);
+            }
+            crate::process_log::append(process_id, &process_info.model_name, line);
+        }
+        crate::output_stream::emit(process_id, lines.clone(), &process_info.status, None);
+        process_info.output.extend(lines);
         // Keep only last 1000 lines to prevent memory issues
         if process_info.output.len() > 1000 {
             process_info.output.drain(0..process_info.output.len() - 1000);
@@ -431,55 +890,58 @@ pub async fn terminate_process(
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("Terminating process: {}", process_id);
     
-    // Kill the child process first
-    {
-        let mut child_processes = state.child_processes.lock().await;
-        if let Some(handle_arc) = child_processes.remove(&process_id) {
-            let mut handle_guard = handle_arc.lock().await;
-            if let Some(mut child) = handle_guard.take_child() {
-                match child.kill().await {
-                    Ok(_) => println!("Successfully killed process: {}", process_id),
-                    Err(e) => eprintln!("Failed to kill process {}: {}", process_id, e),
-                }
-            }
+    // Remote servers are stopped on the host they were launched on, before the tunnel
+    // goes away, even when the remote host setting changed since
+    let remote = state.processes.get(&process_id).await.and_then(|p| p.remote_host);
+    if let Some(remote) = remote {
+        if let Err(e) = crate::remote::stop_remote_server(&process_id, &remote).await {
+            eprintln!("Failed to stop remote server {}: {}", process_id, e);
         }
     }
     
-    // Update process status and remove from tracking
-    {
-        let mut processes = state.running_processes.lock().await;
-        if let Some(process_info) = processes.get_mut(&process_id) {
-            process_info.status = ProcessStatus::Stopped;
+    // Kill the child process first
+    if let Some(handle_arc) = state.processes.release_child(&process_id).await {
+        let mut handle_guard = handle_arc.lock().await;
+        if let Some(mut child) = handle_guard.take_child() {
+            match child.kill().await {
+                Ok(_) => println!("Successfully killed process: {}", process_id),
+                Err(e) => eprintln!("Failed to kill process {}: {}", process_id, e),
+            }
         }
-        processes.remove(&process_id);
     }
     
+    forget_process(&process_id, state).await;
+    
     Ok(())
 }
 
+/// Drop a process from tracking along with everything held for it: output subscribers,
+/// the log file, the local socket bridge, shares and firewall rules. The child must
+/// already be released.
+pub async fn forget_process(process_id: &str, state: &AppState) {
+    state.processes.forget(process_id).await;
+    crate::output_stream::forget(process_id);
+    crate::process_log::close(process_id);
+    crate::local_socket::stop_bridge(process_id);
+    crate::share::revoke_for_process(process_id);
+    crate::firewall::on_model_stopped(process_id).await;
+    
+    crate::gpu_power::on_model_stopped(state).await;
+}
+
 pub async fn get_process_logs(
     process_id: String,
     state: &AppState,
 ) -> Result<ProcessOutput, Box<dyn std::error::Error>> {
-    let mut processes = state.running_processes.lock().await;
+    let processes = state.processes.running.lock().await;
     
-    if let Some(process_info) = processes.get_mut(&process_id) {
-        // Get new output since last check
-        let total_lines = process_info.output.len();
-        let last_sent = process_info.last_sent_line.unwrap_or(0);
-        
-        let new_output = if last_sent < total_lines {
-            let new_lines = process_info.output[last_sent..].to_vec();
-            // Update the last sent line index
-            process_info.last_sent_line = Some(total_lines);
-            new_lines
-        } else {
-            Vec::new()
-        };
-        
+    // A snapshot of the buffer, new lines arrive as `process-output` events numbered from `next_line`
+    if let Some(process_info) = processes.get(&process_id) {
         Ok(ProcessOutput {
-            output: new_output,
-            is_running: matches!(process_info.status, ProcessStatus::Running | ProcessStatus::Starting),
+            output: process_info.output.clone(),
+            next_line: crate::output_stream::next_line(&process_id),
+            is_running: matches!(process_info.status, ProcessStatus::Running | ProcessStatus::Starting | ProcessStatus::Restarting),
+            ready: matches!(process_info.status, ProcessStatus::Running),
             return_code: None,
         })
     } else {
@@ -487,6 +949,230 @@ pub async fn get_process_logs(
     }
 }
 
+// Ports handed out to launches that haven't registered their process yet, so models
+// started together don't pick the same one. Registering, or the timeout, gives them back
+static RESERVED_PORTS: LazyLock<std::sync::Mutex<HashMap<u16, Instant>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+const PORT_RESERVATION: Duration = Duration::from_secs(60);
+
+/// Pick the port for a launch and hold it until the server registers. The requested port
+/// is kept when it is free, otherwise the first free one in the configured range is used
+pub async fn resolve_launch_port(state: &AppState, model_config: &ModelConfig) -> Result<u16, String> {
+    let requested_port = parse_port_from_args(&model_config.custom_args, model_config.server_port);
+    let actual_port = allocate_port(state, requested_port, true).await?;
+    
+    // If we had to change the port, use it for this session only
+    if actual_port != requested_port {
+        println!("Port {} was in use, using port {} instead", requested_port, actual_port);
+    }
+    Ok(actual_port)
+}
+
+async fn allocate_port(state: &AppState, requested_port: u16, reserve: bool) -> Result<u16, String> {
+    let range = state.config.lock().await.port_range.clone();
+    let held = state.processes.held_ports().await;
+    
+    let mut reserved = RESERVED_PORTS.lock().unwrap();
+    reserved.retain(|_, at| at.elapsed() < PORT_RESERVATION);
+    let is_free = |port: u16| !held.contains(&port) && !reserved.contains_key(&port) && is_port_available(port);
+    let port = if is_free(requested_port) {
+        requested_port
+    } else {
+        (range.start..=range.end)
+            .find(|&port| is_free(port))
+            .ok_or_else(|| format!("No free port between {} and {}", range.start, range.end))?
+    };
+    if reserve {
+        reserved.insert(port, Instant::now());
+    }
+    Ok(port)
+}
+
+/// The launch on this port has registered its process, which holds the port from now on
+pub fn release_port_reservation(port: u16) {
+    RESERVED_PORTS.lock().unwrap().remove(&port);
+}
+
+// Largest batch given to an embedding model, llama-server rejects longer inputs anyway
+const EMBEDDING_MAX_BATCH: u64 = 8192;
+
+/// Serving flags for embedding and reranking models, which fail as chat servers. Each input
+/// has to fit in one physical batch, so the batch sizes are raised to the context. Sizes
+/// already set in the launch settings or custom args are left alone.
+fn embedding_args(model_config: &ModelConfig, header: &crate::gguf::GgufHeader) -> Vec<String> {
+    let configured: Vec<String> = crate::launch_settings::to_args(&model_config.launch_settings)
+        .into_iter()
+        .chain(parse_custom_args(&model_config.custom_args))
+        .collect();
+    let explicit = configured
+        .iter()
+        .any(|arg| matches!(arg.as_str(), "--embedding" | "--embeddings" | "--reranking" | "--rerank"));
+    if explicit {
+        return Vec::new();
+    }
+    
+    let Some(flag) = header.embedding_mode() else {
+        return Vec::new();
+    };
+    let value_of = |flag: &str| {
+        configured.iter()
+            .position(|arg| crate::presets::same_flag(arg, flag))
+            .and_then(|index| configured.get(index + 1))
+    };
+    // A context set by the user bounds the batch as well
+    let batch = value_of("-c")
+        .and_then(|value| value.parse::<u64>().ok())
+        .or_else(|| header.context_length())
+        .unwrap_or(512)
+        .min(EMBEDDING_MAX_BATCH)
+        .to_string();
+    let mut args = vec![flag.to_string()];
+    for flag in ["-c", "-b", "-ub"] {
+        if value_of(flag).is_none() {
+            args.extend([flag.to_string(), batch.clone()]);
+        }
+    }
+    args
+}
+
+/// Flags that come from the model files rather than its settings: EOS fixups, embedding
+/// serving and the projector next to the model
+#[derive(Debug, Clone, Default)]
+pub struct ModelFileArgs {
+    fixups: Vec<String>,
+    embedding: Vec<String>,
+    mmproj: Option<String>,
+}
+
+/// Read the model's header and folder once for `build_server_args`, off the async runtime
+pub async fn read_model_file_args(model_config: &ModelConfig) -> ModelFileArgs {
+    let model_config = model_config.clone();
+    tokio::task::spawn_blocking(move || {
+        let model_path = crate::scanner::first_shard(&model_config.model_path);
+        let header = std::fs::File::open(&model_path)
+            .map(std::io::BufReader::new)
+            .and_then(|mut reader| crate::gguf::read_header(&mut reader, false))
+            .ok();
+        ModelFileArgs {
+            fixups: header.as_ref()
+                .map(|header| crate::fixups::fixups_for(&model_config, header))
+                .unwrap_or_default()
+                .into_iter()
+                .flat_map(|fixup| fixup.args)
+                .collect(),
+            embedding: header.as_ref().map(|header| embedding_args(&model_config, header)).unwrap_or_default(),
+            mmproj: resolve_mmproj(&model_config),
+        }
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Assemble the llama-server argument list for a model config on the given port
+pub fn build_server_args(model_config: &ModelConfig, port: u16, files: &ModelFileArgs) -> Vec<String> {
+    let mut args = vec![
+        "-m".to_string(),
+        // Settings saved before shards were grouped may still point at a later shard
+        crate::scanner::first_shard(&model_config.model_path),
+        "--host".to_string(),
+        model_config.server_host.clone(),
+        "--port".to_string(),
+        port.to_string(),
+    ];
+    
+    // Corrections for known-broken models go before custom args so users can still override them
+    args.extend(files.fixups.iter().cloned());
+    
+    args.extend(files.embedding.iter().cloned());
+    
+    args.extend(crate::kv_overrides::build_args(&model_config.kv_overrides));
+    
+    for lora in &model_config.lora_paths {
+        match model_config.lora_scales.get(lora) {
+            Some(scale) if (*scale - 1.0).abs() > f32::EPSILON => {
+                args.extend(["--lora-scaled".to_string(), lora.clone(), scale.to_string()]);
+            }
+            _ => args.extend(["--lora".to_string(), lora.clone()]),
+        }
+    }
+    
+    let mmproj_in_custom_args = model_config.custom_args.contains("--mmproj") || model_config.custom_args.contains("--no-mmproj");
+    if !mmproj_in_custom_args {
+        if let Some(mmproj) = &files.mmproj {
+            args.push("--mmproj".to_string());
+            args.push(mmproj.clone());
+        }
+    }
+    
+    args.extend(crate::launch_settings::to_args(&model_config.launch_settings));
+    
+    // Add custom arguments if present
+    if !model_config.custom_args.trim().is_empty() {
+        args.extend(parse_custom_args(&model_config.custom_args));
+    }
+    
+    args
+}
+
+/// The configured projector, or the mmproj*.gguf sitting next to the model. When a
+/// folder holds several, the one covering the most inputs (audio and vision over
+/// either alone) wins; ties need mmproj_path set to pick one.
+fn resolve_mmproj(model_config: &ModelConfig) -> Option<String> {
+    if !model_config.mmproj_path.trim().is_empty() {
+        return Some(model_config.mmproj_path.trim().to_string());
+    }
+    let model_path = std::path::Path::new(&model_config.model_path);
+    if crate::huggingface::is_mmproj_file(&model_config.model_path) {
+        return None;
+    }
+    let mut projectors: Vec<String> = std::fs::read_dir(model_path.parent()?)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            crate::huggingface::is_mmproj_file(name) && name.to_lowercase().ends_with(".gguf")
+        })
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    if projectors.len() <= 1 {
+        return projectors.pop();
+    }
+    let mut ranked: Vec<(usize, String)> = projectors
+        .into_iter()
+        .map(|path| (crate::gguf::read_projector_modalities(std::path::Path::new(&path)).len(), path))
+        .collect();
+    ranked.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+    (ranked[0].0 > ranked[1].0).then(|| ranked.swap_remove(0).1)
+}
+
+/// Add the default arguments of the build being launched, under the model's own
+pub fn apply_version_defaults(global_config: &GlobalConfig, executable_path: &std::path::Path, args: &mut Vec<String>) {
+    let defaults = crate::version_args::defaults_for(&global_config.version_default_args, executable_path);
+    crate::version_args::layer_under(args, defaults);
+}
+
+/// Variable assignments in front of a copied command line, in the syntax of the local shell
+fn env_prefix(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(name, value)| {
+            if cfg!(windows) {
+                format!("set \"{}={}\" && ", name, value)
+            } else {
+                format!("{}={} ", name, quote_arg(value))
+            }
+        })
+        .collect()
+}
+
+/// Quote an argument for display when it contains whitespace
+pub fn quote_arg(arg: &str) -> String {
+    if arg.is_empty() || arg.contains(char::is_whitespace) {
+        format!("\"{}\"", arg)
+    } else {
+        arg.to_string()
+    }
+}
+
 fn parse_port_from_args(custom_args: &str, default_port: u16) -> u16 {
     if let Some(port_pos) = custom_args.find("--port") {
         let after_port = &custom_args[port_pos + 6..];
@@ -527,20 +1213,20 @@ fn is_port_available(port: u16) -> bool {
     }
 }
 
-fn find_available_port(start_port: u16) -> u16 {
-    let mut port = start_port;
-    while !is_port_available(port) {
-        port += 1;
-        // Prevent infinite loop by setting a reasonable upper limit
-        if port-start_port > 10 {
-            // Only search for next 10 ports
-            return start_port;
+/// Names the OS would reject or misread when the server process is spawned
+pub fn validate_env_vars(env_vars: &HashMap<String, String>) -> Result<(), String> {
+    for (name, value) in env_vars {
+        if name.is_empty() || name.contains(['=', '\0']) || name.chars().any(char::is_whitespace) {
+            return Err(format!("Invalid environment variable name: {:?}", name));
+        }
+        if value.contains('\0') {
+            return Err(format!("Invalid value for environment variable {}", name));
         }
     }
-    port
+    Ok(())
 }
 
-fn parse_custom_args(custom_args: &str) -> Vec<String> {
+pub fn parse_custom_args(custom_args: &str) -> Vec<String> {
     let mut args = Vec::new();
     let mut current_arg = String::new();
     let mut in_quotes = false;
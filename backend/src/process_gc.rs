@@ -0,0 +1,137 @@
+// Stale process reconciliation. A server normally leaves the registry through
+// `handle_process_output` once its pipes close, but a process killed from outside
+// can leave its entry behind (e.g. a grandchild still holding the pipes, or a
+// remote server that vanished with its host). This job checks live entries against
+// the OS process table and `/health`, and prunes the ones that are gone.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessStatus as OsProcessStatus, ProcessesToUpdate, System};
+use tauri::Emitter;
+
+use crate::models::{ProcessInfo, ProcessStatus};
+use crate::AppState;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(3);
+// Checks in a row an entry has to fail before it is pruned. The second local check
+// rules out catching a server in the middle of a regular stop; remote servers have
+// no PID to look at, so they get a little more slack on a flaky connection.
+const LOCAL_MAX_MISSES: u32 = 2;
+const REMOTE_MAX_MISSES: u32 = 3;
+
+// Consecutive failed checks per process id
+static MISSES: LazyLock<std::sync::Mutex<HashMap<String, u32>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize)]
+struct StaleProcess {
+    process_id: String,
+    model_name: String,
+    reason: String,
+}
+
+pub async fn run_reconcile_job(state: AppState, app_handle: tauri::AppHandle) {
+    let client = match reqwest::Client::builder().timeout(HEALTH_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Stale process check disabled, failed to create HTTP client: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        for stale in find_stale(&state, &client).await {
+            prune(&state, &stale).await;
+            let _ = app_handle.emit("stale-process-removed", &stale);
+        }
+    }
+}
+
+async fn find_stale(state: &AppState, client: &reqwest::Client) -> Vec<StaleProcess> {
+    let live: Vec<ProcessInfo> = state.processes.list().await
+        .into_iter()
+        .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running))
+        .collect();
+    let pids = state.processes.child_pids().await;
+
+    let dead_pids: Vec<u32> = {
+        let pids: Vec<u32> = pids.values().copied().collect();
+        tokio::task::spawn_blocking(move || dead_processes(&pids)).await.unwrap_or_default()
+    };
+
+    MISSES.lock().unwrap().retain(|id, _| live.iter().any(|p| &p.id == id));
+
+    let mut stale = Vec::new();
+    for process in live {
+        let (gone, max_misses, reason) = if process.remote_host.is_some() {
+            let gone = !is_reachable(client, &process).await;
+            (gone, REMOTE_MAX_MISSES, "remote server is unreachable")
+        } else {
+            let exited = match pids.get(&process.id) {
+                Some(pid) => dead_pids.contains(pid),
+                None => true, // Handle already taken but the entry was never updated
+            };
+            // The port answering means something is still serving it, leave it alone
+            let gone = exited && !is_reachable(client, &process).await;
+            (gone, LOCAL_MAX_MISSES, "server process is no longer running")
+        };
+
+        let misses = {
+            let mut misses = MISSES.lock().unwrap();
+            if !gone {
+                misses.remove(&process.id);
+                continue;
+            }
+            let count = misses.entry(process.id.clone()).or_insert(0);
+            *count += 1;
+            *count
+        };
+        if misses < max_misses {
+            continue;
+        }
+
+        stale.push(StaleProcess {
+            process_id: process.id,
+            model_name: process.model_name,
+            reason: reason.to_string(),
+        });
+    }
+    stale
+}
+
+/// PIDs from the list that are gone or only left as zombies
+fn dead_processes(pids: &[u32]) -> Vec<u32> {
+    if pids.is_empty() {
+        return Vec::new();
+    }
+    let targets: Vec<Pid> = pids.iter().map(|pid| Pid::from_u32(*pid)).collect();
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&targets), true);
+    pids.iter()
+        .copied()
+        .filter(|pid| {
+            sys.process(Pid::from_u32(*pid))
+                .is_none_or(|p| matches!(p.status(), OsProcessStatus::Zombie | OsProcessStatus::Dead))
+        })
+        .collect()
+}
+
+/// Any HTTP answer counts, llama-server returns 503 while the model is still loading
+async fn is_reachable(client: &reqwest::Client, process: &ProcessInfo) -> bool {
+    client.get(format!("{}/health", process.client_url())).send().await.is_ok()
+}
+
+async fn prune(state: &AppState, stale: &StaleProcess) {
+    println!("Removing stale process {} ({}): {}", stale.process_id, stale.model_name, stale.reason);
+    if let Some(handle) = state.processes.release_child(&stale.process_id).await {
+        if let Some(mut child) = handle.lock().await.take_child() {
+            let _ = child.start_kill();
+        }
+    }
+    MISSES.lock().unwrap().remove(&stale.process_id);
+    crate::process::forget_process(&stale.process_id, state).await;
+}
@@ -0,0 +1,108 @@
+// Server output on disk. The in-memory buffer only holds the last 1000 lines and is gone
+// with the app, so every line is also appended to ~/.llama-os/logs/<model>-<date>.log.
+// A file past MAX_LOG_BYTES is rotated to .1.log, .2.log and so on, the oldest dropped.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use chrono::{Local, NaiveDate};
+
+const LOG_DIR: &str = "logs";
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_ROTATED_FILES: u32 = 5;
+
+struct LogFile {
+    path: PathBuf,
+    date: NaiveDate,
+    file: File,
+    size: u64,
+}
+
+// Process id -> its open log file
+static LOG_FILES: LazyLock<std::sync::Mutex<HashMap<String, LogFile>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+// Process id -> day its log file couldn't be opened, no retries until the next day's file
+static FAILED_OPENS: LazyLock<std::sync::Mutex<HashMap<String, NaiveDate>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+pub fn logs_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".llama-os").join(LOG_DIR))
+}
+
+/// Append one output line to the process's log. A file that can't be opened is reported
+/// once and not tried again until the next day's file or the process's log is closed.
+/// Failures are otherwise ignored, a full disk shouldn't take the server down with it.
+pub fn append(process_id: &str, model_name: &str, line: &str) {
+    let today = Local::now().date_naive();
+    let mut files = LOG_FILES.lock().unwrap();
+
+    // A new day starts a new file
+    if files.get(process_id).is_some_and(|log| log.date != today) {
+        files.remove(process_id);
+    }
+    if !files.contains_key(process_id) {
+        let mut failed = FAILED_OPENS.lock().unwrap();
+        if failed.get(process_id) == Some(&today) {
+            return;
+        }
+        match open(model_name, today) {
+            Ok(log) => {
+                failed.remove(process_id);
+                files.insert(process_id.to_string(), log);
+            }
+            Err(e) => {
+                eprintln!("Failed to open log file for {}: {}", model_name, e);
+                failed.insert(process_id.to_string(), today);
+                return;
+            }
+        }
+    }
+    let Some(log) = files.get_mut(process_id) else {
+        return;
+    };
+
+    let entry = format!("{} {}\n", Local::now().format("%H:%M:%S%.3f"), line);
+    if log.file.write_all(entry.as_bytes()).is_err() {
+        return;
+    }
+    log.size += entry.len() as u64;
+
+    if log.size >= MAX_LOG_BYTES {
+        if let Err(e) = rotate(log) {
+            eprintln!("Failed to rotate {}: {}", log.path.display(), e);
+        }
+    }
+}
+
+/// Close the process's log file, the next line for the same id opens it again
+pub fn close(process_id: &str) {
+    LOG_FILES.lock().unwrap().remove(process_id);
+    FAILED_OPENS.lock().unwrap().remove(process_id);
+}
+
+fn open(model_name: &str, date: NaiveDate) -> std::io::Result<LogFile> {
+    let dir = logs_dir().ok_or_else(|| std::io::Error::other("Could not find home directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    let name = model_name.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|', ' '], "_");
+    let path = dir.join(format!("{}-{}.log", name, date.format("%Y-%m-%d")));
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Ok(LogFile { path, date, file, size })
+}
+
+/// `x.log` becomes `x.1.log`, `x.1.log` becomes `x.2.log`, up to MAX_ROTATED_FILES
+fn rotate(log: &mut LogFile) -> std::io::Result<()> {
+    let rotated = |n: u32| log.path.with_extension(format!("{}.log", n));
+    let _ = std::fs::remove_file(rotated(MAX_ROTATED_FILES));
+    for n in (1..MAX_ROTATED_FILES).rev() {
+        let from = rotated(n);
+        if from.exists() {
+            std::fs::rename(&from, rotated(n + 1))?;
+        }
+    }
+    std::fs::rename(&log.path, rotated(1))?;
+    log.file = OpenOptions::new().create(true).append(true).open(&log.path)?;
+    log.size = 0;
+    Ok(())
+}
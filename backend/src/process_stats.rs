@@ -0,0 +1,91 @@
+// Resource usage of the local servers Llama-OS started, so a terminal window can show
+// what its own model costs: CPU share, resident memory and the VRAM its pid holds.
+// CPU usage is a rate, so the sysinfo System is kept between calls and each sample
+// covers the time since the previous one.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use chrono::Utc;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::models::ProcessResourceUsage;
+use crate::AppState;
+
+static SYSTEM: LazyLock<Mutex<System>> = LazyLock::new(|| Mutex::new(System::new()));
+
+/// Sample every live local server, or just `process_id`, and keep the result on its
+/// ProcessInfo. Remote servers and exited processes have no pid and are left out
+pub async fn sample(state: &AppState, process_id: Option<&str>) -> Result<Vec<ProcessResourceUsage>, String> {
+    let pids: HashMap<String, u32> = state.processes.child_pids().await
+        .into_iter()
+        .filter(|(id, _)| process_id.is_none_or(|wanted| wanted == id))
+        .collect();
+    if pids.is_empty() {
+        return match process_id {
+            Some(_) => Err("Process is not running locally".to_string()),
+            None => Ok(Vec::new()),
+        };
+    }
+
+    let samples = tokio::task::spawn_blocking(move || collect(&pids))
+        .await
+        .map_err(|e| format!("Process stats task failed: {}", e))?;
+
+    let mut processes = state.processes.running.lock().await;
+    for usage in &samples {
+        if let Some(info) = processes.get_mut(&usage.process_id) {
+            info.resources = Some(usage.clone());
+        }
+    }
+    Ok(samples)
+}
+
+fn collect(pids: &HashMap<String, u32>) -> Vec<ProcessResourceUsage> {
+    let targets: Vec<Pid> = pids.values().map(|pid| Pid::from_u32(*pid)).collect();
+    let refresh = ProcessRefreshKind::nothing().with_cpu().with_memory();
+
+    // A pid seen for the first time has no earlier CPU reading to compare with. The lock is
+    // let go for the wait so other callers aren't held up behind it
+    let needs_baseline = {
+        let Ok(mut system) = SYSTEM.lock() else {
+            return Vec::new();
+        };
+        let missing = targets.iter().any(|pid| system.process(*pid).is_none());
+        if missing {
+            system.refresh_processes_specifics(ProcessesToUpdate::Some(&targets), true, refresh);
+        }
+        missing
+    };
+    if needs_baseline {
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    }
+
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1) as f32;
+    let readings: Vec<(&String, u32, f32, u64)> = {
+        let Ok(mut system) = SYSTEM.lock() else {
+            return Vec::new();
+        };
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&targets), true, refresh);
+        pids.iter()
+            .filter_map(|(process_id, pid)| {
+                let process = system.process(Pid::from_u32(*pid))?;
+                // sysinfo counts 100% per core
+                Some((process_id, *pid, process.cpu_usage() / cores, process.memory()))
+            })
+            .collect()
+    };
+
+    let vram = crate::vram::process_usage();
+    let now = Utc::now();
+    readings.into_iter()
+        .map(|(process_id, pid, cpu_percent, memory_bytes)| ProcessResourceUsage {
+            process_id: process_id.clone(),
+            pid,
+            cpu_percent,
+            memory_bytes,
+            vram_bytes: vram.as_ref().map(|usage| usage.get(&pid).copied().unwrap_or(0)),
+            sampled_at: now,
+        })
+        .collect()
+}
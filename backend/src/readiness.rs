@@ -0,0 +1,60 @@
+// Readiness of freshly launched servers. llama-server opens its port before the model
+// is loaded and answers `/health` with 503 until it is, so a process stays `Starting`
+// until `/health` returns 200. Then it becomes `Running` and a `server-ready` event
+// tells the desktop the URL can take requests.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::models::{ProcessInfo, ProcessStatus};
+use crate::AppState;
+
+const PROBE_INTERVAL: Duration = Duration::from_millis(500);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerReady {
+    pub process_id: String,
+    pub model_path: String,
+    pub model_name: String,
+    pub url: String,
+}
+
+pub async fn run_readiness_job(state: AppState, app_handle: tauri::AppHandle) {
+    let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        eprintln!("Failed to create the readiness probe client");
+        return;
+    };
+
+    loop {
+        tokio::time::sleep(PROBE_INTERVAL).await;
+
+        let starting: Vec<ProcessInfo> = state.processes.list().await
+            .into_iter()
+            .filter(|p| matches!(p.status, ProcessStatus::Starting))
+            .collect();
+        for process in starting {
+            let url = process.client_url();
+            if !is_healthy(&client, &url).await {
+                continue;
+            }
+            // The process may have exited while the request was in flight
+            if !state.processes.mark_ready(&process.id).await {
+                continue;
+            }
+            println!("Server {} ({}) is ready at {}", process.id, process.model_name, url);
+            let _ = app_handle.emit("server-ready", ServerReady {
+                process_id: process.id,
+                model_path: process.model_path,
+                model_name: process.model_name,
+                url,
+            });
+        }
+    }
+}
+
+async fn is_healthy(client: &reqwest::Client, url: &str) -> bool {
+    matches!(client.get(format!("{}/health", url)).send().await, Ok(r) if r.status().is_success())
+}
@@ -0,0 +1,198 @@
+use std::process::Stdio;
+use std::sync::Arc;
+use chrono::Utc;
+use tokio::process::Command as TokioCommand;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::process::{build_server_args, handle_process_output, release_port_reservation, resolve_launch_port, ProcessHandle};
+use crate::AppState;
+
+/// `user@host` target for ssh
+fn ssh_target(remote: &RemoteHostConfig) -> String {
+    match &remote.user {
+        Some(user) if !user.is_empty() => format!("{}@{}", user, remote.host),
+        _ => remote.host.clone(),
+    }
+}
+
+/// Connection options shared by every ssh invocation. BatchMode makes ssh fail instead of
+/// prompting for a password nobody can type, so key based auth is required.
+fn ssh_base_args(remote: &RemoteHostConfig) -> Vec<String> {
+    let mut args = vec![
+        "-o".to_string(), "BatchMode=yes".to_string(),
+        "-o".to_string(), "ServerAliveInterval=15".to_string(),
+    ];
+    if let Some(port) = remote.ssh_port {
+        args.push("-p".to_string());
+        args.push(port.to_string());
+    }
+    if let Some(identity_file) = &remote.identity_file {
+        if !identity_file.is_empty() {
+            args.push("-i".to_string());
+            args.push(identity_file.clone());
+        }
+    }
+    args
+}
+
+/// Quote an argument for the remote POSIX shell
+pub(crate) fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn pid_file(process_id: &str) -> String {
+    format!("/tmp/llama-os-{}.pid", process_id)
+}
+
+fn ssh_command(remote: &RemoteHostConfig) -> TokioCommand {
+    let mut cmd = TokioCommand::new("ssh");
+    cmd.args(ssh_base_args(remote));
+
+    // Hide console window on Windows release builds
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    cmd
+}
+
+/// Launch llama-server on the configured SSH host and forward its port to localhost.
+/// The local ssh client is tracked like a regular server process, its output carries the remote logs.
+pub async fn launch_remote_model_server(
+    model_config: ModelConfig,
+    remote: &RemoteHostConfig,
+    state: &AppState,
+) -> Result<LaunchResult, Box<dyn std::error::Error>> {
+    if remote.host.trim().is_empty() {
+        return Err("No remote host configured".into());
+    }
+
+    // The tunnel binds locally, so the free port check applies to this machine.
+    // The server itself only listens on the remote loopback.
+    let local_port = resolve_launch_port(state, &model_config).await?;
+    let mut remote_config = model_config.clone();
+    remote_config.server_host = "127.0.0.1".to_string();
+    let model_files = crate::process::read_model_file_args(&remote_config).await;
+    let server_args = build_server_args(&remote_config, local_port, &model_files);
+
+    let process_id = Uuid::new_v4().to_string();
+    let server_path = if remote.llama_server_path.is_empty() { "llama-server" } else { remote.llama_server_path.as_str() };
+    let quoted_args: Vec<String> = server_args.iter().map(|a| shell_quote(a)).collect();
+    // ssh doesn't forward the environment, the model's variables go through `env` on the host
+    let mut env_vars: Vec<String> = model_config.env_vars.iter()
+        .map(|(name, value)| shell_quote(&format!("{}={}", name, value)))
+        .collect();
+    env_vars.sort();
+    let env_prefix = if env_vars.is_empty() { String::new() } else { format!("env {} ", env_vars.join(" ")) };
+    let remote_command = format!(
+        "echo $$ > {}; exec {}{} {}",
+        pid_file(&process_id),
+        env_prefix,
+        shell_quote(server_path),
+        quoted_args.join(" ")
+    );
+
+    let mut cmd = ssh_command(remote);
+    cmd.arg("-o").arg("ExitOnForwardFailure=yes")
+       .arg("-L").arg(format!("{}:{}:127.0.0.1:{}", model_config.server_host, local_port, local_port))
+       .arg(ssh_target(remote))
+       .arg(remote_command)
+       .stdin(Stdio::null())
+       .stdout(Stdio::piped())
+       .stderr(Stdio::piped())
+       .kill_on_drop(true);
+
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start ssh: {}", e))?;
+    let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to get stderr")?;
+
+    let model_name = model_config.model_path
+        .rsplit(['/', '\\'])
+        .next()
+        .map(|n| n.trim_end_matches(".gguf").to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let process_info = ProcessInfo {
+        id: process_id.clone(),
+        model_path: model_config.model_path.clone(),
+        model_name: model_name.clone(),
+        host: model_config.server_host.clone(),
+        port: local_port,
+        command: std::iter::once(server_path.to_string()).chain(server_args).collect(),
+        status: ProcessStatus::Starting,
+        output: vec![format!("[INFO] Launching on remote host {}", remote.host)],
+        created_at: Utc::now(),
+        remote_host: Some(remote.clone()),
+        restarts: 0,
+        metrics: ProcessMetrics::default(),
+        interactive: false,
+        resources: None,
+    };
+
+    let process_handle = Arc::new(Mutex::new(ProcessHandle::new(child, process_id.clone())));
+    state.processes.register(process_info, process_handle.clone()).await;
+    release_port_reservation(local_port);
+
+    let state_clone = state.clone();
+    let process_id_clone = process_id.clone();
+    tokio::spawn(async move {
+        handle_process_output(state_clone, process_id_clone, process_handle, stdout, stderr).await;
+    });
+
+    crate::capabilities::spawn_startup_probe(process_id.clone(), state.clone());
+
+    Ok(LaunchResult {
+        success: true,
+        process_id,
+        server_host: model_config.server_host,
+        server_port: local_port,
+        model_name,
+        message: format!("Model server launched on {}", remote.host),
+        computed_context: None,
+    })
+}
+
+/// Stop the remote server through its pid file. Closing the tunnel alone would leave it running.
+pub async fn stop_remote_server(process_id: &str, remote: &RemoteHostConfig) -> Result<(), String> {
+    let pid_file = pid_file(process_id);
+    let output = ssh_command(remote)
+        .arg(ssh_target(remote))
+        .arg(format!("kill $(cat {0}) 2>/dev/null; rm -f {0}", pid_file))
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ssh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// List GGUF files in the remote models directory
+pub async fn list_remote_models(remote: &RemoteHostConfig) -> Result<Vec<String>, String> {
+    if remote.models_directory.is_empty() {
+        return Err("No remote models directory configured".to_string());
+    }
+
+    let output = ssh_command(remote)
+        .arg(ssh_target(remote))
+        .arg(format!("find {} -name '*.gguf' -type f", shell_quote(&remote.models_directory)))
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ssh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let mut models: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    models.sort();
+    Ok(models)
+}
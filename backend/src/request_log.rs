@@ -0,0 +1,263 @@
+// Opt-in log of inference requests for debugging prompt regressions. Chats talk to
+// llama-server directly, so the frontend reports each finished request; prompts and
+// responses are redacted before anything touches disk. A logged request can be
+// replayed against any running model, e.g. to compare two quants of the same model.
+// With encryption at rest on, each line is sealed like the chat store.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::get_app_data_path;
+use crate::encryption;
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+const REQUEST_LOG_FILE: &str = "request_log.jsonl";
+// Once the log grows past this it is cut down to its newer half
+const MAX_LOG_BYTES: u64 = 32 * 1024 * 1024;
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(600);
+const REDACTED: &str = "[REDACTED]";
+
+// Always applied: API keys, access tokens and e-mail addresses
+static BUILTIN_REDACTIONS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"sk-[A-Za-z0-9_-]{16,}",
+        r"hf_[A-Za-z0-9]{20,}",
+        r"(?i)bearer\s+[A-Za-z0-9._~+/=-]{8,}",
+        r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).unwrap())
+    .collect()
+});
+
+/// A finished request as reported by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InferenceRequestSample {
+    pub host: String,
+    pub port: u16,
+    pub endpoint: String, // e.g. /v1/chat/completions
+    pub request: Value,   // Body as sent
+    pub response: Option<String>, // Generated text
+    pub status: Option<u16>,
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestLogEntry {
+    pub id: String,
+    pub logged_at: DateTime<Utc>,
+    pub model_name: String,
+    pub endpoint: String,
+    pub request: Value,
+    pub response: Option<String>,
+    pub status: Option<u16>,
+    pub duration_ms: Option<u64>,
+    pub replay_of: Option<String>, // Log id of the request this one replayed
+}
+
+/// Check user patterns before they are saved
+pub fn validate_patterns(patterns: &[String]) -> Result<(), String> {
+    for pattern in patterns {
+        Regex::new(pattern).map_err(|e| format!("Invalid redaction pattern '{}': {}", pattern, e))?;
+    }
+    Ok(())
+}
+
+fn redaction_rules(patterns: &[String]) -> Vec<Regex> {
+    let mut rules = BUILTIN_REDACTIONS.clone();
+    rules.extend(patterns.iter().filter_map(|pattern| Regex::new(pattern).ok()));
+    rules
+}
+
+fn redact_text(text: &str, rules: &[Regex]) -> String {
+    rules.iter().fold(text.to_string(), |text, rule| rule.replace_all(&text, REDACTED).into_owned())
+}
+
+fn redact_value(value: &mut Value, rules: &[Regex]) {
+    match value {
+        Value::String(text) => *text = redact_text(text, rules),
+        Value::Array(items) => items.iter_mut().for_each(|item| redact_value(item, rules)),
+        Value::Object(fields) => fields.values_mut().for_each(|field| redact_value(field, rules)),
+        _ => {}
+    }
+}
+
+/// Log a finished request when logging is on. Returns the new log id.
+pub async fn record(sample: InferenceRequestSample, state: &AppState) -> Result<Option<String>, String> {
+    let settings = state.config.lock().await.request_log.clone();
+    if !settings.enabled {
+        return Ok(None);
+    }
+
+    let model_name = state.processes.list().await
+        .into_iter()
+        .find(|p| p.port == sample.port)
+        .map(|p| p.model_name)
+        .unwrap_or_else(|| format!("{}:{}", sample.host, sample.port));
+
+    let entry = RequestLogEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        logged_at: Utc::now(),
+        model_name,
+        endpoint: sample.endpoint,
+        request: sample.request,
+        response: sample.response,
+        status: sample.status,
+        duration_ms: sample.duration_ms,
+        replay_of: None,
+    };
+    let id = entry.id.clone();
+    append_entry(entry, &settings.redact_patterns).await?;
+    Ok(Some(id))
+}
+
+async fn append_entry(mut entry: RequestLogEntry, patterns: &[String]) -> Result<(), String> {
+    let rules = redaction_rules(patterns);
+    redact_value(&mut entry.request, &rules);
+    entry.response = entry.response.map(|response| redact_text(&response, &rules));
+
+    let path = get_app_data_path(REQUEST_LOG_FILE).await.map_err(|e| e.to_string())?;
+    let mut line = encryption::seal(&serde_json::to_string(&entry).map_err(|e| e.to_string())?)?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .map_err(|e| e.to_string())?;
+    file.write_all(line.as_bytes()).await.map_err(|e| e.to_string())?;
+    drop(file);
+
+    if tokio::fs::metadata(&path).await.is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let contents = tokio::fs::read_to_string(&path).await.map_err(|e| e.to_string())?;
+        let lines: Vec<&str> = contents.lines().collect();
+        let kept = lines[lines.len() / 2..].join("\n") + "\n";
+        tokio::fs::write(&path, kept).await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+async fn read_entries() -> Result<Vec<RequestLogEntry>, String> {
+    let path = get_app_data_path(REQUEST_LOG_FILE).await.map_err(|e| e.to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| e.to_string())?;
+    // Skip lines that can't be parsed (e.g. a partial write after a crash)
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| encryption::decrypt(line).ok())
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
+
+/// Encrypt or decrypt every logged request to match the encryption setting.
+/// Returns the number of entries converted.
+pub async fn convert_all(encrypt: bool) -> Result<usize, String> {
+    let path = get_app_data_path(REQUEST_LOG_FILE).await.map_err(|e| e.to_string())?;
+    if !path.exists() {
+        return Ok(0);
+    }
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| e.to_string())?;
+
+    let mut converted = 0;
+    let mut kept = String::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let line = if encrypt == encryption::is_sealed(line) {
+            line.to_string()
+        } else {
+            converted += 1;
+            if encrypt { encryption::encrypt(line)? } else { encryption::decrypt(line)? }
+        };
+        kept.push_str(&line);
+        kept.push('\n');
+    }
+    if converted > 0 {
+        tokio::fs::write(&path, kept).await.map_err(|e| e.to_string())?;
+    }
+    Ok(converted)
+}
+
+/// Newest entries first, optionally for a single model
+pub async fn list(limit: usize, model: Option<String>) -> Result<Vec<RequestLogEntry>, String> {
+    Ok(read_entries().await?
+        .into_iter()
+        .rev()
+        .filter(|entry| model.as_ref().is_none_or(|m| m == &entry.model_name))
+        .take(limit)
+        .collect())
+}
+
+/// Send a logged request again to a running model, matched by process id, model path or name.
+/// The request is sent as logged, redactions included, without streaming, and the
+/// replay is logged as well when logging is on.
+pub async fn replay(log_id: &str, target_model: &str, state: &AppState) -> Result<RequestLogEntry, String> {
+    let original = read_entries().await?
+        .into_iter()
+        .find(|entry| entry.id == log_id)
+        .ok_or("Logged request not found")?;
+
+    let target = state.processes.list().await
+        .into_iter()
+        .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running))
+        .find(|p| p.id == target_model || p.model_path == target_model || p.model_name == target_model)
+        .ok_or_else(|| format!("No running server for '{}'", target_model))?;
+
+    let mut body = original.request.clone();
+    if let Some(fields) = body.as_object_mut() {
+        fields.insert("stream".to_string(), Value::Bool(false));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(REPLAY_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let started = std::time::Instant::now();
+    let response = client
+        .post(format!("{}{}", target.client_url(), original.endpoint))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Replay request failed: {}", e))?;
+    let status = response.status().as_u16();
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let entry = RequestLogEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        logged_at: Utc::now(),
+        model_name: target.model_name,
+        endpoint: original.endpoint,
+        request: body,
+        response: Some(generated_text(&text).unwrap_or(text)),
+        status: Some(status),
+        duration_ms: Some(duration_ms),
+        replay_of: Some(original.id),
+    };
+
+    let settings = state.config.lock().await.request_log.clone();
+    if settings.enabled {
+        append_entry(entry.clone(), &settings.redact_patterns).await?;
+    }
+    Ok(entry)
+}
+
+/// The text of a non-streamed OpenAI-style or native llama-server completion
+fn generated_text(body: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(body).ok()?;
+    let choice = value.get("choices").and_then(|c| c.get(0));
+    choice
+        .and_then(|c| c.get("message").and_then(|m| m.get("content")))
+        .or_else(|| choice.and_then(|c| c.get("text")))
+        .or_else(|| value.get("content"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
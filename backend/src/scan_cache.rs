@@ -0,0 +1,105 @@
+// GGUF headers parsed by earlier scans, kept in ~/.llama-os so a rescan only reads
+// files that changed. Entries are keyed by path and trusted while the file's size and
+// modification time still match; anything else is parsed again and replaces the entry.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::get_app_data_path;
+use crate::models::GgufMetadata;
+
+const SCAN_CACHE_FILE: &str = "scan_cache.json";
+// Bump when GgufMetadata gains fields, older caches are then dropped instead of served incomplete
+const SCAN_CACHE_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFile {
+    size: u64,
+    modified_ms: u128,
+    metadata: GgufMetadata,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScanCache {
+    version: u32,
+    files: HashMap<String, CachedFile>,
+    #[serde(skip)]
+    loaded: bool,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+static CACHE: LazyLock<std::sync::Mutex<ScanCache>> = LazyLock::new(|| std::sync::Mutex::new(ScanCache::default()));
+
+/// Read the cache file once per run, a missing or outdated file starts an empty cache
+pub async fn load() {
+    if CACHE.lock().unwrap().loaded {
+        return;
+    }
+    let mut cache = match read_cache_file().await {
+        Some(cache) if cache.version == SCAN_CACHE_VERSION => cache,
+        _ => ScanCache { version: SCAN_CACHE_VERSION, ..Default::default() },
+    };
+    cache.loaded = true;
+    *CACHE.lock().unwrap() = cache;
+}
+
+async fn read_cache_file() -> Option<ScanCache> {
+    let path = get_app_data_path(SCAN_CACHE_FILE).await.ok()?;
+    let contents = tokio::fs::read_to_string(&path).await.ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Write the cache back if this scan parsed anything, dropping files that are gone
+pub async fn save() {
+    let contents = {
+        let mut cache = CACHE.lock().unwrap();
+        if !cache.dirty {
+            return;
+        }
+        cache.files.retain(|path, _| Path::new(path).is_file());
+        cache.dirty = false;
+        match serde_json::to_string(&*cache) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("Failed to serialize scan cache: {}", e);
+                return;
+            }
+        }
+    };
+    let path = match get_app_data_path(SCAN_CACHE_FILE).await {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Failed to save scan cache: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = tokio::fs::write(&path, contents).await {
+        eprintln!("Failed to save scan cache: {}", e);
+    }
+}
+
+/// Metadata of a model file, from the cache when the file is unchanged since it was parsed
+pub fn metadata_for(file_path: &Path) -> Result<GgufMetadata, Box<dyn std::error::Error>> {
+    let file_metadata = std::fs::metadata(file_path)?;
+    let size = file_metadata.len();
+    let modified_ms = file_metadata.modified()?.duration_since(UNIX_EPOCH)?.as_millis();
+    let key = file_path.to_string_lossy().to_string();
+
+    if let Some(cached) = CACHE.lock().unwrap().files.get(&key) {
+        if cached.size == size && cached.modified_ms == modified_ms {
+            return Ok(cached.metadata.clone());
+        }
+    }
+
+    // Parsed outside the lock, other scan workers keep hitting the cache meanwhile
+    let metadata = crate::scanner::extract_gguf_metadata(file_path)?;
+    let mut cache = CACHE.lock().unwrap();
+    cache.files.insert(key, CachedFile { size, modified_ms, metadata: metadata.clone() });
+    cache.dirty = true;
+    Ok(metadata)
+}
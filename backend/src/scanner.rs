@@ -1,13 +1,39 @@
 use std::fs;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::BufReader;
 use std::path::Path;
+use std::sync::LazyLock;
+use futures_util::StreamExt;
 use glob::glob;
 use regex::Regex;
+use serde::Serialize;
+use tauri::Emitter;
 use crate::models::*;
 
-pub async fn scan_models(directory: &str) -> Result<Vec<ModelInfo>, Box<dyn std::error::Error>> {
+// Split files are named `<name>-00001-of-00005.gguf`
+pub(crate) static SPLIT_FILE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^(.+?)-(\d{5})-of-(\d{5})\.gguf$").unwrap());
+
+// Header reads in flight at once, enough to hide disk latency without flooding a NAS
+const SCAN_CONCURRENCY: usize = 8;
+// Models per `model-scan-progress` event
+const SCAN_BATCH_SIZE: usize = 24;
+
+/// Models found so far, sent while a scan is running so the desktop fills in as it goes
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelScanProgress {
+    pub directory: String,
+    pub models: Vec<ModelInfo>,
+    pub scanned: usize,
+    pub total: usize, // Models (split sets count once) in this directory
+}
+
+/// Scan a directory, reading model headers in parallel and handing each batch of
+/// results to `on_batch` with the number of models scanned and the total
+pub async fn scan_models(
+    directory: &str,
+    mut on_batch: impl FnMut(Vec<ModelInfo>, usize, usize),
+) -> Result<(), Box<dyn std::error::Error>> {
     if directory.is_empty() || !Path::new(directory).is_dir() {
-        return Ok(Vec::new());
+        return Ok(());
     }
     
     let pattern = format!("{}/**/*.gguf", directory);
@@ -18,37 +44,185 @@ pub async fn scan_models(directory: &str) -> Result<Vec<ModelInfo>, Box<dyn std:
     
     // Group files by base name (handle split files)
     for path in files {
+        // Files parked by an unfinished batch delete are not models
+        if path.components().any(|c| c.as_os_str().to_string_lossy().starts_with(crate::batch::TRASH_DIR_PREFIX)) {
+            continue;
+        }
+        
         let path_str = path.to_string_lossy().to_string();
         let file_name = path.file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("")
             .to_string();
         
-        // Check if this is a split file (e.g., model-00001-of-00005.gguf)
-        let re = Regex::new(r"(.+?)-\d{5}-of-\d{5}\.gguf$")?;
-        if let Some(captures) = re.captures(&file_name) {
+        // Vision projectors are loaded alongside a model with --mmproj, not on their own
+        if crate::huggingface::is_mmproj_file(&file_name) {
+            continue;
+        }
+        
+        // Adapters are attached to a model with --lora, scan_lora_adapters lists them
+        if in_lora_folder(&path) {
+            continue;
+        }
+        
+        // Shards of a split file are grouped per folder, so two copies don't merge
+        if let Some(captures) = SPLIT_FILE.captures(&file_name) {
             let base_name = captures.get(1).unwrap().as_str().to_string();
-            model_groups.entry(base_name).or_insert_with(Vec::new).push(path_str);
+            let folder = path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+            model_groups.entry((folder, base_name)).or_insert_with(Vec::new).push(path_str);
         } else {
-            model_groups.entry(path_str.clone()).or_insert_with(Vec::new).push(path_str);
+            model_groups.entry((String::new(), path_str.clone())).or_insert_with(Vec::new).push(path_str);
         }
     }
     
-    let mut models = Vec::new();
+    let total = model_groups.len();
+    let mut results = futures_util::stream::iter(model_groups)
+        .map(|((_, base_name), mut file_list)| {
+            tokio::task::spawn_blocking(move || {
+                // llama-server is pointed at the first shard and finds the rest next to it
+                file_list.sort();
+                process_model_group(&base_name, &file_list).ok()
+            })
+        })
+        .buffer_unordered(SCAN_CONCURRENCY);
     
-    for (base_name, file_list) in model_groups {
-        if let Ok(model_info) = process_model_group(&base_name, &file_list).await {
-            models.push(model_info);
+    let mut scanned = 0;
+    let mut batch = Vec::new();
+    while let Some(result) = results.next().await {
+        scanned += 1;
+        if let Ok(Some(model_info)) = result {
+            batch.push(model_info);
+        }
+        if batch.len() >= SCAN_BATCH_SIZE || scanned == total {
+            on_batch(std::mem::take(&mut batch), scanned, total);
         }
     }
     
-    // Sort by name
+    Ok(())
+}
+
+/// Scan the models directory together with the extra model directories into one listing,
+/// emitting `model-scan-progress` as batches come in when an app handle is given
+pub async fn scan_library(config: &GlobalConfig, app_handle: Option<&tauri::AppHandle>) -> Result<Vec<ModelInfo>, Box<dyn std::error::Error>> {
+    let mut models = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    crate::scan_cache::load().await;
+    
+    let directories = std::iter::once(&config.models_directory).chain(&config.extra_model_directories);
+    for (index, directory) in directories.enumerate() {
+        let on_batch = |batch: Vec<ModelInfo>, scanned: usize, total: usize| {
+            // The same folder listed twice or nested in another shows its models once.
+            // Checking every model also covers an extra directory inside the models directory
+            let batch: Vec<ModelInfo> = batch
+                .into_iter()
+                .filter(|m| seen.insert(m.path.clone()))
+                .map(|mut m| {
+                    m.read_only = in_extra_directory(Path::new(&m.path), &config.extra_model_directories);
+                    m
+                })
+                .collect();
+            if let Some(app_handle) = app_handle {
+                let _ = app_handle.emit("model-scan-progress", ModelScanProgress {
+                    directory: directory.clone(),
+                    models: batch.clone(),
+                    scanned,
+                    total,
+                });
+            }
+            models.extend(batch);
+        };
+        match scan_models(directory, on_batch).await {
+            Ok(()) => {}
+            Err(e) if index == 0 => return Err(e),
+            // An unmounted share or unplugged drive shouldn't hide the rest of the library
+            Err(e) => eprintln!("Failed to scan extra model directory {}: {}", directory, e),
+        }
+    }
     models.sort_by(|a, b| a.name.cmp(&b.name));
+    crate::scan_cache::save().await;
     
     Ok(models)
 }
 
-async fn process_model_group(base_name: &str, file_list: &[String]) -> Result<ModelInfo, Box<dyn std::error::Error>> {
+/// The `-00001-of-N` file of a split model given any of its shards, llama-server only
+/// loads a split set from its first part. Other paths are returned unchanged
+pub fn first_shard(path: &str) -> String {
+    let file_path = Path::new(path);
+    let file_name = file_path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    match SPLIT_FILE.captures(file_name) {
+        Some(captures) if &captures[2] != "00001" => file_path
+            .with_file_name(format!("{}-00001-of-{}.gguf", &captures[1], &captures[3]))
+            .to_string_lossy()
+            .to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Adapters kept in a `loras/` folder count as such even without `general.type` metadata
+fn in_lora_folder(path: &Path) -> bool {
+    path.parent()
+        .is_some_and(|parent| parent.components().any(|c| c.as_os_str().eq_ignore_ascii_case("loras")))
+}
+
+/// LoRA adapters in the models directory and the extra model directories
+pub async fn scan_lora_adapters(config: &GlobalConfig) -> Vec<LoraAdapterInfo> {
+    let directories: Vec<String> = std::iter::once(&config.models_directory)
+        .chain(&config.extra_model_directories)
+        .filter(|d| !d.is_empty() && Path::new(d).is_dir())
+        .cloned()
+        .collect();
+    
+    tokio::task::spawn_blocking(move || {
+        let mut adapters = Vec::new();
+        for directory in directories {
+            let Ok(paths) = glob(&format!("{}/**/*.gguf", directory)) else {
+                continue;
+            };
+            for path in paths.flatten() {
+                if path.components().any(|c| c.as_os_str().to_string_lossy().starts_with(crate::batch::TRASH_DIR_PREFIX)) {
+                    continue;
+                }
+                let is_adapter = in_lora_folder(&path)
+                    || crate::gguf::read_general_type(&path).as_deref() == Some("adapter");
+                if !is_adapter {
+                    continue;
+                }
+                
+                let header = fs::File::open(&path)
+                    .ok()
+                    .and_then(|file| crate::gguf::read_header(&mut BufReader::new(file), false).ok());
+                let string = |key: &str| header.as_ref()?.get(key)?.as_str().map(|s| s.to_string());
+                // Control vectors share the adapter type but load with --control-vector
+                if string("adapter.type").is_some_and(|t| t != "lora") {
+                    continue;
+                }
+                adapters.push(LoraAdapterInfo {
+                    path: path.to_string_lossy().to_string(),
+                    name: string("general.name").unwrap_or_else(|| {
+                        path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+                    }),
+                    size_bytes: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                    architecture: string("general.architecture"),
+                    base_model: string("general.base_model.0.name"),
+                });
+            }
+        }
+        adapters.sort_by(|a, b| a.name.cmp(&b.name));
+        adapters
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Whether a path lies inside one of the read-only extra model directories
+pub fn in_extra_directory(path: &Path, extra_directories: &[String]) -> bool {
+    extra_directories
+        .iter()
+        .filter(|d| !d.trim().is_empty())
+        .any(|d| path.starts_with(d))
+}
+
+fn process_model_group(base_name: &str, file_list: &[String]) -> Result<ModelInfo, Box<dyn std::error::Error>> {
     let first_file = file_list.first().ok_or("Empty file list")?;
     let first_path = Path::new(first_file);
     
@@ -66,8 +240,16 @@ async fn process_model_group(base_name: &str, file_list: &[String]) -> Result<Mo
         .duration_since(std::time::UNIX_EPOCH)?
         .as_secs() as i64;
     
-    // Extract GGUF metadata
-    let gguf_metadata = extract_gguf_metadata(first_path)?;
+    // Extract GGUF metadata, unchanged files come from the scan cache
+    let gguf_metadata = crate::scan_cache::metadata_for(first_path)?;
+    if gguf_metadata.file_type.as_deref() == Some("adapter") {
+        return Err("LoRA adapter, not a model".into());
+    }
+    
+    // Every shard holds a slice of the tensors, the first one alone undercounts
+    let parameter_count = gguf_metadata.parameter_count.map(|first| {
+        first + file_list.iter().skip(1).filter_map(|f| shard_parameter_count(Path::new(f))).sum::<u64>()
+    });
     
     // Determine display name
     let display_name = if file_list.len() > 1 {
@@ -83,6 +265,16 @@ async fn process_model_group(base_name: &str, file_list: &[String]) -> Result<Mo
     // Extract quantization from filename
     let quantization = get_quantization_from_filename(&display_name);
     
+    // A split set is complete when shards 1..=N are all there
+    let first_name = first_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let incomplete = match SPLIT_FILE.captures(first_name) {
+        Some(captures) => {
+            let expected: usize = captures[3].parse().unwrap_or(0);
+            captures[2].parse::<usize>().ok() != Some(1) || file_list.len() != expected
+        }
+        None => false,
+    };
+    
     Ok(ModelInfo {
         path: first_file.clone(),
         name: display_name,
@@ -91,100 +283,62 @@ async fn process_model_group(base_name: &str, file_list: &[String]) -> Result<Mo
         model_name: gguf_metadata.name,
         quantization,
         date: modified_time,
+        shards: file_list.len(),
+        incomplete,
+        read_only: false,
+        context_length: gguf_metadata.context_length,
+        parameter_count,
+        embedding_length: gguf_metadata.embedding_length,
+        block_count: gguf_metadata.block_count,
+        chat_template: gguf_metadata.chat_template,
+        embedding_model: gguf_metadata.embedding_model,
     })
 }
 
 pub fn extract_gguf_metadata(file_path: &Path) -> Result<GgufMetadata, Box<dyn std::error::Error>> {
-    let mut file = fs::File::open(file_path)?;
-    
-    // Read magic bytes
-    let mut magic = [0u8; 4];
-    file.read_exact(&mut magic)?;
-    
-    if &magic != b"GGUF" {
-        return Ok(GgufMetadata {
-            architecture: "Unknown".to_string(),
-            name: file_path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown")
-                .to_string(),
-        });
-    }
-    
-    // Skip version and tensor count
-    file.seek(SeekFrom::Current(12))?;
-    
-    // Read metadata key-value count
-    let mut kv_count_bytes = [0u8; 8];
-    file.read_exact(&mut kv_count_bytes)?;
-    let kv_count = u64::from_le_bytes(kv_count_bytes);
-    
-    let mut architecture = "Unknown".to_string();
-    let mut name = file_path.file_name()
+    let file = fs::File::open(file_path)?;
+    let file_name = file_path.file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("Unknown")
         .to_string();
     
-    // Read key-value pairs
-    for _ in 0..kv_count {
-        // Read key length
-        let mut key_len_bytes = [0u8; 8];
-        if file.read_exact(&mut key_len_bytes).is_err() {
-            break;
+    // Non-GGUF or truncated files still get listed, just without their metadata
+    let header = match crate::gguf::read_header(&mut BufReader::new(file), true) {
+        Ok(header) => header,
+        Err(_) => {
+            return Ok(GgufMetadata {
+                architecture: "Unknown".to_string(),
+                name: file_name,
+                file_type: None,
+                context_length: None,
+                parameter_count: None,
+                embedding_length: None,
+                block_count: None,
+                chat_template: None,
+                embedding_model: false,
+            });
         }
-        let key_len = u64::from_le_bytes(key_len_bytes);
-        
-        // Read key
-        let mut key_bytes = vec![0u8; key_len as usize];
-        if file.read_exact(&mut key_bytes).is_err() {
-            break;
-        }
-        let key = String::from_utf8_lossy(&key_bytes);
-        
-        // Read value type
-        let mut value_type_bytes = [0u8; 4];
-        if file.read_exact(&mut value_type_bytes).is_err() {
-            break;
-        }
-        let value_type = u32::from_le_bytes(value_type_bytes);
-        
-        // Handle string values (type 8)
-        if value_type == 8 {
-            // Read value length
-            let mut value_len_bytes = [0u8; 8];
-            if file.read_exact(&mut value_len_bytes).is_err() {
-                break;
-            }
-            let value_len = u64::from_le_bytes(value_len_bytes);
-            
-            // Read value
-            let mut value_bytes = vec![0u8; value_len as usize];
-            if file.read_exact(&mut value_bytes).is_err() {
-                break;
-            }
-            let value = String::from_utf8_lossy(&value_bytes);
-            
-            match key.as_ref() {
-                "general.architecture" => {
-                    architecture = value.to_string();
-                }
-                "general.name" => {
-                    name = value.to_string();
-                }
-                _ => {}
-            }
-        } else {
-            // Skip non-string values
-            break;
-        }
-    }
+    };
     
+    let string = |key: &str| header.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
     Ok(GgufMetadata {
-        architecture,
-        name,
+        architecture: header.architecture().unwrap_or("Unknown").to_string(),
+        name: string("general.name").unwrap_or(file_name),
+        file_type: string("general.type"),
+        context_length: header.context_length(),
+        parameter_count: header.parameter_count(),
+        embedding_length: header.arch_u64("embedding_length"),
+        block_count: header.arch_u64("block_count"),
+        chat_template: string("tokenizer.chat_template"),
+        embedding_model: header.embedding_mode().is_some(),
     })
 }
 
+/// Parameters in the tensors of one shard, which carries little metadata besides them
+fn shard_parameter_count(file_path: &Path) -> Option<u64> {
+    crate::scan_cache::metadata_for(file_path).ok()?.parameter_count
+}
+
 pub fn get_quantization_from_filename(filename: &str) -> String {
     // Find .gguf extension first, then search backwards for the first dash or dot
     let filename_lower = filename.to_lowercase();
@@ -0,0 +1,249 @@
+// Temporary public links to a running model. A cloudflared quick tunnel exposes a
+// loopback auth proxy, which checks the generated API key before forwarding to the
+// model server. Links are revoked on expiry and when their server stops.
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Duration;
+use chrono::Utc;
+use regex::Regex;
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::process::Command as TokioCommand;
+use uuid::Uuid;
+
+use crate::models::{client_host, ProcessStatus, ShareLink};
+use crate::AppState;
+
+const DEFAULT_EXPIRY_MINUTES: u32 = 60;
+const MAX_EXPIRY_MINUTES: u32 = 24 * 60;
+const TUNNEL_START_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+static TUNNEL_URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"https://[a-z0-9-]+\.trycloudflare\.com").unwrap());
+
+struct Share {
+    link: ShareLink,
+    tunnel: tokio::process::Child,
+    proxy: tokio::task::AbortHandle,
+}
+
+// Active shares keyed by share id
+static SHARES: LazyLock<std::sync::Mutex<HashMap<String, Share>>> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Expose a running server through a quick tunnel until it expires or gets revoked
+pub async fn create(
+    process_id: &str,
+    expires_in_minutes: Option<u32>,
+    state: &AppState,
+    app_handle: tauri::AppHandle,
+) -> Result<ShareLink, String> {
+    let process = state.processes.get(process_id).await.ok_or("Process not found")?;
+    if !matches!(process.status, ProcessStatus::Starting | ProcessStatus::Running) {
+        return Err("The model server is not running".to_string());
+    }
+    let minutes = expires_in_minutes.unwrap_or(DEFAULT_EXPIRY_MINUTES);
+    if minutes == 0 || minutes > MAX_EXPIRY_MINUTES {
+        return Err(format!("Expiry must be between 1 and {} minutes", MAX_EXPIRY_MINUTES));
+    }
+
+    let upstream = format!("{}:{}", client_host(&process.host), process.port);
+    let api_key = format!("sk-share-{}", Uuid::new_v4().simple());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await
+        .map_err(|e| format!("Failed to start share proxy: {}", e))?;
+    let proxy_port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let proxy = tokio::spawn(run_proxy(listener, upstream, api_key.clone())).abort_handle();
+
+    let tunnel_path = {
+        let config = state.config.lock().await;
+        if config.share_tunnel_path.trim().is_empty() { "cloudflared".to_string() } else { config.share_tunnel_path.clone() }
+    };
+    let (tunnel, public_url) = match start_tunnel(&tunnel_path, proxy_port).await {
+        Ok(started) => started,
+        Err(e) => {
+            proxy.abort();
+            return Err(e);
+        }
+    };
+
+    let created_at = Utc::now();
+    let link = ShareLink {
+        id: Uuid::new_v4().to_string(),
+        process_id: process_id.to_string(),
+        public_url,
+        api_key,
+        created_at,
+        expires_at: created_at + chrono::Duration::minutes(minutes as i64),
+    };
+    SHARES.lock().unwrap().insert(link.id.clone(), Share { link: link.clone(), tunnel, proxy });
+    println!("Shared process {} at {} until {}", process_id, link.public_url, link.expires_at);
+
+    let share_id = link.id.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(minutes as u64 * 60)).await;
+        if revoke(&share_id) {
+            println!("Share link {} expired", share_id);
+            let _ = app_handle.emit("share-link-expired", &share_id);
+        }
+    });
+
+    Ok(link)
+}
+
+/// Close a share link. Returns false when it was already gone.
+pub fn revoke(share_id: &str) -> bool {
+    let share = SHARES.lock().unwrap().remove(share_id);
+    match share {
+        Some(mut share) => {
+            share.proxy.abort();
+            if let Err(e) = share.tunnel.start_kill() {
+                eprintln!("Failed to stop tunnel for share {}: {}", share_id, e);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Close every link pointing at a process that is going away
+pub fn revoke_for_process(process_id: &str) {
+    let share_ids: Vec<String> = SHARES.lock().unwrap()
+        .values()
+        .filter(|s| s.link.process_id == process_id)
+        .map(|s| s.link.id.clone())
+        .collect();
+    for share_id in share_ids {
+        revoke(&share_id);
+    }
+}
+
+/// Close every link, used on shutdown since statics never drop their tunnels
+pub fn revoke_all() {
+    let share_ids: Vec<String> = SHARES.lock().unwrap().keys().cloned().collect();
+    for share_id in share_ids {
+        revoke(&share_id);
+    }
+}
+
+/// Start a quick tunnel and wait for cloudflared to report its public URL
+async fn start_tunnel(tunnel_path: &str, proxy_port: u16) -> Result<(tokio::process::Child, String), String> {
+    let mut cmd = TokioCommand::new(tunnel_path);
+    cmd.args(["tunnel", "--no-autoupdate", "--url", &format!("http://127.0.0.1:{}", proxy_port)])
+       .stdin(Stdio::null())
+       .stdout(Stdio::null())
+       .stderr(Stdio::piped())
+       .kill_on_drop(true);
+
+    // Hide console window on Windows release builds
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let mut child = cmd.spawn()
+        .map_err(|e| format!("Failed to start {} (is cloudflared installed?): {}", tunnel_path, e))?;
+    let stderr = child.stderr.take().ok_or("Failed to get tunnel output")?;
+    let mut lines = BufReader::new(stderr).lines();
+
+    let found = tokio::time::timeout(TUNNEL_START_TIMEOUT, async {
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Some(url) = TUNNEL_URL.find(&line) {
+                return Some(url.as_str().to_string());
+            }
+        }
+        None
+    }).await;
+
+    match found {
+        Ok(Some(url)) => {
+            // Keep draining the log so the pipe never fills up and blocks the tunnel
+            tokio::spawn(async move { while let Ok(Some(_)) = lines.next_line().await {} });
+            Ok((child, url))
+        }
+        Ok(None) => Err("The tunnel exited before reporting a public URL".to_string()),
+        Err(_) => {
+            let _ = child.start_kill();
+            Err("Timed out waiting for the tunnel to come up".to_string())
+        }
+    }
+}
+
+async fn run_proxy(listener: TcpListener, upstream: String, api_key: String) {
+    loop {
+        let (client, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                eprintln!("Share proxy stopped accepting: {}", e);
+                break;
+            }
+        };
+        let upstream = upstream.clone();
+        let api_key = api_key.clone();
+        tokio::spawn(async move {
+            if let Err(e) = proxy_connection(client, &upstream, &api_key).await {
+                eprintln!("Share proxy connection failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Check the key on the request head, then forward the connection as is.
+/// The forwarded request asks for `Connection: close` so a pooled tunnel
+/// connection can't carry a second, unchecked request.
+async fn proxy_connection(mut client: TcpStream, upstream: &str, api_key: &str) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    let head_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buffer.len() > MAX_REQUEST_HEAD {
+            return client.write_all(b"HTTP/1.1 431 Request Header Fields Too Large\r\nConnection: close\r\nContent-Length: 0\r\n\r\n").await;
+        }
+        let mut chunk = [0u8; 4096];
+        let read = client.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+    let mut lines = head.split("\r\n").filter(|l| !l.is_empty());
+    let request_line = lines.next().unwrap_or_default();
+    let headers: Vec<&str> = lines.collect();
+
+    let authorized = headers.iter().any(|h| {
+        h.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("authorization")
+                && value.trim().strip_prefix("Bearer ").is_some_and(|key| key.trim() == api_key)
+        })
+    });
+    if !authorized {
+        let body = r#"{"error":{"message":"Invalid API key","type":"authentication_error"}}"#;
+        let response = format!(
+            "HTTP/1.1 401 Unauthorized\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(), body
+        );
+        return client.write_all(response.as_bytes()).await;
+    }
+
+    // The share key means nothing to llama-server, so it is not passed on
+    let mut forwarded = String::from(request_line);
+    forwarded.push_str("\r\n");
+    for header in headers {
+        let name = header.split(':').next().unwrap_or_default().trim();
+        if name.eq_ignore_ascii_case("authorization") || name.eq_ignore_ascii_case("connection") {
+            continue;
+        }
+        forwarded.push_str(header);
+        forwarded.push_str("\r\n");
+    }
+    forwarded.push_str("Connection: close\r\n\r\n");
+
+    let mut server = TcpStream::connect(upstream).await?;
+    server.write_all(forwarded.as_bytes()).await?;
+    server.write_all(&buffer[head_end..]).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut server).await?;
+    Ok(())
+}
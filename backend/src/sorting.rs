@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::models::{ModelInfo, ModelUsage};
+
+/// Orderings for the desktop icons, matching `DesktopState.sort_type`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortStrategy {
+    Name,
+    Architecture,
+    Family,
+    Size,
+    Date,
+    LastUsed,
+    Quantization,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortedModel {
+    #[serde(flatten)]
+    pub model: ModelInfo,
+    pub group: Option<String>, // Heading for grouped orderings such as by family
+    pub last_used: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Architecture family, so `qwen2`, `qwen3moe` and `qwen3` end up together
+pub fn architecture_family(architecture: &str) -> String {
+    let lower = architecture.to_lowercase();
+    let family: String = lower.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+    if family.is_empty() { lower } else { family }
+}
+
+/// Approximate bits per weight for a quantization name, higher means better quality.
+/// Unknown names sort below everything else.
+pub fn quantization_rank(quantization: &str) -> f32 {
+    let q = quantization.to_uppercase();
+    match q.as_str() {
+        "F32" | "FP32" => return 32.0,
+        "F16" | "FP16" | "BF16" => return 16.0,
+        "TQ1_0" => return 1.69,
+        "TQ2_0" => return 2.06,
+        _ => {}
+    }
+    if q.starts_with("MXFP4") {
+        return 4.25;
+    }
+
+    let digits = q.trim_start_matches("IQ").trim_start_matches('Q');
+    let bits = match digits.chars().next().and_then(|c| c.to_digit(10)) {
+        Some(bits) => bits as f32,
+        None => return -1.0,
+    };
+    // Within a bit width: _L > _M > _S > _XS > _XXS, and K-quants/_1 sit slightly above plain ones
+    let variant = if q.ends_with("_XXS") {
+        0.0
+    } else if q.ends_with("_XS") {
+        0.1
+    } else if q.ends_with("_S") || q.ends_with("_NL") {
+        0.2
+    } else if q.ends_with("_M") {
+        0.3
+    } else if q.ends_with("_L") || q.ends_with("_XL") {
+        0.4
+    } else if q.ends_with("_1") || q.ends_with("_K") {
+        0.25
+    } else {
+        0.15
+    };
+    // i-quants trade a little quality for size at the same nominal width
+    let iq_penalty = if q.starts_with("IQ") { 0.05 } else { 0.0 };
+    bits + variant - iq_penalty
+}
+
+fn by_name(a: &ModelInfo, b: &ModelInfo) -> Ordering {
+    a.name.to_lowercase().cmp(&b.name.to_lowercase())
+}
+
+fn by_float(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+/// Order scanned models by a strategy. Ties always fall back to the name so the
+/// result is stable between scans.
+pub fn sort_models(
+    models: Vec<ModelInfo>,
+    strategy: SortStrategy,
+    descending: bool,
+    usage: &HashMap<String, ModelUsage>,
+) -> Vec<SortedModel> {
+    let mut sorted: Vec<SortedModel> = models
+        .into_iter()
+        .map(|model| {
+            let group = match strategy {
+                SortStrategy::Family => Some(architecture_family(&model.architecture)),
+                SortStrategy::Architecture => Some(model.architecture.clone()),
+                SortStrategy::Quantization => Some(model.quantization.clone()),
+                _ => None,
+            };
+            let last_used = usage.get(&model.path).and_then(|u| u.last_used);
+            SortedModel { model, group, last_used }
+        })
+        .collect();
+
+    sorted.sort_by(|a, b| {
+        let primary = match strategy {
+            SortStrategy::Name => Ordering::Equal,
+            SortStrategy::Architecture => a.model.architecture.to_lowercase().cmp(&b.model.architecture.to_lowercase()),
+            // Families are grouped, largest models first within a family
+            SortStrategy::Family => a.group.cmp(&b.group).then_with(|| by_float(b.model.size_gb, a.model.size_gb)),
+            SortStrategy::Size => by_float(a.model.size_gb, b.model.size_gb),
+            SortStrategy::Date => a.model.date.cmp(&b.model.date),
+            SortStrategy::LastUsed => a.last_used.cmp(&b.last_used),
+            SortStrategy::Quantization => by_float(quantization_rank(&a.model.quantization) as f64, quantization_rank(&b.model.quantization) as f64),
+        };
+        let ordering = primary.then_with(|| by_name(&a.model, &b.model));
+        if descending { ordering.reverse() } else { ordering }
+    });
+
+    sorted
+}
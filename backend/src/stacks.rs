@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+use crate::models::*;
+use crate::process::{launch_model_server, terminate_process};
+use crate::AppState;
+
+/// Order stack members so every member comes after the members it depends on
+pub fn resolve_launch_order(stack: &StackDefinition) -> Result<Vec<String>, String> {
+    let members: HashMap<&str, &StackMember> = stack.members
+        .iter()
+        .map(|m| (m.model_path.as_str(), m))
+        .collect();
+    
+    for member in &stack.members {
+        for dependency in &member.depends_on {
+            if !members.contains_key(dependency.as_str()) {
+                return Err(format!("'{}' depends on '{}' which is not part of the stack", member.model_path, dependency));
+            }
+        }
+    }
+    
+    let mut ordered: Vec<String> = Vec::new();
+    let mut done: HashSet<&str> = HashSet::new();
+    
+    // Repeatedly take members whose dependencies are all satisfied, keeping the declared order otherwise
+    while ordered.len() < stack.members.len() {
+        let ready: Vec<&StackMember> = stack.members
+            .iter()
+            .filter(|m| !done.contains(m.model_path.as_str()))
+            .filter(|m| m.depends_on.iter().all(|d| done.contains(d.as_str())))
+            .collect();
+        
+        if ready.is_empty() {
+            return Err(format!("Stack '{}' has a dependency cycle", stack.name));
+        }
+        
+        for member in ready {
+            done.insert(member.model_path.as_str());
+            ordered.push(member.model_path.clone());
+        }
+    }
+    
+    Ok(ordered)
+}
+
+/// Launch every member of a stack in dependency order.
+/// If any member fails to start, the members launched so far are stopped again.
+pub async fn launch_stack(name: &str, state: &AppState) -> Result<StackStatus, Box<dyn std::error::Error>> {
+    let stack = {
+        let stacks = state.stacks.lock().await;
+        stacks.get(name).cloned().ok_or_else(|| format!("Stack '{}' not found", name))?
+    };
+    
+    if is_stack_active(name, state).await {
+        return Err(format!("Stack '{}' is already running", name).into());
+    }
+    
+    let order = resolve_launch_order(&stack)?;
+    let mut launched: Vec<String> = Vec::new();
+    
+    for model_path in order {
+        println!("Launching stack '{}' member: {}", name, model_path);
+        // Keep only the message, the boxed error must not be held across awaits
+        let result = launch_model_server(model_path.clone(), state).await.map_err(|e| e.to_string());
+        match result {
+            Ok(result) => launched.push(result.process_id),
+            Err(error) => {
+                eprintln!("Stack '{}' member {} failed to launch: {}", name, model_path, error);
+                for process_id in launched.into_iter().rev() {
+                    let _ = terminate_process(process_id, state).await;
+                }
+                return Err(format!("Failed to launch '{}': {}", model_path, error).into());
+            }
+        }
+    }
+    
+    {
+        let mut stack_processes = state.stack_processes.lock().await;
+        stack_processes.insert(name.to_string(), launched);
+    }
+    
+    get_stack_status(name, state).await
+}
+
+/// Stop every member of a running stack, dependents first
+pub async fn stop_stack(name: &str, state: &AppState) -> Result<StackStatus, Box<dyn std::error::Error>> {
+    let process_ids = {
+        let mut stack_processes = state.stack_processes.lock().await;
+        stack_processes.remove(name).unwrap_or_default()
+    };
+    
+    for process_id in process_ids.into_iter().rev() {
+        if let Err(e) = terminate_process(process_id.clone(), state).await {
+            eprintln!("Failed to stop stack '{}' process {}: {}", name, process_id, e);
+        }
+    }
+    
+    get_stack_status(name, state).await
+}
+
+/// Aggregate the process status of every member of a stack
+pub async fn get_stack_status(name: &str, state: &AppState) -> Result<StackStatus, Box<dyn std::error::Error>> {
+    let stack = {
+        let stacks = state.stacks.lock().await;
+        stacks.get(name).cloned().ok_or_else(|| format!("Stack '{}' not found", name))?
+    };
+    
+    let process_ids = {
+        let stack_processes = state.stack_processes.lock().await;
+        stack_processes.get(name).cloned().unwrap_or_default()
+    };
+    
+    let processes = state.processes.running.lock().await;
+    let members: Vec<StackMemberStatus> = stack.members
+        .iter()
+        .map(|member| {
+            let process = process_ids
+                .iter()
+                .filter_map(|id| processes.get(id))
+                .find(|p| p.model_path == member.model_path);
+            StackMemberStatus {
+                model_path: member.model_path.clone(),
+                process_id: process.map(|p| p.id.clone()),
+                status: process.map(|p| p.status.clone()),
+                host: process.map(|p| p.host.clone()),
+                port: process.map(|p| p.port),
+            }
+        })
+        .collect();
+    
+    let alive = members
+        .iter()
+        .filter(|m| matches!(m.status, Some(ProcessStatus::Starting | ProcessStatus::Running)))
+        .count();
+    let stack_state = if alive == 0 {
+        StackState::Stopped
+    } else if alive == members.len() {
+        StackState::Running
+    } else {
+        StackState::Partial
+    };
+    
+    Ok(StackStatus {
+        name: name.to_string(),
+        state: stack_state,
+        members,
+    })
+}
+
+async fn is_stack_active(name: &str, state: &AppState) -> bool {
+    let process_ids = {
+        let stack_processes = state.stack_processes.lock().await;
+        stack_processes.get(name).cloned().unwrap_or_default()
+    };
+    let processes = state.processes.running.lock().await;
+    process_ids.iter().any(|id| processes.contains_key(id))
+}
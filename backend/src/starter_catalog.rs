@@ -0,0 +1,89 @@
+// Curated starter models per use case, bundled so a fresh install has something to
+// offer before the user knows what to search for. Each entry lists its quants from
+// smallest to largest; the recommendation is the largest one that fits the memory
+// the model will actually run from (VRAM when there is a GPU, otherwise half of RAM).
+
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+use sysinfo::System;
+
+use crate::models::StarterModel;
+
+const BUNDLED_CATALOG: &str = include_str!("../catalog/starter_models.json");
+
+// KV cache, compute buffers and whatever else is already on the GPU
+const VRAM_RESERVE_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct CatalogEntry {
+    id: String,
+    name: String,
+    use_case: String,
+    repo_id: String,
+    description: String,
+    quants: Vec<CatalogQuant>,
+    #[serde(default)]
+    extra_files: Vec<String>, // Needed with every quant, e.g. a vision projector
+    #[serde(default)]
+    extra_size_bytes: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogQuant {
+    quant: String,
+    files: Vec<String>,
+    size_bytes: u64,
+}
+
+static CATALOG: LazyLock<Vec<CatalogEntry>> = LazyLock::new(|| {
+    serde_json::from_str(BUNDLED_CATALOG).unwrap_or_else(|e| {
+        eprintln!("Failed to parse bundled starter catalog: {}", e);
+        Vec::new()
+    })
+});
+
+/// The catalog with a quant picked for this machine. Blocking: probes the GPU.
+pub fn resolve_catalog() -> Vec<StarterModel> {
+    let budget = memory_budget();
+    CATALOG.iter().filter_map(|entry| recommend(entry, budget)).collect()
+}
+
+/// Files to download for a catalog entry, as (repo id, files)
+pub fn download_plan(entry_id: &str) -> Result<(String, Vec<String>), String> {
+    let model = resolve_catalog()
+        .into_iter()
+        .find(|m| m.id == entry_id)
+        .ok_or_else(|| format!("Unknown starter model: {}", entry_id))?;
+    Ok((model.repo_id, model.files))
+}
+
+fn recommend(entry: &CatalogEntry, budget: u64) -> Option<StarterModel> {
+    let total = |quant: &CatalogQuant| quant.size_bytes + entry.extra_size_bytes;
+    let fitting = entry.quants.iter().rev().find(|q| total(q) <= budget);
+    let (quant, fits) = match fitting {
+        Some(quant) => (quant, true),
+        None => (entry.quants.first()?, false),
+    };
+
+    Some(StarterModel {
+        id: entry.id.clone(),
+        name: entry.name.clone(),
+        use_case: entry.use_case.clone(),
+        repo_id: entry.repo_id.clone(),
+        description: entry.description.clone(),
+        quant: quant.quant.clone(),
+        files: quant.files.iter().chain(&entry.extra_files).cloned().collect(),
+        size_bytes: total(quant),
+        fits,
+    })
+}
+
+fn memory_budget() -> u64 {
+    if let Some(total_vram) = crate::system_monitor::total_vram_bytes() {
+        return total_vram.saturating_sub(VRAM_RESERVE_BYTES);
+    }
+    let mut sys = System::new();
+    sys.refresh_memory();
+    sys.total_memory() / 2
+}
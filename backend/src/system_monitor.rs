@@ -11,6 +11,12 @@ pub struct SystemStats {
     pub gpu_usage: f32,
     pub gpu_memory_total_gb: f32,
     pub gpu_memory_used_gb: f32,
+    // Set when the GPU shares system memory (Apple Silicon, integrated GPUs), GPU memory is
+    // then the share it holds of RAM rather than VRAM of its own
+    #[serde(default)]
+    pub unified_memory: bool,
+    #[serde(default)]
+    pub memory_pressure: Option<f32>, // Percent, macOS only
     pub timestamp: u64,
 }
 
@@ -27,7 +33,22 @@ pub async fn get_system_stats() -> Result<SystemStats, String> {
     let memory_used_gb = sys.used_memory() as f32 / (1024.0 * 1024.0 * 1024.0);
     
     // GPU information
-    let (gpu_name, gpu_usage, gpu_memory_total_gb, gpu_memory_used_gb) = get_gpu_info();
+    let gb = 1024.0 * 1024.0 * 1024.0;
+    let (gpu_name, gpu_usage, gpu_memory_total_gb, gpu_memory_used_gb, unified_memory) = match crate::gpu_probe::primary() {
+        Some(sample) => (
+            sample.name,
+            sample.usage_percent,
+            (sample.memory_total_bytes as f64 / gb) as f32,
+            (sample.memory_used_bytes as f64 / gb) as f32,
+            sample.shared_memory,
+        ),
+        None => ("No GPU detected".to_string(), 0.0, 0.0, 0.0, false),
+    };
+
+    #[cfg(target_os = "macos")]
+    let memory_pressure = crate::apple_silicon::memory_pressure();
+    #[cfg(not(target_os = "macos"))]
+    let memory_pressure = None;
     
     let timestamp = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -42,47 +63,27 @@ pub async fn get_system_stats() -> Result<SystemStats, String> {
         gpu_usage,
         gpu_memory_total_gb,
         gpu_memory_used_gb,
+        unified_memory,
+        memory_pressure,
         timestamp,
     })
 }
 
-fn get_gpu_info() -> (String, f32, f32, f32) {
-    // Try to get NVIDIA GPU info
-    match nvml_wrapper::Nvml::init() {
-        Ok(nvml) => {
-            match nvml.device_count() {
-                Ok(count) if count > 0 => {
-                    match nvml.device_by_index(0) {
-                        Ok(device) => {
-                            let name = device.name().unwrap_or_else(|_| "NVIDIA GPU".to_string());
-                            
-                            // Get GPU utilization
-                            let gpu_usage = match device.utilization_rates() {
-                                Ok(util) => util.gpu as f32,
-                                Err(_) => 0.0,
-                            };
-                            
-                            // Get GPU memory info
-                            let (gpu_memory_total_gb, gpu_memory_used_gb) = match device.memory_info() {
-                                Ok(mem_info) => {
-                                    let total = mem_info.total as f32 / (1024.0 * 1024.0 * 1024.0);
-                                    let used = mem_info.used as f32 / (1024.0 * 1024.0 * 1024.0);
-                                    (total, used)
-                                },
-                                Err(_) => (0.0, 0.0),
-                            };
-                            
-                            (name, gpu_usage, gpu_memory_total_gb, gpu_memory_used_gb)
-                        },
-                        Err(_) => ("NVIDIA GPU (info unavailable)".to_string(), 0.0, 0.0, 0.0)
-                    }
-                },
-                _ => ("No NVIDIA GPU detected".to_string(), 0.0, 0.0, 0.0)
-            }
-        },
-        Err(_) => {
-            // Fallback for non-NVIDIA GPUs or when NVML is not available
-            ("No NVIDIA GPU detected".to_string(), 0.0, 0.0, 0.0)
-        }
-    }
-}
\ No newline at end of file
+/// The primary GPU when it has a memory budget. Apple Silicon reports Metal's wired limit
+/// as its total; integrated GPUs without one report none and don't count
+fn gpu_with_memory() -> Option<crate::gpu_probe::GpuSample> {
+    crate::gpu_probe::primary().filter(|sample| sample.memory_total_bytes > 0)
+}
+
+/// Free memory on the primary GPU in bytes, None when no GPU reports its memory.
+/// Blocking: NVML init and the counter baseline can take a moment.
+pub fn free_vram_bytes() -> Option<u64> {
+    let sample = gpu_with_memory()?;
+    Some(sample.memory_total_bytes.saturating_sub(sample.memory_used_bytes))
+}
+
+/// Total memory of the primary GPU in bytes, None when no GPU reports its memory.
+/// Blocking, like free_vram_bytes.
+pub fn total_vram_bytes() -> Option<u64> {
+    gpu_with_memory().map(|sample| sample.memory_total_bytes)
+}
@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use crate::config::get_app_data_path;
+
+const THEMES_DIR: &str = "themes";
+const WALLPAPERS_DIR: &str = "wallpapers";
+const THEMES_FILE: &str = "themes.json";
+
+// Wallpapers larger than this are rejected to keep the data directory reasonable
+const MAX_WALLPAPER_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperInfo {
+    pub id: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: u64,
+}
+
+/// A user-defined color theme, optionally paired with a wallpaper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomTheme {
+    pub id: String,
+    pub name: String,
+    pub colors: HashMap<String, String>,
+    #[serde(default)]
+    pub wallpaper_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct ThemesFile {
+    themes: Vec<CustomTheme>,
+}
+
+async fn themes_dir() -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+    let path = get_app_data_path(THEMES_DIR).await.map_err(|e| e.to_string())?;
+    fs::create_dir_all(path.join(WALLPAPERS_DIR)).await?;
+    Ok(path)
+}
+
+async fn wallpapers_dir() -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(themes_dir().await?.join(WALLPAPERS_DIR))
+}
+
+/// Detect the image format from its magic bytes
+fn detect_image_mime(header: &[u8]) -> Option<&'static str> {
+    if header.starts_with(&[0x89, b'P', b'N', b'G']) {
+        Some("image/png")
+    } else if header.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if header.len() >= 12 && &header[0..4] == b"RIFF" && &header[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if header.starts_with(b"BM") {
+        Some("image/bmp")
+    } else {
+        None
+    }
+}
+
+fn extension_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "bmp",
+    }
+}
+
+async fn wallpaper_info(path: &Path) -> Option<WallpaperInfo> {
+    let metadata = fs::metadata(path).await.ok()?;
+    let mut header = [0u8; 12];
+    let mut file = fs::File::open(path).await.ok()?;
+    use tokio::io::AsyncReadExt;
+    let read = file.read(&mut header).await.ok()?;
+    let mime_type = detect_image_mime(&header[..read])?;
+    let file_name = path.file_name()?.to_str()?.to_string();
+    Some(WallpaperInfo {
+        id: path.file_stem()?.to_str()?.to_string(),
+        file_name,
+        mime_type: mime_type.to_string(),
+        size: metadata.len(),
+    })
+}
+
+/// Copy an image into the wallpapers folder after validating its format and size
+pub async fn import_wallpaper(source_path: &str) -> Result<WallpaperInfo, Box<dyn std::error::Error + Send + Sync>> {
+    let source = Path::new(source_path);
+    let metadata = fs::metadata(source).await.map_err(|e| format!("Cannot read {}: {}", source_path, e))?;
+    if !metadata.is_file() {
+        return Err("Wallpaper source is not a file".into());
+    }
+    if metadata.len() > MAX_WALLPAPER_BYTES {
+        return Err(format!("Wallpaper is larger than {} MB", MAX_WALLPAPER_BYTES / (1024 * 1024)).into());
+    }
+    
+    let bytes = fs::read(source).await?;
+    let mime_type = detect_image_mime(&bytes).ok_or("Unsupported image format (use PNG, JPEG, GIF, WebP or BMP)")?;
+    
+    let id = uuid::Uuid::new_v4().to_string();
+    let file_name = format!("{}.{}", id, extension_for_mime(mime_type));
+    let destination = wallpapers_dir().await?.join(&file_name);
+    fs::write(&destination, &bytes).await?;
+    
+    println!("Imported wallpaper {} as {:?}", source_path, destination);
+    Ok(WallpaperInfo {
+        id,
+        file_name,
+        mime_type: mime_type.to_string(),
+        size: metadata.len(),
+    })
+}
+
+pub async fn list_wallpapers() -> Result<Vec<WallpaperInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut wallpapers = Vec::new();
+    let mut entries = fs::read_dir(wallpapers_dir().await?).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.is_file() {
+            if let Some(info) = wallpaper_info(&path).await {
+                wallpapers.push(info);
+            }
+        }
+    }
+    wallpapers.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok(wallpapers)
+}
+
+async fn find_wallpaper(id: &str) -> Result<(PathBuf, WallpaperInfo), Box<dyn std::error::Error + Send + Sync>> {
+    // Ids are generated uuids, anything else could escape the wallpapers folder
+    if uuid::Uuid::parse_str(id).is_err() {
+        return Err("Invalid wallpaper id".into());
+    }
+    let mut entries = fs::read_dir(wallpapers_dir().await?).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.file_stem().and_then(|s| s.to_str()) == Some(id) {
+            if let Some(info) = wallpaper_info(&path).await {
+                return Ok((path, info));
+            }
+        }
+    }
+    Err("Wallpaper not found".into())
+}
+
+/// Return a wallpaper as a data URL the desktop can use directly as a CSS background
+pub async fn get_wallpaper_data(id: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    use base64::Engine;
+    let (path, info) = find_wallpaper(id).await?;
+    let bytes = fs::read(&path).await?;
+    Ok(format!(
+        "data:{};base64,{}",
+        info.mime_type,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// Delete a wallpaper and detach it from any theme using it
+pub async fn remove_wallpaper(id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (path, _) = find_wallpaper(id).await?;
+    fs::remove_file(&path).await?;
+    
+    let mut themes = load_themes_file().await?;
+    for theme in themes.themes.iter_mut() {
+        if theme.wallpaper_id.as_deref() == Some(id) {
+            theme.wallpaper_id = None;
+        }
+    }
+    save_themes_file(&themes).await
+}
+
+async fn load_themes_file() -> Result<ThemesFile, Box<dyn std::error::Error + Send + Sync>> {
+    let path = themes_dir().await?.join(THEMES_FILE);
+    if !path.exists() {
+        return Ok(ThemesFile::default());
+    }
+    let contents = fs::read_to_string(&path).await?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+async fn save_themes_file(themes: &ThemesFile) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = themes_dir().await?.join(THEMES_FILE);
+    fs::write(&path, serde_json::to_string_pretty(themes)?).await?;
+    Ok(())
+}
+
+pub async fn list_custom_themes() -> Result<Vec<CustomTheme>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(load_themes_file().await?.themes)
+}
+
+/// Insert or replace a custom theme; a missing id creates a new theme
+pub async fn save_custom_theme(mut theme: CustomTheme) -> Result<CustomTheme, Box<dyn std::error::Error + Send + Sync>> {
+    if theme.name.trim().is_empty() {
+        return Err("Theme name cannot be empty".into());
+    }
+    if let Some(wallpaper_id) = &theme.wallpaper_id {
+        find_wallpaper(wallpaper_id).await?;
+    }
+    if theme.id.is_empty() {
+        theme.id = uuid::Uuid::new_v4().to_string();
+    }
+    
+    let mut themes = load_themes_file().await?;
+    themes.themes.retain(|t| t.id != theme.id);
+    themes.themes.push(theme.clone());
+    save_themes_file(&themes).await?;
+    Ok(theme)
+}
+
+pub async fn delete_custom_theme(id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut themes = load_themes_file().await?;
+    themes.themes.retain(|t| t.id != id);
+    save_themes_file(&themes).await
+}
+
+#[tauri::command]
+pub async fn import_wallpaper_command(source_path: String) -> Result<WallpaperInfo, String> {
+    import_wallpaper(&source_path).await.map_err(|e| format!("Failed to import wallpaper: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_wallpapers_command() -> Result<Vec<WallpaperInfo>, String> {
+    list_wallpapers().await.map_err(|e| format!("Failed to list wallpapers: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_wallpaper_data_command(id: String) -> Result<String, String> {
+    get_wallpaper_data(&id).await.map_err(|e| format!("Failed to load wallpaper: {}", e))
+}
+
+#[tauri::command]
+pub async fn remove_wallpaper_command(id: String) -> Result<(), String> {
+    remove_wallpaper(&id).await.map_err(|e| format!("Failed to remove wallpaper: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_custom_themes_command() -> Result<Vec<CustomTheme>, String> {
+    list_custom_themes().await.map_err(|e| format!("Failed to list themes: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_custom_theme_command(theme: CustomTheme) -> Result<CustomTheme, String> {
+    save_custom_theme(theme).await.map_err(|e| format!("Failed to save theme: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_custom_theme_command(id: String) -> Result<(), String> {
+    delete_custom_theme(&id).await.map_err(|e| format!("Failed to delete theme: {}", e))
+}
@@ -0,0 +1,127 @@
+// Experimental "try before download". llama-server fetches a single GGUF straight
+// from the Hub (`--hf-repo`/`--hf-file`) into a throwaway cache and loads it as soon
+// as it lands, so a small quant can be sanity-checked without adding it to the
+// library. Split models are fetched in full by llama.cpp from their first shard.
+
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::models::{LaunchResult, ModelConfig, ProcessStatus};
+use crate::AppState;
+
+const TRIAL_CACHE_DIR: &str = "trial-cache";
+const TRIAL_PATH_PREFIX: &str = "hf://";
+
+/// Whether a process model path points at a trial instead of a local file
+pub fn is_trial_path(model_path: &str) -> bool {
+    model_path.starts_with(TRIAL_PATH_PREFIX)
+}
+
+async fn cache_dir() -> Result<std::path::PathBuf, String> {
+    crate::config::get_app_data_path(TRIAL_CACHE_DIR).await
+        .map_err(|e| format!("Failed to resolve trial cache: {}", e))
+}
+
+pub async fn launch_trial(repo_id: &str, filename: &str, state: &AppState) -> Result<LaunchResult, String> {
+    let repo_id = repo_id.trim().trim_matches('/');
+    let filename = filename.trim().trim_start_matches('/');
+    if repo_id.split('/').filter(|part| !part.is_empty()).count() != 2 {
+        return Err("Repository must look like owner/name".to_string());
+    }
+    if !filename.to_lowercase().ends_with(".gguf") {
+        return Err("Only GGUF files can be tried".to_string());
+    }
+
+    let global_config = state.config.lock().await.clone();
+    if global_config.offline_mode {
+        return Err("Trying a model needs network access, offline mode is on".to_string());
+    }
+    if global_config.remote_host.enabled {
+        return Err("Trying a model is only supported for local servers".to_string());
+    }
+
+    let executable_path = crate::process::resolve_llama_server_path_with_fallback(state, &global_config).await;
+    if !executable_path.exists() {
+        return Err(format!("Server executable not found at: {:?}", executable_path));
+    }
+
+    let cache = cache_dir().await?;
+    fs::create_dir_all(&cache).await.map_err(|e| format!("Failed to create trial cache: {}", e))?;
+
+    let model_config = ModelConfig::new(format!("{}{}/{}", TRIAL_PATH_PREFIX, repo_id, filename));
+    let port = crate::process::resolve_launch_port(state, &model_config).await?;
+    let mut args = vec![
+        "--hf-repo".to_string(),
+        repo_id.to_string(),
+        "--hf-file".to_string(),
+        filename.to_string(),
+        "--host".to_string(),
+        model_config.server_host.clone(),
+        "--port".to_string(),
+        port.to_string(),
+    ];
+    crate::process::apply_version_defaults(&global_config, &executable_path, &mut args);
+
+    // llama.cpp downloads from MODEL_ENDPOINT, point it at the fastest mirror
+    let endpoint = crate::mirrors::ranked_endpoints(state).await
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| crate::mirrors::PRIMARY_ENDPOINT.to_string());
+    let mut envs = vec![
+        ("LLAMA_CACHE".to_string(), cache.to_string_lossy().to_string()),
+        ("MODEL_ENDPOINT".to_string(), format!("{}/", endpoint)),
+    ];
+    // Only the Hub gets the token, same as our own requests
+    if let Some(token) = crate::huggingface::access_token().filter(|_| endpoint == crate::mirrors::PRIMARY_ENDPOINT) {
+        envs.push(("HF_TOKEN".to_string(), token));
+    }
+
+    println!("Trying {}/{} from {} (cache {:?})", repo_id, filename, endpoint, cache);
+    let process_id = Uuid::new_v4().to_string();
+    let host = model_config.server_host.clone();
+    let mut result = crate::process::spawn_server_process(state, process_id, &executable_path, &model_config, host, port, args, envs)
+        .await
+        .map_err(|e| format!("Failed to start trial server: {}", e))?;
+    result.message = "Trial server started, the model is downloading into a temporary cache".to_string();
+    Ok(result)
+}
+
+/// Delete everything in the trial cache. Returns the number of bytes freed.
+pub async fn clear_cache(state: &AppState) -> Result<u64, String> {
+    let trials_running = state.processes.list().await
+        .iter()
+        .any(|p| is_trial_path(&p.model_path) && matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running));
+    if trials_running {
+        return Err("Stop running trials before clearing their cache".to_string());
+    }
+
+    let cache = cache_dir().await?;
+    let freed = tokio::task::spawn_blocking({
+        let cache = cache.clone();
+        move || directory_size(&cache)
+    })
+    .await
+    .unwrap_or(0);
+
+    match fs::remove_dir_all(&cache).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(format!("Failed to clear trial cache: {}", e)),
+    }
+    println!("Cleared trial cache, freed {} bytes", freed);
+    Ok(freed)
+}
+
+fn directory_size(path: &std::path::Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
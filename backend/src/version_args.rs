@@ -0,0 +1,71 @@
+// Default arguments scoped to llama.cpp builds. Flags that were added or renamed
+// upstream can be set once for a range of builds instead of in every model's
+// settings. They are layered under the model arguments: a flag the model sets
+// itself always wins.
+
+use std::path::Path;
+
+use crate::models::VersionArgRule;
+use crate::process::parse_custom_args;
+
+/// Build number from a version folder such as `b6710` or `b6710-cuda`
+pub fn build_number(folder_name: &str) -> Option<u32> {
+    let digits: String = folder_name
+        .trim_start_matches('v')
+        .strip_prefix('b')?
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+fn rule_matches(rule: &VersionArgRule, build: Option<u32>) -> bool {
+    match build {
+        Some(build) => rule.min_build.is_none_or(|min| build >= min) && rule.max_build.is_none_or(|max| build <= max),
+        // Custom builds without a number only pick up unbounded rules
+        None => rule.min_build.is_none() && rule.max_build.is_none(),
+    }
+}
+
+/// Default arguments for the build that owns `executable_path`. When rules overlap the later one wins.
+pub fn defaults_for(rules: &[VersionArgRule], executable_path: &Path) -> Vec<String> {
+    let build = executable_path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .and_then(build_number);
+
+    let mut defaults = Vec::new();
+    for rule in rules.iter().rev().filter(|rule| rule_matches(rule, build)) {
+        layer_under(&mut defaults, parse_custom_args(&rule.args));
+    }
+    defaults
+}
+
+fn flag_name(arg: &str) -> Option<&str> {
+    let is_flag = arg.starts_with('-') && arg.parse::<f64>().is_err();
+    is_flag.then(|| arg.split('=').next().unwrap_or(arg))
+}
+
+/// Split arguments into flags with their values
+fn group_flags(args: Vec<String>) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    for arg in args {
+        match groups.last_mut() {
+            Some(group) if flag_name(&arg).is_none() => group.push(arg),
+            _ => groups.push(vec![arg]),
+        }
+    }
+    groups
+}
+
+/// Append the default flags `args` doesn't already set
+pub fn layer_under(args: &mut Vec<String>, defaults: Vec<String>) {
+    let set: Vec<String> = args.iter().filter_map(|a| flag_name(a)).map(|f| f.to_string()).collect();
+    for group in group_flags(defaults) {
+        let overridden = group.first().and_then(|f| flag_name(f)).is_some_and(|f| set.iter().any(|s| s == f));
+        if !overridden {
+            args.extend(group);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// Runtime check for an installed llama.cpp build. Picking the wrong asset (CUDA build
+// without a matching driver, Vulkan without a loader) usually only shows up when a
+// real model fails to launch. This runs the build's `--version` and `--list-devices`
+// and a tiny benchmark on a micro model to catch that right after installing. The
+// micro model is fetched at startup so the check also works offline later.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command as TokioCommand;
+
+use crate::benchmark::{run_llama_bench, tool_path};
+use crate::config::get_app_data_path;
+use crate::AppState;
+
+// 260K-parameter llama used by llama.cpp's own server tests, about 1 MB
+const MICRO_MODEL_URL: &str = "https://huggingface.co/ggml-org/models/resolve/main/tinyllamas/stories260K.gguf";
+const MICRO_MODEL_FILE: &str = "smoke-test-stories260K.gguf";
+const TOOL_TIMEOUT: Duration = Duration::from_secs(60);
+const SMOKE_ARGS: [&str; 8] = ["-p", "16", "-n", "8", "-r", "1", "-ngl", "99"];
+
+// Backend libraries shipped in release archives, with the device name prefixes they report
+const GPU_BACKENDS: [(&str, &str, &[&str]); 5] = [
+    ("ggml-cuda", "CUDA", &["CUDA"]),
+    ("ggml-vulkan", "Vulkan", &["Vulkan"]),
+    ("ggml-hip", "ROCm", &["ROCm", "HIP"]),
+    ("ggml-sycl", "SYCL", &["SYCL"]),
+    ("ggml-metal", "Metal", &["Metal", "MTL"]),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionTestReport {
+    pub path: String,
+    pub version: Option<String>,
+    pub devices: Vec<String>,                // As listed by llama-server, e.g. "CUDA0: NVIDIA GeForce RTX 4090"
+    pub expected_backend: Option<String>,    // GPU backend the build ships libraries for, None for CPU builds
+    pub backend_ok: bool,
+    pub smoke_test_passed: Option<bool>,     // None when the micro model was not available
+    pub generation_tps: Option<f64>,
+    pub problems: Vec<String>,
+}
+
+pub async fn test_version(version_dir: &Path, offline: bool) -> Result<VersionTestReport, String> {
+    let server_path = tool_path(version_dir, "llama-server");
+    if !server_path.exists() {
+        return Err(format!("llama-server not found at: {:?}", server_path));
+    }
+
+    let mut problems = Vec::new();
+    let version_output = run_tool(&server_path, &["--version"]).await?;
+    let version = version_output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("version:"))
+        .map(|v| v.trim().to_string());
+    if version.is_none() {
+        problems.push("llama-server did not report a version".to_string());
+    }
+
+    // Older builds don't know --list-devices, their init log lines name the devices instead
+    let devices_output = run_tool(&server_path, &["--list-devices"]).await.unwrap_or_default();
+    let mut devices = parse_devices(&devices_output);
+    if devices.is_empty() {
+        devices = parse_init_lines(&version_output);
+    }
+
+    let expected = expected_backend(version_dir);
+    let backend_ok = match expected {
+        Some((name, prefixes)) => {
+            let found = devices.iter().any(|d| prefixes.iter().any(|p| d.starts_with(p)));
+            if !found {
+                problems.push(format!("{} backend did not initialize, check the GPU driver or pick another build", name));
+            }
+            found
+        }
+        None => true,
+    };
+
+    let (smoke_test_passed, generation_tps) = match micro_model(offline).await {
+        Ok(model_path) => {
+            let args: Vec<String> = SMOKE_ARGS.iter().map(|a| a.to_string()).collect();
+            match tokio::time::timeout(TOOL_TIMEOUT, run_llama_bench(version_dir, &model_path.to_string_lossy(), &args)).await {
+                Ok(Ok((results, _))) => {
+                    let tps = results.iter().find(|r| r.n_gen > 0).map(|r| r.avg_ts);
+                    (Some(true), tps)
+                }
+                Ok(Err(e)) => {
+                    problems.push(format!("Smoke inference failed: {}", e));
+                    (Some(false), None)
+                }
+                Err(_) => {
+                    problems.push("Smoke inference timed out".to_string());
+                    (Some(false), None)
+                }
+            }
+        }
+        Err(e) => {
+            problems.push(format!("Smoke inference skipped: {}", e));
+            (None, None)
+        }
+    };
+
+    Ok(VersionTestReport {
+        path: version_dir.to_string_lossy().to_string(),
+        version,
+        devices,
+        expected_backend: expected.map(|(name, _)| name.to_string()),
+        backend_ok,
+        smoke_test_passed,
+        generation_tps,
+        problems,
+    })
+}
+
+/// Run a tool to completion and return stdout and stderr together
+async fn run_tool(path: &Path, args: &[&str]) -> Result<String, String> {
+    let mut cmd = TokioCommand::new(path);
+    cmd.args(args)
+       .stdin(Stdio::null())
+       .kill_on_drop(true);
+
+    // Hide console window on Windows release builds
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let output = tokio::time::timeout(TOOL_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| format!("{:?} {} timed out", path, args.join(" ")))?
+        .map_err(|e| format!("Failed to run {:?}: {}", path, e))?;
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    if !output.status.success() {
+        return Err(format!("{:?} {} exited with {}", path, args.join(" "), output.status));
+    }
+    Ok(text)
+}
+
+/// Device lines from `--list-devices`, e.g. "  CUDA0: NVIDIA GeForce RTX 4090 (24563 MiB, 23000 MiB free)"
+fn parse_devices(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            line.split_once(':').is_some_and(|(name, _)| {
+                !name.is_empty() && !name.contains(' ') && name.ends_with(|c: char| c.is_ascii_digit())
+            })
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Backend init lines such as "ggml_cuda_init: found 1 CUDA devices:" and the device lines after them
+fn parse_init_lines(output: &str) -> Vec<String> {
+    let mut devices = Vec::new();
+    // HIP builds go through the CUDA backend and report "found 1 ROCm devices:"
+    let mut prefix = "CUDA";
+    for line in output.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("ggml_cuda_init: found ") {
+            if let Some(name) = rest.split_whitespace().nth(1) {
+                prefix = if name.eq_ignore_ascii_case("rocm") { "ROCm" } else { "CUDA" };
+            }
+        } else if let Some(rest) = line.strip_prefix("Device ") {
+            // "Device 0: NVIDIA GeForce RTX 4090, compute capability 8.9"
+            devices.push(format!("{}{}", prefix, rest));
+        } else if let Some(rest) = line.strip_prefix("ggml_vulkan: ") {
+            // "ggml_vulkan: 0 = NVIDIA GeForce RTX 4090 (NVIDIA) | uma: 0 | ..."
+            if let Some((index, name)) = rest.split_once(" = ") {
+                if index.chars().all(|c| c.is_ascii_digit()) {
+                    devices.push(format!("Vulkan{}: {}", index, name));
+                }
+            }
+        }
+    }
+    devices
+}
+
+/// GPU backend a build ships, judged by the backend libraries in its folder
+fn expected_backend(version_dir: &Path) -> Option<(&'static str, &'static [&'static str])> {
+    let file_names: Vec<String> = std::fs::read_dir(version_dir)
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_lowercase()).collect())
+        .unwrap_or_default();
+    GPU_BACKENDS
+        .iter()
+        .find(|(library, _, _)| file_names.iter().any(|name| name.contains(library)))
+        .map(|(_, name, prefixes)| (*name, *prefixes))
+}
+
+/// Fetch the micro model in the background unless it is there or offline mode is on
+pub async fn prefetch_micro_model(state: AppState) {
+    if state.config.lock().await.offline_mode {
+        return;
+    }
+    if let Err(e) = micro_model(false).await {
+        eprintln!("Smoke test model unavailable: {}", e);
+    }
+}
+
+/// The micro model, fetched into the data directory on first use
+async fn micro_model(offline: bool) -> Result<PathBuf, String> {
+    let path = get_app_data_path(MICRO_MODEL_FILE).await.map_err(|e| e.to_string())?;
+    if path.exists() {
+        return Ok(path);
+    }
+    if offline {
+        return Err("the micro model is not downloaded yet and offline mode is on".to_string());
+    }
+
+    let bytes = reqwest::Client::new()
+        .get(MICRO_MODEL_URL)
+        .header("User-Agent", "Llama-OS-Tauri/1.0")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("failed to download the micro model: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("failed to download the micro model: {}", e))?;
+    let temp = crate::downloader::partial_path(&path);
+    tokio::fs::write(&temp, &bytes).await.map_err(|e| e.to_string())?;
+    tokio::fs::rename(&temp, &path).await.map_err(|e| e.to_string())?;
+    Ok(path)
+}
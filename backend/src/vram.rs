@@ -0,0 +1,190 @@
+// "What's using my VRAM". NVML lists the processes holding memory on each GPU; pids
+// that belong to servers Llama-OS started are attributed to their model, everything
+// else (games, browsers, other inference tools) is reported by executable name so a
+// launch that runs out of memory can be explained.
+
+use std::collections::{HashMap, HashSet};
+
+use nvml_wrapper::enums::device::UsedGpuMemory;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+use crate::models::{GpuVramBreakdown, VramConsumer};
+use crate::AppState;
+
+// Adapter LUID -> pid -> dedicated bytes, from the Windows performance counters
+type CounterUsage = HashMap<u64, HashMap<u32, u64>>;
+
+pub async fn get_breakdown(state: &AppState) -> Result<Vec<GpuVramBreakdown>, String> {
+    // pid -> (process id, model name) for every server we started
+    let mut managed = HashMap::new();
+    for (process_id, pid) in state.processes.child_pids().await {
+        let model_name = state.processes.get(&process_id).await.map(|p| p.model_name);
+        managed.insert(pid, (process_id, model_name));
+    }
+
+    tokio::task::spawn_blocking(move || collect(&managed))
+        .await
+        .map_err(|e| format!("VRAM breakdown task failed: {}", e))?
+}
+
+fn collect(managed: &HashMap<u32, (String, Option<String>)>) -> Result<Vec<GpuVramBreakdown>, String> {
+    let nvml = nvml_wrapper::Nvml::init()
+        .map_err(|e| format!("Per-process VRAM usage needs an NVIDIA GPU with NVML: {}", e))?;
+    let count = nvml.device_count().map_err(|e| format!("Failed to list GPUs: {}", e))?;
+
+    // WDDM drivers hide per-process usage from NVML, the performance counters still have it
+    let mut counter_usage: Option<CounterUsage> = None;
+    let mut sys = System::new();
+    let mut gpus = Vec::new();
+
+    for index in 0..count {
+        let device = nvml.device_by_index(index).map_err(|e| format!("Failed to open GPU {}: {}", index, e))?;
+        let name = device.name().unwrap_or_else(|_| "NVIDIA GPU".to_string());
+        let memory = device.memory_info().map_err(|e| format!("Failed to read memory of GPU {}: {}", index, e))?;
+
+        let usage = device_process_usage(&device, &mut counter_usage);
+
+        let pids: Vec<Pid> = usage.keys().map(|pid| Pid::from_u32(*pid)).collect();
+        sys.refresh_processes(ProcessesToUpdate::Some(&pids), true);
+
+        let mut consumers: Vec<VramConsumer> = usage
+            .into_iter()
+            .map(|(pid, used_bytes)| {
+                let (process_id, model_name) = match managed.get(&pid) {
+                    Some((process_id, model_name)) => (Some(process_id.clone()), model_name.clone()),
+                    None => (None, None),
+                };
+                let name = sys
+                    .process(Pid::from_u32(pid))
+                    .map(|p| p.name().to_string_lossy().to_string())
+                    .unwrap_or_else(|| format!("PID {}", pid));
+                VramConsumer { pid, name, used_bytes, process_id, model_name }
+            })
+            .collect();
+        consumers.sort_by(|a, b| b.used_bytes.cmp(&a.used_bytes).then(a.pid.cmp(&b.pid)));
+
+        let attributed: u64 = consumers.iter().filter_map(|c| c.used_bytes).sum();
+        gpus.push(GpuVramBreakdown {
+            index,
+            name,
+            total_bytes: memory.total,
+            used_bytes: memory.used,
+            unattributed_bytes: memory.used.saturating_sub(attributed),
+            consumers,
+        });
+    }
+
+    Ok(gpus)
+}
+
+/// Memory each process holds on one GPU. WDDM drivers hide the sizes from NVML, the
+/// performance counters of the adapter at the device's PCI address fill them in. They
+/// are read once and shared across devices
+fn device_process_usage(
+    device: &nvml_wrapper::Device,
+    counter_usage: &mut Option<CounterUsage>,
+) -> HashMap<u32, Option<u64>> {
+    let mut usage = nvml_process_usage(device);
+    if usage.values().any(|used| used.is_none()) {
+        let counters = counter_usage.get_or_insert_with(process_counter_usage);
+        if let Some(adapter) = device_luid(device).and_then(|luid| counters.get(&luid)) {
+            for (pid, used) in usage.iter_mut() {
+                if used.is_none() {
+                    *used = adapter.get(pid).copied();
+                }
+            }
+        }
+    }
+    usage
+}
+
+/// What NVML itself knows about each process on the GPU, None where the driver hides the size
+fn nvml_process_usage(device: &nvml_wrapper::Device) -> HashMap<u32, Option<u64>> {
+    // A process doing both compute and graphics shows up in both lists
+    let mut usage: HashMap<u32, Option<u64>> = HashMap::new();
+    let compute = device.running_compute_processes().unwrap_or_default();
+    let graphics = device.running_graphics_processes().unwrap_or_default();
+    for process in compute.into_iter().chain(graphics) {
+        let used = match process.used_gpu_memory {
+            UsedGpuMemory::Used(bytes) => Some(bytes),
+            UsedGpuMemory::Unavailable => None,
+        };
+        let entry = usage.entry(process.pid).or_insert(None);
+        *entry = (*entry).max(used);
+    }
+    usage
+}
+
+/// VRAM per pid summed over all GPUs, for a model split across cards. None when neither
+/// NVML nor the Windows counters can say; a pid missing from the map holds no VRAM.
+/// Blocking, NVML init takes a moment
+pub fn process_usage() -> Option<HashMap<u32, u64>> {
+    let Some(nvml) = nvml_wrapper::Nvml::init().ok() else {
+        let totals = counter_totals();
+        return (cfg!(windows) && !totals.is_empty()).then_some(totals);
+    };
+    let mut totals: HashMap<u32, u64> = HashMap::new();
+    let mut unsized_pids = HashSet::new();
+    for index in 0..nvml.device_count().ok()? {
+        let Ok(device) = nvml.device_by_index(index) else {
+            continue;
+        };
+        for (pid, used) in nvml_process_usage(&device) {
+            let total = totals.entry(pid).or_default();
+            match used {
+                Some(bytes) => *total += bytes,
+                None => {
+                    unsized_pids.insert(pid);
+                }
+            }
+        }
+    }
+
+    // The counters' figure for a pid already spans every adapter, so it replaces the
+    // per-device sum once rather than being added for each GPU the pid is on
+    if !unsized_pids.is_empty() {
+        let counters = counter_totals();
+        for pid in unsized_pids {
+            if let Some(bytes) = counters.get(&pid) {
+                totals.insert(pid, *bytes);
+            }
+        }
+    }
+    Some(totals)
+}
+
+/// Counter usage per pid summed over every adapter
+fn counter_totals() -> HashMap<u32, u64> {
+    let mut totals: HashMap<u32, u64> = HashMap::new();
+    for adapter in process_counter_usage().into_values() {
+        for (pid, bytes) in adapter {
+            *totals.entry(pid).or_default() += bytes;
+        }
+    }
+    totals
+}
+
+#[cfg(windows)]
+fn process_counter_usage() -> CounterUsage {
+    crate::gpu_counters::process_memory_usage()
+}
+
+#[cfg(not(windows))]
+fn process_counter_usage() -> CounterUsage {
+    HashMap::new()
+}
+
+#[cfg(windows)]
+fn device_luid(device: &nvml_wrapper::Device) -> Option<u64> {
+    let pci = device.pci_info().ok()?;
+    // bus_id reads like "00000000:01:00.0", the function number follows the dot
+    let function = pci.bus_id.rsplit('.').next()
+        .and_then(|function| u32::from_str_radix(function, 16).ok())
+        .unwrap_or(0);
+    crate::gpu_counters::adapter_luid(pci.bus, pci.device, function)
+}
+
+#[cfg(not(windows))]
+fn device_luid(_device: &nvml_wrapper::Device) -> Option<u64> {
+    None
+}
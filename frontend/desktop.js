@@ -5,6 +5,10 @@ class DesktopManager {
     constructor() {
         this.windows = new Map();
         this.selectedIcon = null;
+        this.pinnedModels = new Set(); // Paths pinned to the start menu
+        this.tagFilter = null; // Tag the desktop is filtered by, "favorites" for favorite models
+        this.desktopFolders = []; // Virtual folders grouping model icons, kept by the backend
+        this.openFolderId = null; // Folder shown on the desktop instead of the top level
         this.windowZIndex = 1000;
         this.iconPositions = new Map(); // Store custom icon positions
         this.hintTimer = null; // Timer for model hint
@@ -22,9 +26,12 @@ class DesktopManager {
         this.updateClock();
         setInterval(() => this.updateClock(), 1000);
         
-        // Update system stats every 2 seconds
+        // The backend pushes system stats, server and download summaries together
         this.updateSystemStats();
-        setInterval(() => this.updateSystemStats(), 2000);
+        window.__TAURI__.event.listen('app-status', (event) => this.handleAppStatus(event.payload));
+        
+        // GGUF files dropped onto the window are offered for import
+        this.setupFileDrop();
         
         // Wait for DOM to be fully loaded before showing content
         if (document.readyState === 'loading') {
@@ -48,6 +55,168 @@ class DesktopManager {
         this.handlePageLoad();
     }
     
+    setupFileDrop() {
+        const webview = window.__TAURI__.webview && window.__TAURI__.webview.getCurrentWebview();
+        if (!webview) return;
+        webview.onDragDropEvent((event) => {
+            if (event.payload.type !== 'drop') return;
+            const paths = (event.payload.paths || []).filter(path => path.toLowerCase().endsWith('.gguf'));
+            if (paths.length > 0) {
+                this.importDroppedModels(paths);
+            } else if ((event.payload.paths || []).length > 0) {
+                this.showNotification('Only .gguf model files can be imported', 'info');
+            }
+        });
+        window.__TAURI__.event.listen('model-scan-progress', (event) => this.appendScannedModels(event.payload.models));
+        window.__TAURI__.event.listen('model-sleeping', (event) => {
+            const sleeping = event.payload;
+            this.showNotification(`${sleeping.model_name} was idle for ${sleeping.idle_minutes} min and went to sleep, launch it to wake it`, 'info');
+            this.markSleepingModels();
+        });
+        window.__TAURI__.event.listen('model-import-progress', (event) => {
+            const progress = event.payload;
+            if (!progress.file || progress.total_bytes === 0) return;
+            const percent = Math.floor(progress.copied_bytes / progress.total_bytes * 100);
+            const name = progress.file.split(/[\\/]/).pop();
+            this.updateImportProgress(`Importing ${name} (${progress.completed + 1}/${progress.total}) ${percent}%`);
+        });
+    }
+
+    updateImportProgress(text) {
+        let indicator = document.getElementById('import-progress');
+        if (!indicator) {
+            indicator = document.createElement('div');
+            indicator.id = 'import-progress';
+            indicator.className = 'import-progress';
+            document.body.appendChild(indicator);
+        }
+        indicator.textContent = text;
+    }
+
+    async importDroppedModels(paths) {
+        const count = paths.length === 1 ? paths[0].split(/[\\/]/).pop() : `${paths.length} files`;
+        const mode = await ModalDialog.showCustom({
+            title: 'Import Models',
+            content: `<p>Add ${count} to the models directory?</p>
+                <p><small>Copy keeps the originals, Move relocates them and Link leaves them where they are.</small></p>`,
+            buttons: [
+                { text: 'Cancel', className: 'btn-secondary', action: () => null },
+                { text: 'Link', className: 'btn-secondary', action: () => 'symlink' },
+                { text: 'Move', className: 'btn-secondary', action: () => 'move' },
+                { text: 'Copy', className: 'btn-primary', action: () => 'copy' }
+            ]
+        });
+        if (!mode) return;
+
+        try {
+            const result = await invoke('import_model_files', { paths, mode, onConflict: 'rename' });
+            await this.showImportResult(result);
+        } catch (error) {
+            this.showNotification('Import failed: ' + error, 'error');
+        } finally {
+            const indicator = document.getElementById('import-progress');
+            if (indicator) indicator.remove();
+        }
+    }
+
+    // Bring in an LM Studio library, keeping its publisher/repo folders so the
+    // Hugging Face window sees those files as downloaded
+    async importLmStudioModels() {
+        const choice = await ModalDialog.showCustom({
+            title: 'Import from LM Studio',
+            content: `<p>Add the models of your LM Studio library to the models directory?</p>
+                <p><small>Link leaves them in place so LM Studio keeps working with them, Copy duplicates them and Move hands them over. Files imported before are skipped.</small></p>`,
+            buttons: [
+                { text: 'Cancel', className: 'btn-secondary', action: () => null },
+                { text: 'Choose Folder...', className: 'btn-secondary', action: () => 'choose' },
+                { text: 'Move', className: 'btn-secondary', action: () => 'move' },
+                { text: 'Copy', className: 'btn-secondary', action: () => 'copy' },
+                { text: 'Link', className: 'btn-primary', action: () => 'symlink' }
+            ]
+        });
+        if (!choice) return;
+
+        // Libraries moved away from the default location are picked by hand, linked like the default
+        let directory = null;
+        let mode = choice;
+        if (choice === 'choose') {
+            directory = await window.__TAURI__.dialog.open({ directory: true, title: 'LM Studio models folder' });
+            if (!directory) return;
+            mode = 'symlink';
+        }
+
+        try {
+            this.updateImportProgress('Importing from LM Studio...');
+            const result = await invoke('import_lm_studio_models', { directory, mode });
+            if (result.imported.length === 0 && result.skipped.length === 0) {
+                this.showNotification('No GGUF models found in the LM Studio library', 'info');
+            }
+            await this.showImportResult(result);
+        } catch (error) {
+            this.showNotification('Import failed: ' + error, 'error');
+        } finally {
+            const indicator = document.getElementById('import-progress');
+            if (indicator) indicator.remove();
+        }
+    }
+
+    async showImportResult(result) {
+        if (result.imported.length > 0) {
+            this.showNotification(`Imported ${result.imported.length} file${result.imported.length === 1 ? '' : 's'}`, 'success');
+            await this.refreshDesktop();
+        }
+        if (result.skipped.length > 0) {
+            // A re-run over a whole library skips most of it, keep the list readable
+            const shown = result.skipped.slice(0, 20).map(s => `${s.source.split(/[\\/]/).pop()}: ${s.reason}`);
+            if (result.skipped.length > shown.length) {
+                shown.push(`...and ${result.skipped.length - shown.length} more`);
+            }
+            await ModalDialog.showInfo({
+                title: 'Some Files Were Skipped',
+                message: shown.join('\n')
+            });
+        }
+    }
+
+    async exportManifest() {
+        const path = await window.__TAURI__.dialog.save({
+            defaultPath: 'llama-os-manifest.json',
+            filters: [{ name: 'Manifest', extensions: ['json'] }]
+        });
+        if (!path) return;
+
+        try {
+            const manifest = await invoke('export_manifest', { path });
+            this.showNotification(`Exported ${manifest.models.length} model${manifest.models.length === 1 ? '' : 's'} to the manifest`, 'success');
+        } catch (error) {
+            this.showNotification(error.toString(), 'error');
+        }
+    }
+
+    async applyManifest() {
+        const path = await window.__TAURI__.dialog.open({
+            multiple: false,
+            filters: [{ name: 'Manifest', extensions: ['json'] }]
+        });
+        if (!path) return;
+
+        try {
+            const result = await invoke('apply_manifest', { path });
+            const lines = [];
+            if (result.llama_cpp) lines.push(`llama.cpp: ${result.llama_cpp}`);
+            lines.push(`Downloads started: ${result.downloads.length}`);
+            lines.push(`Already present: ${result.present.length}`);
+            lines.push(`Launch settings restored: ${result.configured}`);
+            if (result.skipped.length > 0) {
+                lines.push('', 'Skipped:', ...result.skipped);
+            }
+            await ModalDialog.showInfo({ title: 'Manifest Applied', message: lines.join('\n') });
+            await this.refreshDesktop();
+        } catch (error) {
+            this.showNotification(error.toString(), 'error');
+        }
+    }
+
     handlePageLoad() {
         // Wait for all resources to load
         if (document.readyState === 'complete') {
@@ -213,6 +382,8 @@ class DesktopManager {
         
         // Load models and populate desktop
         await this.loadModels();        
+        await this.loadStartMenuModels();
+        this.showStartupVersionWarning();
         
         // Update custom arguments indicators
         setTimeout(() => {
@@ -220,6 +391,19 @@ class DesktopManager {
         }, 500);
     }
     
+    // The backend checks the active llama.cpp version before this window exists and keeps
+    // the result until it is asked for
+    async showStartupVersionWarning() {
+        try {
+            const warning = await invoke('get_startup_version_warning');
+            if (warning) {
+                await ModalDialog.showInfo({ title: 'llama.cpp Version Changed', message: warning.message });
+            }
+        } catch (error) {
+            console.error('Error checking the llama.cpp version:', error);
+        }
+    }
+    
     async loadConfiguration() {
         try {
             const config = await invoke('get_config');
@@ -232,9 +416,27 @@ class DesktopManager {
         }
     }
     
+    // Icons for a batch of a scan still running, replaced by the full listing once it ends
+    appendScannedModels(models) {
+        const desktopIcons = document.getElementById('desktop-icons');
+        if (!this.scanStreaming || !desktopIcons) return;
+        models.forEach(model => {
+            const iconElement = this.createModelIcon(model);
+            iconElement.classList.add('fade-in');
+            desktopIcons.appendChild(iconElement);
+        });
+        this.renderDesktopFolders();
+    }
+
     async loadModels(useAnimation = true) {
+        await this.loadDesktopFolders();
+        // Large libraries take a while, show models as the scan finds them
+        this.scanStreaming = true;
+        const desktopIcons = document.getElementById('desktop-icons');
+        if (desktopIcons) desktopIcons.innerHTML = '';
         try {
             const result = await invoke('scan_models_command');
+            this.scanStreaming = false;
             if (result && result.success && result.models) {
                 this.refreshDesktopIcons(result.models, useAnimation);
             } else {
@@ -245,12 +447,15 @@ class DesktopManager {
             console.error('Error loading models:', error);
             this.showNotification('Error loading models', 'error');
             this.refreshDesktopIcons([], useAnimation);
+        } finally {
+            this.scanStreaming = false;
         }
     }
     
     updateConfigUI(config) {
         const modelsDir = document.getElementById('models-directory');
         const execFolder = document.getElementById('executable-folder');
+        const hfToken = document.getElementById('hf-token');
         const themeColor = document.getElementById('theme-color');
         const backgroundColor = document.getElementById('background-color');
         const themeSyncButton = document.getElementById('theme-sync-button');
@@ -261,6 +466,29 @@ class DesktopManager {
         if (execFolder && config.executable_folder) {
             execFolder.value = config.executable_folder;
         }
+        if (hfToken) {
+            hfToken.value = config.hf_token || '';
+        }
+        const extraDirs = document.getElementById('extra-model-directories');
+        if (extraDirs) {
+            extraDirs.value = (config.extra_model_directories || []).join('\n');
+        }
+        const speedLimit = document.getElementById('download-speed-limit');
+        if (speedLimit) {
+            speedLimit.value = config.download_speed_limit ? +(config.download_speed_limit / (1024 * 1024)).toFixed(2) : '';
+        }
+        const acceleratedTransfer = document.getElementById('accelerated-transfer');
+        const acceleratedConnections = document.getElementById('accelerated-connections');
+        if (acceleratedTransfer && config.accelerated_transfer) {
+            acceleratedTransfer.checked = !!config.accelerated_transfer.enabled;
+            acceleratedConnections.value = config.accelerated_transfer.connections || '';
+        }
+        const portRangeStart = document.getElementById('port-range-start');
+        const portRangeEnd = document.getElementById('port-range-end');
+        if (portRangeStart && config.port_range) {
+            portRangeStart.value = config.port_range.start;
+            portRangeEnd.value = config.port_range.end;
+        }
         if (themeColor && config.theme_color) {
             themeColor.value = config.theme_color;
         }
@@ -334,7 +562,7 @@ class DesktopManager {
             
             if (icon) {
                 this.selectIcon(icon);
-                this.showContextMenu(e.clientX, e.clientY, 'icon');
+                this.showContextMenu(e.clientX, e.clientY, icon.dataset.folderId !== undefined ? 'folder' : 'icon');
             } else if (e.target.closest('.desktop') && !taskbar) {
                 // Only show desktop context menu if not clicking on taskbar
                 this.showContextMenu(e.clientX, e.clientY, 'desktop');
@@ -351,7 +579,8 @@ class DesktopManager {
 
             iconsContainer.addEventListener('dblclick', (e) => {
                 const icon = e.target.closest('.desktop-icon');
-                if (icon) this.launchModel(icon);
+                if (icon?.dataset.folderId !== undefined) this.openDesktopFolder(icon.dataset.folderId || null);
+                else if (icon) this.launchModel(icon);
             });
 
             // Add drag functionality
@@ -361,7 +590,7 @@ class DesktopManager {
         // Hint functionality
         iconsContainer.addEventListener('mouseover', (e) => {
             const icon = e.target.closest('.desktop-icon');
-            if (icon) {
+            if (icon && icon.dataset.folderId === undefined) {
                 this.showModelHint(icon);
             }
         });
@@ -381,8 +610,40 @@ class DesktopManager {
                 if (action) {
                     if (action === 'open' && this.selectedIcon) {
                         this.launchModel(this.selectedIcon);
+                    } else if (action === 'launch-again' && this.selectedIcon) {
+                        this.launchModel(this.selectedIcon, true);
                     } else if (action === 'launch-external' && this.selectedIcon) {
                         this.launchModelExternal(this.selectedIcon);
+                    } else if (action === 'launch-cli' && this.selectedIcon) {
+                        this.launchModelCli(this.selectedIcon);
+                    } else if (action === 'benchmark' && this.selectedIcon) {
+                        this.showBenchmark(this.selectedIcon);
+                    } else if (action === 'launch-command' && this.selectedIcon) {
+                        this.showLaunchCommand(this.selectedIcon);
+                    } else if (action === 'check-format' && this.selectedIcon) {
+                        this.checkModelFormat(this.selectedIcon.dataset.path);
+                    } else if (action === 'merge-split' && this.selectedIcon) {
+                        this.mergeSplitModel(this.selectedIcon.dataset.path);
+                    } else if (action === 'toggle-favorite' && this.selectedIcon) {
+                        this.toggleFavorite(this.selectedIcon);
+                    } else if (action === 'edit-tags' && this.selectedIcon) {
+                        this.editModelTags(this.selectedIcon.dataset.path);
+                    } else if (action === 'apply-preset' && this.selectedIcon) {
+                        this.chooseArgumentPreset(this.selectedIcon.dataset.path);
+                    } else if (action === 'move-to-folder' && this.selectedIcon) {
+                        this.chooseFolderForModel(this.selectedIcon.dataset.path);
+                    } else if (action === 'new-folder') {
+                        this.createDesktopFolder();
+                    } else if (action === 'open-folder' && this.selectedIcon) {
+                        this.openDesktopFolder(this.selectedIcon.dataset.folderId || null);
+                    } else if (action === 'rename-folder' && this.selectedIcon) {
+                        this.renameDesktopFolder(this.selectedIcon.dataset.folderId);
+                    } else if (action === 'delete-folder' && this.selectedIcon) {
+                        this.deleteDesktopFolder(this.selectedIcon.dataset.folderId);
+                    } else if (action === 'filter-tags') {
+                        this.chooseTagFilter();
+                    } else if (action === 'toggle-pin' && this.selectedIcon) {
+                        this.togglePinnedModel(this.selectedIcon.dataset.path);
                     } else if (action === 'properties' && this.selectedIcon) {
                         this.showProperties(this.selectedIcon);
                     } else if (action === 'refresh') {
@@ -443,12 +704,19 @@ class DesktopManager {
             saveConfig.addEventListener('click', () => this.saveConfiguration());
         }
 
+        document.getElementById('export-manifest')?.addEventListener('click', () => this.exportManifest());
+        document.getElementById('apply-manifest')?.addEventListener('click', () => this.applyManifest());
+
         // Start menu actions
         const startMenu = document.getElementById('start-menu');
         if (startMenu) {
             startMenu.addEventListener('click', (e) => {
-                const action = e.target.closest('[data-action]')?.dataset.action;
-                if (action) {
+                const item = e.target.closest('[data-action]');
+                const action = item?.dataset.action;
+                if (action === 'launch-recent') {
+                    this.launchRecentModel(item.dataset.path);
+                    this.hideStartMenu();
+                } else if (action) {
                     this.handleStartMenuAction(action);
                     this.hideStartMenu();
                 }
@@ -520,14 +788,44 @@ class DesktopManager {
                     <span>Sort by Date</span>
                     <span class="sort-arrow">${getSortArrow('date')}</span>
                 </div>
+                <div class="context-menu-item" data-action="sort-family">
+                    <span>Sort by Family</span>
+                    <span class="sort-arrow">${getSortArrow('family')}</span>
+                </div>
+                <div class="context-menu-item" data-action="sort-last_used">
+                    <span>Sort by Last Used</span>
+                    <span class="sort-arrow">${getSortArrow('last_used')}</span>
+                </div>
                 <div class="context-menu-separator"></div>
                 <div class="context-menu-item" data-action="refresh"><span class="material-icons">refresh</span> Refresh Desktop</div>
+                <div class="context-menu-item" data-action="import-lm-studio"><span class="material-icons">move_to_inbox</span> Import from LM Studio...</div>
+                <div class="context-menu-item" data-action="new-folder"><span class="material-icons">create_new_folder</span> New Folder...</div>
+                <div class="context-menu-item" data-action="filter-tags"><span class="material-icons">filter_list</span> ${this.tagFilter ? `Filter: ${this.escapeHtml(this.tagFilter)}...` : 'Filter by Tag...'}</div>
+            `;
+        } else if (type === 'folder') {
+            menuItems = this.selectedIcon?.dataset.folderId ? `
+                <div class="context-menu-item" data-action="open-folder"><span class="material-icons">folder_open</span> Open</div>
+                <div class="context-menu-item" data-action="rename-folder"><span class="material-icons">edit</span> Rename...</div>
+                <div class="context-menu-item" data-action="delete-folder"><span class="material-icons">folder_delete</span> Delete Folder</div>
+            ` : `
+                <div class="context-menu-item" data-action="open-folder"><span class="material-icons">arrow_back</span> Back to Desktop</div>
             `;
         } else { // 'icon'
             menuItems = `
                 <div class="context-menu-item" data-action="open"><span class="material-icons">rocket_launch</span> Launch Model</div>
+                <div class="context-menu-item" data-action="launch-again"><span class="material-icons">replay</span> Launch Again (last arguments)</div>
                 <div class="context-menu-item" data-action="launch-external"><span class="material-icons">computer</span> Launch as External Terminal</div>
+                <div class="context-menu-item" data-action="launch-cli"><span class="material-icons">keyboard</span> Chat in Terminal (llama-cli)</div>
+                <div class="context-menu-item" data-action="launch-command"><span class="material-icons">terminal</span> Show Launch Command</div>
                 <div class="context-menu-separator"></div>
+                <div class="context-menu-item" data-action="benchmark"><span class="material-icons">speed</span> Benchmark...</div>
+                <div class="context-menu-item" data-action="check-format"><span class="material-icons">fact_check</span> Check Format</div>
+                ${Number(this.selectedIcon?.dataset.shards) > 1 ? '<div class="context-menu-item" data-action="merge-split"><span class="material-icons">merge_type</span> Merge Split Files</div>' : ''}
+                <div class="context-menu-item" data-action="toggle-favorite"><span class="material-icons">${this.selectedIcon?.classList.contains('favorite') ? 'star_border' : 'star'}</span> ${this.selectedIcon?.classList.contains('favorite') ? 'Remove from Favorites' : 'Add to Favorites'}</div>
+                <div class="context-menu-item" data-action="edit-tags"><span class="material-icons">label</span> Edit Tags...</div>
+                <div class="context-menu-item" data-action="apply-preset"><span class="material-icons">tune</span> Apply Preset...</div>
+                <div class="context-menu-item" data-action="move-to-folder"><span class="material-icons">drive_file_move</span> Move to Folder...</div>
+                <div class="context-menu-item" data-action="toggle-pin"><span class="material-icons">push_pin</span> ${this.pinnedModels.has(this.selectedIcon?.dataset.path) ? 'Unpin from Start Menu' : 'Pin to Start Menu'}</div>
                 <div class="context-menu-item" data-action="properties"><span class="material-icons">settings</span> Properties</div>
             `;
         }
@@ -601,6 +899,7 @@ class DesktopManager {
                 <hr>
                 <span>Architecture:</span> ${arch}<br>
                 <span>Quantization:</span> ${quant}<br>
+                ${icon.dataset.embedding ? '<span>Type:</span> Embedding model<br>' : ''}
                 <span>Size:</span> ${formattedSize} GB<br>
                 <span>Modified:</span> ${dateTime}
             `;
@@ -626,6 +925,364 @@ class DesktopManager {
         const startMenu = document.getElementById('start-menu');
         if (startMenu) {
             startMenu.classList.toggle('hidden');
+            if (!startMenu.classList.contains('hidden')) {
+                this.loadStartMenuModels();
+            }
+        }
+    }
+
+    // Pinned and recently launched models, tracked by the backend
+    async loadStartMenuModels() {
+        try {
+            this.renderStartMenuModels(await invoke('get_recent_models', { limit: 5 }));
+        } catch (error) {
+            console.error('Error loading recent models:', error);
+        }
+    }
+
+    renderStartMenuModels(models) {
+        this.pinnedModels = new Set(models.filter(m => m.pinned).map(m => m.path));
+        const container = document.getElementById('start-menu-models');
+        if (!container) return;
+
+        container.innerHTML = models.map(model => `
+            <div class="start-menu-item${model.exists ? '' : ' missing'}" data-action="launch-recent" data-path="${this.escapeHtml(model.path)}" title="${this.escapeHtml(model.path)}">
+                <span class="material-icons">${model.pinned ? 'push_pin' : 'history'}</span>
+                <span class="start-menu-text">${this.escapeHtml(model.name)}</span>
+            </div>
+        `).join('') + (models.length ? '<div class="start-menu-separator"></div>' : '');
+    }
+
+    async togglePinnedModel(modelPath) {
+        try {
+            const pinned = !this.pinnedModels.has(modelPath);
+            await invoke('pin_model', { path: modelPath, pinned });
+            await this.loadStartMenuModels();
+        } catch (error) {
+            console.error('Error pinning model:', error);
+            this.showNotification(`Error pinning model: ${error}`, 'error');
+        }
+    }
+
+    async toggleFavorite(icon) {
+        try {
+            const config = await invoke('set_model_favorite', { modelPath: icon.dataset.path, favorite: !icon.classList.contains('favorite') });
+            icon.classList.toggle('favorite', config.favorite);
+            if (this.tagFilter === 'favorites') await this.applyTagFilter();
+        } catch (error) {
+            this.showNotification(`Error updating favorites: ${error}`, 'error');
+        }
+    }
+
+    // Single line text input in a dialog, resolves to null when cancelled
+    async promptText({ title, value = '', placeholder = '', hint = '' }) {
+        const input = document.createElement('input');
+        input.type = 'text';
+        input.className = 'property-input';
+        input.id = 'prompt-text-input';
+        input.placeholder = placeholder;
+        input.setAttribute('value', value);
+        return ModalDialog.showCustom({
+            title,
+            content: `${input.outerHTML}${hint ? `<br><small>${hint}</small>` : ''}`,
+            buttons: [
+                { text: 'Cancel', className: 'btn-secondary', action: () => null },
+                { text: 'OK', className: 'btn-primary', action: () => document.getElementById('prompt-text-input').value }
+            ]
+        });
+    }
+
+    async editModelTags(modelPath) {
+        const config = await invoke('get_model_settings', { modelPath });
+        const known = await invoke('get_model_tags').catch(() => []);
+        const value = await this.promptText({
+            title: 'Edit Tags',
+            value: (config.tags || []).join(', '),
+            placeholder: 'coding, roleplay, ...',
+            hint: known.length > 0 ? `In use: ${known.map(t => this.escapeHtml(t.tag)).join(', ')}` : ''
+        });
+        if (value === null) return;
+        const tags = value.split(',');
+
+        try {
+            await invoke('set_model_tags', { modelPath, tags });
+            if (this.tagFilter && this.tagFilter !== 'favorites') await this.applyTagFilter();
+        } catch (error) {
+            this.showNotification(`Error saving tags: ${error}`, 'error');
+        }
+    }
+
+    // Models stopped by their idle timeout stay dimmed until they are launched again
+    async markSleepingModels() {
+        let sleeping = [];
+        try {
+            sleeping = await invoke('get_sleeping_models');
+        } catch (error) {
+            console.error('Error loading sleeping models:', error);
+        }
+        const paths = new Set(sleeping.map(model => model.model_path));
+        document.querySelectorAll('.desktop-icon:not(.desktop-folder)').forEach(icon => {
+            icon.classList.toggle('sleeping', paths.has(icon.dataset.path));
+        });
+    }
+
+    async loadDesktopFolders() {
+        try {
+            this.desktopFolders = await invoke('get_desktop_folders');
+        } catch (error) {
+            console.error('Error loading desktop folders:', error);
+            this.desktopFolders = [];
+        }
+        if (!this.desktopFolders.some(f => f.id === this.openFolderId)) this.openFolderId = null;
+    }
+
+    // Folder tiles go first, models filed in a folder only show while it is open
+    renderDesktopFolders() {
+        const desktopIcons = document.getElementById('desktop-icons');
+        if (!desktopIcons) return;
+        desktopIcons.querySelectorAll('.desktop-folder').forEach(tile => tile.remove());
+
+        const openFolder = this.desktopFolders.find(f => f.id === this.openFolderId);
+        const tiles = openFolder
+            ? [{ id: '', name: `.. (${openFolder.name})`, icon: 'drive_folder_upload' }]
+            : this.desktopFolders.map(f => ({ id: f.id, name: f.name, icon: 'folder', count: f.models.length }));
+        tiles.reverse().forEach(folder => {
+            const tile = document.createElement('div');
+            tile.className = 'desktop-icon desktop-folder fade-in';
+            tile.dataset.folderId = folder.id;
+            tile.title = folder.count !== undefined ? `${folder.count} model${folder.count === 1 ? '' : 's'}` : 'Back to the desktop';
+            tile.innerHTML = `
+                <div class="icon-image"><span class="material-icons">${folder.icon}</span></div>
+                <div class="icon-label">${this.escapeHtml(folder.name)}</div>
+            `;
+            // Dropping a model on a folder files it there, on the back tile returns it to the desktop
+            tile.addEventListener('dragover', (e) => {
+                e.preventDefault();
+                e.dataTransfer.dropEffect = 'move';
+                tile.classList.add('drag-over');
+            });
+            tile.addEventListener('dragleave', () => tile.classList.remove('drag-over'));
+            tile.addEventListener('drop', (e) => {
+                e.preventDefault();
+                tile.classList.remove('drag-over');
+                const draggedPath = e.dataTransfer.getData('text/plain');
+                if (draggedPath) this.moveModelsToFolder([draggedPath], folder.id || null);
+            });
+            desktopIcons.prepend(tile);
+        });
+
+        const filed = new Set(this.desktopFolders.flatMap(f => f.models));
+        const shown = openFolder ? new Set(openFolder.models) : null;
+        desktopIcons.querySelectorAll('.desktop-icon:not(.desktop-folder)').forEach(icon => {
+            const hidden = shown ? !shown.has(icon.dataset.path) : filed.has(icon.dataset.path);
+            icon.classList.toggle('folder-hidden', hidden);
+        });
+    }
+
+    openDesktopFolder(folderId) {
+        this.openFolderId = folderId;
+        this.renderDesktopFolders();
+    }
+
+    async createDesktopFolder(modelPaths = []) {
+        const name = await this.promptText({ title: 'New Folder', placeholder: 'Folder name' });
+        if (name === null) return null;
+        try {
+            const folder = await invoke('create_desktop_folder', { name });
+            this.desktopFolders.push(folder);
+            if (modelPaths.length > 0) {
+                await this.moveModelsToFolder(modelPaths, folder.id);
+            } else {
+                this.openDesktopFolder(this.openFolderId);
+            }
+            return folder;
+        } catch (error) {
+            this.showNotification(`Error creating folder: ${error}`, 'error');
+            return null;
+        }
+    }
+
+    async renameDesktopFolder(folderId) {
+        const folder = this.desktopFolders.find(f => f.id === folderId);
+        if (!folder) return;
+        const name = await this.promptText({ title: 'Rename Folder', value: folder.name });
+        if (name === null) return;
+        try {
+            await invoke('rename_desktop_folder', { folderId, name });
+            await this.loadDesktopFolders();
+            this.openDesktopFolder(this.openFolderId);
+        } catch (error) {
+            this.showNotification(`Error renaming folder: ${error}`, 'error');
+        }
+    }
+
+    async deleteDesktopFolder(folderId) {
+        const folder = this.desktopFolders.find(f => f.id === folderId);
+        if (!folder) return;
+        const confirmed = await ModalDialog.showConfirmation({
+            title: 'Delete Folder',
+            message: `Delete the folder "${folder.name}"?\n\nIts ${folder.models.length} model(s) go back to the desktop, no files are deleted.`,
+            confirmText: 'Delete',
+            cancelText: 'Cancel',
+            type: 'danger'
+        });
+        if (!confirmed) return;
+        try {
+            await invoke('delete_desktop_folder', { folderId });
+            await this.loadDesktopFolders();
+            this.openDesktopFolder(this.openFolderId);
+        } catch (error) {
+            this.showNotification(`Error deleting folder: ${error}`, 'error');
+        }
+    }
+
+    async moveModelsToFolder(modelPaths, folderId) {
+        try {
+            this.desktopFolders = await invoke('assign_models_to_folder', { modelPaths, folderId });
+            this.openDesktopFolder(this.openFolderId);
+        } catch (error) {
+            this.showNotification(`Error moving to folder: ${error}`, 'error');
+        }
+    }
+
+    async chooseFolderForModel(modelPath) {
+        const choice = await ModalDialog.showCustom({
+            title: 'Move to Folder',
+            content: this.desktopFolders.length > 0 ? 'Choose a folder for this model.' : 'There are no folders yet.',
+            buttons: [
+                { text: 'Cancel', className: 'btn-secondary', action: () => null },
+                { text: 'Desktop', className: 'btn-secondary', action: () => '' },
+                ...this.desktopFolders.map(f => ({ text: this.escapeHtml(f.name), className: 'btn-secondary', action: () => f.id })),
+                { text: 'New Folder...', className: 'btn-primary', action: () => 'new' }
+            ]
+        });
+        if (choice === null) return;
+        if (choice === 'new') {
+            await this.createDesktopFolder([modelPath]);
+        } else {
+            await this.moveModelsToFolder([modelPath], choice || null);
+        }
+    }
+
+    // Merge a named argument preset into a model's custom arguments
+    async chooseArgumentPreset(modelPath) {
+        const presets = await invoke('list_argument_presets').catch(() => []);
+        const choice = await ModalDialog.showCustom({
+            title: 'Apply Preset',
+            content: presets.length > 0
+                ? `<p>Flags in the preset replace the same flags in this model's arguments, the others are kept.</p>
+                    ${presets.map(p => `<p><small><b>${this.escapeHtml(p.name)}</b>: ${this.escapeHtml(p.args)}</small></p>`).join('')}`
+                : 'There are no presets yet.',
+            buttons: [
+                { text: 'Cancel', className: 'btn-secondary', action: () => null },
+                ...presets.map(p => ({ text: this.escapeHtml(p.name), className: 'btn-secondary', action: () => p.name })),
+                { text: 'New Preset...', className: 'btn-primary', action: () => '' }
+            ]
+        });
+        if (choice === null) return;
+
+        const name = choice || await this.createArgumentPreset();
+        if (!name) return;
+        try {
+            await invoke('apply_argument_preset', { name, modelPaths: [modelPath] });
+            this.showNotification(`Applied preset ${name}`, 'success');
+            this.updateCustomArgsIndicators();
+        } catch (error) {
+            this.showNotification(`Error applying preset: ${error}`, 'error');
+        }
+    }
+
+    // Returns the name of the saved preset, or null when cancelled
+    async createArgumentPreset(args = '') {
+        const name = (await this.promptText({ title: 'New Preset', placeholder: 'Low VRAM' }))?.trim();
+        if (!name) return null;
+        const presetArgs = args || (await this.promptText({
+            title: `Arguments for ${name}`,
+            placeholder: '--ctx-size 16384 -ngl 99 -fa on',
+            hint: 'Saved presets can be applied to any model from its context menu'
+        }))?.trim();
+        if (!presetArgs) return null;
+        try {
+            await invoke('save_argument_preset', { preset: { name, args: presetArgs, description: '' } });
+            return name;
+        } catch (error) {
+            this.showNotification(`Error saving preset: ${error}`, 'error');
+            return null;
+        }
+    }
+
+    async chooseTagFilter() {
+        const tags = await invoke('get_model_tags').catch(() => []);
+        const choice = await ModalDialog.showCustom({
+            title: 'Filter by Tag',
+            content: tags.length > 0 ? 'Show only models with this tag.' : 'No tags yet, add some with "Edit Tags..." on a model.',
+            buttons: [
+                { text: 'Show All', className: 'btn-secondary', action: () => '' },
+                { text: 'Favorites', className: 'btn-secondary', action: () => 'favorites' },
+                ...tags.map(t => ({ text: `${this.escapeHtml(t.tag)} (${t.count})`, className: 'btn-secondary', action: () => t.tag }))
+            ]
+        });
+        if (choice === null) return;
+        this.tagFilter = choice || null;
+        await this.applyTagFilter();
+    }
+
+    // Hide icons outside the current tag filter and mark favorites
+    async applyTagFilter() {
+        try {
+            const favorites = new Set(await invoke('get_models_with_tag', { tag: 'favorites' }));
+            const visible = this.tagFilter === 'favorites'
+                ? favorites
+                : this.tagFilter ? new Set(await invoke('get_models_with_tag', { tag: this.tagFilter })) : null;
+            document.querySelectorAll('.desktop-icon:not(.desktop-folder)').forEach(icon => {
+                icon.classList.toggle('favorite', favorites.has(icon.dataset.path));
+                icon.style.display = visible && !visible.has(icon.dataset.path) ? 'none' : '';
+            });
+        } catch (error) {
+            console.error('Error applying tag filter:', error);
+        }
+    }
+
+    async checkModelFormat(modelPath) {
+        try {
+            const report = await invoke('inspect_model_format', { path: modelPath });
+            const lines = [
+                `Format: ${report.format}${report.version ? ` v${report.version}` : ''}`,
+                report.shards ? `Shards: ${report.shards}` : null,
+                `Loads in llama-server: ${report.loadable ? 'yes' : 'no'}`,
+                report.guidance
+            ].filter(Boolean);
+            await ModalDialog.showInfo({ title: 'Model Format', message: lines.join('\n') });
+        } catch (error) {
+            this.showNotification(`Error checking format: ${error}`, 'error');
+        }
+    }
+
+    async mergeSplitModel(modelPath) {
+        const confirmed = await ModalDialog.showConfirmation({
+            title: 'Merge Split Files',
+            message: 'Merge the parts of this model into a single GGUF file? The parts are kept, the merged copy needs the same amount of free space.',
+            confirmText: 'Merge',
+            cancelText: 'Cancel'
+        });
+        if (!confirmed) return;
+
+        this.showNotification('Merging model files...', 'info');
+        try {
+            const output = await invoke('merge_split_model', { path: modelPath });
+            this.showNotification(`Merged into ${output}`, 'success');
+            await this.loadModels(false);
+        } catch (error) {
+            this.showNotification(`${error}`, 'error');
+        }
+    }
+
+    launchRecentModel(modelPath) {
+        const icon = Array.from(document.querySelectorAll('.desktop-icon')).find(i => i.dataset.path === modelPath);
+        if (icon) {
+            this.launchModel(icon);
+        } else {
+            this.showNotification('Model not found on the desktop', 'error');
         }
     }
 
@@ -736,6 +1393,9 @@ class DesktopManager {
             case 'refresh':
                 this.refreshDesktop();
                 break;
+            case 'import-lm-studio':
+                this.importLmStudioModels();
+                break;
             case 'about':
                 this.showAboutDialog();
                 break;
@@ -918,7 +1578,14 @@ class DesktopManager {
                     // Use Tauri restart command if available to clean up processes
                     if (window.__TAURI__ && window.__TAURI__.core) {
                         console.log('💮 [CLEANUP] Using Tauri restart command for cleanup...');
-                        await window.__TAURI__.core.invoke('restart_application');
+                        const result = await window.__TAURI__.core.invoke('restart_application');
+                        if (result && result.success === false && result.requests_in_flight) {
+                            if (!confirm(`Running models have ${result.warning}. Restart anyway?`)) {
+                                loadingOverlay.remove();
+                                return;
+                            }
+                            await window.__TAURI__.core.invoke('restart_application', { force: true });
+                        }
                         console.log('✅ [CLEANUP COMPLETE] Process cleanup finished');
                     }
                     
@@ -1039,7 +1706,7 @@ class DesktopManager {
     }
 
     setupIconDragging() {
-        const icons = document.querySelectorAll('.desktop-icon');
+        const icons = document.querySelectorAll('.desktop-icon:not(.desktop-folder)');
         icons.forEach(icon => {
             icon.draggable = true;
 
@@ -1090,7 +1757,7 @@ class DesktopManager {
         });
     }
 
-    sortIcons(sortType, save = true, toggleDirection = true) {
+    async sortIcons(sortType, save = true, toggleDirection = true) {
         const iconsContainer = document.getElementById('desktop-icons');
         let icons = Array.from(iconsContainer.querySelectorAll('.desktop-icon'));
         
@@ -1107,32 +1774,49 @@ class DesktopManager {
         
         console.log('Sorting icons by:', sortType, 'direction:', this.sortDirection);
 
-        icons.sort((a, b) => {
-            let aValue = a.dataset[sortType];
-            let bValue = b.dataset[sortType];
-            let comparison = 0;
+        // Prefer the backend ordering, it knows about families and usage history
+        let backendOrder = null;
+        try {
+            const sorted = await window.__TAURI__.core.invoke('get_sorted_models', {
+                sortType,
+                direction: this.sortDirection
+            });
+            backendOrder = new Map(sorted.map((model, index) => [model.path, index]));
+        } catch (error) {
+            console.warn('Backend sort unavailable, sorting locally:', error);
+        }
 
-            switch (sortType) {
-                case 'date':
-                case 'size':
-                    comparison = parseFloat(aValue) - parseFloat(bValue);
-                    break;
-                case 'quantization':
-                    const getQuantValue = (s) => {
-                        if (s === 'Unknown') return -1;
-                        const match = s.match(/(\d+)/);
-                        return match ? parseInt(match[0], 10) : -1;
-                    };
-                    comparison = getQuantValue(aValue) - getQuantValue(bValue);
-                    break;
-                case 'name':
-                case 'architecture':
-                default:
-                    comparison = aValue.localeCompare(bValue, undefined, { numeric: true });
-                    break;
-            }
-            return this.sortDirection === 'asc' ? comparison : -comparison;
-        });
+        if (backendOrder) {
+            const position = (icon) => backendOrder.has(icon.dataset.path) ? backendOrder.get(icon.dataset.path) : Number.MAX_SAFE_INTEGER;
+            icons.sort((a, b) => position(a) - position(b));
+        } else {
+            icons.sort((a, b) => {
+                let aValue = a.dataset[sortType];
+                let bValue = b.dataset[sortType];
+                let comparison = 0;
+
+                switch (sortType) {
+                    case 'date':
+                    case 'size':
+                        comparison = parseFloat(aValue) - parseFloat(bValue);
+                        break;
+                    case 'quantization':
+                        const getQuantValue = (s) => {
+                            if (s === 'Unknown') return -1;
+                            const match = s.match(/(\d+)/);
+                            return match ? parseInt(match[0], 10) : -1;
+                        };
+                        comparison = getQuantValue(aValue) - getQuantValue(bValue);
+                        break;
+                    case 'name':
+                    case 'architecture':
+                    default:
+                        comparison = (aValue || '').localeCompare(bValue || '', undefined, { numeric: true });
+                        break;
+                }
+                return this.sortDirection === 'asc' ? comparison : -comparison;
+            });
+        }
 
         this.reorderIcons(icons);
 
@@ -1300,7 +1984,7 @@ class DesktopManager {
         return false;
     }
 
-    async launchModel(icon) {
+    async launchModel(icon, relaunch = false) {
         const modelPath = icon.dataset.path;
         const modelName = icon.dataset.name;
 
@@ -1361,10 +2045,15 @@ class DesktopManager {
             // Show progress notification
             this.showNotification(`Starting ${modelName}...`, 'info');
             
-            const result = await invoke('launch_model', { modelPath: modelPath });
+            // Relaunch repeats the previous invocation instead of the current settings
+            const result = await invoke(relaunch ? 'relaunch_last' : 'launch_model', { modelPath: modelPath });
             console.log('Launch model result:', result);
 
             if (result.success) {
+                icon.classList.remove('sleeping');
+                if (result.computed_context) {
+                    this.showNotification(`Context sized to ${result.computed_context} tokens for free VRAM`, 'info');
+                }
                 console.log('Opening server terminal...');
                 console.log('Terminal manager methods:', Object.getOwnPropertyNames(terminalManager.__proto__));
                 
@@ -1446,6 +2135,140 @@ class DesktopManager {
         }
     }
 
+    async launchModelCli(icon) {
+        const modelPath = icon.dataset.path;
+        const modelName = icon.dataset.name;
+
+        try {
+            const result = await invoke('launch_model_cli', { modelPath: modelPath });
+            terminalManager.openCliTerminal(result.process_id, result.model_name, modelPath);
+        } catch (error) {
+            this.showNotification(`Failed to start llama-cli for ${modelName}: ${error}`, 'error');
+        }
+    }
+
+    // llama-bench with a few common knobs, comma separated values test every combination
+    async showBenchmark(icon) {
+        const modelPath = icon.dataset.path;
+        const modelName = icon.dataset.name;
+        const field = (id, label, value, placeholder = '') =>
+            `<label class="benchmark-field">${label}<input type="text" class="property-input" id="${id}" value="${value}" placeholder="${placeholder}"></label>`;
+        const numbers = (id) => (document.getElementById(id)?.value || '')
+            .split(',').map(v => parseInt(v.trim(), 10)).filter(v => !isNaN(v) && v >= 0);
+
+        const options = await ModalDialog.showCustom({
+            title: `Benchmark - ${modelName}`,
+            content: `${field('bench-prompt', 'Prompt tokens', '512')}
+                ${field('bench-gen', 'Generated tokens', '128')}
+                ${field('bench-ngl', 'GPU layers', '', 'llama-bench default')}
+                <label class="benchmark-field">Flash attention
+                    <select class="property-input" id="bench-fa">
+                        <option value="">Default</option>
+                        <option value="0">Off</option>
+                        <option value="1">On</option>
+                        <option value="0,1">Compare off and on</option>
+                    </select>
+                </label>
+                ${field('bench-reps', 'Repetitions', '3')}
+                <small>Stop running servers first, they compete for the same GPU.</small>`,
+            buttons: [
+                { text: 'Cancel', className: 'btn-secondary', action: () => null },
+                { text: 'History', className: 'btn-secondary', action: () => 'history' },
+                {
+                    text: 'Run', className: 'btn-primary', action: () => ({
+                        prompt_tokens: numbers('bench-prompt'),
+                        gen_tokens: numbers('bench-gen'),
+                        gpu_layers: numbers('bench-ngl'),
+                        flash_attention: numbers('bench-fa').map(v => v === 1),
+                        repetitions: numbers('bench-reps')[0] || null
+                    })
+                }
+            ]
+        });
+        if (!options) return;
+
+        if (options !== 'history') {
+            this.showNotification(`Benchmarking ${modelName}, this can take a few minutes...`, 'info');
+            try {
+                await invoke('run_benchmark', { modelPath, options });
+            } catch (error) {
+                this.showNotification(`${error}`, 'error');
+                return;
+            }
+        }
+        await this.showBenchmarkHistory(modelPath, modelName);
+    }
+
+    // Runs of every file in the model's folder, so quants and llama.cpp builds line up side by side
+    async showBenchmarkHistory(modelPath, modelName) {
+        const folder = modelPath.replace(/[\\/][^\\/]*$/, '');
+        const records = (await invoke('get_benchmark_history', {}).catch(() => []))
+            .filter(r => r.model_path.replace(/[\\/][^\\/]*$/, '') === folder);
+        const average = (results) => results.length > 0
+            ? (results.reduce((sum, r) => sum + r.avg_ts, 0) / results.length).toFixed(1)
+            : '-';
+        const fileName = (path) => this.escapeHtml(path.split(/[\\/]/).pop());
+
+        const latest = records.find(r => r.model_path === modelPath);
+        const latestRows = (latest?.results || []).map(r => `<tr>
+                <td>${r.n_prompt > 0 ? `pp${r.n_prompt}` : `tg${r.n_gen}`}</td>
+                <td>${r.n_gpu_layers}</td>
+                <td>${r.flash_attn == null ? '-' : r.flash_attn ? 'on' : 'off'}</td>
+                <td>${r.avg_ts.toFixed(1)} ± ${r.stddev_ts.toFixed(1)}</td>
+            </tr>`).join('');
+        const historyRows = records.map(r => `<tr class="${r.model_path === modelPath ? 'current' : ''}">
+                <td title="${this.escapeHtml(r.results[0]?.model_type || '')}">${fileName(r.model_path)}</td>
+                <td>${this.escapeHtml(r.version)}</td>
+                <td>${new Date(r.timestamp).toLocaleString()}</td>
+                <td>${average(r.results.filter(x => x.n_prompt > 0 && x.n_gen === 0))}</td>
+                <td>${average(r.results.filter(x => x.n_gen > 0 && x.n_prompt === 0))}</td>
+            </tr>`).join('');
+
+        await ModalDialog.showCustom({
+            title: `Benchmarks - ${modelName}`,
+            content: records.length === 0 ? 'No benchmarks yet for this folder.' : `
+                ${latest ? `<h4>Latest run (${this.escapeHtml(latest.version)})</h4>
+                <table class="benchmark-table"><tr><th>Test</th><th>GPU layers</th><th>Flash attn</th><th>tok/s</th></tr>${latestRows}</table>` : ''}
+                <h4>History</h4>
+                <table class="benchmark-table"><tr><th>File</th><th>Build</th><th>Date</th><th>pp tok/s</th><th>tg tok/s</th></tr>${historyRows}</table>`,
+            buttons: [{ text: 'Close', className: 'btn-secondary', action: () => null }]
+        });
+    }
+
+    // The exact command a launch would run, to check custom arguments or run it by hand
+    async showLaunchCommand(icon) {
+        const modelName = icon.dataset.name;
+        let command;
+        try {
+            command = await invoke('get_launch_command', { modelPath: icon.dataset.path });
+        } catch (error) {
+            this.showNotification(`Failed to get the launch command: ${error}`, 'error');
+            return;
+        }
+
+        const notes = [];
+        if (!command.executable_exists) notes.push('The llama-server executable was not found at this path.');
+        if (command.port_reassigned) notes.push(`The configured port is taken, port ${command.port} would be used.`);
+        if (command.context_size) notes.push(`Auto context picked ${command.context_size} tokens for the free VRAM right now.`);
+
+        const copy = await ModalDialog.showCustom({
+            title: `Launch Command - ${modelName}`,
+            content: `<pre class="launch-command">${this.escapeHtml(command.command_line)}</pre>
+                ${notes.map(note => `<p><small>${this.escapeHtml(note)}</small></p>`).join('')}`,
+            buttons: [
+                { text: 'Close', className: 'btn-secondary', action: () => false },
+                { text: 'Copy', className: 'btn-primary', action: () => true }
+            ]
+        });
+        if (!copy) return;
+        try {
+            await navigator.clipboard.writeText(command.command_line);
+            this.showNotification('Launch command copied', 'success');
+        } catch (error) {
+            this.showNotification('Failed to copy the launch command', 'error');
+        }
+    }
+
     showProperties(icon) {
         if (propertiesManager) {
             propertiesManager.showProperties(icon);
@@ -2683,6 +3506,8 @@ class DesktopManager {
     async saveConfiguration() {
         const modelsDir = document.getElementById('models-directory').value;
         const execFolder = document.getElementById('executable-folder').value;
+        const hfTokenInput = document.getElementById('hf-token');
+        const extraDirsInput = document.getElementById('extra-model-directories');
         const themeColor = document.getElementById('theme-color').value;
         const backgroundColor = document.getElementById('background-color').value;
         const themeSyncButton = document.getElementById('theme-sync-button');
@@ -2694,9 +3519,34 @@ class DesktopManager {
                 executableFolder: execFolder,
                 themeColor: themeColor,
                 backgroundColor: backgroundColor,
-                themeIsSynced: themeIsSynced
+                themeIsSynced: themeIsSynced,
+                hfToken: hfTokenInput ? hfTokenInput.value : null,
+                extraModelDirectories: extraDirsInput
+                    ? extraDirsInput.value.split('\n').map(d => d.trim()).filter(Boolean)
+                    : null
             });
 
+            const speedLimitInput = document.getElementById('download-speed-limit');
+            if (speedLimitInput) {
+                const mbPerSecond = Math.max(0, parseFloat(speedLimitInput.value) || 0);
+                await invoke('set_download_speed_limit', { bytesPerSecond: Math.round(mbPerSecond * 1024 * 1024) });
+            }
+
+            const acceleratedInput = document.getElementById('accelerated-transfer');
+            if (acceleratedInput) {
+                const connections = parseInt(document.getElementById('accelerated-connections').value, 10) || 0;
+                await invoke('set_accelerated_transfer', {
+                    settings: { enabled: acceleratedInput.checked, connections: Math.min(Math.max(connections, 0), 32) }
+                });
+            }
+
+            const portRangeStart = document.getElementById('port-range-start');
+            if (portRangeStart) {
+                const start = parseInt(portRangeStart.value, 10) || 8080;
+                const end = parseInt(document.getElementById('port-range-end').value, 10) || start + 99;
+                await invoke('set_port_range', { portRange: { start, end } });
+            }
+
             if (result.success) {
                 this.showNotification('Configuration saved!', 'success');
                 this.applyTheme(themeColor, backgroundColor);
@@ -2719,34 +3569,50 @@ class DesktopManager {
         }
     }
 
+    createModelIcon(model) {
+        const iconElement = document.createElement('div');
+        iconElement.className = 'desktop-icon';
+        iconElement.setAttribute('data-path', model.path);
+        iconElement.setAttribute('data-name', model.name);
+        iconElement.setAttribute('data-size', model.size_gb);
+        iconElement.setAttribute('data-architecture', model.architecture);
+        iconElement.setAttribute('data-quantization', model.quantization);
+        iconElement.setAttribute('data-date', model.date);
+        iconElement.setAttribute('data-shards', model.shards || 1);
+        if (model.read_only) {
+            iconElement.setAttribute('data-read-only', 'true');
+        }
+        if (model.embedding_model) {
+            iconElement.setAttribute('data-embedding', 'true');
+        }
+        if (model.incomplete) {
+            iconElement.classList.add('incomplete');
+            iconElement.title = `Split model with missing parts (${model.shards} found)`;
+        }
+
+        iconElement.innerHTML = `
+            <div class="icon-image">
+                <img src="./assets/gguf.png" class="model-icon">
+                <div class="architecture-label">${model.architecture.substring(0, 7)}</div>
+            </div>
+            <div class="icon-label">${model.name.replace('.gguf', '')}</div>
+        `;
+        return iconElement;
+    }
+
     refreshDesktopIcons(models, useAnimation = true) {
         const desktopIcons = document.getElementById('desktop-icons');
         if (!desktopIcons) return;
 
         // Clear existing icons
         desktopIcons.innerHTML = '';
+        // Full scan results, the properties window reads GGUF details from here
+        this.modelsByPath = new Map(models.map(model => [model.path, model]));
 
         // Create new icons from models data
-        models.forEach((model, index) => {
-            const iconElement = document.createElement('div');
-            iconElement.className = 'desktop-icon';
-            iconElement.setAttribute('data-path', model.path);
-            iconElement.setAttribute('data-name', model.name);
-            iconElement.setAttribute('data-size', model.size_gb);
-            iconElement.setAttribute('data-architecture', model.architecture);
-            iconElement.setAttribute('data-quantization', model.quantization);
-            iconElement.setAttribute('data-date', model.date);
-
-            iconElement.innerHTML = `
-                <div class="icon-image">
-                    <img src="./assets/gguf.png" class="model-icon">
-                    <div class="architecture-label">${model.architecture.substring(0, 7)}</div>
-                </div>
-                <div class="icon-label">${model.name.replace('.gguf', '')}</div>
-            `;
+        models.forEach(model => desktopIcons.appendChild(this.createModelIcon(model)));
+        this.renderDesktopFolders();
 
-            desktopIcons.appendChild(iconElement);
-        });
 
         // Add fade-in animation to all new icons simultaneously if requested
         if (useAnimation) {
@@ -2782,6 +3648,8 @@ class DesktopManager {
         setTimeout(() => {
             this.updateCustomArgsIndicators();
         }, 150);
+        this.applyTagFilter();
+        this.markSleepingModels();
         
         //this.showNotification(`Desktop refreshed with ${models.length} model(s)`, 'success');
     }
@@ -2793,6 +3661,7 @@ class DesktopManager {
             // Add a small delay to ensure file system has processed any recent changes
             await new Promise(resolve => setTimeout(resolve, 100));
             
+            await this.loadDesktopFolders();
             const result = await invoke('scan_models_command');
             
             if (result.success && result.models) {
@@ -2809,7 +3678,9 @@ class DesktopManager {
     async hasCustomArguments(modelPath) {
         try {
             const config = await invoke('get_model_settings', { modelPath: modelPath });
-            return config && config.custom_args && config.custom_args.trim() !== '';
+            if (!config) return false;
+            const hasLaunchSettings = Object.values(config.launch_settings || {}).some(value => value !== null && value !== undefined);
+            return hasLaunchSettings || (config.custom_args && config.custom_args.trim() !== '');
         } catch (error) {
             console.error('Error checking custom arguments:', error);
             return false;
@@ -3022,9 +3893,19 @@ class DesktopManager {
         document.getElementById('current-time').textContent = timeString;
     }
 
-    async updateSystemStats() {
+    handleAppStatus(status) {
+        this.appStatus = status;
+        if (status.system) {
+            this.updateSystemStats(status.system);
+        }
+        if (window.downloadManager) {
+            window.downloadManager.handleDownloadSummary(status.downloads);
+        }
+    }
+
+    async updateSystemStats(latestStats = null) {
         try {
-            const stats = await invoke('get_system_stats');
+            const stats = latestStats || await invoke('get_system_stats');
             
             // Get or create the system monitor icon
             let monitorIcon = document.getElementById('system-monitor-icon');
@@ -3093,32 +3974,7 @@ class DesktopManager {
         // Update content
         popup.innerHTML = `
             <div class="system-info-content">
-                <div class="system-info-header">
-                    <span class="material-icons">monitor</span>
-                    <h3>System Information</h3>
-                </div>
-                <div class="system-info-item">
-                    <span class="label">CPU Usage:</span>
-                    <span class="value">${stats.cpu_usage.toFixed(1)}%</span>
-                </div>
-                <div class="system-info-item">
-                    <span class="label">Memory:</span>
-                    <span class="value">${stats.memory_used_gb.toFixed(2)} GB / ${stats.memory_total_gb.toFixed(2)} GB</span>
-                </div>
-                <div class="system-info-item">
-                    <span class="label">GPU:</span>
-                    <span class="value">${stats.gpu_name}</span>
-                </div>
-                ${stats.gpu_name !== "Unknown" && stats.gpu_name !== "No NVIDIA GPU detected" && stats.gpu_name !== "No GPU detected" ? `
-                <div class="system-info-item">
-                    <span class="label">GPU Usage:</span>
-                    <span class="value">${stats.gpu_usage.toFixed(1)}%</span>
-                </div>
-                <div class="system-info-item">
-                    <span class="label">GPU Memory:</span>
-                    <span class="value">${stats.gpu_memory_used_gb.toFixed(2)} GB / ${stats.gpu_memory_total_gb.toFixed(2)} GB</span>
-                </div>
-                ` : ''}
+                ${this.systemInfoHTML(stats)}
             </div>
         `;
         
@@ -3142,38 +3998,38 @@ class DesktopManager {
         if (popup) {
             const content = popup.querySelector('.system-info-content');
             if (content) {
-                content.innerHTML = `
-                    <div class="system-info-header">
-                        <span class="material-icons">monitor</span>
-                        <h3>System Information</h3>
-                    </div>
-                    <div class="system-info-item">
-                        <span class="label">CPU Usage:</span>
-                        <span class="value">${stats.cpu_usage.toFixed(1)}%</span>
-                    </div>
-                    <div class="system-info-item">
-                        <span class="label">Memory:</span>
-                        <span class="value">${stats.memory_used_gb.toFixed(2)} GB / ${stats.memory_total_gb.toFixed(2)} GB</span>
-                    </div>
-                    <div class="system-info-item">
-                        <span class="label">GPU:</span>
-                        <span class="value">${stats.gpu_name}</span>
-                    </div>
-                    ${stats.gpu_name !== "Unknown" && stats.gpu_name !== "No NVIDIA GPU detected" && stats.gpu_name !== "No GPU detected" ? `
-                    <div class="system-info-item">
-                        <span class="label">GPU Usage:</span>
-                        <span class="value">${stats.gpu_usage.toFixed(1)}%</span>
-                    </div>
-                    <div class="system-info-item">
-                        <span class="label">GPU Memory:</span>
-                        <span class="value">${stats.gpu_memory_used_gb.toFixed(2)} GB / ${stats.gpu_memory_total_gb.toFixed(2)} GB</span>
-                    </div>
-                    ` : ''}
-                `;
+                content.innerHTML = this.systemInfoHTML(stats);
             }
         }
     }
 
+    systemInfoHTML(stats) {
+        const item = (label, value) => `
+            <div class="system-info-item">
+                <span class="label">${label}:</span>
+                <span class="value">${value}</span>
+            </div>`;
+        const hasGpu = stats.gpu_name !== "Unknown" && stats.gpu_name !== "No NVIDIA GPU detected" && stats.gpu_name !== "No GPU detected";
+        const memory = `${stats.memory_used_gb.toFixed(2)} GB / ${stats.memory_total_gb.toFixed(2)} GB`;
+        // With unified memory the GPU's figure is its share of RAM, against the limit it may wire
+        const gpuMemory = stats.unified_memory
+            ? `${stats.gpu_memory_used_gb.toFixed(2)} GB${stats.gpu_memory_total_gb > 0 ? ` (limit ${stats.gpu_memory_total_gb.toFixed(2)} GB)` : ''}`
+            : `${stats.gpu_memory_used_gb.toFixed(2)} GB / ${stats.gpu_memory_total_gb.toFixed(2)} GB`;
+
+        return `
+            <div class="system-info-header">
+                <span class="material-icons">monitor</span>
+                <h3>System Information</h3>
+            </div>
+            ${item('CPU Usage', `${stats.cpu_usage.toFixed(1)}%`)}
+            ${item(stats.unified_memory ? 'Unified Memory' : 'Memory', memory)}
+            ${stats.memory_pressure != null ? item('Memory Pressure', `${stats.memory_pressure.toFixed(0)}%`) : ''}
+            ${item('GPU', stats.gpu_name)}
+            ${hasGpu ? item('GPU Usage', `${stats.gpu_usage.toFixed(1)}%`) : ''}
+            ${hasGpu ? item(stats.unified_memory ? 'GPU Share' : 'GPU Memory', gpuMemory) : ''}
+        `;
+    }
+
     hideSystemInfoPopup() {
         // Update button active state
         this.updateTaskbarButtonState('system-monitor-icon', false);
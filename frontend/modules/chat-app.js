@@ -8,12 +8,14 @@ class ChatApplication {
         this.windowElement = null;
         this.streamingAbortController = null; // For canceling streaming requests
         this.configVisible = false;
+        this.pendingAudio = null; // Audio staged by stage_audio_input for the next message
 
         // Generation stats tracking
         this.generationStats = {
             startTime: null,
             firstTokenTime: null,
             tokenCount: 0,
+            promptTokens: null,
             lastTokenTime: null
         };
 
@@ -34,6 +36,85 @@ class ChatApplication {
     init() {
         this.createChatWindow();
         this.loadSavedChats();
+
+        // Chats started from the CLI, deep links or the tray come in through the backend
+        if (window.__TAURI__ && window.__TAURI__.event) {
+            window.__TAURI__.event.listen('open-chat-request', (event) => {
+                this.openExternalChat(event.payload);
+            });
+            // Titles are generated by the backend once a chat has its first reply
+            window.__TAURI__.event.listen('chat-title-updated', (event) => {
+                this.applyChatTitle(event.payload.chat_id, event.payload.title);
+            });
+        }
+    }
+
+    // A generated title, falling back to the model name until there is one
+    chatLabel(chatData) {
+        return chatData.title || chatData.name;
+    }
+
+    applyChatTitle(chatId, title) {
+        const chatData = this.chats.get(chatId);
+        if (!chatData) return;
+        chatData.title = title;
+
+        const chatItem = document.getElementById(`chat-item-${chatId}`);
+        if (chatItem) {
+            const name = chatItem.querySelector('.chat-item-name');
+            if (name) {
+                name.textContent = title;
+                name.title = title;
+            }
+            const details = chatItem.querySelector('.chat-item-details');
+            if (details) details.textContent = `${chatData.name} · ${chatData.host}:${chatData.port}`;
+        }
+        if (this.activeChat === chatId) {
+            const chatTitle = document.getElementById('chat-title');
+            if (chatTitle) {
+                chatTitle.textContent = `${title} (${chatData.host}:${chatData.port})`;
+                chatTitle.title = chatTitle.textContent;
+            }
+        }
+        this.saveChatData(chatId);
+    }
+
+    async openExternalChat(request) {
+        if (request.launched && window.terminalManager) {
+            window.terminalManager.openServerTerminal(request.process_id, request.model_name, request.host, request.port, request.model_path, '');
+        }
+
+        const chatData = {
+            id: request.chat_id,
+            name: request.model_name,
+            host: request.host,
+            port: request.port,
+            messages: [],
+            status: 'disconnected',
+            statusMessage: null,
+            created: Date.now(),
+            config: { ...this.defaultConfig }
+        };
+        this.chats.set(chatData.id, chatData);
+        this.addChatToList(chatData);
+        this.show();
+        this.selectChat(chatData.id);
+        this.saveChatData();
+
+        // A freshly launched server needs a while to load the model
+        for (let attempt = 0; attempt < 120; attempt++) {
+            await this.testConnection(chatData.id);
+            if (chatData.status === 'connected' || !this.chats.has(chatData.id)) break;
+            await new Promise(resolve => setTimeout(resolve, 2000));
+        }
+
+        if (request.initial_prompt && chatData.status === 'connected' && this.activeChat === chatData.id) {
+            const input = document.getElementById('chat-input');
+            if (input) {
+                input.value = request.initial_prompt;
+                this.sendMessage();
+            }
+        }
     }
 
     resetGenerationStats() {
@@ -41,10 +122,59 @@ class ChatApplication {
             startTime: null,
             firstTokenTime: null,
             tokenCount: 0,
+            promptTokens: null,
             lastTokenTime: null
         };
     }
 
+    // Report a finished request for the per-model analytics, failures are only logged
+    async recordRequestAnalytics(chatData, cancelled = false) {
+        const stats = this.generationStats;
+        if (!stats.startTime || stats.tokenCount === 0) return;
+        try {
+            const invoke = window.__TAURI__?.core?.invoke;
+            if (!invoke) return;
+            await invoke('record_chat_request', {
+                sample: {
+                    host: chatData.host,
+                    port: chatData.port,
+                    chat_name: chatData.name,
+                    started_at: new Date(stats.startTime).toISOString(),
+                    duration_ms: (stats.lastTokenTime || Date.now()) - stats.startTime,
+                    time_to_first_token_ms: stats.firstTokenTime ? stats.firstTokenTime - stats.startTime : null,
+                    prompt_tokens: stats.promptTokens,
+                    completion_tokens: stats.tokenCount,
+                    cancelled
+                }
+            });
+        } catch (error) {
+            console.warn('Failed to record chat analytics:', error);
+        }
+    }
+
+    // Opt-in request log, the backend drops it unless logging is enabled
+    async logInferenceRequest(chatData, content) {
+        if (!this.lastRequestBody) return;
+        try {
+            const invoke = window.__TAURI__?.core?.invoke;
+            if (!invoke) return;
+            const stats = this.generationStats;
+            await invoke('log_inference_request', {
+                sample: {
+                    host: chatData.host,
+                    port: chatData.port,
+                    endpoint: '/v1/chat/completions',
+                    request: this.lastRequestBody,
+                    response: content,
+                    status: 200,
+                    duration_ms: stats.startTime ? (stats.lastTokenTime || Date.now()) - stats.startTime : null
+                }
+            });
+        } catch (error) {
+            console.warn('Failed to log request:', error);
+        }
+    }
+
     calculateGenerationStats(stopReason = null) {
         if (!this.generationStats.startTime) {
             return null;
@@ -219,8 +349,12 @@ class ChatApplication {
                         </div>
                         <div class="chat-input-area">
                             <div class="chat-input-container">
+                                <div class="chat-attachment" id="chat-attachment" style="display: none;"></div>
                                 <textarea class="chat-input" id="chat-input" placeholder="Type your message..." autocomplete="off" rows="2"
                                        oninput="chatApp.autoResizeInput(this)" onkeydown="chatApp.handleInputKeydown(event)"></textarea>
+                                <button class="chat-attach" id="chat-attach" onclick="chatApp.attachAudio()" title="Attach audio (WAV or MP3) for models with an audio projector">
+                                    <span class="material-icons">mic</span>
+                                </button>
                                 <button class="chat-send" id="chat-send" onclick="chatApp.handleSendButtonClick()" title="Send message">
                                     <span class="material-icons">arrow_upward</span>
                                 </button>
@@ -454,11 +588,13 @@ class ChatApplication {
         chatItem.className = `chat-list-item ${chatData.status}`;
         chatItem.id = `chat-item-${chatData.id}`;
 
+        const label = this.escapeHtml(this.chatLabel(chatData));
+        const details = `${chatData.title ? `${this.escapeHtml(chatData.name)} · ` : ''}${this.escapeHtml(chatData.host)}:${chatData.port}`;
         chatItem.innerHTML = `
             <div class="chat-item-content" onclick="chatApp.selectChat('${chatData.id}')">
                 <div class="chat-item-info">
-                    <div class="chat-item-name" title="${this.escapeHtml(chatData.name)}">${this.escapeHtml(chatData.name)}</div>
-                    <div class="chat-item-details">${this.escapeHtml(chatData.host)}:${chatData.port}</div>
+                    <div class="chat-item-name" title="${label}">${label}</div>
+                    <div class="chat-item-details">${details}</div>
                 </div>
                 <button class="chat-delete-btn" onclick="event.stopPropagation(); chatApp.deleteChatFromList('${chatData.id}')" title="Delete Chat">
                     <span class="material-icons">delete</span>
@@ -621,7 +757,7 @@ class ChatApplication {
         // Update chat header
         const chatTitle = document.getElementById('chat-title');
         if (chatTitle) {
-            const titleText = `${chatData.name} (${chatData.host}:${chatData.port})`;
+            const titleText = `${this.chatLabel(chatData)} (${chatData.host}:${chatData.port})`;
             chatTitle.textContent = titleText;
             chatTitle.title = titleText; // Add tooltip for full text when truncated
         }
@@ -880,6 +1016,11 @@ class ChatApplication {
             this.updateSidebarVisibility();
 
             this.saveChatData();
+            try {
+                await window.__TAURI__?.core?.invoke('remove_chat_state', { chatId });
+            } catch (error) {
+                console.warn('Failed to remove stored chat:', error);
+            }
         }
     }
 
@@ -1072,8 +1213,9 @@ class ChatApplication {
         if (!input) return;
 
         const message = input.value.trim();
+        const audio = this.pendingAudio;
 
-        if (!message) return;
+        if (!message && !audio) return;
 
         const chatData = this.chats.get(this.activeChat);
         if (!chatData || chatData.status !== 'connected') return;
@@ -1090,6 +1232,10 @@ class ChatApplication {
             content: message,
             timestamp: Date.now()
         };
+        if (audio) {
+            userMessage.audio = audio;
+            this.clearAudio();
+        }
 
         chatData.messages.push(userMessage);
         this.addMessageToUI(userMessage, true);
@@ -1111,7 +1257,7 @@ class ChatApplication {
             // Prepare messages with system prompt if configured
             let messages = chatData.messages.map(msg => ({
                 role: msg.role,
-                content: msg.content
+                content: msg.audio ? this.audioContent(msg) : msg.content
             }));
 
             // Add system prompt if configured
@@ -1122,20 +1268,32 @@ class ChatApplication {
                 });
             }
 
+            const requestBody = {
+                messages: messages,
+                stream: requestConfig.stream,
+                max_tokens: requestConfig.max_tokens,
+                temperature: requestConfig.temperature,
+                top_k: requestConfig.top_k,
+                top_p: requestConfig.top_p,
+                repeat_penalty: requestConfig.repeat_penalty
+            };
+            // Keep the base64 audio out of the request log
+            this.lastRequestBody = {
+                ...requestBody,
+                messages: messages.map(msg => Array.isArray(msg.content) ? {
+                    ...msg,
+                    content: msg.content.map(part => part.type === 'input_audio'
+                        ? { type: 'input_audio', input_audio: { format: part.input_audio.format, data: '(omitted)' } }
+                        : part)
+                } : msg)
+            };
+
             const response = await fetch(`http://${chatData.host}:${chatData.port}/v1/chat/completions`, {
                 method: 'POST',
                 headers: {
                     'Content-Type': 'application/json',
                 },
-                body: JSON.stringify({
-                    messages: messages,
-                    stream: requestConfig.stream,
-                    max_tokens: requestConfig.max_tokens,
-                    temperature: requestConfig.temperature,
-                    top_k: requestConfig.top_k,
-                    top_p: requestConfig.top_p,
-                    repeat_penalty: requestConfig.repeat_penalty
-                }),
+                body: JSON.stringify(requestBody),
                 signal: this.streamingAbortController.signal
             });
 
@@ -1211,8 +1369,13 @@ class ChatApplication {
 
         const time = new Date(message.timestamp).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit', hour12: false });
         
+        const audioChip = message.audio
+            ? `<div class="message-audio"><span class="material-icons">audiotrack</span>${this.escapeHtml(message.audio.file_name)}</div>`
+            : '';
+        
         messageDiv.innerHTML = `
             <div class="message-content">
+                ${audioChip}
                 ${this.formatMessage(message.content, false)}
                 <div class="message-time">${time}</div>
             </div>
@@ -1728,6 +1891,14 @@ class ChatApplication {
                                 // Use actual token count from API if available
                                 this.generationStats.tokenCount = parsed.usage.completion_tokens;
                             }
+                            if (parsed.usage && parsed.usage.prompt_tokens) {
+                                this.generationStats.promptTokens = parsed.usage.prompt_tokens;
+                            }
+                            // llama-server reports exact counts with the last chunk
+                            if (parsed.timings) {
+                                if (parsed.timings.predicted_n) this.generationStats.tokenCount = parsed.timings.predicted_n;
+                                if (parsed.timings.prompt_n != null) this.generationStats.promptTokens = parsed.timings.prompt_n;
+                            }
                         } catch (parseError) {
                             console.warn('Failed to parse streaming data:', data, parseError);
                             // Continue processing other lines instead of failing
@@ -1758,6 +1929,8 @@ class ChatApplication {
                 chatData.messages.push(assistantMessage);
                 this.addMessageToUI(assistantMessage, true, true);
                 this.saveChatData();
+                this.recordRequestAnalytics(chatData);
+                this.logInferenceRequest(chatData, fullContent);
             } else {
                 throw new Error('No content received from server');
             }
@@ -1777,6 +1950,7 @@ class ChatApplication {
                 chatData.messages.push(partialMessage);
                 this.addMessageToUI(partialMessage, true, true);
                 this.saveChatData();
+                this.recordRequestAnalytics(chatData, true);
             } else {
                 // Add an error message if there's no content at all
                 this.addErrorMessage(
@@ -1941,6 +2115,62 @@ class ChatApplication {
         }
     }
 
+    // Stage a WAV or MP3 file to go with the next message as an input_audio part
+    async attachAudio() {
+        const dialog = window.__TAURI__?.dialog;
+        const invoke = window.__TAURI__?.core?.invoke;
+        if (!dialog || !invoke) return;
+        
+        const path = await dialog.open({
+            multiple: false,
+            filters: [{ name: 'Audio', extensions: ['wav', 'mp3'] }]
+        });
+        if (!path) return;
+        
+        try {
+            this.pendingAudio = await invoke('stage_audio_input', { path });
+            this.renderAttachment();
+        } catch (error) {
+            this.addErrorMessage(String(error), 'Audio Error');
+        }
+    }
+    
+    clearAudio() {
+        this.pendingAudio = null;
+        this.renderAttachment();
+    }
+    
+    renderAttachment() {
+        const attachment = document.getElementById('chat-attachment');
+        if (!attachment) return;
+        if (!this.pendingAudio) {
+            attachment.style.display = 'none';
+            attachment.innerHTML = '';
+            return;
+        }
+        attachment.style.display = 'flex';
+        attachment.innerHTML = `
+            <span class="material-icons">audiotrack</span>
+            <span class="chat-attachment-name">${this.escapeHtml(this.pendingAudio.file_name)}</span>
+            <button class="chat-attachment-remove" onclick="chatApp.clearAudio()" title="Remove audio">
+                <span class="material-icons">close</span>
+            </button>
+        `;
+    }
+    
+    // OpenAI-style content parts, which llama-server hands to the audio projector
+    audioContent(message) {
+        const parts = [];
+        if (message.content) {
+            parts.push({ type: 'text', text: message.content });
+        }
+        parts.push({
+            type: 'input_audio',
+            input_audio: { data: message.audio.data, format: message.audio.format }
+        });
+        return parts;
+    }
+    
     autoResizeInput(textarea) {
         // Store original height and reset to measure content
         const originalHeight = textarea.style.height;
@@ -2065,7 +2295,7 @@ class ChatApplication {
         }
     }
 
-    saveChatData() {
+    saveChatData(chatId = this.activeChat) {
         try {
             const chatData = {};
             this.chats.forEach((data, id) => {
@@ -2075,6 +2305,34 @@ class ChatApplication {
         } catch (error) {
             console.error('Error saving chat data:', error);
         }
+        this.persistChat(chatId);
+    }
+
+    // The backend keeps chats in its searchable store and titles them after the first reply
+    async persistChat(chatId) {
+        const chatData = chatId ? this.chats.get(chatId) : null;
+        const invoke = window.__TAURI__?.core?.invoke;
+        if (!chatData || !invoke) return;
+        try {
+            await invoke('save_chat_state', {
+                chatId,
+                chatState: {
+                    model_name: chatData.name,
+                    host: chatData.host,
+                    port: chatData.port,
+                    title: chatData.title || null,
+                    messages: chatData.messages
+                        .filter(message => message.role === 'user' || message.role === 'assistant')
+                        .map(message => ({
+                            role: message.role,
+                            content: message.content || '',
+                            timestamp: new Date(message.timestamp).toISOString()
+                        }))
+                }
+            });
+        } catch (error) {
+            console.warn('Failed to save chat:', error);
+        }
     }
 
     loadSavedChats() {
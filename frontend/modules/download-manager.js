@@ -134,17 +134,11 @@ class DownloadManager {
 
     // Update download progress in real-time
     updateDownloadProgress(updatedDownload) {
-        console.log('Updating download progress for ID:', updatedDownload.id);
-        console.log('Download status:', updatedDownload.status);
-        console.log('Download progress:', updatedDownload.progress);
-        
         // Find and update the download in our local array
         const downloadIndex = this.downloads.findIndex(d => d.id === updatedDownload.id);
         if (downloadIndex !== -1) {
-            console.log('Found existing download at index:', downloadIndex);
             this.downloads[downloadIndex] = updatedDownload;
         } else {
-            console.log('Adding new download to array');
             // If not found, add it to the array
             this.downloads.push(updatedDownload);
         }
@@ -182,9 +176,21 @@ class DownloadManager {
             }
         };
         
-        // Monitor every 2000ms (increased since we now have real-time updates)
-        console.log('Starting Tauri download monitoring every 2000ms');
-        setInterval(monitorDownloads, 2000);
+        // Refreshed from the backend's app-status summary instead of a timer
+        this.refreshDownloads = monitorDownloads;
+        monitorDownloads();
+    }
+
+    // Progress arrives as download-progress events, the full list is only
+    // fetched again when downloads start, stop or change state
+    handleDownloadSummary(summary) {
+        const summaryJson = JSON.stringify({ active: summary.active, paused: summary.paused, failed: summary.failed });
+        if (summaryJson !== this.lastDownloadSummaryJson) {
+            this.lastDownloadSummaryJson = summaryJson;
+            if (this.refreshDownloads) {
+                this.refreshDownloads();
+            }
+        }
     }
     
     
@@ -202,6 +208,26 @@ class DownloadManager {
         }
     }
     
+    // One-click retry with a sibling quantization suggested by the backend
+    async downloadAlternative(downloadId, index) {
+        const download = this.downloads.find(d => d.id === downloadId);
+        const alternative = download?.alternatives?.[index];
+        if (!alternative) return;
+        try {
+            const invoke = this.getInvoke();
+            if (invoke) {
+                await invoke('download_model', {
+                    modelId: alternative.model_id,
+                    filename: alternative.files[0],
+                    files: alternative.files
+                });
+            }
+        } catch (error) {
+            console.error('Error downloading alternative quantization:', error);
+            this.desktop.showNotification(error.message || error, 'error');
+        }
+    }
+
     async resumeDownload(downloadId) {
         try {
             const invoke = this.getInvoke();
@@ -246,6 +272,7 @@ class DownloadManager {
         return this.downloads.some(download => 
             download.status === 'Downloading' || 
             download.status === 'Starting' || 
+            download.status === 'Retrying' || 
             download.status === 'Extracting'
         );
     }
@@ -332,6 +359,7 @@ class DownloadManager {
            const statusIcon = {
                'Starting': '<span class="material-icons">hourglass_top</span>',
                 'Downloading': '<span class="material-icons">download</span>',
+                'Retrying': '<span class="material-icons">autorenew</span>',
                 'Paused': '<span class="material-icons">pause</span>',
                 'Extracting': '<span class="material-icons">folder_zip</span>',
                 'Completed': '<span class="material-icons">check_circle</span>',
@@ -339,7 +367,7 @@ class DownloadManager {
                 'Cancelled': '<span class="material-icons">cancel</span>',
             }[download.status] || '<span class="material-icons">help</span>';
 
-            const isActiveDownload = download.status === 'Downloading' || download.status === 'Starting' || download.status === 'Paused' || download.status === 'Extracting';
+            const isActiveDownload = download.status === 'Downloading' || download.status === 'Starting' || download.status === 'Retrying' || download.status === 'Paused' || download.status === 'Extracting';
 
             const progressBar = (isActiveDownload && download.status !== 'Failed') ? `
                 <div class="download-progress">
@@ -350,7 +378,9 @@ class DownloadManager {
                         <span class="download-progress-text">${download.status === 'Extracting' ? (download.extraction_progress || 0) : (download.progress || 0)}%</span>
                         ${download.status === 'Downloading' && download.total_bytes > 0 ? `<span class="download-size">${this.formatFileSize(download.downloaded_bytes || 0)} / ${this.formatFileSize(download.total_bytes)}</span>` : ''}
                         ${download.status === 'Downloading' && download.speed > 0 ? `<span class="download-speed">${this.formatFileSize(download.speed)}/s</span>` : ''}
+                        ${download.status === 'Downloading' && download.eta_seconds != null ? `<span class="download-speed" title="Time left for ${download.current_file || 'this file'}">${this.formatTime(download.eta_seconds)} left</span>` : ''}
                         ${download.status === 'Paused' ? `<span class="download-paused-text">Paused</span>` : ''}
+                        ${download.status === 'Retrying' ? `<span class="download-retrying-text" title="${download.message || ''}">${download.message || 'Retrying'}</span>` : ''}
                         ${download.status === 'Extracting' ? `<span class="download-extracting-text">Extracting</span>` : ''}
                     </div>
                     ${download.status === 'Downloading' && download.total_files > 1 ? `
@@ -371,6 +401,16 @@ class DownloadManager {
 
             const errorMsg = download.status === 'Failed' ? `
                 <div class="download-error">${download.error || 'Download failed'}</div>
+                ${download.alternatives && download.alternatives.length > 0 ? `
+                    <div class="download-alternatives">
+                        <span>Try another quantization:</span>
+                        ${download.alternatives.slice(0, 4).map((alt, index) => `
+                            <button class="download-alternative" onclick="downloadManager.downloadAlternative('${download.id}', ${index})" title="${alt.files.join(', ')}">
+                                ${alt.quantization_type || alt.files[0]} (${this.formatFileSize(alt.size)})
+                            </button>
+                        `).join('')}
+                    </div>
+                ` : ''}
             ` : '';
 
             const timeDisplay = `Running for ${this.formatTime(download.elapsed_time)}`;
@@ -389,7 +429,7 @@ class DownloadManager {
             }
             
             // Clean up the name for display
-            downloadName = downloadName.replace(/\.download$/, '').replace(/\.gguf$/, '');
+            downloadName = downloadName.replace(/\.(download|part)$/, '').replace(/\.gguf$/, '');
 
             return `
                 <div class="download-item ${download.status}">
@@ -404,7 +444,7 @@ class DownloadManager {
                         <div class="download-controls">
                             ${(() => {
                                 if (isActiveDownload) {
-                                    if (download.status === 'Downloading' || download.status === 'Starting' || download.status === 'Extracting') {
+                                    if (download.status === 'Downloading' || download.status === 'Starting' || download.status === 'Retrying' || download.status === 'Extracting') {
                                         return `
                                             <button class="download-pause" onclick="downloadManager.pauseDownload('${download.id}')" title="Pause download">
                                                 <span class="material-icons">pause</span>
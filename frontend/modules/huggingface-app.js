@@ -6,6 +6,8 @@ class HuggingFaceApp {
         // Initialize Tauri API access - defer until needed
         this.invoke = null;
         this.tauriInitialized = false;
+        // Download id -> { modelId, filename } of downloads started from this window
+        this.watchedDownloads = new Map();
         // Don't call initTauriAPI here - wait until first use
         this.setupEventListeners();
     }
@@ -59,6 +61,10 @@ class HuggingFaceApp {
                 this.refreshAllDownloadStatus();
             });
             
+            window.__TAURI__.event.listen('download-progress', (event) => {
+                this.handleDownloadProgress(event.payload);
+            });
+            
             // Listen for download completion events
             window.__TAURI__.event.listen('download-complete', () => {
                 console.log('Download complete event received, refreshing download status...');
@@ -127,6 +133,30 @@ class HuggingFaceApp {
                                 <option value="updated">Recently Updated</option>
                             </select>
                         </div>
+                        <div class="sorting-controls filter-controls">
+                            <select id="hf-pipeline" class="sort-select" title="Task">
+                                <option value="">Any task</option>
+                                <option value="text-generation">Text generation</option>
+                                <option value="embedding">Embedding</option>
+                                <option value="image-text-to-text">Vision</option>
+                            </select>
+                            <select id="hf-license" class="sort-select" title="License">
+                                <option value="">Any license</option>
+                                <option value="apache-2.0">Apache 2.0</option>
+                                <option value="mit">MIT</option>
+                                <option value="llama3.1">Llama 3.1</option>
+                                <option value="gemma">Gemma</option>
+                                <option value="other">Other</option>
+                            </select>
+                            <select id="hf-size" class="sort-select" title="Parameters">
+                                <option value="">Any size</option>
+                                <option value="tiny">&lt; 3B</option>
+                                <option value="small">3B - 9B</option>
+                                <option value="medium">9B - 35B</option>
+                                <option value="large">35B - 100B</option>
+                                <option value="huge">100B+</option>
+                            </select>
+                        </div>
                     </div>
                 </div>
                 
@@ -141,6 +171,7 @@ class HuggingFaceApp {
                             <button class="suggestion-btn" onclick="huggingFaceApp.quickSearch('qwen')"># qwen</button>
                             <button class="suggestion-btn" onclick="huggingFaceApp.quickSearch('codellama')"># codellama</button>
                         </div>
+                        <div class="starter-catalog" id="hf-starter-catalog"></div>
                     </div>
                 </div>
             </div>
@@ -156,6 +187,57 @@ class HuggingFaceApp {
         this.setupHuggingFaceSearchListeners();
         
         this.desktop.addTaskbarItem('Hugging Face', this.windowId, 'HF');
+        this.loadStarterCatalog();
+    }
+    
+    async loadStarterCatalog() {
+        const invoke = this.getInvoke();
+        const container = document.getElementById('hf-starter-catalog');
+        if (!invoke || !container) return;
+        
+        try {
+            const models = await invoke('get_starter_catalog');
+            if (!models.length) return;
+            container.innerHTML = `
+                <span class="suggestion-label">Recommended for this machine:</span>
+                ${models.map(model => `
+                    <div class="quant-item starter-item">
+                        <div class="quant-info">
+                            <span class="quant-name">${model.name} <small>(${model.use_case}, ${model.quant})</small></span>
+                            <span class="quant-size">${model.description} - ${this.formatFileSize(model.size_bytes)}${model.fits ? '' : ' - may not fit in memory'}</span>
+                        </div>
+                        <button class="quant-download-btn" data-status="available" onclick="huggingFaceApp.downloadStarter('${model.id}', this)">Download</button>
+                    </div>
+                `).join('')}
+            `;
+        } catch (error) {
+            console.error('Failed to load starter catalog:', error);
+        }
+    }
+    
+    async downloadStarter(entryId, button) {
+        const invoke = this.getInvoke();
+        if (!invoke) return;
+        
+        button.disabled = true;
+        button.innerHTML = 'Downloading...';
+        button.dataset.status = 'downloading';
+        try {
+            const result = await invoke('download_starter_model', { entryId: entryId });
+            this.desktop.showNotification(`Download started: ${result.download_id}`, 'success');
+            if (typeof downloadManager !== 'undefined' && downloadManager) {
+                downloadManager.showDownloadManager();
+            }
+        } catch (error) {
+            console.error('Starter download error:', error);
+            this.desktop.showNotification('Download failed: ' + (error && error.message ? error.message : error), 'error');
+            if (error && error.code === 'gated' && error.url) {
+                this.desktop.openUrl(error.url);
+            }
+            button.disabled = false;
+            button.innerHTML = 'Download';
+            button.dataset.status = 'available';
+        }
     }
     
     setupHuggingFaceSearchListeners() {
@@ -182,6 +264,15 @@ class HuggingFaceApp {
         limitSelect.addEventListener('change', () => {
             this.performHuggingFaceSearch();
         });
+
+        // Filter changes
+        ['#hf-pipeline', '#hf-license', '#hf-size'].forEach(selector => {
+            window.querySelector(selector)?.addEventListener('change', () => {
+                if (searchInput.value.trim()) {
+                    this.performHuggingFaceSearch();
+                }
+            });
+        });
         
         // Focus search input
         setTimeout(() => searchInput.focus(), 100);
@@ -265,7 +356,12 @@ class HuggingFaceApp {
             const result = await invoke('search_huggingface', {
                 query: query,
                 limit: parseInt(limitSelect.value),
-                sortBy: sortBySelect.value
+                sortBy: sortBySelect.value,
+                filters: {
+                    pipeline_tag: window.querySelector('#hf-pipeline')?.value || null,
+                    license: window.querySelector('#hf-license')?.value || null,
+                    parameter_size: window.querySelector('#hf-size')?.value || null
+                }
             });
             
             this.displayHuggingFaceResults(result.models, query);
@@ -323,6 +419,8 @@ class HuggingFaceApp {
                                         <span class="stat-downloads" title="${this.formatNumber(model.downloads)} downloads">⬇ ${this.formatNumber(model.downloads)}</span>
                                         <span class="stat-likes" title="${this.formatNumber(model.likes)} likes">❤ ${this.formatNumber(model.likes)}</span>
                                         <span class="stat-updated" title="Last updated: ${model.lastModified || 'Unknown'}">${updatedText}</span>
+                                        ${model.parameters ? `<span class="stat-params" title="Parameters">${(model.parameters / 1e9).toFixed(1)}B</span>` : ''}
+                                        ${model.pipeline_tag ? `<span class="stat-pipeline" title="Task">${model.pipeline_tag}</span>` : ''}
                                     </div>
                                 </div>
                             `;
@@ -493,7 +591,10 @@ class HuggingFaceApp {
         const fileItems = sortedFileKeys.map(filename => {
             const fileData = model.gguf_files[filename];
             const size = this.formatFileSize(fileData.size);
-            const sizeText = size !== 'Unknown size' ? ` (${size})` : '';
+            const partsText = fileData.parts && fileData.parts.length > 1 ? `, ${fileData.parts.length} parts` : '';
+            const sizeText = size !== 'Unknown size' ? ` (${size}${partsText})` : '';
+            const projectorLabel = this.projectorLabel(fileData);
+            const projectorBadge = fileData.is_mmproj ? `<span class="quant-badge" title="${projectorLabel}, passed to the model with --mmproj">${projectorLabel}</span>` : '';
             
             // Remove .gguf extension and find common parts within the file's group
             const displayName = filename.replace(/\.gguf$/i, '');
@@ -505,7 +606,11 @@ class HuggingFaceApp {
                     <div class="quant-info">
                         <span class="quant-name">${styledName}</span>
                         <span class="quant-size">${sizeText}</span>
+                        ${projectorBadge}
                     </div>
+                    ${fileData.is_mmproj ? '' : `<button class="quant-try-btn" onclick="huggingFaceApp.tryFile('${model.id}', '${filename}')" title="Run this file from a temporary cache without adding it to your library">
+                        Try
+                    </button>`}
                     <button class="quant-download-btn" onclick="huggingFaceApp.downloadFile('${model.id}', '${filename}', ${index})" data-status="unknown">
                         Download
                     </button>
@@ -516,7 +621,7 @@ class HuggingFaceApp {
         return `
             <div class="model-detail-header">
                 <div class="model-header-top">
-                    <h3 class="model-detail-name">${model.name}</h3>
+                    <h3 class="model-detail-name">${model.name}${model.gated ? ' <span class="material-icons" title="Gated: accept the terms on Hugging Face before downloading" style="font-size: 16px; vertical-align: middle;">lock</span>' : ''}</h3>
                     <button class="model-page-btn" onclick="desktop.openUrl('https://huggingface.co/${model.id}')" title="Open model page on Hugging Face">
                         View on HF
                     </button>
@@ -558,10 +663,47 @@ class HuggingFaceApp {
                 <div class="quantizations-list">
                     ${fileItems}
                 </div>
+                <div class="pattern-download">
+                    <input type="text" class="pattern-include" placeholder="Include, e.g. *Q4_K_M*" title="Comma-separated glob patterns matched against paths in the repo">
+                    <input type="text" class="pattern-exclude" placeholder="Exclude, e.g. *.safetensors" title="Comma-separated glob patterns, matching files are skipped">
+                    <button class="quant-download-btn" onclick="huggingFaceApp.downloadByPattern('${model.id}', this)">Download Matching</button>
+                </div>
             </div>
         `;
     }
     
+    // Download every repo file matching the include globs and none of the exclude ones
+    async downloadByPattern(modelId, button) {
+        const container = button.closest('.pattern-download');
+        const split = value => value.split(',').map(p => p.trim()).filter(p => p);
+        const includePatterns = split(container.querySelector('.pattern-include').value);
+        const excludePatterns = split(container.querySelector('.pattern-exclude').value);
+        if (includePatterns.length === 0 && excludePatterns.length === 0) {
+            this.desktop.showNotification('Enter at least one pattern', 'info');
+            return;
+        }
+        
+        const invoke = this.getInvoke();
+        button.disabled = true;
+        try {
+            const result = await invoke('download_model', {
+                modelId: modelId,
+                filename: '',
+                files: [],
+                includePatterns: includePatterns,
+                excludePatterns: excludePatterns
+            });
+            this.desktop.showNotification(`Download started: ${result.download_id}`, 'success');
+            if (typeof downloadManager !== 'undefined' && downloadManager) {
+                downloadManager.showDownloadManager();
+            }
+        } catch (error) {
+            this.desktop.showNotification('Download failed: ' + (error && error.message ? error.message : error), 'error');
+        } finally {
+            button.disabled = false;
+        }
+    }
+    
     async downloadFile(modelId, filename, index) {
         const window = this.desktop.windows.get(this.windowId);
         if (!window) return;
@@ -614,7 +756,25 @@ class HuggingFaceApp {
         }
         
         const fileData = modelData.gguf_files[filename];
-        const files = [filename]; // Just the single file
+        // Split models come as one entry listing every shard
+        const files = fileData.parts && fileData.parts.length > 0 ? fileData.parts : [filename];
+        
+        // Multimodal models only accept images or audio with their projector, offer it with the quant
+        let includeMmproj = false;
+        const projector = Object.values(modelData.gguf_files).find(file => file.is_mmproj);
+        if (projector && !fileData.is_mmproj) {
+            const modalities = projector.modalities || [];
+            const inputs = modalities.includes('audio')
+                ? (modalities.includes('vision') ? 'images and audio' : 'audio')
+                : 'images';
+            includeMmproj = await ModalDialog.showConfirmation({
+                title: modalities.includes('audio') ? 'Multimodal Model' : 'Vision Model',
+                message: `This model has a ${this.projectorLabel(projector).toLowerCase()} (mmproj) that lets it take ${inputs}. Download it too?`,
+                confirmText: 'Download Both',
+                cancelText: 'Model Only',
+                type: 'info'
+            });
+        }
         
         if (downloadBtn) {
             // Disable the button and show downloading state
@@ -623,10 +783,25 @@ class HuggingFaceApp {
             downloadBtn.dataset.status = 'downloading';
         }
         
-        invoke('download_model', {
+        const startDownload = (force) => invoke('download_model', {
             modelId: modelId,
             filename: filename,
-            files: files
+            files: files,
+            force: force,
+            includeMmproj: includeMmproj
+        });
+        startDownload(false).catch(async error => {
+            // The backend refuses downloads that won't fit, let the user override it
+            if (!error || error.code !== 'insufficient_space') throw error;
+            const confirmed = await ModalDialog.showConfirmation({
+                title: 'Not Enough Disk Space',
+                message: `${error.message}\n\nDownload anyway?`,
+                confirmText: 'Download Anyway',
+                cancelText: 'Cancel',
+                type: 'danger'
+            });
+            if (!confirmed) throw { code: 'cancelled', message: 'Download cancelled' };
+            return startDownload(true);
         }).then(result => {
             console.log('Download command successful:', result);
             this.desktop.showNotification(`Download started: ${result.download_id}`, 'success');
@@ -645,7 +820,17 @@ class HuggingFaceApp {
             this.monitorDownload(result.download_id, modelId, filename);
         }).catch(error => {
             console.error('Download error:', error);
-            this.desktop.showNotification('Download failed: ' + error, 'error');
+            if (error && error.code === 'gated') {
+                // Terms have to be accepted on the Hub, open the repo page for that
+                this.desktop.showNotification(error.message, 'error');
+                if (error.url) {
+                    this.desktop.openUrl(error.url);
+                }
+            } else if (error && error.code === 'cancelled') {
+                this.desktop.showNotification(error.message, 'info');
+            } else {
+                this.desktop.showNotification('Download failed: ' + (error && error.message ? error.message : error), 'error');
+            }
             
             // Reset button on error
             if (downloadBtn) {
@@ -657,6 +842,39 @@ class HuggingFaceApp {
         });
     }
 
+    async tryFile(modelId, filename) {
+        const invoke = this.getInvoke();
+        if (!invoke) {
+            this.desktop.showNotification('Error: Tauri API not available', 'error');
+            return;
+        }
+        
+        this.desktop.showNotification(`Starting a trial of ${filename}...`, 'info');
+        try {
+            // llama.cpp fetches the remaining shards of a split model on its own, it only needs the first one's path
+            const modelData = this.desktop.windows.get(this.windowId)?.querySelector('#model-details-content')?.modelData;
+            const parts = modelData?.gguf_files[filename]?.parts;
+            const result = await invoke('try_model_from_hub', { modelId: modelId, filename: parts && parts.length > 0 ? parts[0] : filename });
+            this.desktop.showNotification(result.message, 'success');
+            
+            // The server log shows llama.cpp's download progress before the model loads
+            if (await this.desktop.ensureTerminalManager() && terminalManager) {
+                const config = await invoke('get_config');
+                await terminalManager.openServerTerminal(
+                    result.process_id,
+                    `${result.model_name} (trial)`,
+                    result.server_host,
+                    result.server_port,
+                    `hf://${modelId}/${filename}`,
+                    config.active_executable_version || 'N/A'
+                );
+            }
+        } catch (error) {
+            console.error('Trial error:', error);
+            this.desktop.showNotification('Trial failed: ' + error, 'error');
+        }
+    }
+
     groupFilesBySimilarity(filenames) {
         if (filenames.length <= 1) {
             return [filenames];
@@ -820,83 +1038,87 @@ class HuggingFaceApp {
         }
     }
     
-    // Monitor a specific download and update button status
+    projectorLabel(fileData) {
+        const modalities = fileData.modalities || [];
+        if (modalities.includes('audio') && modalities.includes('vision')) return 'Vision + audio projector';
+        if (modalities.includes('audio')) return 'Audio projector';
+        return 'Vision projector';
+    }
+    
+    // Follow a download through its download-progress events and update its button
     async monitorDownload(downloadId, modelId, filename) {
+        this.watchedDownloads.set(downloadId, { modelId, filename });
+        
+        // Events sent before we started watching are gone, catch up once
         const invoke = this.getInvoke();
         if (!invoke) return;
+        try {
+            const status = await invoke('get_download_status', { downloadId });
+            this.handleDownloadProgress(status);
+        } catch (error) {
+            console.error('Error checking download status:', error);
+            this.watchedDownloads.delete(downloadId);
+            this.updateDownloadButton(modelId, filename, { status: 'Cancelled' });
+        }
+    }
+    
+    handleDownloadProgress(status) {
+        const watched = status && this.watchedDownloads.get(status.id);
+        if (!watched) return;
+        
+        if (['Completed', 'Failed', 'Cancelled'].includes(status.status)) {
+            this.watchedDownloads.delete(status.id);
+            if (status.status === 'Failed') {
+                this.desktop.showNotification(`Download failed: ${watched.filename}`, 'error');
+            }
+        }
+        this.updateDownloadButton(watched.modelId, watched.filename, status);
+    }
+    
+    updateDownloadButton(modelId, filename, status) {
+        const window = this.desktop.windows.get(this.windowId);
+        if (!window) return;
+        
+        const fileItem = window.querySelector(`[data-filename="${filename}"][data-model-id="${modelId}"]`);
+        const downloadBtn = fileItem?.querySelector('.quant-download-btn');
+        if (!downloadBtn) return;
         
-        const checkDownloadStatus = async () => {
-            try {
-                const status = await invoke('get_download_status', { downloadId });
+        switch (status.status) {
+            case 'Retrying':
+                downloadBtn.innerHTML = 'Retrying...';
+                downloadBtn.dataset.status = 'downloading';
+                downloadBtn.disabled = true;
+                break;
                 
-                const window = this.desktop.windows.get(this.windowId);
-                if (!window) return;
+            case 'Downloading':
+            case 'Starting':
+            case 'Extracting':
+                downloadBtn.innerHTML = `Downloading... ${status.progress}%`;
+                downloadBtn.dataset.status = 'downloading';
+                downloadBtn.disabled = true;
+                break;
                 
-                const fileItem = window.querySelector(`[data-filename="${filename}"][data-model-id="${modelId}"]`);
-                const downloadBtn = fileItem?.querySelector('.quant-download-btn');
+            case 'Completed':
+                downloadBtn.innerHTML = 'Downloaded';
+                downloadBtn.dataset.status = 'downloaded';
+                downloadBtn.disabled = true;
+                downloadBtn.classList.add('downloaded');
+                break;
                 
-                if (!downloadBtn) return;
+            case 'Failed':
+            case 'Cancelled':
+                downloadBtn.innerHTML = 'Download';
+                downloadBtn.dataset.status = 'available';
+                downloadBtn.disabled = false;
+                downloadBtn.classList.remove('downloaded');
+                break;
                 
-                switch (status.status) {
-                    case 'Downloading':
-                    case 'Starting':
-                        downloadBtn.innerHTML = `Downloading... ${status.progress}%`;
-                        downloadBtn.dataset.status = 'downloading';
-                        downloadBtn.disabled = true;
-                        // Continue monitoring
-                        setTimeout(checkDownloadStatus, 1000);
-                        break;
-                        
-                    case 'Completed':
-                        downloadBtn.innerHTML = 'Downloaded';
-                        downloadBtn.dataset.status = 'downloaded';
-                        downloadBtn.disabled = true;
-                        downloadBtn.classList.add('downloaded');
-                        break;
-                        
-                    case 'Failed':
-                    case 'Cancelled':
-                        downloadBtn.innerHTML = 'Download';
-                        downloadBtn.dataset.status = 'available';
-                        downloadBtn.disabled = false;
-                        downloadBtn.classList.remove('downloaded');
-                        if (status.status === 'Failed') {
-                            this.desktop.showNotification(`Download failed: ${filename}`, 'error');
-                        }
-                        break;
-                        
-                    case 'Paused':
-                        downloadBtn.innerHTML = 'Paused';
-                        downloadBtn.dataset.status = 'paused';
-                        downloadBtn.disabled = true;
-                        // Continue monitoring
-                        setTimeout(checkDownloadStatus, 2000);
-                        break;
-                        
-                    default:
-                        // Continue monitoring for unknown states
-                        setTimeout(checkDownloadStatus, 1000);
-                        break;
-                }
-            } catch (error) {
-                console.error('Error checking download status:', error);
-                // Stop monitoring on error and reset button
-                const window = this.desktop.windows.get(this.windowId);
-                if (window) {
-                    const fileItem = window.querySelector(`[data-filename="${filename}"][data-model-id="${modelId}"]`);
-                    const downloadBtn = fileItem?.querySelector('.quant-download-btn');
-                    if (downloadBtn) {
-                        downloadBtn.innerHTML = 'Download';
-                        downloadBtn.dataset.status = 'available';
-                        downloadBtn.disabled = false;
-                        downloadBtn.classList.remove('downloaded');
-                    }
-                }
-            }
-        };
-        
-        // Start monitoring
-        setTimeout(checkDownloadStatus, 1000);
+            case 'Paused':
+                downloadBtn.innerHTML = 'Paused';
+                downloadBtn.dataset.status = 'paused';
+                downloadBtn.disabled = true;
+                break;
+        }
     }
     
     quickSearch(query) {
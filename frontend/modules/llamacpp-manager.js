@@ -8,8 +8,15 @@ class LlamaCppReleasesManager {
         this.initTauriAPI();
 
         // UI state
-        this.hideOtherPlatforms = true; // default ON: emphasize Windows assets
+        this.hideOtherPlatforms = true; // default ON: emphasize assets for this machine
         this.lastReleases = null; // cache latest fetched releases for re-rendering
+        this.platform = { os: 'win', arch: 'x64' }; // replaced by the backend's answer on first load
+    }
+
+    // Label for the platform filter, e.g. "macOS arm64 only"
+    platformLabel() {
+        const names = { win: 'Windows', macos: 'macOS', ubuntu: 'Linux' };
+        return `${names[this.platform.os] || this.platform.os} ${this.platform.arch} only`;
     }
     
     initTauriAPI() {
@@ -133,6 +140,7 @@ class LlamaCppReleasesManager {
                                 <span class="material-icons">check_circle</span> Set Active
                             </button>
                         `}
+                        ${isReady ? `<button class="installed-test" onclick="llamacppReleasesManager.testVersion('${escapedPath}', this)" title="Check that the GPU backend initializes and run a tiny inference"><span class="material-icons">fact_check</span></button>` : ''}
                         <button class="installed-delete" onclick="llamacppReleasesManager.deleteVersion('${escapedPath}')"><span class="material-icons">delete</span></button>
                     </div>
                 </div>
@@ -143,17 +151,85 @@ class LlamaCppReleasesManager {
         `;
     }
 
-    async setActiveVersion(path) {
+    async setActiveVersion(path, force = false) {
         try {
             const invoke = this.getInvoke();
             if (!invoke) throw new Error('Tauri API not available');
-            await invoke('set_active_llamacpp_version', { path });
+            const result = await invoke('set_active_llamacpp_version', { path, force });
+            // A slower build found by the version benchmark stays inactive until confirmed
+            if (!result.activated) {
+                const confirmed = await ModalDialog.showConfirmation({
+                    title: 'Performance Regression',
+                    message: `${result.regression_check?.message || 'The new build benchmarked slower than the active one.'}\n\nSwitch to this version anyway?`,
+                    confirmText: 'Switch Anyway',
+                    cancelText: 'Keep Current'
+                });
+                if (confirmed) await this.setActiveVersion(path, true);
+                return;
+            }
             this.loadInstalledVersions();
+            if (result.cuda_runtime) this.offerCudaRuntime(path, result.cuda_runtime);
         } catch (e) {
             alert(`Failed to set active version: ${e.message || e}`);
         }
     }
 
+    // CUDA builds need the cudart DLLs, which only come with the toolkit or the release's separate archive
+    async offerCudaRuntime(path, status) {
+        const invoke = this.getInvoke();
+        if (!invoke) return;
+        try {
+            const missing = status.missing.join(', ');
+            if (!status.asset) {
+                await ModalDialog.showInfo({
+                    title: 'CUDA Runtime Missing',
+                    message: `This build needs ${missing}, which weren't found in its folder or on PATH.\n\nInstall the CUDA ${status.cuda_version || ''} toolkit or copy the DLLs next to llama-server.`
+                });
+                return;
+            }
+            const confirmed = await ModalDialog.showConfirmation({
+                title: 'CUDA Runtime Missing',
+                message: `This build needs ${missing}, which weren't found in its folder or on PATH.\n\nDownload ${status.asset.name} (${this.formatFileSize(status.asset.size)}) into this version?`,
+                confirmText: 'Download',
+                cancelText: 'Not now'
+            });
+            if (confirmed) {
+                await invoke('install_cuda_runtime', { path });
+            }
+        } catch (e) {
+            console.error('Failed to check the CUDA runtime:', e);
+        }
+    }
+
+    async testVersion(path, button) {
+        if (button) button.disabled = true;
+        try {
+            const invoke = this.getInvoke();
+            if (!invoke) throw new Error('Tauri API not available');
+            const report = await invoke('test_llamacpp_version', { path });
+            const lines = [
+                `Version: ${report.version || 'unknown'}`,
+                `Backend: ${report.expected_backend || 'CPU'}${report.backend_ok ? '' : ' (not initialized)'}`,
+                `Devices: ${report.devices.length ? report.devices.join(', ') : 'none reported'}`
+            ];
+            if (report.smoke_test_passed) {
+                lines.push(`Smoke inference: passed${report.generation_tps ? ` (${report.generation_tps.toFixed(1)} t/s)` : ''}`);
+            }
+            if (report.problems.length) {
+                lines.push('', ...report.problems);
+            }
+            const passed = report.backend_ok && report.smoke_test_passed !== false;
+            await ModalDialog.showInfo({
+                title: passed ? 'Build OK' : 'Build Has Problems',
+                message: lines.join('\n')
+            });
+        } catch (e) {
+            alert(`Failed to test version: ${e.message || e}`);
+        } finally {
+            if (button) button.disabled = false;
+        }
+    }
+
     async deleteVersion(path) {
         let confirmed = false;
         try {
@@ -370,9 +446,63 @@ class LlamaCppReleasesManager {
         const targetPane = releaseItem.querySelector(`[data-pane="${tabName}"]`);
         if (targetPane) {
             targetPane.classList.add('active');
+            if (tabName === 'changes' && !targetPane.dataset.loaded) {
+                this.loadReleaseChanges(targetPane, releaseItem.querySelector('.release-tag')?.textContent);
+            }
+        }
+    }
+
+    // Commits between the active build and this release, fetched the first time the tab opens
+    async loadReleaseChanges(pane, toTag) {
+        if (!toTag) return;
+        pane.innerHTML = '<div class="loading-commit-info">Comparing with the active build...</div>';
+        try {
+            const invoke = this.getInvoke();
+            if (!invoke) {
+                throw new Error('Tauri API not available');
+            }
+            const result = await invoke('get_release_changes', { fromTag: null, toTag: toTag });
+            pane.dataset.loaded = 'true';
+            pane.innerHTML = this.formatReleaseChanges(result);
+        } catch (error) {
+            console.error('Error loading release changes:', error);
+            pane.innerHTML = `<div class="error-commit-info">${this.escapeHtml(String(error))}</div>`;
         }
     }
 
+    formatReleaseChanges(result) {
+        if (!result.changes.length) {
+            return `<div class="release-changes-summary">No changes between ${this.escapeHtml(result.from_tag)} and ${this.escapeHtml(result.to_tag)}.</div>`;
+        }
+        const breakingCount = result.changes.filter(change => change.breaking).length;
+        const direction = result.downgrade
+            ? `Going back to ${this.escapeHtml(result.to_tag)} drops ${result.total_commits} commits from ${this.escapeHtml(result.from_tag)}`
+            : `${result.total_commits} commits since ${this.escapeHtml(result.from_tag)}`;
+        const breakingNote = breakingCount ? ` &middot; <span class="release-change-breaking">${breakingCount} may need attention</span>` : '';
+        const truncatedNote = result.truncated
+            ? ` &middot; showing the first ${result.changes.length}, <a href="#" onclick="event.preventDefault(); desktop.openUrl('${result.compare_url}')">see all on GitHub</a>`
+            : '';
+
+        // Newest first reads like a changelog
+        const items = [...result.changes].reverse().map(change => `
+            <div class="release-change${change.breaking ? ' breaking' : ''}">
+                ${change.area ? `<span class="release-change-area">${this.escapeHtml(change.area)}</span>` : ''}
+                <a href="#" class="release-change-title" onclick="event.preventDefault(); desktop.openUrl('${change.html_url}')" title="${this.escapeHtml(change.author)}, ${new Date(change.date).toLocaleDateString()}">${this.escapeHtml(change.area ? change.title.slice(change.title.indexOf(':') + 1).trim() : change.title)}</a>
+            </div>
+        `).join('');
+
+        return `
+            <div class="release-changes-summary">${direction}${breakingNote}${truncatedNote}</div>
+            <div class="release-changes">${items}</div>
+        `;
+    }
+
+    escapeHtml(text) {
+        const div = document.createElement('div');
+        div.textContent = text ?? '';
+        return div.innerHTML;
+    }
+
     // Show llama.cpp release manager
     showLlamaCppManager() {
         const windowId = 'llamacpp-manager';
@@ -399,7 +529,7 @@ class LlamaCppReleasesManager {
                             <span class="material-icons">refresh</span> Refresh Releases
                         </button>
                         <button class="llamacpp-refresh platform-toggle" id="llamacpp-platform-toggle-ctrl" style="display: none;" onclick="llamacppReleasesManager.togglePlatformFilter()" title="Toggle platform visibility">
-                            <span class="material-icons">layers</span> Windows only
+                            <span class="material-icons">layers</span> ${this.platformLabel()}
                         </button>
                     </div>
                 </div>
@@ -439,10 +569,12 @@ class LlamaCppReleasesManager {
             content.innerHTML = '<div class="loading-releases">Loading releases...</div>';
             
             // Fetch both releases and installed versions in parallel
-            const [releases, installedVersions] = await Promise.all([
+            const [releases, installedVersions, platform] = await Promise.all([
                 this.getLlamaCppReleases(),
-                this.getInvoke()('list_llamacpp_versions')
+                this.getInvoke()('list_llamacpp_versions'),
+                this.getInvoke()('get_llamacpp_platform').catch(() => null)
             ]);
+            if (platform) this.platform = platform;
 
             this.lastReleases = releases;
             this.renderLlamaCppReleases(releases, installedVersions);
@@ -468,18 +600,15 @@ class LlamaCppReleasesManager {
 
         const installedTags = new Set(installedVersions.map(v => v.name.toLowerCase()));
 
-        const isWindowsAsset = (name) => {
+        const isWindowsAsset = (name) => /-win-|windows|win64|win32/i.test(name) && !/mac|darwin|osx|linux|ubuntu/i.test(name);
+        // xcframework archives are for embedding in apps, they carry no llama-server
+        const isMacAsset = (name) => /mac|darwin|osx|macos/i.test(name) && !/xcframework/i.test(name);
+        const isLinuxAsset = (name) => /linux|ubuntu|debian|fedora/i.test(name);
+        const isArm = (name) => /arm64|aarch64/i.test(name);
+        const isPlatformAsset = (name) => {
             const n = String(name);
-            const isWin = /(win|windows|win64|win32|x64|amd64)/i.test(n);
-            const isOther = /(mac|darwin|osx|linux|ubuntu|debian|arch|fedora|arm64|aarch64|raspi|rpi)/i.test(n);
-            return isWin && !isOther;
-        };
-        const isMacAsset = (name) => /mac|darwin|osx|apple|macos/i.test(name);
-        const isLinuxAsset = (name) => /linux|ubuntu|debian|arch|fedora/i.test(name);
-        const shouldShowAsset = (name) => {
-            if (!this.hideOtherPlatforms) return true;
-            // Default to Windows filter for this app
-            return isWindowsAsset(name);
+            const osMatches = { win: isWindowsAsset, macos: isMacAsset, ubuntu: isLinuxAsset }[this.platform.os] || isWindowsAsset;
+            return osMatches(n) && isArm(n) === (this.platform.arch === 'arm64');
         };
 
         const releasesHTML = releases.map(release => {
@@ -489,11 +618,11 @@ class LlamaCppReleasesManager {
             const installedBadge = isInstalled ? '<span class="badge installed">Installed</span>' : '';
 
             // Preserve expansion state by not altering release-item class outside
-            // Sort assets: preferred platform (Windows) first when filter is on; keep stable otherwise
+            // Sort assets: this machine's platform first when filter is on; keep stable otherwise
             const assetsSorted = [...release.assets].sort((a, b) => {
-                const aWin = isWindowsAsset(a.name || '');
-                const bWin = isWindowsAsset(b.name || '');
-                if (this.hideOtherPlatforms && aWin !== bWin) return aWin ? -1 : 1;
+                const aOwn = isPlatformAsset(a.name || '');
+                const bOwn = isPlatformAsset(b.name || '');
+                if (this.hideOtherPlatforms && aOwn !== bOwn) return aOwn ? -1 : 1;
                 return String(a.name || '').localeCompare(String(b.name || ''));
             });
 
@@ -502,8 +631,7 @@ class LlamaCppReleasesManager {
                     const name = asset.name || '';
                     const warnCuda = /cudart/i.test(name);
                     const warningHTML = warnCuda ? '<span class="asset-note" style="margin-left: 8px; color: rgba(255,255,255,0.6);">Required for CUDA</span>' : '';
-                    const isWin = isWindowsAsset(name);
-                    const grayClass = this.hideOtherPlatforms && !isWin ? ' dim-asset' : '';
+                    const grayClass = this.hideOtherPlatforms && !isPlatformAsset(name) ? ' dim-asset' : '';
                     return `
                         <div class="release-asset${grayClass}">
                             <div class="asset-info">
@@ -544,6 +672,9 @@ class LlamaCppReleasesManager {
                             <button class="release-tab" onclick="llamacppReleasesManager.switchReleaseTab(this, 'downloads')" data-tab="downloads">
                                 <span class="material-icons">download</span> Downloads (${release.assets.length})
                             </button>
+                            <button class="release-tab" onclick="llamacppReleasesManager.switchReleaseTab(this, 'changes')" data-tab="changes">
+                                <span class="material-icons">difference</span> Changes
+                            </button>
                         </div>
                         <div class="release-tab-content">
                             <div class="release-tab-pane active" data-pane="notes">
@@ -552,6 +683,7 @@ class LlamaCppReleasesManager {
                             <div class="release-tab-pane" data-pane="downloads">
                                 ${assetsHTML}
                             </div>
+                            <div class="release-tab-pane" data-pane="changes"></div>
                         </div>
                     </div>
                 </div>
@@ -572,7 +704,7 @@ class LlamaCppReleasesManager {
             <div class="releases-header">
                 <p>Found ${releases.length} llama.cpp releases</p>
                 <button class="platform-badge" id="llamacpp-platform-badge" onclick="llamacppReleasesManager.togglePlatformFilter()" title="Toggle platform visibility">
-                    ${this.hideOtherPlatforms ? 'Windows only' : 'All platforms'}
+                    ${this.hideOtherPlatforms ? this.platformLabel() : 'All platforms'}
                 </button>
             </div>
             ${releasesHTML}
@@ -600,11 +732,11 @@ class LlamaCppReleasesManager {
         this.hideOtherPlatforms = !this.hideOtherPlatforms;
         const btn = document.getElementById('llamacpp-platform-toggle-ctrl');
         if (btn) {
-            btn.innerHTML = `<span class="material-icons">layers</span> ${this.hideOtherPlatforms ? 'Windows only' : 'All platforms'}`;
+            btn.innerHTML = `<span class="material-icons">layers</span> ${this.hideOtherPlatforms ? this.platformLabel() : 'All platforms'}`;
         }
         const badge = document.getElementById('llamacpp-platform-badge');
         if (badge) {
-            badge.textContent = this.hideOtherPlatforms ? 'Windows only' : 'All platforms';
+            badge.textContent = this.hideOtherPlatforms ? this.platformLabel() : 'All platforms';
         }
         if (this.lastReleases) {
             // Re-render with cached releases and newly fetched installed versions
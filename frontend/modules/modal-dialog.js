@@ -217,12 +217,10 @@ class ModalDialog {
             buttons.forEach((btn, index) => {
                 const buttonElement = modal.querySelector(`[data-button-index="${index}"]`);
                 buttonElement.addEventListener('click', () => {
+                    // Actions may read inputs in the dialog, so they run before it is removed
+                    const result = btn.action ? btn.action() : index;
                     overlay.remove();
-                    if (btn.action) {
-                        resolve(btn.action());
-                    } else {
-                        resolve(index);
-                    }
+                    resolve(result);
                 });
             });
             
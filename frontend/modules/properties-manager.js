@@ -59,6 +59,8 @@ class PropertiesManager {
                 console.log('Loaded config for', modelPath, ':', config);
                 const content = await this.generatePropertiesContent(config, modelPath);
                 const window = this.desktop.createWindow(windowId, `Properties - ${modelName}`, 'properties-window', content);
+                this.loadLoraAdapters(window, config);
+                this.loadMemoryEstimate(window, modelPath);
                 // Add to taskbar
                 this.desktop.addTaskbarItem(`Properties - ${modelName}`, windowId, '<span class="material-icons">settings</span>');
                 this.setupPropertiesSync(window);
@@ -69,14 +71,166 @@ class PropertiesManager {
             });
     }
 
+    // Restart of a server that exits without being stopped, the wait doubles per attempt
+    restartPolicyHTML(policy = {}) {
+        const mode = policy.mode || 'never';
+        const option = (value, label) => `<option value="${value}" ${mode === value ? 'selected' : ''}>${label}</option>`;
+        return `
+                                <label class="property-checkbox restart-policy" title="Start the server again when it exits without being stopped from Llama-OS">
+                                    Restart
+                                    <select class="property-select" data-field="restart_mode">
+                                        ${option('never', 'Never')}
+                                        ${option('on_crash', 'On crash')}
+                                        ${option('always', 'Always')}
+                                    </select>
+                                    up to <input type="number" class="property-input" data-field="restart_max_retries" min="1" value="${policy.max_retries ?? 3}"> times,
+                                    after <input type="number" class="property-input" data-field="restart_backoff_secs" min="0" value="${policy.backoff_secs ?? 5}"> s
+                                </label>`;
+    }
+
+    // Models from extra model directories are listed in place and can't be deleted
+    deleteButtonHTML(modelPath) {
+        const icon = [...document.querySelectorAll('.desktop-icon')].find(el => el.dataset.path === modelPath);
+        if (icon?.dataset.readOnly === 'true') return '';
+        return `<button class="delete-file-btn-floating" onclick="propertiesManager.deleteModelFile('${btoa(modelPath)}')" title="Delete this model file">
+                        <span class="material-icons">delete</span>
+                    </button>`;
+    }
+
+    // Adapters found in the model folders, attached ones first in their launch order
+    async loadLoraAdapters(window, config) {
+        const list = window.querySelector('.lora-adapter-list');
+        if (!list) return;
+
+        let adapters = [];
+        try {
+            adapters = await this.getInvoke()('scan_lora_adapters');
+        } catch (error) {
+            console.error('Error scanning LoRA adapters:', error);
+        }
+        const attached = config.lora_paths || [];
+        const scales = config.lora_scales || {};
+        const byPath = new Map(adapters.map(adapter => [adapter.path, adapter]));
+        const rows = [
+            // An attached adapter that is no longer found stays listed so it can be detached
+            ...attached.map(path => byPath.get(path) || { path, name: path.split(/[\\/]/).pop(), missing: true }),
+            ...adapters.filter(adapter => !attached.includes(adapter.path))
+        ];
+
+        list.innerHTML = '';
+        if (rows.length === 0) {
+            list.innerHTML = '<div class="no-settings">No LoRA adapters found. Put adapter .gguf files in a loras folder inside your models directory.</div>';
+            return;
+        }
+        const model = this.desktop.modelsByPath?.get(config.model_path);
+        rows.forEach(adapter => {
+            const row = document.createElement('label');
+            row.className = 'property-checkbox lora-adapter';
+            row.dataset.loraPath = adapter.path;
+
+            const toggle = document.createElement('input');
+            toggle.type = 'checkbox';
+            toggle.checked = attached.includes(adapter.path);
+
+            const name = document.createElement('span');
+            name.className = 'lora-adapter-name';
+            const details = [adapter.base_model, adapter.architecture].filter(Boolean).join(', ');
+            name.textContent = adapter.missing ? `${adapter.name} (missing)` : adapter.name;
+            name.title = [adapter.path, details].filter(Boolean).join('\n');
+            if (model && adapter.architecture && adapter.architecture !== model.architecture) {
+                name.textContent += ` (${adapter.architecture})`;
+                row.classList.add('lora-adapter-mismatch');
+            }
+
+            const scale = document.createElement('input');
+            scale.type = 'number';
+            scale.className = 'property-input lora-adapter-scale';
+            scale.step = '0.05';
+            scale.value = scales[adapter.path] ?? 1;
+            scale.title = 'Scale, 1 applies the adapter as trained';
+
+            row.append(toggle, name, scale);
+            list.appendChild(row);
+        });
+    }
+
+    // Expected VRAM/RAM at the saved settings, so an oversized model shows before launching
+    async loadMemoryEstimate(window, modelPath) {
+        const target = window.querySelector('.model-memory-estimate');
+        if (!target) return;
+        const gib = (bytes) => (bytes / 1024 ** 3).toFixed(1) + ' GB';
+        try {
+            const estimate = await this.getInvoke()('estimate_model_memory', { modelPath });
+            const parts = [
+                `<span><strong>VRAM:</strong> ${gib(estimate.vram_bytes)}${estimate.gpu_free_bytes != null ? ` of ${gib(estimate.gpu_free_bytes)} free` : ''}</span>`,
+                `<span><strong>RAM:</strong> ${gib(estimate.ram_bytes)}</span>`,
+                `<span><strong>KV cache:</strong> ${gib(estimate.kv_cache_bytes)} at ${estimate.context_size.toLocaleString()} tokens</span>`,
+            ];
+            if (estimate.layers_that_fit != null) {
+                parts.push(`<span><strong>GPU layers that fit:</strong> ${estimate.layers_that_fit} / ${estimate.block_count}</span>`);
+            }
+            target.innerHTML = `<div class="model-info-facts">${parts.join('')}</div>`;
+            if (estimate.fits_in_vram === false) {
+                target.insertAdjacentHTML('beforeend', `<div class="model-memory-warning">Offloading ${estimate.gpu_layers} layers will likely run out of VRAM, lower GPU layers or context size.</div>`);
+            }
+        } catch (error) {
+            target.textContent = `Memory estimate unavailable: ${error}`;
+        }
+    }
+
+    // GGUF details from the last scan, shown above the launch settings
+    modelInfoHTML(modelPath) {
+        const model = this.desktop.modelsByPath?.get(modelPath);
+        if (!model) return '';
+        const escape = (text) => this.desktop.escapeHtml(text);
+        const params = model.parameter_count
+            ? (model.parameter_count >= 1e9 ? (model.parameter_count / 1e9).toFixed(1) + 'B' : (model.parameter_count / 1e6).toFixed(0) + 'M')
+            : null;
+        const facts = [
+            ['Architecture', model.architecture],
+            ['Parameters', params],
+            ['Context', model.context_length?.toLocaleString()],
+            ['Embedding', model.embedding_length?.toLocaleString()],
+            ['Layers', model.block_count],
+        ].filter(([, value]) => value);
+        return `
+                        <div class="property-group model-info">
+                            <h4>Model</h4>
+                            <div class="model-info-facts">
+                                ${facts.map(([label, value]) => `<span><strong>${label}:</strong> ${escape(String(value))}</span>`).join('')}
+                            </div>
+                            <div class="model-memory-estimate">Estimating memory...</div>
+                            ${model.chat_template ? `<details class="model-info-template"><summary>Chat template</summary><pre>${escape(model.chat_template)}</pre></details>` : ''}
+                        </div>`;
+    }
+
+    launchSettingsToArgs(settings) {
+        if (!settings) return '';
+        return [
+            ['-c', settings.context_size],
+            ['-ngl', settings.gpu_layers],
+            ['-t', settings.threads],
+            ['-fa', settings.flash_attention],
+            ['-b', settings.batch_size],
+            ['-ub', settings.ubatch_size],
+            ['--temp', settings.temperature]
+        ].filter(([, value]) => value !== null && value !== undefined)
+            .map(([flag, value]) => `${flag} ${value}`)
+            .join(' ');
+    }
+
     async generatePropertiesContent(config, modelPath) {
         try {
             // Load settings configuration
             const settingsConfig = await this.desktop.loadSettingsConfig();
             console.log('Settings config loaded:', settingsConfig.length, 'settings');
             
+            // The structured launch settings are shown as arguments ahead of the custom ones
+            const allArgs = [this.launchSettingsToArgs(config.launch_settings), config.custom_args || '']
+                .filter(args => args.trim()).join(' ');
+
             // Parse current arguments to populate individual settings
-            const parsedSettings = await this.desktop.parseArgumentsToSettings(config.custom_args || '');
+            const parsedSettings = await this.desktop.parseArgumentsToSettings(allArgs);
             console.log('Parsed settings:', parsedSettings);
             
             // Filter to only show enabled settings
@@ -102,9 +256,7 @@ class PropertiesManager {
             
             return `
                 <div class="properties-container">
-                    <button class="delete-file-btn-floating" onclick="propertiesManager.deleteModelFile('${btoa(modelPath)}')" title="Delete this model file">
-                        <span class="material-icons">delete</span>
-                    </button>
+                    ${this.deleteButtonHTML(modelPath)}
                     
                     <div class="properties-sidebar">
                         <h4>Settings</h4>
@@ -114,19 +266,45 @@ class PropertiesManager {
                     </div>
                     
                     <div class="properties-main">
+                        ${this.modelInfoHTML(modelPath)}
                         <div class="property-group" data-model-path="${btoa(modelPath)}">
                             <h4>Active Settings</h4>
                             <div class="active-settings">
                                 ${settingsHTML || '<div class="no-settings">No settings configured. Click settings from the sidebar to add them.</div>'}
                             </div>
                         </div>
+                        <div class="property-group">
+                            <h4>LoRA Adapters</h4>
+                            <div class="lora-adapter-list"><div class="no-settings">Scanning for adapters...</div></div>
+                        </div>
                     </div>
                     
                     <div class="properties-button-container">
                         <div class="properties-bottom-section">
                             <div class="custom-args-section">
                                 <h4>Custom Arguments</h4>
-                                <textarea class="property-textarea" data-field="custom_args" placeholder="Additional custom arguments will be preserved">${config.custom_args || ''}</textarea>
+                                <textarea class="property-textarea" data-field="custom_args" placeholder="Additional custom arguments will be preserved">${allArgs}</textarea>
+                                <button class="properties-btn save-preset-btn" onclick="propertiesManager.saveArgsAsPreset()" title="Keep these arguments as a preset to apply to other models">Save as Preset...</button>
+                                <label class="property-checkbox" title="Serve through a local socket instead of a TCP port (loopback only on Windows)">
+                                    <input type="checkbox" data-field="local_socket" ${config.local_socket ? 'checked' : ''}>
+                                    Local only (no network port)
+                                </label>
+                                <label class="property-checkbox" title="Replace -c with the largest context that fits in free VRAM for the chosen -ngl">
+                                    <input type="checkbox" data-field="auto_context" ${config.auto_context ? 'checked' : ''}>
+                                    Fit context to free VRAM at launch
+                                </label>
+                                ${this.restartPolicyHTML(config.restart_policy)}
+                                <label class="property-checkbox idle-timeout" title="Stop the server to free its VRAM after this many minutes without requests, 0 keeps it running">
+                                    Sleep after
+                                    <input type="number" class="property-input" data-field="idle_timeout_minutes" min="0" value="${config.idle_timeout_minutes || 0}">
+                                    idle minutes
+                                </label>
+                                <label class="property-checkbox" title="Vision projector passed with --mmproj. Leave empty to use the mmproj file next to the model">
+                                    Vision projector
+                                    <input type="text" class="property-input" data-field="mmproj_path" value="${config.mmproj_path || ''}" placeholder="Auto-detect">
+                                </label>
+                                <h4>Environment Variables</h4>
+                                <textarea class="property-textarea env-vars" data-field="env_vars" placeholder="One NAME=value per line, e.g. GGML_CUDA_FORCE_MMQ=1">${this.desktop.escapeHtml(Object.entries(config.env_vars || {}).map(([name, value]) => `${name}=${value}`).join('\n'))}</textarea>
                             </div>
                             <div class="button-section">
                                 <div class="button-note">
@@ -145,9 +323,7 @@ class PropertiesManager {
             console.error('Error generating properties content:', error);
             return `
                 <div class="properties-container">
-                    <button class="delete-file-btn-floating" onclick="propertiesManager.deleteModelFile('${btoa(modelPath)}')" title="Delete this model file">
-                        <span class="material-icons">delete</span>
-                    </button>
+                    ${this.deleteButtonHTML(modelPath)}
                     
                     <div class="properties-sidebar">
                         <h4>Settings</h4>
@@ -169,7 +345,7 @@ class PropertiesManager {
                         <div class="properties-bottom-section">
                             <div class="custom-args-section">
                                 <h4>Custom Arguments</h4>
-                                <textarea class="property-textarea" data-field="custom_args" placeholder="Enter custom arguments manually">${config.custom_args || ''}</textarea>
+                                <textarea class="property-textarea" data-field="custom_args" placeholder="Enter custom arguments manually">${allArgs}</textarea>
                             </div>
                             <div class="button-section">
                                 <div class="button-note">
@@ -272,6 +448,13 @@ class PropertiesManager {
         const modelPath = atob(propertyGroup.dataset.modelPath);
         const textarea = activeWindow.querySelector('[data-field="custom_args"]');
         const customArgs = textarea ? textarea.value.trim() : '';
+        const localSocketToggle = activeWindow.querySelector('[data-field="local_socket"]');
+        const autoContextToggle = activeWindow.querySelector('[data-field="auto_context"]');
+        const mmprojInput = activeWindow.querySelector('[data-field="mmproj_path"]');
+        const restartMode = activeWindow.querySelector('[data-field="restart_mode"]');
+        const idleTimeoutInput = activeWindow.querySelector('[data-field="idle_timeout_minutes"]');
+        const envVarsInput = activeWindow.querySelector('[data-field="env_vars"]');
+        const loraRows = [...activeWindow.querySelectorAll('.lora-adapter')];
 
         console.log('Saving arguments for', modelPath, ':', customArgs);
 
@@ -282,13 +465,55 @@ class PropertiesManager {
                 throw new Error('Tauri API not available');
             }
 
+            // Keep fields this window doesn't edit (e.g. disable_fixups)
+            const existing = await invoke('get_model_settings', { modelPath: modelPath }).catch(() => ({}));
+
             // Create ModelConfig object to match Rust struct
             const config = {
+                ...existing,
                 custom_args: customArgs,
+                // The textarea holds every argument, the backend lifts the common flags back out
+                launch_settings: {},
                 server_host: '127.0.0.1',
                 server_port: 8080,
-                model_path: modelPath
+                model_path: modelPath,
+                local_socket: localSocketToggle ? localSocketToggle.checked : !!existing.local_socket,
+                auto_context: autoContextToggle ? autoContextToggle.checked : !!existing.auto_context,
+                mmproj_path: mmprojInput ? mmprojInput.value.trim() : (existing.mmproj_path || '')
             };
+            if (envVarsInput) {
+                config.env_vars = {};
+                envVarsInput.value.split('\n').map(line => line.trim()).filter(Boolean).forEach(line => {
+                    const separator = line.indexOf('=');
+                    if (separator <= 0) throw new Error(`Expected NAME=value, got "${line}"`);
+                    config.env_vars[line.slice(0, separator).trim()] = line.slice(separator + 1).trim();
+                });
+            }
+            if (idleTimeoutInput) {
+                const minutes = parseInt(idleTimeoutInput.value, 10);
+                config.idle_timeout_minutes = isNaN(minutes) || minutes < 0 ? 0 : minutes;
+            }
+            if (restartMode) {
+                const number = (field, fallback) => {
+                    const value = parseInt(activeWindow.querySelector(`[data-field="${field}"]`)?.value, 10);
+                    return isNaN(value) || value < 0 ? fallback : value;
+                };
+                config.restart_policy = {
+                    mode: restartMode.value,
+                    max_retries: Math.max(1, number('restart_max_retries', 3)),
+                    backoff_secs: number('restart_backoff_secs', 5)
+                };
+            }
+            // Rows only exist once the adapter scan finished, until then keep what was saved
+            if (loraRows.length > 0) {
+                const checked = loraRows.filter(row => row.querySelector('input[type="checkbox"]').checked);
+                config.lora_paths = checked.map(row => row.dataset.loraPath);
+                config.lora_scales = {};
+                checked.forEach(row => {
+                    const scale = parseFloat(row.querySelector('.lora-adapter-scale').value);
+                    if (!isNaN(scale) && scale !== 1) config.lora_scales[row.dataset.loraPath] = scale;
+                });
+            }
 
             await invoke('update_model_settings', {
                 modelPath: modelPath,
@@ -307,6 +532,19 @@ class PropertiesManager {
         }
     }
 
+    async saveArgsAsPreset() {
+        const activeWindow = document.querySelector('.properties-window:not(.hidden)');
+        const args = activeWindow?.querySelector('[data-field="custom_args"]')?.value.trim();
+        if (!args) {
+            this.desktop.showNotification('There are no arguments to save as a preset', 'info');
+            return;
+        }
+        const name = await this.desktop.createArgumentPreset(args);
+        if (name) {
+            this.desktop.showNotification(`Saved preset ${name}`, 'success');
+        }
+    }
+
     closePropertiesWindow() {
         const activeWindow = document.querySelector('.properties-window:not(.hidden)');
         if (activeWindow) {
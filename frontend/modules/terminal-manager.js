@@ -5,10 +5,38 @@ class TerminalManager {
         this.desktop = desktop;
         this.terminals = new Map(); // Store terminal instances
         this.terminalCounter = 0;
+        this.outputStreams = new Map(); // Process id -> output stream of its terminal window
+        this.resourceWindows = new Set(); // Windows showing their process stats
+        this.resourcePoller = null; // One interval refreshing the stats of all of them
         
         // Initialize Tauri API access
         this.invoke = null;
         this.initTauriAPI();
+        
+        // Servers that died outside the app are pruned by the backend, say so instead of failing silently
+        if (window.__TAURI__ && window.__TAURI__.event) {
+            window.__TAURI__.event.listen('stale-process-removed', (event) => {
+                const stale = event.payload;
+                this.desktop.showNotification(`${stale.model_name} stopped: ${stale.reason}`, 'info');
+                const windowId = `server_${stale.process_id}`;
+                if (this.terminals.has(windowId)) {
+                    this.updateServerStatus(windowId, 'stopped', -1);
+                }
+            });
+            // Server output is pushed in batches, each terminal window takes the ones for its process
+            window.__TAURI__.event.listen('process-output', (event) => {
+                this.outputStreams.get(event.payload.process_id)?.receive(event.payload);
+            });
+            // Running only once the backend sees /health answer, the port opens before the model is loaded
+            window.__TAURI__.event.listen('server-ready', (event) => {
+                const ready = event.payload;
+                const windowId = `server_${ready.process_id}`;
+                if (this.terminals.get(windowId)?.status === 'starting') {
+                    this.updateServerStatus(windowId, 'running');
+                }
+                this.desktop.showNotification(`${ready.model_name} is ready at ${ready.url}`, 'success');
+            });
+        }
     }
     
     initTauriAPI() {
@@ -36,14 +64,18 @@ class TerminalManager {
     
         const windowId = `server_${processId}`;
         console.log('Creating terminal window with ID:', windowId);
+        // Embedding and reranking servers only answer API requests, there is nothing to chat with
+        const embeddingModel = this.desktop.modelsByPath?.get(modelPath)?.embedding_model;
     
         const content = `
             <div class="server-terminal-container">
                 <div class="server-info">
                     <span class="server-status starting"><span class="material-icons" style="color: #ffc107; font-size: 14px;">circle</span> Starting</span>
                     <span class="server-details">${modelName} - <span class="clickable" style="cursor: pointer; text-decoration: underline;" onclick="terminalManager.openUrl('http://${host}:${port}')">${host}:${port}</span><button class="copy-link-btn" style="background: none; border: none; cursor: pointer; margin-left: 5px; padding: 0; font-size: 14px; vertical-align: middle;" onclick="terminalManager.copyToClipboard('http://${host}:${port}', this)" title="Copy link"><span class="material-icons" style="font-size: 14px; color: var(--theme-text-muted);">content_copy</span></button></span>
+                    <span class="server-metrics" id="server-metrics-${windowId}"></span>
+                    <span class="server-metrics server-resources" id="server-resources-${windowId}"></span>
                     <div class="server-controls">
-                        <button class="server-btn" id="chat-btn-${windowId}"><span class="material-icons">chat</span> Chat</button>
+                        ${embeddingModel ? '' : `<button class="server-btn" id="chat-btn-${windowId}"><span class="material-icons">chat</span> Chat</button>`}
                         <button class="server-btn stop-btn" id="stop-btn-${windowId}"><span class="material-icons">stop</span> Stop</button>
                     </div>
                 </div>
@@ -76,6 +108,23 @@ class TerminalManager {
             return null;
         }
 
+        // A terminal reopened for a server that kept running picks up its earlier output
+        const saved = await this.loadTerminalHistory(windowId);
+        if (saved && saved.output.length > 0) {
+            const outputDiv = document.getElementById(`server-output-${windowId}`);
+            if (outputDiv) {
+                const fragment = document.createDocumentFragment();
+                saved.output.forEach(line => {
+                    const lineDiv = document.createElement('div');
+                    lineDiv.className = 'server-line';
+                    lineDiv.textContent = line.toString();
+                    fragment.appendChild(lineDiv);
+                });
+                outputDiv.appendChild(fragment);
+                outputDiv.scrollTop = outputDiv.scrollHeight;
+            }
+        }
+
         // Store model info for this terminal
         this.terminals.set(windowId, {
             processId,
@@ -84,7 +133,8 @@ class TerminalManager {
             host,
             port,
             status: 'starting',
-            output: [], // Store terminal output lines
+            output: saved ? saved.output : [], // Store terminal output lines
+            history: saved ? saved.history : [],
             activeVersion: activeVersion
         });
 
@@ -110,9 +160,9 @@ class TerminalManager {
             }
         }, 0);
 
-        console.log('Starting output polling...');
-        // Start polling for output
-        this.startServerOutputPolling(processId, windowId);
+        // Output arrives as process-output events from here on
+        this.startServerOutputStream(processId, windowId, !(saved && saved.output.length > 0));
+        this.startResourcePolling(windowId);
 
         // Also add a status check after a few seconds to ensure we show something
         setTimeout(() => {
@@ -124,13 +174,112 @@ class TerminalManager {
         return window;
     }
 
-    startServerOutputPolling(processId, windowId) {
+    // llama-cli with its stdin piped, the output area works like a server's and the input line feeds the session
+    openCliTerminal(processId, modelName, modelPath) {
+        const windowId = `cli_${processId}`;
+        const content = `
+            <div class="server-terminal-container">
+                <div class="server-info">
+                    <span class="server-status running"><span class="material-icons" style="color: #4caf50; font-size: 14px;">circle</span> Running</span>
+                    <span class="server-details">${modelName} - llama-cli</span>
+                    <span class="server-metrics server-resources" id="server-resources-${windowId}"></span>
+                    <div class="server-controls">
+                        <button class="server-btn stop-btn" id="stop-btn-${windowId}"><span class="material-icons">stop</span> Stop</button>
+                    </div>
+                </div>
+                <div class="server-output" id="server-output-${windowId}"><div class="server-line server-system">Starting llama-cli for ${modelName}...</div></div>
+                <form class="cli-input-row" id="cli-input-row-${windowId}">
+                    <span class="cli-prompt">&gt;</span>
+                    <input type="text" class="cli-input" id="cli-input-${windowId}" placeholder="Type a message, Enter to send" autocomplete="off">
+                </form>
+            </div>
+        `;
+
+        const window = this.desktop.createWindow(windowId, `llama-cli - ${modelName}`, 'server-terminal-window', content);
+        if (!window) {
+            console.error('Failed to create cli terminal window!');
+            return null;
+        }
+
+        this.terminals.set(windowId, {
+            processId,
+            modelName,
+            modelPath,
+            host: '',
+            port: 0,
+            status: 'running',
+            interactive: true,
+            output: [],
+            history: []
+        });
+        this.desktop.addTaskbarItem(`llama-cli - ${modelName}`, windowId, '<span class="material-icons">terminal</span>');
+
+        document.getElementById(`stop-btn-${windowId}`)?.addEventListener('click', () => {
+            const terminalInfo = this.terminals.get(windowId);
+            this.stopServer(terminalInfo.processId, windowId, modelPath, modelName);
+        });
+
+        // Up and down walk back through what was sent, like a shell
+        const input = document.getElementById(`cli-input-${windowId}`);
+        let historyIndex = null;
+        input?.addEventListener('keydown', (e) => {
+            const history = this.terminals.get(windowId)?.history || [];
+            if (e.key === 'ArrowUp' && history.length > 0) {
+                e.preventDefault();
+                historyIndex = historyIndex === null ? history.length - 1 : Math.max(0, historyIndex - 1);
+                input.value = history[historyIndex];
+            } else if (e.key === 'ArrowDown' && historyIndex !== null) {
+                e.preventDefault();
+                historyIndex = historyIndex + 1 < history.length ? historyIndex + 1 : null;
+                input.value = historyIndex === null ? '' : history[historyIndex];
+            }
+        });
+        document.getElementById(`cli-input-row-${windowId}`)?.addEventListener('submit', async (e) => {
+            e.preventDefault();
+            const terminalInfo = this.terminals.get(windowId);
+            if (!input.value || terminalInfo?.status !== 'running') return;
+            const text = input.value;
+            input.value = '';
+            historyIndex = null;
+            try {
+                await this.getInvoke()('send_process_input', { processId: terminalInfo.processId, input: text });
+                this.recordTerminalInput(windowId, text);
+            } catch (error) {
+                input.value = text;
+                this.desktop.showNotification(`${error}`, 'error');
+            }
+        });
+        input?.focus();
+
+        this.startServerOutputStream(processId, windowId);
+        this.startResourcePolling(windowId);
+        return window;
+    }
+
+    // A finished session can't be resumed, a new one replaces it in a fresh window
+    async restartCliSession(windowId) {
+        const terminalInfo = this.terminals.get(windowId);
+        if (!terminalInfo) return;
+        try {
+            const result = await this.getInvoke()('launch_model_cli', { modelPath: terminalInfo.modelPath });
+            this.desktop.closeWindow(windowId);
+            this.openCliTerminal(result.process_id, result.model_name, terminalInfo.modelPath);
+        } catch (error) {
+            this.desktop.showNotification(`Failed to start llama-cli: ${error}`, 'error');
+        }
+    }
+
+    startServerOutputStream(processId, windowId, withSnapshot = true) {
         const terminalInfo = this.terminals.get(windowId);
         if (!terminalInfo) return;
 
         // Track last scroll position to determine if user is scrolled up
         let isScrolledToBottom = true;
         
+        // Snapshots for the session are throttled, batches can arrive every 50ms
+        let lastSaveTime = 0;
+        const saveInterval = 2000; // ms
+
         // Batch updates to reduce DOM operations
         let outputBuffer = [];
         let updateTimer = null;
@@ -165,112 +314,122 @@ class TerminalManager {
             }
         };
 
-        const pollOutput = async () => {
-            try {
-                console.log(`Polling output for process ${processId}...`);
-                // Use Tauri command instead of fetch
-                const invoke = this.getInvoke();
-                if (!invoke) {
-                    console.error('Tauri invoke not available for output polling');
-                    return;
-                }
-                const data = await invoke('get_process_output', { processId });
-                console.log(`Output data received:`, data);
+        // Batches from the backend, either a `process-output` event or the initial snapshot
+        const handleOutput = (lines, data) => {
+            const outputDiv = document.getElementById(`server-output-${windowId}`);
+            if (!outputDiv) {
+                // Window closed, its output stays in the backend buffer and the log file
+                this.outputStreams.delete(processId);
+                return;
+            }
 
-                const outputDiv = document.getElementById(`server-output-${windowId}`);
+            // Update scroll position tracking
+            const scrollTop = outputDiv.scrollTop;
+            const scrollHeight = outputDiv.scrollHeight;
+            const clientHeight = outputDiv.clientHeight;
+            isScrolledToBottom = (scrollTop + clientHeight >= scrollHeight - 5);
 
-                if (!outputDiv) {
-                    console.warn(`Output div not found for ${windowId}`);
-                    return;
+            if (lines.length > 0) {
+                outputBuffer.push(...lines);
+                // A request just finished, its timings are in the process metrics now
+                if (lines.some(line => line.includes('eval time'))) {
+                    this.refreshServerMetrics(windowId, processId);
                 }
-
-                // Update scroll position tracking
-                const scrollTop = outputDiv.scrollTop;
-                const scrollHeight = outputDiv.scrollHeight;
-                const clientHeight = outputDiv.clientHeight;
-                isScrolledToBottom = (scrollTop + clientHeight >= scrollHeight - 5);
-
-                // Add new output lines to buffer if they exist
-                if (data.output && Array.isArray(data.output) && data.output.length > 0) {
-                    console.log(`Adding ${data.output.length} output lines to buffer`);
-                    outputBuffer.push(...data.output);
-                    
-                    // Check for server ready message and update status
-                    const serverReadyMessage = "main: server is listening on http://";
-                    const hasServerReadyMessage = data.output.some(line => 
-                        line && line.toString().includes(serverReadyMessage)
-                    );
-                    
-                    if (hasServerReadyMessage && terminalInfo.status === 'starting') {
-                        console.log('Server ready message detected, updating status to running');
-                        this.updateServerStatus(windowId, 'running');
+                
+                // Save output to terminal data (keep last 1000 lines)
+                const terminalData = this.terminals.get(windowId);
+                if (terminalData) {
+                    if (!terminalData.output) terminalData.output = [];
+                    terminalData.output.push(...lines);
+                    // Keep only last 1000 lines to prevent memory issues
+                    if (terminalData.output.length > 1000) {
+                        terminalData.output = terminalData.output.slice(-1000);
                     }
-                    
-                    // Save output to terminal data (keep last 1000 lines)
-                    const terminalData = this.terminals.get(windowId);
-                    if (terminalData) {
-                        if (!terminalData.output) terminalData.output = [];
-                        terminalData.output.push(...data.output);
-                        // Keep only last 1000 lines to prevent memory issues
-                        if (terminalData.output.length > 1000) {
-                            terminalData.output = terminalData.output.slice(-1000);
-                        }
-                        this.terminals.set(windowId, terminalData);
+                    this.terminals.set(windowId, terminalData);
+                    if (Date.now() - lastSaveTime > saveInterval) {
+                        lastSaveTime = Date.now();
+                        this.saveTerminalState(windowId, terminalData);
                     }
-                    
-                    // Throttle updates to prevent UI freezing but be more responsive
-                    const now = Date.now();
-                    if (now - lastOutputTime > minUpdateInterval || outputBuffer.length > 50) {
-                        console.log('Flushing output buffer immediately');
+                }
+                
+                // Throttle updates to prevent UI freezing but be more responsive
+                const now = Date.now();
+                if (now - lastOutputTime > minUpdateInterval || outputBuffer.length > 50) {
+                    flushOutputBuffer(outputDiv);
+                    lastOutputTime = now;
+                } else if (!updateTimer) {
+                    // Schedule buffer flush
+                    updateTimer = setTimeout(() => {
                         flushOutputBuffer(outputDiv);
-                        lastOutputTime = now;
-                    } else if (!updateTimer) {
-                        // Schedule buffer flush
-                        updateTimer = setTimeout(() => {
-                            console.log('Flushing output buffer (scheduled)');
-                            flushOutputBuffer(outputDiv);
-                            updateTimer = null;
-                            lastOutputTime = Date.now();
-                        }, minUpdateInterval);
-                    }
-                } else {
-                    console.log('No new output data received');
+                        updateTimer = null;
+                        lastOutputTime = Date.now();
+                    }, minUpdateInterval);
                 }
+            }
 
-                // Check if process is still running
-                if (data.is_running !== false && (terminalInfo.status === 'running' || terminalInfo.status === 'starting')) {
-                    console.log('Process still running, continuing polling in 100ms');
-                    // Continue polling if process is still running - faster polling for better responsiveness
-                    setTimeout(pollOutput, 100);
-                } else if (data.is_running === false) {
-                    console.log('Process has stopped, finalizing output');
-                    // Process has stopped, flush any remaining output
-                    if (updateTimer) {
-                        clearTimeout(updateTimer);
-                    }
-                    flushOutputBuffer(outputDiv);
-                    this.updateServerStatus(windowId, 'stopped', data.return_code || 0);
+            const status = this.terminals.get(windowId)?.status;
+            // Covers a server-ready event sent before this window was listening
+            if (data.ready && status === 'starting') {
+                this.updateServerStatus(windowId, 'running');
+            } else if (data.is_running && data.ready === false && status === 'running') {
+                // Crashed and being restarted by its restart policy
+                this.updateServerStatus(windowId, 'starting');
+            }
+
+            if (data.is_running === false) {
+                console.log('Process has stopped, finalizing output');
+                this.outputStreams.delete(processId);
+                if (updateTimer) {
+                    clearTimeout(updateTimer);
+                }
+                flushOutputBuffer(outputDiv);
+                this.updateServerStatus(windowId, 'stopped', data.return_code || 0);
+            }
+        };
+
+        // Events that arrive while the snapshot loads wait in the queue, line numbers drop the overlap
+        const stream = { nextLine: null, queue: [] };
+        stream.receive = (event) => {
+            if (stream.nextLine === null) {
+                stream.queue.push(event);
+                return;
+            }
+            const skip = Math.max(0, stream.nextLine - event.first_line);
+            stream.nextLine = Math.max(stream.nextLine, event.first_line + event.lines.length);
+            handleOutput(event.lines.slice(skip), event);
+        };
+        this.outputStreams.set(processId, stream);
+
+        const loadSnapshot = async () => {
+            try {
+                const invoke = this.getInvoke();
+                if (!invoke) {
+                    console.error('Tauri invoke not available for server output');
+                    return;
                 }
+                const data = await invoke('get_process_output', { processId });
+                stream.nextLine = data.next_line;
+                // A terminal that already shows earlier output only takes what comes next
+                handleOutput(withSnapshot ? data.output : [], data);
+                stream.queue.splice(0).forEach(stream.receive);
             } catch (error) {
-                console.error('Error polling server output:', error);
-                const terminalInfo = this.terminals.get(windowId);
+                console.error('Error loading server output:', error);
+                this.outputStreams.delete(processId);
 
-                // If the server is gone or another error, stop polling.
+                // If the server is gone or another error, stop listening.
                 this.updateServerStatus(windowId, 'stopped', -1);
                 const outputDiv = document.getElementById(`server-output-${windowId}`);
                 if (outputDiv) {
                     const errorDiv = document.createElement('div');
                     errorDiv.className = 'server-line server-error';
-                    errorDiv.textContent = `Connection to server lost. Polling stopped. Error: ${error.message}`;
+                    errorDiv.textContent = `Connection to server lost. Error: ${error.message}`;
                     outputDiv.appendChild(errorDiv);
                     outputDiv.scrollTop = outputDiv.scrollHeight;
                 }
-                return; // Stop polling
             }
         };
 
-        // Start polling immediately
-        pollOutput();
+        loadSnapshot();
     }
 
     async checkServerHealth(windowId, host, port, modelName) {
@@ -302,6 +461,83 @@ class TerminalManager {
         }
     }
 
+    async refreshServerMetrics(windowId, processId) {
+        const invoke = this.getInvoke();
+        const metricsSpan = document.getElementById(`server-metrics-${windowId}`);
+        if (!invoke || !metricsSpan) return;
+
+        try {
+            const metrics = await invoke('get_process_metrics', { processId });
+            if (metrics.last_tokens_per_second == null) return;
+            const parts = [`${metrics.last_tokens_per_second.toFixed(1)} tok/s`];
+            if (metrics.prompt_tokens_per_second != null) {
+                parts.push(`prompt ${metrics.prompt_tokens_per_second.toFixed(1)} tok/s`);
+            }
+            metricsSpan.textContent = parts.join(' · ');
+            metricsSpan.title = `Last request: ${metrics.last_prompt_tokens ?? '?'} prompt tokens in ${metrics.prompt_eval_ms != null ? metrics.prompt_eval_ms.toFixed(0) : '?'} ms, ` +
+                `${metrics.last_generated_tokens ?? '?'} tokens generated\nRequests completed: ${metrics.completed_requests}`;
+        } catch (error) {
+            console.warn('Failed to get process metrics:', error);
+        }
+    }
+
+    // CPU, memory and VRAM of the window's process, refreshed while the window is open.
+    // All windows share one poll of every server, matched up by process id on each tick
+    // so a restarted server is picked up
+    startResourcePolling(windowId, intervalMs = 3000) {
+        this.resourceWindows.add(windowId);
+        if (!this.resourcePoller) {
+            this.resourcePoller = setInterval(() => this.refreshResources(), intervalMs);
+        }
+        this.refreshResources();
+    }
+
+    async refreshResources() {
+        const formatBytes = (bytes) => bytes >= 1024 ** 3 ? `${(bytes / 1024 ** 3).toFixed(1)} GB` : `${Math.round(bytes / 1024 ** 2)} MB`;
+        const active = [];
+        for (const windowId of this.resourceWindows) {
+            const span = document.getElementById(`server-resources-${windowId}`);
+            if (!span) {
+                this.resourceWindows.delete(windowId);
+                continue;
+            }
+            const terminalInfo = this.terminals.get(windowId);
+            if (!terminalInfo || !['running', 'starting'].includes(terminalInfo.status)) {
+                span.textContent = '';
+                continue;
+            }
+            active.push({ span, processId: terminalInfo.processId });
+        }
+        if (this.resourceWindows.size === 0) {
+            clearInterval(this.resourcePoller);
+            this.resourcePoller = null;
+        }
+        if (active.length === 0) return;
+
+        let samples;
+        try {
+            samples = await this.getInvoke()('get_process_stats', {});
+        } catch (error) {
+            console.warn('Failed to get process stats:', error);
+            return;
+        }
+        const byProcess = new Map(samples.map(stats => [stats.process_id, stats]));
+        for (const { span, processId } of active) {
+            const stats = byProcess.get(processId);
+            if (!stats) {
+                // Exited between ticks, or a remote server without a local process
+                span.textContent = '';
+                continue;
+            }
+            const parts = [`CPU ${stats.cpu_percent.toFixed(0)}%`, `RAM ${formatBytes(stats.memory_bytes)}`];
+            if (stats.vram_bytes != null) {
+                parts.push(`VRAM ${formatBytes(stats.vram_bytes)}`);
+            }
+            span.textContent = parts.join(' · ');
+            span.title = `PID ${stats.pid}`;
+        }
+    }
+
     updateServerStatus(windowId, status, returnCode = null) {
         const window = this.desktop.windows.get(windowId);
         const terminalInfo = this.terminals.get(windowId);
@@ -341,7 +577,11 @@ class TerminalManager {
                         stopBtn.parentNode.replaceChild(newStopBtn, stopBtn);
                         // Add event listener
                         newStopBtn.addEventListener('click', () => {
-                            this.restartServer(windowId, terminalInfo.modelPath, terminalInfo.modelName);
+                            if (terminalInfo.interactive) {
+                                this.restartCliSession(windowId);
+                            } else {
+                                this.restartServer(windowId, terminalInfo.modelPath, terminalInfo.modelName);
+                            }
                         });
                     }
 
@@ -367,8 +607,27 @@ class TerminalManager {
                 console.error('Tauri invoke not available for process termination');
                 return;
             }
-            await invoke('kill_process', { processId });
+            let result = await invoke('kill_process', { processId });
+            if (result && result.success === false && result.requests_in_flight) {
+                // Another client is mid-response, only stop when confirmed
+                const message = `${modelName} has ${result.warning}. Stop it anyway?`;
+                let confirmed = false;
+                try {
+                    confirmed = await ModalDialog.showConfirmation({
+                        title: 'Stop Server',
+                        message,
+                        confirmText: 'Stop',
+                        cancelText: 'Cancel',
+                        type: 'danger'
+                    });
+                } catch (e) {
+                    confirmed = confirm(message);
+                }
+                if (!confirmed) return;
+                result = await invoke('kill_process', { processId, force: true });
+            }
             
+            this.outputStreams.delete(processId);
             this.updateServerStatus(windowId, 'stopped', 0);
             this.desktop.showNotification(`${modelName} stopped`, 'info');
             
@@ -460,8 +719,8 @@ class TerminalManager {
                     }
                 }
 
-                // Start polling for new output
-                this.startServerOutputPolling(result.process_id, windowId);
+                // Follow the new process's output
+                this.startServerOutputStream(result.process_id, windowId);
                 this.desktop.showNotification(`${modelName} restarted`, 'success');
             } else {
                 throw new Error(result.error || 'Failed to launch model');
@@ -533,30 +792,50 @@ class TerminalManager {
     // Session management methods
     async saveTerminalState(windowId, terminalData) {
         try {
-            const response = await fetch('/api/session/terminal', {
-                method: 'POST',
-                headers: { 'Content-Type': 'application/json' },
-                body: JSON.stringify({
-                    windowId: windowId,
-                    processId: terminalData.processId,
-                    modelName: terminalData.modelName,
-                    modelPath: terminalData.modelPath,
-                    host: terminalData.host,
-                    port: terminalData.port,
-                    status: terminalData.status,
+            const invoke = this.getInvoke();
+            await invoke('save_terminal_state', {
+                terminalId: windowId,
+                terminalState: {
+                    process_id: terminalData.processId || '',
+                    model_name: terminalData.modelName || '',
+                    model_path: terminalData.modelPath || '',
+                    host: terminalData.host || '',
+                    port: terminalData.port || 0,
+                    status: terminalData.status || 'stopped',
                     output: terminalData.output || [],
-                    activeVersion: terminalData.activeVersion || ''
-                })
+                    history: terminalData.history || []
+                }
             });
-
-            if (!response.ok) {
-                throw new Error('Failed to save terminal state');
-            }
         } catch (error) {
             console.error('Error saving terminal state:', error);
         }
     }
 
+    // Remember input sent to an interactive tool so the terminal can offer it again
+    async recordTerminalInput(windowId, input) {
+        const terminalData = this.terminals.get(windowId);
+        if (!terminalData) return;
+        if (!terminalData.history) terminalData.history = [];
+        if (input.trim() && terminalData.history[terminalData.history.length - 1] !== input) {
+            terminalData.history.push(input);
+        }
+        try {
+            await this.getInvoke()('record_terminal_input', { terminalId: windowId, input });
+        } catch (error) {
+            // Not saved yet, the next snapshot carries the history along
+            await this.saveTerminalState(windowId, terminalData);
+        }
+    }
+
+    // Scrollback and input history saved for a terminal earlier in this session
+    async loadTerminalHistory(windowId) {
+        try {
+            return await this.getInvoke()('get_terminal_history', { terminalId: windowId });
+        } catch (error) {
+            return null;
+        }
+    }
+
     // Check for existing terminal for a model
     getExistingTerminal(modelPath) {
         return Array.from(this.terminals.entries()).find(([windowId, terminalInfo]) =>
@@ -608,6 +887,7 @@ class TerminalManager {
             }
         }
         this.terminals.delete(windowId);
+        this.getInvoke()('remove_terminal_state', { terminalId: windowId }).catch(() => {});
     }
     
     // Method to open URL in default browser
@@ -658,8 +938,18 @@ class TerminalManager {
         console.log('Session data terminals:', this.desktop.sessionData.terminals);
         console.log('Session data windows:', this.desktop.sessionData.windows);
 
-        // First restore terminals data
-        for (const [windowId, terminalData] of Object.entries(this.desktop.sessionData.terminals || {})) {
+        // First restore terminals data, saved by the backend with its own field names
+        for (const [windowId, saved] of Object.entries(this.desktop.sessionData.terminals || {})) {
+            const terminalData = {
+                processId: saved.process_id,
+                modelName: saved.model_name,
+                modelPath: saved.model_path,
+                host: saved.host,
+                port: saved.port,
+                status: saved.status,
+                output: saved.output || [],
+                history: saved.history || []
+            };
             console.log('Loading terminal data for', windowId, ':', terminalData);
             console.log('Terminal output length:', terminalData.output ? terminalData.output.length : 'no output');
             
@@ -729,6 +1019,8 @@ class TerminalManager {
                         ${terminalData.status}
                     </span>
                     <span class="server-details">${terminalData.modelName} - <span class="clickable" style="cursor: pointer; text-decoration: underline;" onclick="terminalManager.openUrl('http://${terminalData.host}:${terminalData.port}')">${terminalData.host}:${terminalData.port}</span><button class="copy-link-btn" style="background: none; border: none; cursor: pointer; margin-left: 5px; padding: 0; font-size: 14px; vertical-align: middle;" onclick="terminalManager.copyToClipboard('http://${terminalData.host}:${terminalData.port}', this)" title="Copy link"><span class="material-icons" style="font-size: 14px; color: var(--theme-text-muted);">content_copy</span></button></span>
+                    <span class="server-metrics" id="server-metrics-${windowId}"></span>
+                    <span class="server-metrics server-resources" id="server-resources-${windowId}"></span>
                     <div class="server-controls">
                         <button class="server-btn" onclick="terminalManager.openChatForServer('${windowId}', '${terminalData.modelName}', '${terminalData.host}', ${terminalData.port})"><span class="material-icons">chat</span> Chat</button>
                         ${terminalData.status === 'running' || terminalData.status === 'starting' ? 
@@ -780,9 +1072,10 @@ class TerminalManager {
             }
         }, 100);
         
-        // Resume output polling if process is still running or starting
+        // Resume the output stream if process is still running or starting
         if ((terminalData.status === 'running' || terminalData.status === 'starting') && terminalData.processId) {
-            this.startServerOutputPolling(terminalData.processId, windowId);
+            this.startServerOutputStream(terminalData.processId, windowId, false);
+            this.startResourcePolling(windowId);
         }
     }
 